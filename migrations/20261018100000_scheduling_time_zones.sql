@@ -0,0 +1,34 @@
+--------------------------------------------------------------------------------
+-- TIME ZONE AWARE SCHEDULING
+--------------------------------------------------------------------------------
+
+-- IANA time zone names (e.g. 'Europe/Istanbul'). Validated by the application against the
+-- bundled tz database. A user without a zone inherits the zone of their company.
+ALTER TABLE companies ADD COLUMN time_zone TEXT NOT NULL DEFAULT 'UTC';
+ALTER TABLE users ADD COLUMN time_zone TEXT;
+
+-- Professional Recurring Availabilities: Weekly availability expressed in local wall-clock time.
+-- Expanded into concrete slots per occurrence, so a 09:00-17:00 rule stays 09:00-17:00 local
+-- time across daylight saving transitions.
+CREATE TABLE professional_recurring_availabilities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    professional_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    weekday SMALLINT NOT NULL CHECK (weekday BETWEEN 1 AND 7), -- ISO 8601: 1 = Monday
+    local_start_time TIME NOT NULL,
+    local_end_time TIME NOT NULL,
+    time_zone TEXT NOT NULL,
+    valid_from DATE NOT NULL,
+    valid_until DATE,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK (local_end_time > local_start_time),
+    CHECK (valid_until IS NULL OR valid_until >= valid_from)
+);
+
+ALTER TABLE professional_recurring_availabilities ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_own_recurring_availabilities ON professional_recurring_availabilities FOR ALL USING (professional_user_id = current_setting('app.current_user_id', true)::uuid AND (get_current_user_roles() && ARRAY['ohs_specialist', 'doctor']::text[]) AND tenant_id = current_setting('app.current_tenant_id', true)::uuid) WITH CHECK (professional_user_id = current_setting('app.current_user_id', true)::uuid AND tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY view_recurring_availabilities_for_tenant_members ON professional_recurring_availabilities FOR SELECT USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY view_recurring_availabilities_for_super_admin ON professional_recurring_availabilities FOR SELECT USING ('super_admin' = ANY(get_current_user_roles()));
+
+CREATE INDEX idx_prof_recurring_avail_professional_user_id ON professional_recurring_availabilities(professional_user_id);
+CREATE INDEX idx_prof_recurring_avail_tenant_id ON professional_recurring_availabilities(tenant_id);
@@ -0,0 +1,21 @@
+--------------------------------------------------------------------------------
+-- CALENDAR SUBSCRIPTION FEEDS
+--------------------------------------------------------------------------------
+
+-- Calendar Feed Tokens: One secret feed URL per user for subscribing from Outlook, Google
+-- Calendar, etc. Only a SHA-256 hash of the token is stored; rotating the token replaces the row
+-- and invalidates existing subscriptions.
+CREATE TABLE calendar_feed_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    last_accessed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+ALTER TABLE calendar_feed_tokens ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_own_calendar_feed_token ON calendar_feed_tokens FOR ALL USING (user_id = current_setting('app.current_user_id', true)::uuid) WITH CHECK (user_id = current_setting('app.current_user_id', true)::uuid AND tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY manage_calendar_feed_tokens_for_super_admin ON calendar_feed_tokens FOR ALL USING ('super_admin' = ANY(get_current_user_roles()));
+
+CREATE INDEX idx_calendar_feed_tokens_tenant_id ON calendar_feed_tokens(tenant_id);
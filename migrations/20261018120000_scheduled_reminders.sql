@@ -0,0 +1,36 @@
+--------------------------------------------------------------------------------
+-- SCHEDULED REMINDERS
+--------------------------------------------------------------------------------
+
+CREATE TYPE reminder_status AS ENUM (
+    'pending', 'sent', 'cancelled', 'skipped'
+);
+
+-- Scheduled Reminders: One row per recipient, event and offset, written when the event is
+-- booked or moved and consumed by the reminder scheduler. The unique key makes scheduling
+-- idempotent, and rows are claimed with FOR UPDATE SKIP LOCKED and marked sent in the same
+-- transaction that creates the in-app notification, so restarts never deliver twice.
+CREATE TABLE scheduled_reminders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    notification_type notification_type NOT NULL, -- appointment_reminder or training_reminder
+    related_entity_id UUID NOT NULL,
+    related_entity_type TEXT NOT NULL, -- 'appointment' or 'training_session'
+    offset_minutes INTEGER NOT NULL CHECK (offset_minutes > 0),
+    starts_at TIMESTAMPTZ NOT NULL, -- Start time of the event the reminder was computed from
+    remind_at TIMESTAMPTZ NOT NULL,
+    status reminder_status NOT NULL DEFAULT 'pending',
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (related_entity_type, related_entity_id, user_id, offset_minutes)
+);
+
+ALTER TABLE scheduled_reminders ENABLE ROW LEVEL SECURITY;
+CREATE POLICY view_own_scheduled_reminders ON scheduled_reminders FOR SELECT USING (user_id = current_setting('app.current_user_id', true)::uuid);
+CREATE POLICY manage_scheduled_reminders_for_tenant_members ON scheduled_reminders FOR ALL USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY manage_scheduled_reminders_for_super_admin ON scheduled_reminders FOR ALL USING ('super_admin' = ANY(get_current_user_roles()));
+
+CREATE INDEX idx_scheduled_reminders_due ON scheduled_reminders(remind_at) WHERE status = 'pending';
+CREATE INDEX idx_scheduled_reminders_user_id ON scheduled_reminders(user_id);
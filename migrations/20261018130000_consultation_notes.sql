@@ -0,0 +1,112 @@
+--------------------------------------------------------------------------------
+-- CONSULTATION NOTES
+--------------------------------------------------------------------------------
+
+CREATE TYPE consultation_note_status AS ENUM (
+    'draft', 'signed'
+);
+
+-- Consultation Note Templates: Structured sections a professional fills in per appointment type.
+-- Rows without a tenant are system defaults available to every tenant.
+-- sections: [{"key": "findings", "label": "Findings", "required": true, "shared_with_employee": false}, ...]
+CREATE TABLE consultation_note_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID REFERENCES tenants(id) ON DELETE CASCADE,
+    appointment_type appointment_type NOT NULL,
+    name TEXT NOT NULL,
+    sections JSONB NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK (jsonb_typeof(sections) = 'array')
+);
+
+-- Consultation Notes: The structured visit record of an appointment. Content lives in versions;
+-- once signed the note and its versions can no longer change.
+CREATE TABLE consultation_notes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    appointment_id UUID NOT NULL UNIQUE REFERENCES appointments(id) ON DELETE CASCADE,
+    template_id UUID NOT NULL REFERENCES consultation_note_templates(id),
+    author_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status consultation_note_status NOT NULL DEFAULT 'draft',
+    current_version INTEGER NOT NULL DEFAULT 0,
+    signed_at TIMESTAMPTZ,
+    signed_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Consultation Note Versions: Append-only edit history of a note
+CREATE TABLE consultation_note_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    note_id UUID NOT NULL REFERENCES consultation_notes(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL CHECK (version > 0),
+    content JSONB NOT NULL, -- {"<section key>": "<text>", ...}
+    edited_by_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (note_id, version),
+    CHECK (jsonb_typeof(content) = 'object')
+);
+
+-- Signed notes are locked: reject any change to them or new versions, whatever the caller
+CREATE OR REPLACE FUNCTION prevent_signed_consultation_note_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_TABLE_NAME = 'consultation_notes' THEN
+        IF OLD.status = 'signed' THEN
+            RAISE EXCEPTION 'Consultation note % is signed and locked', OLD.id;
+        END IF;
+        RETURN NEW;
+    END IF;
+
+    IF TG_OP = 'INSERT' THEN
+        IF EXISTS (SELECT 1 FROM consultation_notes WHERE id = NEW.note_id AND status = 'signed') THEN
+            RAISE EXCEPTION 'Consultation note % is signed and locked', NEW.note_id;
+        END IF;
+        RETURN NEW;
+    END IF;
+
+    RAISE EXCEPTION 'Consultation note versions are append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER lock_signed_consultation_notes BEFORE UPDATE ON consultation_notes FOR EACH ROW EXECUTE FUNCTION prevent_signed_consultation_note_changes();
+CREATE TRIGGER lock_consultation_note_versions BEFORE INSERT OR UPDATE ON consultation_note_versions FOR EACH ROW EXECUTE FUNCTION prevent_signed_consultation_note_changes();
+
+-- RLS for consultation_note_templates
+ALTER TABLE consultation_note_templates ENABLE ROW LEVEL SECURITY;
+CREATE POLICY view_consultation_templates_for_tenant_members ON consultation_note_templates FOR SELECT USING (tenant_id IS NULL OR tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY manage_consultation_templates_for_tenant_admin ON consultation_note_templates FOR ALL USING ('tenant_admin' = ANY(get_current_user_roles()) AND tenant_id = current_setting('app.current_tenant_id', true)::uuid) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY manage_consultation_templates_for_super_admin ON consultation_note_templates FOR ALL USING ('super_admin' = ANY(get_current_user_roles()));
+
+-- RLS for consultation_notes
+ALTER TABLE consultation_notes ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_consultation_notes_for_professional ON consultation_notes FOR ALL USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND (get_current_user_roles() && ARRAY['ohs_specialist', 'doctor']::text[]) AND EXISTS (SELECT 1 FROM appointments a WHERE a.id = consultation_notes.appointment_id AND a.professional_user_id = current_setting('app.current_user_id', true)::uuid)) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY view_signed_consultation_notes_for_employee ON consultation_notes FOR SELECT USING (status = 'signed' AND tenant_id = current_setting('app.current_tenant_id', true)::uuid AND EXISTS (SELECT 1 FROM appointments a WHERE a.id = consultation_notes.appointment_id AND a.employee_user_id = current_setting('app.current_user_id', true)::uuid));
+
+-- RLS for consultation_note_versions
+ALTER TABLE consultation_note_versions ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_consultation_note_versions_via_note ON consultation_note_versions FOR ALL USING (EXISTS (SELECT 1 FROM consultation_notes cn WHERE cn.id = consultation_note_versions.note_id)) WITH CHECK (EXISTS (SELECT 1 FROM consultation_notes cn WHERE cn.id = consultation_note_versions.note_id));
+
+CREATE INDEX idx_consultation_note_templates_tenant_type ON consultation_note_templates(tenant_id, appointment_type);
+CREATE INDEX idx_consultation_notes_tenant_id ON consultation_notes(tenant_id);
+CREATE INDEX idx_consultation_note_versions_note_id ON consultation_note_versions(note_id);
+
+-- System default templates
+INSERT INTO consultation_note_templates (appointment_type, name, sections) VALUES
+('ohs_consultation', 'OHS consultation', '[
+    {"key": "complaint", "label": "Presenting concern", "required": true, "shared_with_employee": true},
+    {"key": "workplace_factors", "label": "Workplace risk factors", "required": true, "shared_with_employee": false},
+    {"key": "assessment", "label": "Assessment", "required": true, "shared_with_employee": false},
+    {"key": "recommendations", "label": "Recommendations", "required": true, "shared_with_employee": true},
+    {"key": "follow_up", "label": "Follow-up", "required": false, "shared_with_employee": true}
+]'),
+('medical_checkup', 'Periodic medical check-up', '[
+    {"key": "history", "label": "Medical history", "required": true, "shared_with_employee": false},
+    {"key": "examination", "label": "Examination findings", "required": true, "shared_with_employee": false},
+    {"key": "fitness_for_work", "label": "Fitness for work", "required": true, "shared_with_employee": true},
+    {"key": "restrictions", "label": "Restrictions", "required": false, "shared_with_employee": true},
+    {"key": "recommendations", "label": "Recommendations", "required": false, "shared_with_employee": true}
+]');
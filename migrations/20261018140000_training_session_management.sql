@@ -0,0 +1,22 @@
+--------------------------------------------------------------------------------
+-- TRAINING SESSION MANAGEMENT
+--------------------------------------------------------------------------------
+
+-- Sessions start as drafts visible only to their host and tenant admins; publishing makes
+-- them visible to the rest of the tenant. Cancellation keeps the row for history.
+ALTER TABLE training_sessions
+    ADD COLUMN published_at TIMESTAMPTZ, -- NULL while the session is a draft
+    ADD COLUMN cancelled_at TIMESTAMPTZ,
+    ADD COLUMN cancellation_reason TEXT;
+
+-- Sessions created before drafts existed were already visible to everyone
+UPDATE training_sessions SET published_at = COALESCE(created_at, NOW());
+
+ALTER TABLE training_sessions
+    ADD CONSTRAINT training_sessions_time_range CHECK (end_time > start_time),
+    ADD CONSTRAINT training_sessions_max_participants CHECK (max_participants IS NULL OR max_participants > 0);
+
+DROP POLICY view_training_sessions_for_tenant_members ON training_sessions;
+CREATE POLICY view_published_training_sessions_for_tenant_members ON training_sessions FOR SELECT USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND published_at IS NOT NULL);
+
+CREATE INDEX idx_training_sessions_published ON training_sessions(tenant_id, start_time) WHERE published_at IS NOT NULL;
@@ -0,0 +1,11 @@
+--------------------------------------------------------------------------------
+-- TRAINING ENROLLMENT WAITLIST
+--------------------------------------------------------------------------------
+
+-- Enrollments beyond `max_participants` wait in line ordered by `enrolled_at`, and withdrawn
+-- enrollments are kept so the history survives. Capacity is enforced by the API while holding
+-- a row lock on the session, which serializes concurrent enrollments of the same session.
+ALTER TYPE participant_status ADD VALUE IF NOT EXISTS 'waitlisted';
+ALTER TYPE participant_status ADD VALUE IF NOT EXISTS 'withdrawn';
+
+CREATE INDEX idx_training_enrollments_session_queue ON training_enrollments(training_session_id, status, enrolled_at);
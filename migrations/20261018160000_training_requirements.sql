@@ -0,0 +1,38 @@
+--------------------------------------------------------------------------------
+-- MANDATORY TRAINING REQUIREMENTS
+--------------------------------------------------------------------------------
+
+CREATE TYPE training_compliance_status AS ENUM (
+    'compliant', 'due_soon', 'overdue'
+);
+
+-- Training Requirements: Trainings an employee must complete, targeted by company, department
+-- and/or job title (NULL matches everyone). Recurring requirements expire
+-- `recurrence_interval_months` after the last completion; one-off ones never expire.
+CREATE TABLE training_requirements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    description TEXT,
+    company_id UUID REFERENCES companies(id) ON DELETE CASCADE,
+    department TEXT,
+    job_title TEXT,
+    recurrence_interval_months INTEGER CHECK (recurrence_interval_months > 0), -- e.g. 12 for hazardous-class workplaces
+    due_soon_days INTEGER NOT NULL DEFAULT 30 CHECK (due_soon_days >= 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Completing an enrollment of a linked session fulfils the requirement
+ALTER TABLE training_sessions
+    ADD COLUMN requirement_id UUID REFERENCES training_requirements(id) ON DELETE SET NULL;
+
+ALTER TABLE training_requirements ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_training_requirements_for_tenant_admin ON training_requirements FOR ALL USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND 'tenant_admin' = ANY(get_current_user_roles())) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY view_training_requirements_for_tenant_members ON training_requirements FOR SELECT USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+CREATE POLICY view_training_requirements_for_super_admin ON training_requirements FOR SELECT USING ('super_admin' = ANY(get_current_user_roles()));
+
+CREATE INDEX idx_training_requirements_tenant_id ON training_requirements(tenant_id) WHERE is_active;
+CREATE INDEX idx_training_sessions_requirement_id ON training_sessions(requirement_id);
@@ -0,0 +1,39 @@
+--------------------------------------------------------------------------------
+-- TRAINING ATTENDANCE
+--------------------------------------------------------------------------------
+
+CREATE TYPE attendance_event_type AS ENUM (
+    'join', 'leave'
+);
+
+-- Attendance Events: Join/leave events reported by the live session for each participant.
+-- Attended minutes are derived from these; the table is append-only.
+CREATE TABLE training_attendance_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    training_session_id UUID NOT NULL REFERENCES training_sessions(id) ON DELETE CASCADE,
+    enrollment_id UUID NOT NULL REFERENCES training_enrollments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type attendance_event_type NOT NULL,
+    source TEXT NOT NULL DEFAULT 'client', -- 'client', 'websocket' or 'media_room'
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Share of the session a participant must attend to be marked as attended
+ALTER TABLE training_sessions
+    ADD COLUMN min_attendance_percent INTEGER NOT NULL DEFAULT 75
+        CHECK (min_attendance_percent BETWEEN 0 AND 100);
+
+ALTER TABLE training_enrollments
+    ADD COLUMN attended_minutes INTEGER,
+    ADD COLUMN attendance_overridden_by UUID REFERENCES users(id) ON DELETE SET NULL, -- Set when a specialist decided manually
+    ADD COLUMN attendance_overridden_at TIMESTAMPTZ,
+    ADD COLUMN attendance_override_note TEXT;
+
+ALTER TABLE training_attendance_events ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_own_attendance_events ON training_attendance_events FOR ALL USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND user_id = current_setting('app.current_user_id', true)::uuid) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND user_id = current_setting('app.current_user_id', true)::uuid);
+CREATE POLICY view_attendance_events_for_host_or_admin ON training_attendance_events FOR SELECT USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND (('tenant_admin' = ANY(get_current_user_roles())) OR EXISTS (SELECT 1 FROM training_sessions ts WHERE ts.id = training_attendance_events.training_session_id AND ts.host_user_id = current_setting('app.current_user_id', true)::uuid)));
+
+CREATE INDEX idx_training_attendance_events_enrollment ON training_attendance_events(enrollment_id, occurred_at);
+CREATE INDEX idx_training_attendance_events_session_id ON training_attendance_events(training_session_id);
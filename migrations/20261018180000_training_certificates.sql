@@ -0,0 +1,36 @@
+--------------------------------------------------------------------------------
+-- TRAINING CERTIFICATES
+--------------------------------------------------------------------------------
+
+-- Training Certificates: One per completed enrollment. The printed details are a snapshot
+-- taken at issue time, so a certificate keeps reading the same after names change.
+-- The PDF is rendered in the background; storage_key stays NULL until it is stored.
+CREATE TABLE training_certificates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    enrollment_id UUID NOT NULL UNIQUE REFERENCES training_enrollments(id) ON DELETE CASCADE,
+    training_session_id UUID NOT NULL REFERENCES training_sessions(id) ON DELETE CASCADE,
+    employee_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    verification_code TEXT NOT NULL UNIQUE, -- Printed on the certificate and encoded in its QR code
+    tenant_name TEXT NOT NULL,
+    company_name TEXT NOT NULL,
+    employee_name TEXT NOT NULL,
+    training_title TEXT NOT NULL,
+    host_name TEXT,
+    completed_at TIMESTAMPTZ NOT NULL,
+    storage_key TEXT,
+    render_attempts INTEGER NOT NULL DEFAULT 0,
+    last_render_error TEXT,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    rendered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+ALTER TABLE training_certificates ENABLE ROW LEVEL SECURITY;
+CREATE POLICY view_own_training_certificates ON training_certificates FOR SELECT USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND employee_user_id = current_setting('app.current_user_id', true)::uuid);
+CREATE POLICY manage_training_certificates_for_host_or_admin ON training_certificates FOR ALL USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND (('tenant_admin' = ANY(get_current_user_roles())) OR EXISTS (SELECT 1 FROM training_sessions ts WHERE ts.id = training_certificates.training_session_id AND ts.host_user_id = current_setting('app.current_user_id', true)::uuid))) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+
+CREATE INDEX idx_training_certificates_pending ON training_certificates(created_at) WHERE storage_key IS NULL;
+CREATE INDEX idx_training_certificates_session_id ON training_certificates(training_session_id);
+CREATE INDEX idx_training_certificates_employee ON training_certificates(employee_user_id);
@@ -0,0 +1,12 @@
+--------------------------------------------------------------------------------
+-- CERTIFICATE REVOCATION
+--------------------------------------------------------------------------------
+
+-- A revoked certificate keeps its row and code so that verification reports it as revoked
+-- instead of unknown.
+ALTER TABLE training_certificates
+    ADD COLUMN revoked_at TIMESTAMPTZ,
+    ADD COLUMN revoked_reason TEXT, -- Internal; not shown on the public verification page
+    ADD COLUMN revoked_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD CONSTRAINT training_certificates_revocation_check
+        CHECK ((revoked_at IS NULL) = (revoked_reason IS NULL));
@@ -0,0 +1,39 @@
+--------------------------------------------------------------------------------
+-- QUIZ AUTHORING
+--------------------------------------------------------------------------------
+
+CREATE TYPE quiz_question_type AS ENUM (
+    'single_choice', 'multiple_choice', 'true_false', 'free_text'
+);
+
+-- A session has at most one quiz; passing it completes the training
+ALTER TABLE training_quizzes
+    ADD CONSTRAINT training_quizzes_training_session_id_key UNIQUE (training_session_id),
+    ADD COLUMN pass_threshold_percent INTEGER NOT NULL DEFAULT 70
+        CHECK (pass_threshold_percent BETWEEN 1 AND 100),
+    ADD COLUMN time_limit_minutes INTEGER CHECK (time_limit_minutes > 0); -- NULL for no limit
+
+-- Existing free text types were written as 'SINGLE_CHOICE', 'MULTIPLE_CHOICE', ...
+ALTER TABLE quiz_questions
+    ALTER COLUMN question_type TYPE quiz_question_type USING lower(question_type)::quiz_question_type,
+    ADD COLUMN position INTEGER;
+
+UPDATE quiz_questions q
+SET position = ordered.position
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY quiz_id ORDER BY created_at, id) AS position
+    FROM quiz_questions
+) ordered
+WHERE ordered.id = q.id;
+
+UPDATE quiz_questions SET points = 1 WHERE points IS NULL;
+
+-- Options: [{"key": "a", "text": "..."}] for choice questions, NULL for free text.
+-- correct_answer_key: the correct option key, comma separated keys for multiple choice,
+-- NULL for free text (graded manually).
+ALTER TABLE quiz_questions
+    ALTER COLUMN position SET NOT NULL,
+    ALTER COLUMN points SET NOT NULL,
+    ADD CONSTRAINT quiz_questions_points_check CHECK (points > 0),
+    -- Deferred so that questions can be reordered in one statement
+    ADD CONSTRAINT quiz_questions_quiz_position_key UNIQUE (quiz_id, position) DEFERRABLE INITIALLY DEFERRED;
@@ -0,0 +1,33 @@
+--------------------------------------------------------------------------------
+-- QUIZ ATTEMPTS
+--------------------------------------------------------------------------------
+
+-- in_progress -> pending_review (free text answers await grading) -> graded
+CREATE TYPE quiz_attempt_status AS ENUM ('in_progress', 'pending_review', 'graded');
+
+ALTER TABLE training_quizzes
+    ADD COLUMN max_attempts INTEGER CHECK (max_attempts > 0); -- NULL for unlimited
+
+-- An enrollment may retry a failed quiz, so attempts are numbered per enrollment
+ALTER TABLE quiz_attempts
+    DROP CONSTRAINT quiz_attempts_enrollment_id_key,
+    ADD COLUMN attempt_number INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN status quiz_attempt_status NOT NULL DEFAULT 'in_progress',
+    ADD COLUMN expires_at TIMESTAMPTZ, -- NULL when the quiz has no time limit
+    ADD COLUMN points_earned INTEGER,
+    ADD COLUMN points_possible INTEGER,
+    ADD CONSTRAINT quiz_attempts_enrollment_attempt_key UNIQUE (enrollment_id, attempt_number);
+
+UPDATE quiz_attempts SET status = 'graded' WHERE completed_at IS NOT NULL;
+
+CREATE UNIQUE INDEX idx_quiz_attempts_open ON quiz_attempts(enrollment_id) WHERE status = 'in_progress';
+
+-- points_awarded stays NULL for free text answers until they are graded
+ALTER TABLE quiz_attempt_answers
+    ADD COLUMN points_awarded INTEGER CHECK (points_awarded >= 0),
+    ADD COLUMN graded_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN graded_at TIMESTAMPTZ,
+    ADD CONSTRAINT quiz_attempt_answers_attempt_question_key UNIQUE (attempt_id, question_id);
+
+CREATE POLICY grade_quiz_attempts_for_quiz_owner_or_admin ON quiz_attempts FOR UPDATE USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND EXISTS (SELECT 1 FROM training_quizzes tq WHERE tq.id = quiz_attempts.quiz_id AND (((get_current_user_roles() && ARRAY['ohs_specialist', 'doctor']::text[]) AND tq.created_by_user_id = current_setting('app.current_user_id', true)::uuid) OR ('tenant_admin' = ANY(get_current_user_roles())))));
+CREATE POLICY grade_quiz_attempt_answers_for_quiz_owner_or_admin ON quiz_attempt_answers FOR UPDATE USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND EXISTS (SELECT 1 FROM quiz_attempts qa JOIN training_quizzes tq ON tq.id = qa.quiz_id WHERE qa.id = quiz_attempt_answers.attempt_id AND (((get_current_user_roles() && ARRAY['ohs_specialist', 'doctor']::text[]) AND tq.created_by_user_id = current_setting('app.current_user_id', true)::uuid) OR ('tenant_admin' = ANY(get_current_user_roles())))));
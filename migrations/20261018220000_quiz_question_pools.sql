@@ -0,0 +1,19 @@
+--------------------------------------------------------------------------------
+-- QUIZ QUESTION POOLS
+--------------------------------------------------------------------------------
+
+-- Lowercase tags group questions of a pool, e.g. 'ppe' or 'fire-safety'
+ALTER TABLE quiz_questions
+    ADD COLUMN tag TEXT CHECK (tag IS NULL OR length(tag) BETWEEN 1 AND 40);
+
+-- Attempts draw questions_per_attempt random questions, or tag_quotas ({"ppe": 2}) per tag;
+-- with neither, every question in authored order
+ALTER TABLE training_quizzes
+    ADD COLUMN questions_per_attempt INTEGER CHECK (questions_per_attempt > 0),
+    ADD COLUMN tag_quotas JSONB,
+    ADD CONSTRAINT training_quizzes_draw_check CHECK (questions_per_attempt IS NULL OR tag_quotas IS NULL);
+
+-- Questions drawn for an attempt in the order shown, with their option order:
+-- [{"question_id": "...", "option_keys": ["c", "a", "b"]}]. Empty for attempts started before pools.
+ALTER TABLE quiz_attempts
+    ADD COLUMN layout JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,14 @@
+--------------------------------------------------------------------------------
+-- TRAINING FEEDBACK
+--------------------------------------------------------------------------------
+
+-- Ratings are 1 (poor) to 5 (excellent); feedback opens once attendance is decided
+ALTER TABLE training_enrollments
+    ADD CONSTRAINT training_enrollments_feedback_rating_check
+        CHECK (feedback_rating IS NULL OR feedback_rating BETWEEN 1 AND 5),
+    ADD COLUMN feedback_submitted_at TIMESTAMPTZ;
+
+UPDATE training_enrollments SET feedback_submitted_at = updated_at WHERE feedback_rating IS NOT NULL;
+
+-- Rating reports scan sessions of a tenant by start time
+CREATE INDEX idx_training_sessions_tenant_start ON training_sessions(tenant_id, start_time);
@@ -0,0 +1,36 @@
+--------------------------------------------------------------------------------
+-- TRAINING MATERIAL UPLOADS
+--------------------------------------------------------------------------------
+
+-- Type and original name of the stored file, for downloads
+ALTER TABLE training_materials
+    ADD COLUMN content_type TEXT,
+    ADD COLUMN file_name TEXT;
+
+CREATE UNIQUE INDEX idx_training_materials_file_s3_key ON training_materials(file_s3_key);
+
+-- Material Uploads: A file the uploader was handed a presigned PUT URL for. The material
+-- is only created once the upload is confirmed and the stored object matches what was declared.
+CREATE TABLE training_material_uploads (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    uploader_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    training_session_id UUID REFERENCES training_sessions(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    description TEXT,
+    material_type training_material_type NOT NULL,
+    object_key TEXT NOT NULL UNIQUE,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    expires_at TIMESTAMPTZ NOT NULL, -- The presigned URL stops working at this time
+    material_id UUID REFERENCES training_materials(id) ON DELETE SET NULL,
+    confirmed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+ALTER TABLE training_material_uploads ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_own_training_material_uploads ON training_material_uploads FOR ALL USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND (uploader_user_id = current_setting('app.current_user_id', true)::uuid OR 'tenant_admin' = ANY(get_current_user_roles()))) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid);
+
+CREATE INDEX idx_training_material_uploads_tenant_id ON training_material_uploads(tenant_id);
+CREATE INDEX idx_training_material_uploads_pending ON training_material_uploads(expires_at) WHERE confirmed_at IS NULL;
@@ -0,0 +1,28 @@
+--------------------------------------------------------------------------------
+-- FILE ACCESS LOGS
+--------------------------------------------------------------------------------
+
+CREATE TYPE file_resource_type AS ENUM (
+    'training_material', 'safety_report_attachment', 'training_certificate'
+);
+
+-- File Access Logs: One entry per download link handed out. Continuation range requests
+-- of a download already logged (a player seeking in a video) are not logged again.
+CREATE TABLE file_access_logs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    resource_type file_resource_type NOT NULL,
+    resource_id UUID NOT NULL,
+    object_key TEXT NOT NULL,
+    byte_range TEXT, -- The Range header of the request, if any
+    user_agent TEXT,
+    accessed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE file_access_logs ENABLE ROW LEVEL SECURITY;
+CREATE POLICY insert_own_file_access_logs ON file_access_logs FOR INSERT WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND user_id = current_setting('app.current_user_id', true)::uuid);
+CREATE POLICY view_file_access_logs_for_admin ON file_access_logs FOR SELECT USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND 'tenant_admin' = ANY(get_current_user_roles()));
+
+CREATE INDEX idx_file_access_logs_tenant_accessed ON file_access_logs(tenant_id, accessed_at);
+CREATE INDEX idx_file_access_logs_resource ON file_access_logs(resource_type, resource_id);
@@ -0,0 +1,9 @@
+--------------------------------------------------------------------------------
+-- UPLOAD QUARANTINE
+--------------------------------------------------------------------------------
+
+-- Uploads the malware scan flagged. The object stays in storage for inspection, but no
+-- material is created for it and the upload cannot be confirmed.
+ALTER TABLE training_material_uploads
+    ADD COLUMN quarantined_at TIMESTAMPTZ,
+    ADD COLUMN quarantine_reason TEXT; -- Signature the scanner matched
@@ -0,0 +1,23 @@
+--------------------------------------------------------------------------------
+-- MATERIAL VIDEO PROCESSING
+--------------------------------------------------------------------------------
+
+CREATE TYPE material_processing_status AS ENUM ('pending', 'processing', 'ready', 'failed');
+
+-- Videos are transcoded into HLS renditions by a background worker; the status is NULL for
+-- materials that need no processing. Renditions are stored below `renditions/{id}/` of the
+-- tenant's prefix: a master playlist, one directory of segments per rendition and a poster.
+ALTER TABLE training_materials
+    ADD COLUMN processing_status material_processing_status,
+    ADD COLUMN processing_attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN processing_error TEXT,
+    ADD COLUMN processing_started_at TIMESTAMPTZ,
+    ADD COLUMN processed_at TIMESTAMPTZ,
+    ADD COLUMN hls_renditions TEXT[], -- Names of the renditions, e.g. {720p,480p,360p}
+    ADD COLUMN duration_seconds DOUBLE PRECISION,
+    ADD COLUMN poster_key TEXT;
+
+UPDATE training_materials SET processing_status = 'pending' WHERE material_type = 'video';
+
+CREATE INDEX idx_training_materials_processing ON training_materials(created_at)
+    WHERE processing_status IN ('pending', 'processing');
@@ -0,0 +1,42 @@
+--------------------------------------------------------------------------------
+-- SELF-PACED MATERIAL PROGRESS
+--------------------------------------------------------------------------------
+
+-- Completion rules of recorded video and document sessions: share of a video that must be
+-- watched, and share of a document's pages that must be viewed
+ALTER TABLE training_sessions
+    ADD COLUMN min_watched_percent INTEGER NOT NULL DEFAULT 90
+        CHECK (min_watched_percent BETWEEN 1 AND 100),
+    ADD COLUMN min_pages_viewed_percent INTEGER NOT NULL DEFAULT 100
+        CHECK (min_pages_viewed_percent BETWEEN 1 AND 100);
+
+-- Material Progress: How far a participant got through one material of a session, from the
+-- progress pings of their player or viewer. Watched ranges are kept merged, so seeking back
+-- and forth never counts a part twice.
+CREATE TABLE training_material_progress (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    training_session_id UUID NOT NULL REFERENCES training_sessions(id) ON DELETE CASCADE,
+    enrollment_id UUID NOT NULL REFERENCES training_enrollments(id) ON DELETE CASCADE,
+    material_id UUID NOT NULL REFERENCES training_materials(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    position_seconds DOUBLE PRECISION, -- Resume position of a video
+    watched_ranges JSONB NOT NULL DEFAULT '[]', -- Merged [start, end] second pairs
+    watched_seconds DOUBLE PRECISION NOT NULL DEFAULT 0,
+    duration_seconds DOUBLE PRECISION, -- Reported by the player while the video is not processed
+    current_page INTEGER, -- Resume page of a document
+    pages_viewed INTEGER[] NOT NULL DEFAULT '{}',
+    page_count INTEGER,
+    completed_at TIMESTAMPTZ,
+    last_ping_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (enrollment_id, material_id)
+);
+
+ALTER TABLE training_material_progress ENABLE ROW LEVEL SECURITY;
+CREATE POLICY manage_own_material_progress ON training_material_progress FOR ALL USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND user_id = current_setting('app.current_user_id', true)::uuid) WITH CHECK (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND user_id = current_setting('app.current_user_id', true)::uuid);
+CREATE POLICY view_material_progress_for_host_or_admin ON training_material_progress FOR SELECT USING (tenant_id = current_setting('app.current_tenant_id', true)::uuid AND (('tenant_admin' = ANY(get_current_user_roles())) OR EXISTS (SELECT 1 FROM training_sessions ts WHERE ts.id = training_material_progress.training_session_id AND ts.host_user_id = current_setting('app.current_user_id', true)::uuid)));
+
+CREATE INDEX idx_training_material_progress_session ON training_material_progress(training_session_id);
+CREATE INDEX idx_training_material_progress_material ON training_material_progress(material_id);
@@ -0,0 +1,22 @@
+--------------------------------------------------------------------------------
+-- CALL LOG TRACKING
+--------------------------------------------------------------------------------
+
+-- A dropped call may be dialed again within the same appointment, so an appointment can
+-- have several call logs
+ALTER TABLE call_logs DROP CONSTRAINT IF EXISTS call_logs_appointment_id_key;
+
+-- Calls are peer to peer over TURN; the column keeps how the call went (connections, time
+-- limit, who ended it and why)
+ALTER TABLE call_logs RENAME COLUMN mediasoup_session_info TO metadata;
+ALTER TABLE call_logs ALTER COLUMN metadata SET DEFAULT '{}';
+
+-- The professional of the appointment, kept when the appointment itself is deleted so that
+-- call minutes can still be reported per professional
+ALTER TABLE call_logs
+    ADD COLUMN professional_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD CONSTRAINT call_logs_end_after_start CHECK (end_time IS NULL OR end_time >= start_time),
+    ADD CONSTRAINT call_logs_duration_not_negative CHECK (duration_seconds IS NULL OR duration_seconds >= 0);
+
+CREATE INDEX idx_call_logs_tenant_start_time ON call_logs(tenant_id, start_time);
+CREATE INDEX idx_call_logs_professional_user_id ON call_logs(professional_user_id);
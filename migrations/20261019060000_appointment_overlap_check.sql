@@ -0,0 +1,23 @@
+--------------------------------------------------------------------------------
+-- APPOINTMENT OVERLAP CHECK
+--------------------------------------------------------------------------------
+
+-- Whether either participant already holds an active appointment overlapping the range.
+-- Runs as the owner so the check sees every appointment of both participants, not only the
+-- ones the booking user's row level security lets them read.
+CREATE OR REPLACE FUNCTION appointment_slot_taken(
+    p_professional_user_id UUID,
+    p_employee_user_id UUID,
+    p_start_time TIMESTAMPTZ,
+    p_end_time TIMESTAMPTZ,
+    p_exclude_id UUID
+)
+RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM appointments
+        WHERE (professional_user_id = p_professional_user_id OR employee_user_id = p_employee_user_id)
+          AND status IN ('pending', 'confirmed')
+          AND start_time < p_end_time AND end_time > p_start_time
+          AND (p_exclude_id IS NULL OR id <> p_exclude_id)
+    );
+$$ LANGUAGE sql STABLE SECURITY DEFINER SET search_path = public;
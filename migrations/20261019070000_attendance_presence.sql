@@ -0,0 +1,8 @@
+--------------------------------------------------------------------------------
+-- ATTENDANCE PRESENCE
+--------------------------------------------------------------------------------
+
+-- Last time a connection of the participant was seen in the live session's room. A join
+-- whose leave was never recorded, as when the server went away, counts until then.
+ALTER TABLE training_enrollments
+    ADD COLUMN presence_seen_at TIMESTAMPTZ;
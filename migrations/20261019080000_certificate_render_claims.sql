@@ -0,0 +1,8 @@
+--------------------------------------------------------------------------------
+-- CERTIFICATE RENDER CLAIMS
+--------------------------------------------------------------------------------
+
+-- When a worker took the certificate to render it. Rendering happens outside any transaction,
+-- so the claim keeps other workers off it; claims of a worker that died are taken over.
+ALTER TABLE training_certificates
+    ADD COLUMN render_claimed_at TIMESTAMPTZ;
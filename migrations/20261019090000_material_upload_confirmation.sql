@@ -0,0 +1,8 @@
+--------------------------------------------------------------------------------
+-- MATERIAL UPLOAD CONFIRMATION
+--------------------------------------------------------------------------------
+
+-- When the checks of a confirmation started. They run outside any transaction, so a second
+-- confirmation is turned away meanwhile; a claim left by a request that died expires.
+ALTER TABLE training_material_uploads
+    ADD COLUMN confirm_started_at TIMESTAMPTZ;
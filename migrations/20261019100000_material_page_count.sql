@@ -0,0 +1,8 @@
+--------------------------------------------------------------------------------
+-- MATERIAL PAGE COUNT
+--------------------------------------------------------------------------------
+
+-- Pages of a document or slides of a deck, counted from the file when its upload is confirmed.
+-- Viewers no longer report it, so a participant cannot shrink the document to complete it.
+ALTER TABLE training_materials
+    ADD COLUMN page_count INTEGER CHECK (page_count > 0);
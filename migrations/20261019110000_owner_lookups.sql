@@ -0,0 +1,40 @@
+--------------------------------------------------------------------------------
+-- LOOKUPS WITHOUT A CALLER CONTEXT
+--------------------------------------------------------------------------------
+
+-- Resolves a calendar feed token hash to its owner and records the access. Feed requests come
+-- from calendar clients without a session, so this runs as the owner of the table; the API then
+-- reads the feed in a row level security context of the returned user.
+CREATE OR REPLACE FUNCTION calendar_feed_owner(p_token_hash TEXT)
+RETURNS TABLE (user_id UUID, tenant_id UUID, company_id UUID, roles user_role[]) AS $$
+    WITH touched AS (
+        UPDATE calendar_feed_tokens t
+        SET last_accessed_at = NOW()
+        WHERE t.token_hash = p_token_hash
+        RETURNING t.user_id, t.tenant_id
+    )
+    SELECT touched.user_id, touched.tenant_id, u.company_id,
+           ARRAY(
+               SELECT r.role FROM user_tenant_context_roles r
+               WHERE r.user_id = touched.user_id AND r.tenant_id = touched.tenant_id
+           )
+    FROM touched
+    JOIN users u ON u.id = touched.user_id
+    WHERE u.status = 'active';
+$$ LANGUAGE sql VOLATILE SECURITY DEFINER SET search_path = public;
+
+-- The effective zone of a user: their own, falling back to their company's. Reminders are
+-- written in each recipient's zone, which the user scheduling them usually cannot read.
+CREATE OR REPLACE FUNCTION user_time_zone(p_user_id UUID)
+RETURNS TEXT AS $$
+    SELECT COALESCE(u.time_zone, c.time_zone)
+    FROM users u
+    LEFT JOIN companies c ON c.id = u.company_id
+    WHERE u.id = p_user_id;
+$$ LANGUAGE sql STABLE SECURITY DEFINER SET search_path = public;
+
+-- Only the role running the migrations, which the API connects as, may call them.
+REVOKE EXECUTE ON FUNCTION calendar_feed_owner(TEXT) FROM PUBLIC;
+GRANT EXECUTE ON FUNCTION calendar_feed_owner(TEXT) TO CURRENT_USER;
+REVOKE EXECUTE ON FUNCTION user_time_zone(UUID) FROM PUBLIC;
+GRANT EXECUTE ON FUNCTION user_time_zone(UUID) TO CURRENT_USER;
@@ -0,0 +1,9 @@
+--------------------------------------------------------------------------------
+-- CALL LOG HEARTBEATS
+--------------------------------------------------------------------------------
+
+-- Calls live in the memory of the API. Answered calls note on every heartbeat that they still
+-- run, so a log the API lost in a restart or crash is closed at the last time it was seen.
+ALTER TABLE call_logs ADD COLUMN last_seen_at TIMESTAMPTZ;
+
+CREATE INDEX idx_call_logs_open ON call_logs(start_time) WHERE end_time IS NULL;
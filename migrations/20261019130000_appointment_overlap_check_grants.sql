@@ -0,0 +1,9 @@
+--------------------------------------------------------------------------------
+-- APPOINTMENT OVERLAP CHECK GRANTS
+--------------------------------------------------------------------------------
+
+-- The check sees every appointment of the participants, so anyone able to call it could probe
+-- when any user is booked. Only the role running the migrations, which the API connects as,
+-- may call it; the API only checks the participants of the booking at hand.
+REVOKE EXECUTE ON FUNCTION appointment_slot_taken(UUID, UUID, TIMESTAMPTZ, TIMESTAMPTZ, UUID) FROM PUBLIC;
+GRANT EXECUTE ON FUNCTION appointment_slot_taken(UUID, UUID, TIMESTAMPTZ, TIMESTAMPTZ, UUID) TO CURRENT_USER;
@@ -1,18 +1,33 @@
-use std::sync::{Arc, Mutex};
 use sqlx::PgPool;
-use tokio::sync::broadcast;
 use crate::config;
+use crate::core::scanner::Scanner;
+use crate::core::storage::Storage;
+use crate::modules::notification::channels::NotificationDispatcher;
+use crate::modules::realtime::calls::Calls;
+use crate::modules::realtime::gateway::Gateway;
 
 #[derive(Clone)]
 #[allow(unused)]
 pub struct AppState {
     pub db: PgPool,
     pub env: config::Config,
-    pub ws_tx: Arc<Mutex<broadcast::Sender<String>>>,
+    pub realtime: Gateway,
+    pub calls: Calls,
+    pub notifier: NotificationDispatcher,
+    pub storage: Storage,
+    pub scanner: Scanner,
 }
 
 impl AppState {
-    pub fn new(db: PgPool, env: config::Config, ws_tx: Arc<Mutex<broadcast::Sender<String>>>) -> Self {
-        Self { db, env, ws_tx }
+    pub fn new(
+        db: PgPool,
+        env: config::Config,
+        realtime: Gateway,
+        calls: Calls,
+        notifier: NotificationDispatcher,
+        storage: Storage,
+        scanner: Scanner,
+    ) -> Self {
+        Self { db, env, realtime, calls, notifier, storage, scanner }
     }
 }
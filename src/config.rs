@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use secrecy::SecretString;
 use serde::Deserialize;
 use std::env;
 use std::net::{IpAddr, SocketAddr};
@@ -12,6 +13,11 @@ pub struct Config {
     pub redis: RedisConfig,
     pub s3: Option<S3Config>,
     pub turn: Option<TurnConfig>,
+    pub storage: StorageConfig,
+    pub scanner: ScannerConfig,
+    pub transcoding: TranscodingConfig,
+    pub auth: AuthConfig,
+    pub notifications: NotificationConfig,
     pub app: AppConfig,
 }
 
@@ -52,8 +58,54 @@ pub struct S3Config {
 pub struct TurnConfig {
     pub url_udp: Option<String>,
     pub url_tcp: Option<String>,
+    pub shared_secret: SecretString,  // coturn's `static-auth-secret`
+    pub credential_ttl_seconds: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(unused)]
+pub struct StorageConfig {
+    pub local_dir: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(unused)]
+pub struct ScannerConfig {
+    pub clamav_address: Option<String>,  // `host:port` or the path of clamd's Unix socket
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(unused)]
+pub struct TranscodingConfig {
+    pub ffmpeg_path: String,
+    pub ffprobe_path: String,
+    pub work_dir: String,  // Scratch space for sources and renditions while a video is transcoded
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(unused)]
+pub struct AuthConfig {
+    pub jwt_secret: SecretString,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(unused)]
+pub struct NotificationConfig {
+    pub reminder_offsets_minutes: Vec<i64>,
+    pub reminder_poll_interval_secs: u64,
+    pub expo_push_url: Option<String>,
+    pub expo_access_token: Option<SecretString>,
+    pub smtp: Option<SmtpConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(unused)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
     pub username: String,
-    pub password: String,
+    pub password: SecretString,
+    pub from: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -63,6 +115,7 @@ pub struct AppConfig {
     pub environment: Environment,
     pub static_dir: String,
     pub templates_dir: String,
+    pub public_url: String,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
@@ -128,16 +181,72 @@ impl Config {
         };
 
         // TURN server configuration (optional)
-        let turn_config = if let (Ok(username), Ok(password)) = 
-            (env::var("TURN_USERNAME"), env::var("TURN_PASSWORD")) {
+        let turn_config = if let Ok(shared_secret) = env::var("TURN_SHARED_SECRET") {
             let url_udp = env::var("TURN_URL_UDP").ok();
             let url_tcp = env::var("TURN_URL_TCP").ok();
+            let credential_ttl_seconds = env::var("TURN_CREDENTIAL_TTL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse::<u32>()
+                .context("Failed to parse TURN_CREDENTIAL_TTL_SECONDS")?;
 
             Some(TurnConfig {
                 url_udp,
                 url_tcp,
+                shared_secret: SecretString::from(shared_secret),
+                credential_ttl_seconds,
+            })
+        } else {
+            None
+        };
+
+        // Object storage configuration
+        let storage_local_dir = env::var("STORAGE_LOCAL_DIR").unwrap_or_else(|_| "storage".to_string());
+
+        // Malware scanning of uploads (optional)
+        let clamav_address = env::var("CLAMAV_ADDRESS").ok().filter(|address| !address.is_empty());
+
+        // Video transcoding
+        let ffmpeg_path = env::var("FFMPEG_PATH").unwrap_or_else(|_| "ffmpeg".to_string());
+        let ffprobe_path = env::var("FFPROBE_PATH").unwrap_or_else(|_| "ffprobe".to_string());
+        let transcode_work_dir = env::var("TRANSCODE_WORK_DIR")
+            .unwrap_or_else(|_| env::temp_dir().join("ohs-transcode").to_string_lossy().into_owned());
+
+        // Auth configuration
+        let jwt_secret = env::var("JWT_SECRET").context("JWT_SECRET must be set")?;
+
+        // Notification configuration
+        let reminder_offsets_minutes = env::var("REMINDER_OFFSETS_MINUTES")
+            .unwrap_or_else(|_| "1440,15".to_string())
+            .split(',')
+            .map(|offset| offset.trim().parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to parse REMINDER_OFFSETS_MINUTES")?;
+        let reminder_poll_interval_secs = env::var("REMINDER_POLL_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<u64>()
+            .context("Failed to parse REMINDER_POLL_INTERVAL_SECONDS")?;
+        let expo_push_url = env::var("EXPO_PUSH_URL").ok();
+        let expo_access_token = env::var("EXPO_ACCESS_TOKEN").ok().map(SecretString::from);
+
+        // SMTP configuration (optional)
+        let smtp_config = if let Ok(host) = env::var("SMTP_HOST") {
+            let port = env::var("SMTP_PORT")
+                .unwrap_or_else(|_| "587".to_string())
+                .parse::<u16>()
+                .context("Failed to parse SMTP_PORT")?;
+            let username = env::var("SMTP_USERNAME")
+                .context("SMTP_USERNAME must be set when SMTP_HOST is provided")?;
+            let password = env::var("SMTP_PASSWORD")
+                .context("SMTP_PASSWORD must be set when SMTP_HOST is provided")?;
+            let from = env::var("SMTP_FROM")
+                .context("SMTP_FROM must be set when SMTP_HOST is provided")?;
+
+            Some(SmtpConfig {
+                host,
+                port,
                 username,
-                password,
+                password: SecretString::from(password),
+                from,
             })
         } else {
             None
@@ -155,6 +264,10 @@ impl Config {
         let app_name = env::var("APP_NAME").unwrap_or_else(|_| "OHS Backend".to_string());
         let static_dir = env::var("STATIC_DIR").unwrap_or_else(|_| "static".to_string());
         let templates_dir = env::var("TEMPLATES_DIR").unwrap_or_else(|_| "templates".to_string());
+        let public_url = env::var("APP_PUBLIC_URL")
+            .unwrap_or_else(|_| "http://localhost:8000".to_string())
+            .trim_end_matches('/')
+            .to_string();
 
         Ok(Config {
             server: ServerConfig {
@@ -172,11 +285,33 @@ impl Config {
             },
             s3: s3_config,
             turn: turn_config,
+            storage: StorageConfig {
+                local_dir: storage_local_dir,
+            },
+            scanner: ScannerConfig {
+                clamav_address,
+            },
+            transcoding: TranscodingConfig {
+                ffmpeg_path,
+                ffprobe_path,
+                work_dir: transcode_work_dir,
+            },
+            auth: AuthConfig {
+                jwt_secret: SecretString::from(jwt_secret),
+            },
+            notifications: NotificationConfig {
+                reminder_offsets_minutes,
+                reminder_poll_interval_secs,
+                expo_push_url,
+                expo_access_token,
+                smtp: smtp_config,
+            },
             app: AppConfig {
                 name: app_name,
                 environment,
                 static_dir,
                 templates_dir,
+                public_url,
             },
         })
     }
@@ -0,0 +1,3 @@
+pub mod scanner;
+pub mod storage;
+pub mod utils;
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpStream, UnixStream};
+
+use super::{MalwareScanner, ObjectChunks, ScanError, ScanVerdict};
+
+/// Time clamd may take for the whole exchange; scanning a large video takes a while.
+const SCAN_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone)]
+enum Address {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+/// Streams files to clamd with its `INSTREAM` command.
+///
+/// clamd refuses streams above its `StreamMaxLength` (25 MiB by default); raise it to the
+/// largest material size, or such uploads fail to scan and cannot be confirmed.
+pub struct ClamAvScanner {
+    address: Address,
+}
+
+impl ClamAvScanner {
+    /// `address` is `host:port`, or the path of clamd's Unix socket, optionally prefixed with `unix:`.
+    pub fn new(address: &str) -> Self {
+        let address = match address.strip_prefix("unix:") {
+            Some(path) => Address::Unix(PathBuf::from(path)),
+            None if address.starts_with('/') => Address::Unix(PathBuf::from(address)),
+            None => Address::Tcp(address.to_string()),
+        };
+        Self { address }
+    }
+}
+
+#[async_trait]
+impl MalwareScanner for ClamAvScanner {
+    fn name(&self) -> &'static str {
+        "clamav"
+    }
+
+    async fn scan(&self, object: &mut ObjectChunks<'_>) -> Result<ScanVerdict, ScanError> {
+        let exchange = async {
+            match &self.address {
+                Address::Tcp(address) => instream(TcpStream::connect(address).await?, object).await,
+                Address::Unix(path) => instream(UnixStream::connect(path).await?, object).await,
+            }
+        };
+        let reply = tokio::time::timeout(SCAN_TIMEOUT, exchange)
+            .await
+            .map_err(|_| ScanError::Scanner("clamd did not answer in time".to_string()))??;
+
+        parse_reply(&reply)
+    }
+}
+
+/// Sends the object as length prefixed chunks, ended by an empty one, and reads the reply.
+async fn instream<S>(mut stream: S, object: &mut ObjectChunks<'_>) -> Result<String, ScanError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.write_all(b"zINSTREAM\0").await?;
+    while let Some(chunk) = object.next().await? {
+        stream.write_all(&(chunk.len() as u32).to_be_bytes()).await?;
+        stream.write_all(&chunk).await?;
+    }
+    stream.write_all(&0u32.to_be_bytes()).await?;
+    stream.flush().await?;
+
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).await?;
+    Ok(String::from_utf8_lossy(&reply).into_owned())
+}
+
+/// Replies look like `stream: OK`, `stream: Eicar-Test-Signature FOUND` or `<reason> ERROR`.
+fn parse_reply(reply: &str) -> Result<ScanVerdict, ScanError> {
+    let reply = reply.trim_end_matches(['\0', '\n']).trim();
+    let result = reply.strip_prefix("stream:").map(str::trim).unwrap_or(reply);
+
+    if result == "OK" {
+        Ok(ScanVerdict::Clean)
+    } else if let Some(signature) = result.strip_suffix(" FOUND") {
+        Ok(ScanVerdict::Infected(signature.trim().to_string()))
+    } else {
+        Err(ScanError::Scanner(format!("clamd: {}", reply)))
+    }
+}
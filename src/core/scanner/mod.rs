@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::config::Config;
+use crate::core::storage::{Storage, StorageError};
+
+mod clamav;
+
+pub use clamav::ClamAvScanner;
+
+/// Bytes read from storage and handed to the scanner at a time.
+const CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum ScanError {
+    #[error("Scanner I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Scanner error: {0}")]
+    Scanner(String),
+
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// Malware was found; holds the name of the matched signature.
+    Infected(String),
+}
+
+/// Reads a stored object in chunks, so large files are scanned without holding them in memory.
+pub struct ObjectChunks<'a> {
+    storage: &'a Storage,
+    key: &'a str,
+    size: u64,
+    offset: u64,
+}
+
+impl ObjectChunks<'_> {
+    pub async fn next(&mut self) -> Result<Option<Vec<u8>>, StorageError> {
+        if self.offset >= self.size {
+            return Ok(None);
+        }
+        let end = (self.offset + CHUNK_BYTES).min(self.size) - 1;
+        let chunk = self.storage.get_range(self.key, self.offset..=end).await?;
+        self.offset = end + 1;
+        Ok(Some(chunk))
+    }
+}
+
+/// Checks uploaded files for malware before they become visible to anyone but the uploader.
+#[async_trait]
+pub trait MalwareScanner: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn scan(&self, object: &mut ObjectChunks<'_>) -> Result<ScanVerdict, ScanError>;
+}
+
+/// Accepts every file, for deployments without a scanner.
+pub struct NoopScanner;
+
+#[async_trait]
+impl MalwareScanner for NoopScanner {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    async fn scan(&self, _object: &mut ObjectChunks<'_>) -> Result<ScanVerdict, ScanError> {
+        Ok(ScanVerdict::Clean)
+    }
+}
+
+/// Shared handle to the configured malware scanner.
+#[derive(Clone)]
+pub struct Scanner {
+    backend: Arc<dyn MalwareScanner>,
+}
+
+#[allow(unused)]
+impl Scanner {
+    pub fn new(backend: impl MalwareScanner + 'static) -> Self {
+        Self { backend: Arc::new(backend) }
+    }
+
+    /// ClamAV when `CLAMAV_ADDRESS` is configured, otherwise no scanning.
+    pub fn from_config(config: &Config) -> Self {
+        match &config.scanner.clamav_address {
+            Some(address) => Self::new(ClamAvScanner::new(address)),
+            None => Self::new(NoopScanner),
+        }
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Scans a stored object of `size` bytes.
+    pub async fn scan_object(&self, storage: &Storage, key: &str, size: u64) -> Result<ScanVerdict, ScanError> {
+        let mut object = ObjectChunks {
+            storage,
+            key,
+            size,
+            offset: 0,
+        };
+        self.backend.scan(&mut object).await
+    }
+}
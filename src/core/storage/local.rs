@@ -0,0 +1,176 @@
+use std::io::{ErrorKind, SeekFrom};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use time::OffsetDateTime;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use super::sigv4::uri_encode;
+use super::{key_path, ObjectInfo, ObjectStorage, PresignConditions, PresignMethod, PresignedRequest, StorageError};
+
+/// Stores objects as files below a root directory, for development and single node setups.
+///
+/// Presigned URLs point at the API's own `/storage/{key}` route and carry an HMAC of the
+/// method, key, expiry and required headers, so clients can up- and download without S3.
+pub struct LocalStorage {
+    root: PathBuf,
+    base_url: String,
+    secret: Vec<u8>,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>, base_url: impl Into<String>, secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            secret: secret.into(),
+        }
+    }
+
+    fn mac(
+        &self,
+        method: PresignMethod,
+        key: &str,
+        expires: i64,
+        headers: &[(String, String)],
+        response_params: &[(String, String)],
+    ) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(format!("storage\n{}\n{}\n{}\n", method.as_str(), key, expires).as_bytes());
+        for (name, value) in headers {
+            mac.update(format!("{}:{}\n", name, value.trim()).as_bytes());
+        }
+        for (name, value) in response_params {
+            mac.update(format!("{}={}\n", name, value).as_bytes());
+        }
+        mac
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, _content_type: &str, bytes: Vec<u8>) -> Result<(), StorageError> {
+        let path = key_path(&self.root, key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Write next to the target and rename, so readers never see a partial file
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, bytes).await?;
+        tokio::fs::rename(&partial, &path).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        match tokio::fs::read(key_path(&self.root, key)).await {
+            Ok(bytes) => Ok(bytes),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(StorageError::NotFound(key.to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_range(&self, key: &str, range: RangeInclusive<u64>) -> Result<Vec<u8>, StorageError> {
+        let mut file = match tokio::fs::File::open(key_path(&self.root, key)).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(StorageError::NotFound(key.to_string())),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut bytes = vec![0; (range.end() - range.start() + 1) as usize];
+        file.seek(SeekFrom::Start(*range.start())).await?;
+        file.read_exact(&mut bytes).await?;
+        Ok(bytes)
+    }
+
+    async fn head(&self, key: &str) -> Result<ObjectInfo, StorageError> {
+        match tokio::fs::metadata(key_path(&self.root, key)).await {
+            Ok(metadata) => Ok(ObjectInfo {
+                size_bytes: metadata.len(),
+                content_type: None,
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(StorageError::NotFound(key.to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn copy(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        let path = key_path(&self.root, to);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let partial = path.with_extension("partial");
+        match tokio::fs::copy(key_path(&self.root, from), &partial).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(StorageError::NotFound(from.to_string())),
+            Err(e) => return Err(e.into()),
+        }
+        tokio::fs::rename(&partial, &path).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(key_path(&self.root, key)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn presign(
+        &self,
+        method: PresignMethod,
+        key: &str,
+        expires_in: Duration,
+        conditions: &PresignConditions,
+    ) -> Result<String, StorageError> {
+        let expires = (OffsetDateTime::now_utc() + expires_in).unix_timestamp();
+        let headers = conditions.headers();
+        let response_params = conditions.response_params();
+        let signature = hex::encode(
+            self.mac(method, key, expires, &headers, &response_params)
+                .finalize()
+                .into_bytes(),
+        );
+
+        let mut url = format!(
+            "{}/api/storage/{}?method={}&expires={}&signature={}",
+            self.base_url,
+            uri_encode(key, true),
+            method.as_str(),
+            expires,
+            signature
+        );
+        if !headers.is_empty() {
+            let names: Vec<&str> = headers.iter().map(|(name, _)| name.as_str()).collect();
+            url.push_str(&format!("&headers={}", uri_encode(&names.join(";"), false)));
+        }
+        for (name, value) in &response_params {
+            url.push_str(&format!("&{}={}", name, uri_encode(value, false)));
+        }
+        Ok(url)
+    }
+
+    fn verify_presigned(&self, request: &PresignedRequest<'_>) -> Result<(), StorageError> {
+        let signature = hex::decode(request.signature).map_err(|_| StorageError::InvalidSignature)?;
+        self.mac(request.method, request.key, request.expires, &request.headers, &request.response_params)
+            .verify_slice(&signature)
+            .map_err(|_| StorageError::InvalidSignature)?;
+
+        if OffsetDateTime::now_utc().unix_timestamp() > request.expires {
+            return Err(StorageError::InvalidSignature);
+        }
+        Ok(())
+    }
+}
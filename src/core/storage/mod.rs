@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use thiserror::Error;
+use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::Config;
+
+mod local;
+mod s3;
+mod sigv4;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// Bytes fetched per request when copying an object to a file.
+const DOWNLOAD_CHUNK_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Object not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid object key: {0}")]
+    InvalidKey(String),
+
+    #[error("Presigned URLs must expire within 1 second and {} seconds", sigv4::MAX_PRESIGN_SECONDS)]
+    InvalidExpiry,
+
+    #[error("Invalid or expired presigned URL")]
+    InvalidSignature,
+
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Storage request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+}
+
+/// What a presigned URL lets its holder do with the object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PresignMethod {
+    Get,
+    Put,
+}
+
+impl PresignMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PresignMethod::Get => "GET",
+            PresignMethod::Put => "PUT",
+        }
+    }
+}
+
+/// Headers a presigned request must carry with exactly these values, and for downloads
+/// the headers the object is served with.
+#[derive(Debug, Clone, Default)]
+pub struct PresignConditions {
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    pub response_content_type: Option<String>,
+    pub response_content_disposition: Option<String>,
+}
+
+impl PresignConditions {
+    /// Lowercase header names and values, sorted by name.
+    pub fn headers(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if let Some(content_length) = self.content_length {
+            headers.push(("content-length".to_string(), content_length.to_string()));
+        }
+        if let Some(content_type) = &self.content_type {
+            headers.push(("content-type".to_string(), content_type.clone()));
+        }
+        headers
+    }
+
+    /// Response header overrides, as the S3 query parameters that carry them.
+    pub fn response_params(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        if let Some(disposition) = &self.response_content_disposition {
+            params.push(("response-content-disposition".to_string(), disposition.clone()));
+        }
+        if let Some(content_type) = &self.response_content_type {
+            params.push(("response-content-type".to_string(), content_type.clone()));
+        }
+        params
+    }
+}
+
+/// A URL that grants one kind of access to one object until it expires.
+#[derive(Debug, Clone, Serialize)]
+pub struct PresignedUrl {
+    pub method: PresignMethod,
+    pub url: String,
+    /// Headers the request must send, as signed into the URL.
+    pub headers: BTreeMap<String, String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+/// A request made with a presigned URL of the local backend.
+#[derive(Debug, Clone)]
+pub struct PresignedRequest<'a> {
+    pub method: PresignMethod,
+    pub key: &'a str,
+    pub expires: i64,
+    /// The signed headers, named in the URL, with the values the request sent.
+    pub headers: Vec<(String, String)>,
+    /// Response header overrides from the URL.
+    pub response_params: Vec<(String, String)>,
+    pub signature: &'a str,
+}
+
+/// Size and type of a stored object.
+#[derive(Debug, Clone)]
+pub struct ObjectInfo {
+    pub size_bytes: u64,
+    /// None where the backend does not keep the type.
+    pub content_type: Option<String>,
+}
+
+/// A blob store addressed by slash separated keys.
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), StorageError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    /// Reads the bytes in `range`, which must lie within the object.
+    async fn get_range(&self, key: &str, range: RangeInclusive<u64>) -> Result<Vec<u8>, StorageError>;
+
+    async fn head(&self, key: &str) -> Result<ObjectInfo, StorageError>;
+
+    /// Copies an object within the store, replacing whatever `to` held.
+    async fn copy(&self, from: &str, to: &str) -> Result<(), StorageError>;
+
+    /// Removes an object; removing a missing object is not an error.
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    fn presign(
+        &self,
+        method: PresignMethod,
+        key: &str,
+        expires_in: Duration,
+        conditions: &PresignConditions,
+    ) -> Result<String, StorageError>;
+
+    /// Checks a presigned URL served by this API rather than by the backend itself.
+    fn verify_presigned(&self, _request: &PresignedRequest<'_>) -> Result<(), StorageError> {
+        Err(StorageError::InvalidSignature)
+    }
+}
+
+/// Shared handle to the configured object storage backend.
+#[derive(Clone)]
+pub struct Storage {
+    backend: Arc<dyn ObjectStorage>,
+}
+
+#[allow(unused)]
+impl Storage {
+    pub fn new(backend: impl ObjectStorage + 'static) -> Self {
+        Self { backend: Arc::new(backend) }
+    }
+
+    /// S3 when `S3_ENDPOINT` is configured, otherwise files below `STORAGE_LOCAL_DIR`.
+    pub fn from_config(config: &Config) -> Result<Self, StorageError> {
+        match &config.s3 {
+            Some(s3) => Ok(Self::new(S3Storage::new(s3)?)),
+            None => Ok(Self::new(LocalStorage::new(
+                &config.storage.local_dir,
+                &config.app.public_url,
+                config.auth.jwt_secret.expose_secret().as_bytes(),
+            ))),
+        }
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    pub async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), StorageError> {
+        validate_key(key)?;
+        self.backend.put(key, content_type, bytes).await
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        validate_key(key)?;
+        self.backend.get(key).await
+    }
+
+    pub async fn get_range(&self, key: &str, range: RangeInclusive<u64>) -> Result<Vec<u8>, StorageError> {
+        validate_key(key)?;
+        self.backend.get_range(key, range).await
+    }
+
+    pub async fn head(&self, key: &str) -> Result<ObjectInfo, StorageError> {
+        validate_key(key)?;
+        self.backend.head(key).await
+    }
+
+    /// Copies an object to a local file in chunks, for tools that need a path; returns its size.
+    pub async fn download_to_file(&self, key: &str, path: &Path) -> Result<u64, StorageError> {
+        let size = self.head(key).await?.size_bytes;
+        let mut file = tokio::fs::File::create(path).await?;
+        let mut offset = 0;
+        while offset < size {
+            let end = (offset + DOWNLOAD_CHUNK_BYTES).min(size) - 1;
+            file.write_all(&self.backend.get_range(key, offset..=end).await?).await?;
+            offset = end + 1;
+        }
+        file.flush().await?;
+        Ok(size)
+    }
+
+    pub async fn copy(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        validate_key(from)?;
+        validate_key(to)?;
+        self.backend.copy(from, to).await
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        validate_key(key)?;
+        self.backend.delete(key).await
+    }
+
+    /// A URL clients use to up- or download the object directly, without passing through the API.
+    pub fn presign(
+        &self,
+        method: PresignMethod,
+        key: &str,
+        expires_in: Duration,
+        conditions: &PresignConditions,
+    ) -> Result<PresignedUrl, StorageError> {
+        validate_key(key)?;
+        if expires_in.as_secs() == 0 || expires_in.as_secs() > sigv4::MAX_PRESIGN_SECONDS {
+            return Err(StorageError::InvalidExpiry);
+        }
+
+        let url = self.backend.presign(method, key, expires_in, conditions)?;
+        Ok(PresignedUrl {
+            method,
+            url,
+            headers: conditions.headers().into_iter().collect(),
+            expires_at: OffsetDateTime::now_utc() + expires_in,
+        })
+    }
+
+    pub fn verify_presigned(&self, request: &PresignedRequest<'_>) -> Result<(), StorageError> {
+        validate_key(request.key)?;
+        self.backend.verify_presigned(request)
+    }
+}
+
+/// Key of an object owned by a tenant; every tenant's objects live below their own prefix.
+pub fn tenant_key(tenant_id: Uuid, path: &str) -> String {
+    format!("{}{}", tenant_prefix(tenant_id), path.trim_start_matches('/'))
+}
+
+/// `Content-Disposition` value for a downloaded file, with an ASCII fallback name for old clients.
+pub fn content_disposition(attachment: bool, file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| if c == ' ' || (c.is_ascii_graphic() && !matches!(c, '"' | '\\')) { c } else { '_' })
+        .collect();
+    format!(
+        "{}; filename=\"{}\"; filename*=UTF-8''{}",
+        if attachment { "attachment" } else { "inline" },
+        fallback,
+        sigv4::uri_encode(file_name, false)
+    )
+}
+
+/// Whether a key lies below the tenant's prefix.
+pub fn is_tenant_key(tenant_id: Uuid, key: &str) -> bool {
+    key.starts_with(&tenant_prefix(tenant_id))
+}
+
+fn tenant_prefix(tenant_id: Uuid) -> String {
+    format!("tenants/{}/", tenant_id)
+}
+
+/// Keys are relative paths of plain segments; anything that could escape the
+/// storage root is rejected before it reaches a backend.
+fn validate_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && !key.contains('\\')
+        && Path::new(key)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+fn key_path(root: &Path, key: &str) -> PathBuf {
+    key.split('/').fold(root.to_path_buf(), |path, segment| path.join(segment))
+}
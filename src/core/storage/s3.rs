@@ -0,0 +1,218 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::{Client, Method, StatusCode, Url};
+use time::OffsetDateTime;
+
+use crate::config::S3Config;
+
+use super::sigv4::{self, Credentials};
+use super::{ObjectInfo, ObjectStorage, PresignConditions, PresignMethod, StorageError};
+
+/// Region signed for when `S3_REGION` is not set.
+const DEFAULT_REGION: &str = "us-east-1";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Stores objects in an S3 compatible bucket (AWS, Garage, MinIO), addressed path-style
+/// as `{endpoint}/{bucket}/{key}` so any endpoint works without bucket DNS names.
+pub struct S3Storage {
+    client: Client,
+    endpoint: Url,
+    bucket: String,
+    credentials: Credentials,
+}
+
+impl S3Storage {
+    pub fn new(config: &S3Config) -> Result<Self, StorageError> {
+        let endpoint = Url::parse(&config.endpoint)
+            .map_err(|e| StorageError::Backend(format!("Invalid S3 endpoint: {}", e)))?;
+        if endpoint.host_str().is_none() {
+            return Err(StorageError::Backend("The S3 endpoint has no host".to_string()));
+        }
+        let client = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            bucket: config.bucket_name.clone(),
+            credentials: Credentials {
+                access_key_id: config.access_key_id.clone(),
+                secret_access_key: config.secret_access_key.clone(),
+                region: config.region.clone().unwrap_or_else(|| DEFAULT_REGION.to_string()),
+            },
+        })
+    }
+
+    /// `host[:port]` as sent in the `Host` header.
+    fn host(&self) -> String {
+        let host = self.endpoint.host_str().unwrap_or_default();
+        match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        }
+    }
+
+    /// Encoded path of an object, below any path the endpoint itself has.
+    fn object_path(&self, key: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.endpoint.path().trim_end_matches('/'),
+            sigv4::uri_encode(&self.bucket, false),
+            sigv4::uri_encode(key, true)
+        )
+    }
+
+    fn object_url(&self, path: &str) -> String {
+        format!("{}://{}{}", self.endpoint.scheme(), self.host(), path)
+    }
+
+    /// Sends a header-signed request for an object, signing `extra_headers` as well.
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        extra_headers: &[(String, String)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, StorageError> {
+        let now = OffsetDateTime::now_utc();
+        let path = self.object_path(key);
+        let payload_hash = sigv4::sha256_hex(&body);
+
+        let mut headers = vec![
+            ("host".to_string(), self.host()),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            ("x-amz-date".to_string(), sigv4::amz_date(now)),
+        ];
+        headers.extend_from_slice(extra_headers);
+        let (signature, signed_headers) =
+            sigv4::sign(&self.credentials, method.as_str(), &path, &[], &headers, &payload_hash, now);
+
+        let mut request = self.client.request(method, self.object_url(&path)).header(
+            "authorization",
+            sigv4::authorization(&self.credentials, now, &signature, &signed_headers),
+        );
+        // reqwest derives `Host` from the URL
+        for (name, value) in headers.iter().filter(|(name, _)| name != "host") {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        Ok(request.body(body).send().await?)
+    }
+}
+
+/// Maps an unsuccessful S3 response to a storage error.
+async fn response_error(key: &str, response: reqwest::Response) -> StorageError {
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return StorageError::NotFound(key.to_string());
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    StorageError::Backend(format!("S3 responded {}: {}", status, body.trim()))
+}
+
+#[async_trait]
+impl ObjectStorage for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), StorageError> {
+        let headers = [("content-type".to_string(), content_type.to_string())];
+        let response = self.send(Method::PUT, key, &headers, bytes).await?;
+        if !response.status().is_success() {
+            return Err(response_error(key, response).await);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let response = self.send(Method::GET, key, &[], Vec::new()).await?;
+        if !response.status().is_success() {
+            return Err(response_error(key, response).await);
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn get_range(&self, key: &str, range: RangeInclusive<u64>) -> Result<Vec<u8>, StorageError> {
+        let headers = [("range".to_string(), format!("bytes={}-{}", range.start(), range.end()))];
+        let response = self.send(Method::GET, key, &headers, Vec::new()).await?;
+        if !response.status().is_success() {
+            return Err(response_error(key, response).await);
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn copy(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        let source = format!("/{}/{}", sigv4::uri_encode(&self.bucket, false), sigv4::uri_encode(from, true));
+        let headers = [("x-amz-copy-source".to_string(), source)];
+        let response = self.send(Method::PUT, to, &headers, Vec::new()).await?;
+        if !response.status().is_success() {
+            return Err(response_error(from, response).await);
+        }
+
+        // A copy can fail after S3 has already answered 200; the error is then in the body
+        let body = response.text().await?;
+        if body.contains("<Error>") {
+            return Err(StorageError::Backend(format!("S3 copy failed: {}", body.trim())));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        // S3 answers 204 whether or not the object existed
+        let response = self.send(Method::DELETE, key, &[], Vec::new()).await?;
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            return Err(response_error(key, response).await);
+        }
+        Ok(())
+    }
+
+    async fn head(&self, key: &str) -> Result<ObjectInfo, StorageError> {
+        let response = self.send(Method::HEAD, key, &[], Vec::new()).await?;
+        if !response.status().is_success() {
+            return Err(response_error(key, response).await);
+        }
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let size_bytes = header("content-length")
+            .and_then(|length| length.parse().ok())
+            .ok_or_else(|| StorageError::Backend("S3 sent no object size".to_string()))?;
+
+        Ok(ObjectInfo {
+            size_bytes,
+            content_type: header("content-type"),
+        })
+    }
+
+    fn presign(
+        &self,
+        method: PresignMethod,
+        key: &str,
+        expires_in: Duration,
+        conditions: &PresignConditions,
+    ) -> Result<String, StorageError> {
+        let path = self.object_path(key);
+        let mut headers = conditions.headers();
+        headers.push(("host".to_string(), self.host()));
+
+        let query = sigv4::presigned_query(
+            &self.credentials,
+            method.as_str(),
+            &path,
+            &headers,
+            &conditions.response_params(),
+            OffsetDateTime::now_utc(),
+            expires_in.as_secs(),
+        );
+
+        Ok(format!("{}?{}", self.object_url(&path), query))
+    }
+}
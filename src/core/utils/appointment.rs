@@ -0,0 +1,32 @@
+use thiserror::Error;
+use time::OffsetDateTime;
+
+/// Where an appointment is in its life cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitStage {
+    Pending,
+    Confirmed,
+    Cancelled,
+    Completed,
+    NoShow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum OutcomeError {
+    #[error("The appointment has not started yet")]
+    NotStarted,
+    #[error("The appointment was cancelled")]
+    Cancelled,
+}
+
+/// Whether an appointment was held or missed is known once it started. A recorded outcome can
+/// still be corrected; cancelled appointments keep their cancellation.
+pub fn check_outcome(stage: VisitStage, starts_at: OffsetDateTime, now: OffsetDateTime) -> Result<(), OutcomeError> {
+    if stage == VisitStage::Cancelled {
+        return Err(OutcomeError::Cancelled);
+    }
+    if now < starts_at {
+        return Err(OutcomeError::NotStarted);
+    }
+    Ok(())
+}
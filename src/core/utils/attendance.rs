@@ -0,0 +1,71 @@
+use time::{Duration, OffsetDateTime};
+
+/// Whether a participant entered or left the live session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    Join,
+    Leave,
+}
+
+/// Total time a participant was present within `[window_start, window_end)`.
+///
+/// Events may arrive from several devices at once, so presence is counted while at least one
+/// connection is open. Leaves without a matching join are ignored, and a join that was never
+/// closed counts until `until` (typically now, or the session end once it is over).
+pub fn attended_duration(
+    events: &[(OffsetDateTime, Presence)],
+    window_start: OffsetDateTime,
+    window_end: OffsetDateTime,
+    until: OffsetDateTime,
+) -> Duration {
+    let mut events = events.to_vec();
+    // Joins sort before leaves at the same instant so a reconnect does not close the interval
+    events.sort_by_key(|(at, presence)| (*at, *presence == Presence::Leave));
+
+    let window_end = window_end.min(until);
+    let mut open_connections = 0u32;
+    let mut present_since = None;
+    let mut total = Duration::ZERO;
+
+    let mut close = |from: OffsetDateTime, to: OffsetDateTime| {
+        let from = from.max(window_start);
+        let to = to.min(window_end);
+        if to > from {
+            total += to - from;
+        }
+    };
+
+    for (at, presence) in events {
+        match presence {
+            Presence::Join => {
+                if open_connections == 0 {
+                    present_since = Some(at);
+                }
+                open_connections += 1;
+            }
+            Presence::Leave if open_connections > 0 => {
+                open_connections -= 1;
+                if open_connections == 0
+                    && let Some(since) = present_since.take()
+                {
+                    close(since, at);
+                }
+            }
+            Presence::Leave => {}
+        }
+    }
+
+    if let Some(since) = present_since {
+        close(since, until);
+    }
+
+    total
+}
+
+/// Whether `attended` covers at least `threshold_percent` of a session lasting `session_length`.
+pub fn meets_threshold(attended: Duration, session_length: Duration, threshold_percent: i32) -> bool {
+    if session_length <= Duration::ZERO {
+        return false;
+    }
+    attended.whole_seconds() * 100 >= session_length.whole_seconds() * i64::from(threshold_percent.clamp(0, 100))
+}
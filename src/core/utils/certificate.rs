@@ -0,0 +1,302 @@
+use printpdf::path::PaintMode;
+use printpdf::{Color, Mm, PdfDocument, PdfLayerReference, Rect, Rgb};
+use qrcode::{EcLevel, QrCode};
+use rand::Rng;
+use thiserror::Error;
+
+use super::pdf::{load_typeface, PdfError, Typeface, BOLD_FONT, PT_TO_MM, REGULAR_FONT};
+
+/// Crockford base32: no I, L, O or U, so codes survive being read aloud or typed from paper.
+const CODE_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CODE_GROUPS: usize = 3;
+const CODE_GROUP_LEN: usize = 4;
+
+// A4 landscape
+const PAGE_WIDTH: f32 = 297.0;
+const PAGE_HEIGHT: f32 = 210.0;
+const TEXT_WIDTH: f32 = 237.0;
+const QR_SIZE: f32 = 32.0;
+
+#[derive(Debug, Error)]
+pub enum CertificateError {
+    #[error("Failed to load certificate font: {0}")]
+    Font(String),
+
+    #[error("Failed to encode verification QR code: {0}")]
+    QrCode(#[from] qrcode::types::QrError),
+
+    #[error("Failed to write certificate PDF: {0}")]
+    Pdf(#[from] printpdf::Error),
+}
+
+impl From<PdfError> for CertificateError {
+    fn from(error: PdfError) -> Self {
+        match error {
+            PdfError::Font(message) => Self::Font(message),
+            PdfError::Write(error) => Self::Pdf(error),
+        }
+    }
+}
+
+/// What a certificate says; all values are printed as given.
+#[derive(Debug, Clone)]
+pub struct CertificateContent {
+    pub issuer: String,
+    pub company_name: String,
+    pub employee_name: String,
+    pub training_title: String,
+    pub completed_on: String,
+    pub host_name: Option<String>,
+    pub verification_code: String,
+    pub verification_url: String,
+}
+
+/// Tenant specific look of the certificate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateBranding {
+    pub accent: (u8, u8, u8),
+    pub heading: String,
+    pub footer: Option<String>,
+}
+
+impl Default for CertificateBranding {
+    fn default() -> Self {
+        Self {
+            accent: (0x1E, 0x88, 0xE5),
+            heading: "Certificate of Completion".to_string(),
+            footer: None,
+        }
+    }
+}
+
+impl CertificateBranding {
+    /// Reads the tenant branding setting (`primaryColor`, `certificateHeading`, `certificateFooter`);
+    /// missing or malformed values fall back to the defaults.
+    pub fn from_settings(settings: &serde_json::Value) -> Self {
+        let defaults = Self::default();
+        let text = |key: &str| {
+            settings
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        Self {
+            accent: text("primaryColor")
+                .and_then(|color| parse_hex_color(&color))
+                .unwrap_or(defaults.accent),
+            heading: text("certificateHeading").unwrap_or(defaults.heading),
+            footer: text("certificateFooter"),
+        }
+    }
+}
+
+/// Parses `#RRGGBB` or `#RGB`.
+pub fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 => Some((channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+        3 => {
+            let short = |i: usize| channel(&hex[i..i + 1]).map(|v| v * 17);
+            Some((short(0)?, short(1)?, short(2)?))
+        }
+        _ => None,
+    }
+}
+
+/// A new random verification code such as `7KQ2-M9XD-4TRH`.
+pub fn generate_verification_code() -> String {
+    let mut rng = rand::rng();
+    let chars: Vec<u8> = (0..CODE_GROUPS * CODE_GROUP_LEN)
+        .map(|_| CODE_ALPHABET[rng.random_range(0..CODE_ALPHABET.len())])
+        .collect();
+
+    format_code(&chars)
+}
+
+/// Canonical form of a code as typed by a person: case, spaces and dashes do not matter,
+/// and the look-alikes O, I and L are read as 0 and 1. Returns None for anything else.
+pub fn normalize_verification_code(input: &str) -> Option<String> {
+    let chars: Vec<u8> = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        })
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect();
+
+    let valid = chars.len() == CODE_GROUPS * CODE_GROUP_LEN && chars.iter().all(|c| CODE_ALPHABET.contains(c));
+    valid.then(|| format_code(&chars))
+}
+
+/// Extracts the code from what a QR scanner or a person hands over: either the code itself or
+/// the verification URL printed on the certificate.
+pub fn verification_code_from_payload(payload: &str) -> Option<String> {
+    let payload = payload.trim();
+    let payload = payload.split(['?', '#']).next().unwrap_or(payload);
+    let candidate = payload.trim_end_matches('/').rsplit('/').next().unwrap_or(payload);
+
+    normalize_verification_code(candidate)
+}
+
+/// How the holder is named publicly: the first name and the initial of the last name.
+pub fn holder_display_name(full_name: &str) -> String {
+    let mut parts = full_name.split_whitespace();
+    let first = parts.next().unwrap_or_default();
+
+    match parts.last().and_then(|last| last.chars().next()) {
+        Some(initial) => format!("{} {}.", first, initial.to_uppercase()),
+        None => first.chars().next().map(|c| format!("{}.", c)).unwrap_or_default(),
+    }
+}
+
+fn format_code(chars: &[u8]) -> String {
+    chars
+        .chunks(CODE_GROUP_LEN)
+        .map(|group| String::from_utf8_lossy(group).into_owned())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+struct Canvas<'a> {
+    layer: PdfLayerReference,
+    regular: Typeface<'a>,
+    bold: Typeface<'a>,
+}
+
+impl Canvas<'_> {
+    fn fill(&self, (r, g, b): (u8, u8, u8)) {
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(
+            f32::from(r) / 255.0,
+            f32::from(g) / 255.0,
+            f32::from(b) / 255.0,
+            None,
+        )));
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, y: f32, bold: bool) {
+        let typeface = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(x), Mm(y), &typeface.font);
+    }
+
+    fn centered(&self, text: &str, size: f32, y: f32, bold: bool) {
+        let typeface = if bold { &self.bold } else { &self.regular };
+        let x = (PAGE_WIDTH - typeface.text_width(text, size)) / 2.0;
+        self.text(text, size, x, y, bold);
+    }
+
+    /// Centres `text` at up to `size`, shrinking it to `min_size` and then wrapping onto at most
+    /// `max_lines` lines if it is too wide. Returns the baseline of the last line.
+    fn centered_fit(&self, text: &str, size: f32, min_size: f32, y: f32, max_lines: usize) -> f32 {
+        let mut size = size;
+        while size > min_size && self.bold.text_width(text, size) > TEXT_WIDTH {
+            size -= 1.0;
+        }
+
+        let lines = self.bold.wrap(text, size, TEXT_WIDTH);
+        let mut baseline = y;
+        for (index, line) in lines.iter().take(max_lines).enumerate() {
+            baseline = y - index as f32 * size * PT_TO_MM * 1.3;
+            self.centered(line, size, baseline, true);
+        }
+        baseline
+    }
+
+    fn rect(&self, x: f32, y: f32, width: f32, height: f32, mode: PaintMode) {
+        self.layer
+            .add_rect(Rect::new(Mm(x), Mm(y), Mm(x + width), Mm(y + height)).with_mode(mode));
+    }
+
+    fn qr_code(&self, code: &QrCode, x: f32, y: f32, size: f32) {
+        let width = code.width();
+        let module = size / width as f32;
+
+        for (index, color) in code.to_colors().into_iter().enumerate() {
+            if color == qrcode::Color::Dark {
+                let (column, row) = (index % width, index / width);
+                // Rows run top to bottom, PDF coordinates bottom to top
+                let module_y = y + size - (row + 1) as f32 * module;
+                self.rect(x + column as f32 * module, module_y, module, module, PaintMode::Fill);
+            }
+        }
+    }
+}
+
+/// Renders a single page A4 landscape certificate with a QR code linking to its verification page.
+pub fn render_certificate(
+    content: &CertificateContent,
+    branding: &CertificateBranding,
+) -> Result<Vec<u8>, CertificateError> {
+    let qr_code = QrCode::with_error_correction_level(content.verification_url.as_bytes(), EcLevel::M)?;
+
+    let (doc, page, layer) = PdfDocument::new(
+        format!("{} - {}", branding.heading, content.training_title),
+        Mm(PAGE_WIDTH),
+        Mm(PAGE_HEIGHT),
+        "Certificate",
+    );
+    let canvas = Canvas {
+        layer: doc.get_page(page).get_layer(layer),
+        regular: load_typeface(&doc, REGULAR_FONT)?,
+        bold: load_typeface(&doc, BOLD_FONT)?,
+    };
+
+    let black = (0x21, 0x21, 0x21);
+    let grey = (0x61, 0x61, 0x61);
+
+    // Frame and accent bands
+    canvas.layer.set_outline_color(Color::Rgb(Rgb::new(0.8, 0.8, 0.8, None)));
+    canvas.layer.set_outline_thickness(0.8);
+    canvas.rect(10.0, 10.0, PAGE_WIDTH - 20.0, PAGE_HEIGHT - 20.0, PaintMode::Stroke);
+    canvas.fill(branding.accent);
+    canvas.rect(10.0, PAGE_HEIGHT - 18.0, PAGE_WIDTH - 20.0, 8.0, PaintMode::Fill);
+    canvas.rect(10.0, 10.0, PAGE_WIDTH - 20.0, 3.0, PaintMode::Fill);
+
+    canvas.fill(grey);
+    canvas.centered(&content.issuer, 14.0, 175.0, false);
+
+    canvas.fill(branding.accent);
+    canvas.centered(&branding.heading.to_uppercase(), 28.0, 155.0, true);
+
+    canvas.fill(grey);
+    canvas.centered("This is to certify that", 12.0, 138.0, false);
+
+    canvas.fill(black);
+    canvas.centered_fit(&content.employee_name, 24.0, 16.0, 124.0, 1);
+
+    canvas.fill(grey);
+    canvas.centered(&content.company_name, 13.0, 114.0, false);
+    canvas.centered("has successfully completed the training", 12.0, 101.0, false);
+
+    canvas.fill(black);
+    let title_end = canvas.centered_fit(&content.training_title, 18.0, 13.0, 89.0, 2);
+
+    canvas.fill(grey);
+    let details = match &content.host_name {
+        Some(host) => format!("Completed on {}  ·  Instructor: {}", content.completed_on, host),
+        None => format!("Completed on {}", content.completed_on),
+    };
+    canvas.centered(&details, 11.0, title_end - 12.0, false);
+
+    // Verification block, bottom left text and bottom right QR code
+    canvas.text(&format!("Verification code: {}", content.verification_code), 10.0, 22.0, 30.0, true);
+    canvas.text(&format!("Verify at {}", content.verification_url), 8.0, 22.0, 24.0, false);
+    if let Some(footer) = &branding.footer {
+        canvas.text(footer, 8.0, 22.0, 18.0, false);
+    }
+
+    canvas.fill(black);
+    canvas.qr_code(&qr_code, PAGE_WIDTH - 22.0 - QR_SIZE, 17.0, QR_SIZE);
+
+    Ok(doc.save_to_bytes()?)
+}
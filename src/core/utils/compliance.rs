@@ -0,0 +1,46 @@
+use time::{Duration, Month, OffsetDateTime, UtcOffset};
+
+/// Where an employee stands with a training requirement. Ordered from least to most urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Standing {
+    Compliant,
+    DueSoon,
+    Overdue,
+}
+
+/// Adds calendar months in UTC, keeping the time of day. Days past the end of a shorter month
+/// land on its last day, so a completion on 31 January recurs on 28 or 29 February.
+pub fn add_months(at: OffsetDateTime, months: i32) -> OffsetDateTime {
+    let at = at.to_offset(UtcOffset::UTC);
+    let index = at.year() * 12 + i32::from(u8::from(at.month())) - 1 + months;
+    let year = index.div_euclid(12);
+    let month = Month::try_from(index.rem_euclid(12) as u8 + 1).expect("a month is within 1..=12");
+    let date = time::Date::from_calendar_date(year, month, at.day().min(month.length(year)))
+        .expect("the day is clamped to the month");
+    at.replace_date(date)
+}
+
+/// When a requirement is due again: `recurrence_interval_months` after the last completion.
+/// None when it was never completed or does not recur.
+pub fn due_at(
+    last_completed_at: Option<OffsetDateTime>,
+    recurrence_interval_months: Option<i32>,
+) -> Option<OffsetDateTime> {
+    Some(add_months(last_completed_at?, recurrence_interval_months?))
+}
+
+/// Never completed or past the due date is overdue, the due date counting as past; within
+/// `due_soon_days` of it is due soon. Completed one-off requirements stay compliant.
+pub fn standing(
+    last_completed_at: Option<OffsetDateTime>,
+    due_at: Option<OffsetDateTime>,
+    due_soon_days: i32,
+    now: OffsetDateTime,
+) -> Standing {
+    match (last_completed_at, due_at) {
+        (None, _) => Standing::Overdue,
+        (Some(_), Some(due_at)) if due_at <= now => Standing::Overdue,
+        (Some(_), Some(due_at)) if due_at <= now + Duration::days(i64::from(due_soon_days)) => Standing::DueSoon,
+        (Some(_), _) => Standing::Compliant,
+    }
+}
@@ -0,0 +1,41 @@
+use sqlx::types::Uuid;
+
+/// What a user may do with the consultation note of an appointment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteAccess {
+    /// The professional of the appointment writes, signs and reads the whole note.
+    Author,
+    /// The employee of the appointment only gets the signed visit summary.
+    Employee,
+    /// Anyone else does not learn the note exists.
+    None,
+}
+
+#[allow(unused)]
+impl NoteAccess {
+    pub fn of(user_id: Uuid, professional_user_id: Uuid, employee_user_id: Uuid) -> NoteAccess {
+        if user_id == professional_user_id {
+            NoteAccess::Author
+        } else if user_id == employee_user_id {
+            NoteAccess::Employee
+        } else {
+            NoteAccess::None
+        }
+    }
+
+    /// Drafts, every section and the version history; also what may be saved and signed.
+    pub fn can_edit(self) -> bool {
+        self == NoteAccess::Author
+    }
+
+    /// The visit summary, once the note is signed.
+    pub fn can_read_summary(self) -> bool {
+        self != NoteAccess::None
+    }
+}
+
+/// Whether a section belongs in the visit summary the employee gets: only sections the template
+/// shares with them, and only those that were filled in.
+pub fn in_summary(shared_with_employee: bool, text: &str) -> bool {
+    shared_with_employee && !text.trim().is_empty()
+}
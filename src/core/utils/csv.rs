@@ -0,0 +1,42 @@
+/// Whether a spreadsheet would read the field as a formula. Numbers such as `-3` are left alone.
+fn starts_formula(field: &str) -> bool {
+    field.starts_with(['=', '+', '-', '@', '\t', '\r']) && field.parse::<f64>().is_err()
+}
+
+/// Quotes a field when it holds a separator, quote or line break, doubling inner quotes (RFC 4180).
+/// Text a spreadsheet would run as a formula is prefixed with `'` first, so it shows as typed.
+pub fn escape_field(field: &str) -> String {
+    let field = if starts_formula(field) { format!("'{}", field) } else { field.to_string() };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Builds a CSV document row by row with CRLF line endings.
+#[derive(Debug, Default)]
+pub struct CsvWriter {
+    out: String,
+}
+
+#[allow(unused)]
+impl CsvWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn row<I, S>(&mut self, fields: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let fields: Vec<String> = fields.into_iter().map(|field| escape_field(field.as_ref())).collect();
+        self.out.push_str(&fields.join(","));
+        self.out.push_str("\r\n");
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
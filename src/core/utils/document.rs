@@ -0,0 +1,114 @@
+use std::io::Read;
+
+use flate2::read::DeflateDecoder;
+
+const PPTX: &str = "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+const ODP: &str = "application/vnd.oasis.opendocument.presentation";
+
+/// Largest part of an archive that is inflated to count pages.
+const MAX_INFLATED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Pages of a document, or slides of a deck; None for types without pages we can count
+/// (legacy PowerPoint) and for files that cannot be read.
+pub fn page_count(content_type: &str, bytes: &[u8]) -> Option<i32> {
+    let count = match content_type {
+        "application/pdf" => lopdf::Document::load_mem(bytes).ok()?.get_pages().len(),
+        PPTX => zip_entries(bytes)?
+            .iter()
+            .filter(|entry| is_pptx_slide(&entry.name))
+            .count(),
+        ODP => {
+            let entries = zip_entries(bytes)?;
+            let content = entries.iter().find(|entry| entry.name == "content.xml")?;
+            let xml = String::from_utf8(read_entry(bytes, content)?).ok()?;
+            // A trailing space keeps `<draw:page-thumbnail` out
+            xml.matches("<draw:page ").count() + xml.matches("<draw:page>").count()
+        }
+        _ => return None,
+    };
+    i32::try_from(count).ok().filter(|&count| count > 0)
+}
+
+/// Slides of a PowerPoint deck are stored as `ppt/slides/slide{n}.xml`.
+fn is_pptx_slide(name: &str) -> bool {
+    name.strip_prefix("ppt/slides/slide")
+        .and_then(|rest| rest.strip_suffix(".xml"))
+        .is_some_and(|number| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// A file in a zip archive, as listed by its central directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipEntry {
+    pub name: String,
+    pub method: u16,
+    pub compressed_size: u64,
+    pub local_header_offset: u64,
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Lists the files of a zip archive from its central directory; None for anything that is
+/// not a readable (non ZIP64) archive.
+pub fn zip_entries(bytes: &[u8]) -> Option<Vec<ZipEntry>> {
+    const END_OF_DIRECTORY: u32 = 0x0605_4b50;
+    const DIRECTORY_ENTRY: u32 = 0x0201_4b50;
+
+    // The end record sits behind at most 64 KiB of comment
+    let search_from = bytes.len().saturating_sub(22 + usize::from(u16::MAX));
+    let end = (search_from..=bytes.len().checked_sub(22)?)
+        .rev()
+        .find(|&at| u32_at(bytes, at) == Some(END_OF_DIRECTORY))?;
+    let count = u16_at(bytes, end + 10)?;
+    let mut at = usize::try_from(u32_at(bytes, end + 16)?).ok()?;
+
+    let mut entries = Vec::with_capacity(usize::from(count));
+    for _ in 0..count {
+        if u32_at(bytes, at)? != DIRECTORY_ENTRY {
+            return None;
+        }
+        let name_length = usize::from(u16_at(bytes, at + 28)?);
+        let extra_length = usize::from(u16_at(bytes, at + 30)?);
+        let comment_length = usize::from(u16_at(bytes, at + 32)?);
+        let name = bytes.get(at + 46..at + 46 + name_length)?;
+
+        entries.push(ZipEntry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            method: u16_at(bytes, at + 10)?,
+            compressed_size: u64::from(u32_at(bytes, at + 20)?),
+            local_header_offset: u64::from(u32_at(bytes, at + 42)?),
+        });
+        at += 46 + name_length + extra_length + comment_length;
+    }
+    Some(entries)
+}
+
+/// Reads a stored or deflated file of a zip archive.
+fn read_entry(bytes: &[u8], entry: &ZipEntry) -> Option<Vec<u8>> {
+    const LOCAL_HEADER: u32 = 0x0403_4b50;
+
+    let at = usize::try_from(entry.local_header_offset).ok()?;
+    if u32_at(bytes, at)? != LOCAL_HEADER {
+        return None;
+    }
+    let start = at + 30 + usize::from(u16_at(bytes, at + 26)?) + usize::from(u16_at(bytes, at + 28)?);
+    let data = bytes.get(start..start + usize::try_from(entry.compressed_size).ok()?)?;
+
+    match entry.method {
+        0 => Some(data.to_vec()),
+        8 => {
+            let mut inflated = Vec::new();
+            DeflateDecoder::new(data)
+                .take(MAX_INFLATED_BYTES)
+                .read_to_end(&mut inflated)
+                .ok()?;
+            Some(inflated)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,197 @@
+/// EXIF tag of the pointer from the primary image directory to the GPS directory.
+const GPS_IFD_POINTER: u16 = 0x8825;
+
+const ENTRY_SIZE: usize = 12;
+
+/// Removes the GPS location an image's EXIF data may carry, in place.
+///
+/// The GPS directory and the values it points to are zeroed and its pointer dropped from the
+/// primary directory; everything else, such as the orientation, is kept. The file keeps its
+/// length, so it still matches the size it was uploaded with. Returns whether anything was
+/// removed; files this does not understand are left alone.
+pub fn strip_gps(content_type: &str, bytes: &mut [u8]) -> bool {
+    match content_type {
+        "image/jpeg" => strip_jpeg(bytes),
+        "image/png" => strip_png(bytes),
+        "image/webp" => strip_webp(bytes),
+        _ => false,
+    }
+}
+
+fn strip_jpeg(bytes: &mut [u8]) -> bool {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return false;
+    }
+
+    let mut stripped = false;
+    let mut offset = 2;
+    while offset + 4 <= bytes.len() && bytes[offset] == 0xFF {
+        let marker = bytes[offset + 1];
+        // Image data follows the start of scan; EXIF comes before it
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        let length = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+        let end = offset + 2 + length;
+        if length < 2 || end > bytes.len() {
+            break;
+        }
+        let payload = &mut bytes[offset + 4..end];
+        if marker == 0xE1 && payload.starts_with(b"Exif\0\0") {
+            stripped |= strip_tiff(&mut payload[6..]);
+        }
+        offset = end;
+    }
+    stripped
+}
+
+fn strip_png(bytes: &mut [u8]) -> bool {
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return false;
+    }
+
+    let mut stripped = false;
+    let mut offset = 8;
+    while offset + 12 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize;
+        let data_end = offset + 8 + length;
+        if data_end + 4 > bytes.len() {
+            break;
+        }
+        if &bytes[offset + 4..offset + 8] == b"eXIf" && strip_tiff(&mut bytes[offset + 8..data_end]) {
+            let crc = crc32(&bytes[offset + 4..data_end]);
+            bytes[data_end..data_end + 4].copy_from_slice(&crc.to_be_bytes());
+            stripped = true;
+        }
+        offset = data_end + 4;
+    }
+    stripped
+}
+
+fn strip_webp(bytes: &mut [u8]) -> bool {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return false;
+    }
+
+    let mut stripped = false;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let length = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let data_end = offset + 8 + length;
+        if data_end > bytes.len() {
+            break;
+        }
+        if &bytes[offset..offset + 4] == b"EXIF" {
+            let data = &mut bytes[offset + 8..data_end];
+            // Some writers keep the JPEG style header
+            let tiff = if data.starts_with(b"Exif\0\0") { &mut data[6..] } else { data };
+            stripped |= strip_tiff(tiff);
+        }
+        // Chunks are padded to an even length
+        offset = data_end + (length & 1);
+    }
+    stripped
+}
+
+/// Reads the integers of a TIFF structure in its byte order.
+#[derive(Clone, Copy)]
+struct ByteOrder {
+    little_endian: bool,
+}
+
+impl ByteOrder {
+    fn u16(&self, bytes: &[u8], offset: usize) -> Option<u16> {
+        let value: [u8; 2] = bytes.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.little_endian { u16::from_le_bytes(value) } else { u16::from_be_bytes(value) })
+    }
+
+    fn u32(&self, bytes: &[u8], offset: usize) -> Option<u32> {
+        let value: [u8; 4] = bytes.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian { u32::from_le_bytes(value) } else { u32::from_be_bytes(value) })
+    }
+}
+
+fn strip_tiff(tiff: &mut [u8]) -> bool {
+    strip_tiff_gps(tiff).unwrap_or(false)
+}
+
+fn strip_tiff_gps(tiff: &mut [u8]) -> Option<bool> {
+    let order = match tiff.get(0..2)? {
+        b"II" => ByteOrder { little_endian: true },
+        b"MM" => ByteOrder { little_endian: false },
+        _ => return None,
+    };
+    if order.u16(tiff, 2)? != 42 {
+        return None;
+    }
+
+    let ifd = order.u32(tiff, 4)? as usize;
+    let count = order.u16(tiff, ifd)? as usize;
+    let ifd_end = ifd + 2 + count * ENTRY_SIZE + 4;
+    if ifd_end > tiff.len() {
+        return None;
+    }
+    let Some(index) = (0..count).find(|i| order.u16(tiff, ifd + 2 + i * ENTRY_SIZE) == Some(GPS_IFD_POINTER))
+    else {
+        return Some(false);
+    };
+    let entry = ifd + 2 + index * ENTRY_SIZE;
+    let gps_ifd = order.u32(tiff, entry + 8)? as usize;
+
+    zero_directory(tiff, order, gps_ifd);
+
+    // Drop the pointer entry: later entries and the next directory offset move up by one entry
+    tiff.copy_within(entry + ENTRY_SIZE..ifd_end, entry);
+    tiff[ifd_end - ENTRY_SIZE..ifd_end].fill(0);
+    let count = (count - 1) as u16;
+    let count = if order.little_endian { count.to_le_bytes() } else { count.to_be_bytes() };
+    tiff[ifd..ifd + 2].copy_from_slice(&count);
+
+    Some(true)
+}
+
+/// Zeroes a directory along with the values stored outside of its entries.
+fn zero_directory(tiff: &mut [u8], order: ByteOrder, ifd: usize) {
+    let Some(count) = order.u16(tiff, ifd).map(usize::from) else {
+        return;
+    };
+    for i in 0..count {
+        let entry = ifd + 2 + i * ENTRY_SIZE;
+        let (Some(field_type), Some(values)) = (order.u16(tiff, entry + 2), order.u32(tiff, entry + 4)) else {
+            break;
+        };
+        let size = type_size(field_type).saturating_mul(values as usize);
+        // Values of up to 4 bytes sit in the entry itself
+        if size > 4
+            && let Some(offset) = order.u32(tiff, entry + 8).map(|offset| offset as usize)
+            && let Some(value) = tiff.get_mut(offset..offset.saturating_add(size))
+        {
+            value.fill(0);
+        }
+    }
+    let end = (ifd + 2 + count * ENTRY_SIZE + 4).min(tiff.len());
+    tiff[ifd..end].fill(0);
+}
+
+/// Size in bytes of one value of a TIFF field type.
+fn type_size(field_type: u16) -> usize {
+    match field_type {
+        1 | 2 | 6 | 7 => 1,       // BYTE, ASCII, SBYTE, UNDEFINED
+        3 | 8 => 2,               // SHORT, SSHORT
+        4 | 9 | 11 => 4,          // LONG, SLONG, FLOAT
+        5 | 10 | 12 => 8,         // RATIONAL, SRATIONAL, DOUBLE
+        _ => 0,
+    }
+}
+
+/// CRC-32 as used by PNG chunks.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
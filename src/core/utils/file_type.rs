@@ -0,0 +1,53 @@
+/// Leading bytes read to sniff a file's type. Office documents are zip archives that are told
+/// apart by the names of their first entries, so a few kilobytes are not always enough.
+pub const SNIFF_BYTES: u64 = 64 * 1024;
+
+/// Content types stored as zip archives, which sniff as `application/zip` when the head of the
+/// archive does not give the format away.
+const ZIP_FORMATS: [&str; 6] = [
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.presentation",
+    "application/vnd.oasis.opendocument.spreadsheet",
+];
+
+/// Legacy Office formats, stored as OLE compound files.
+const OLE_FORMATS: [&str; 3] = [
+    "application/msword",
+    "application/vnd.ms-excel",
+    "application/vnd.ms-powerpoint",
+];
+
+/// The content type the leading bytes of a file reveal, by magic numbers, or `text/plain`
+/// for UTF-8 text without NUL bytes.
+pub fn sniff_content_type(head: &[u8]) -> Option<&'static str> {
+    if let Some(kind) = infer::get(head) {
+        return Some(kind.mime_type());
+    }
+    looks_like_text(head).then_some("text/plain")
+}
+
+fn looks_like_text(head: &[u8]) -> bool {
+    if head.is_empty() || head.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // The head may end in the middle of a character
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Whether the sniffed type of a file backs the (normalized) type it was declared as.
+pub fn matches_declared(declared: &str, sniffed: &str) -> bool {
+    declared == sniffed
+        || match sniffed {
+            "application/zip" => ZIP_FORMATS.contains(&declared),
+            "application/x-ole-storage" => OLE_FORMATS.contains(&declared),
+            "text/plain" => declared == "text/csv",
+            "video/x-m4v" => declared == "video/mp4",
+            _ => false,
+        }
+}
@@ -0,0 +1,345 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use thiserror::Error;
+
+/// Target length of a media segment; keyframes are forced at this interval so every
+/// rendition is cut at the same points and players can switch between them.
+pub const SEGMENT_SECONDS: u32 = 6;
+
+pub const MASTER_PLAYLIST: &str = "master.m3u8";
+pub const MEDIA_PLAYLIST: &str = "index.m3u8";
+pub const POSTER: &str = "poster.jpg";
+
+/// Width of the poster image.
+const POSTER_WIDTH: u32 = 640;
+
+/// H.264 Main profile, level 4.0, and AAC-LC, as encoded by `transcode_args`.
+const VIDEO_CODEC: &str = "avc1.4d4028";
+const AUDIO_CODEC: &str = "mp4a.40.2";
+
+/// One rung of the bitrate ladder, sized by the shorter side so portrait videos get the
+/// same quality as landscape ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rendition {
+    pub name: &'static str,
+    pub short_side: u32,
+    pub video_kbps: u32,
+    pub audio_kbps: u32,
+}
+
+impl Rendition {
+    /// Peak bitrate in bits per second, as advertised in the master playlist.
+    pub fn bandwidth(&self) -> u32 {
+        (self.video_kbps * 107 / 100 + self.audio_kbps) * 1000
+    }
+}
+
+pub const LADDER: [Rendition; 4] = [
+    Rendition { name: "1080p", short_side: 1080, video_kbps: 5000, audio_kbps: 128 },
+    Rendition { name: "720p", short_side: 720, video_kbps: 2800, audio_kbps: 128 },
+    Rendition { name: "480p", short_side: 480, video_kbps: 1400, audio_kbps: 96 },
+    Rendition { name: "360p", short_side: 360, video_kbps: 800, audio_kbps: 96 },
+];
+
+/// What ffprobe tells about a source video; sizes are as displayed, after rotation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoInfo {
+    pub width: u32,
+    pub height: u32,
+    pub duration_seconds: f64,
+    pub has_audio: bool,
+}
+
+impl VideoInfo {
+    fn is_portrait(&self) -> bool {
+        self.height > self.width
+    }
+
+    fn short_side(&self) -> u32 {
+        self.width.min(self.height)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ProbeError {
+    #[error("The ffprobe output could not be read: {0}")]
+    Malformed(String),
+
+    #[error("The file has no video stream")]
+    NoVideo,
+}
+
+#[derive(Deserialize)]
+struct Probe {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+    format: Option<ProbeFormat>,
+}
+
+#[derive(Deserialize)]
+struct ProbeStream {
+    codec_type: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    duration: Option<String>,
+    #[serde(default)]
+    tags: ProbeTags,
+    #[serde(default)]
+    side_data_list: Vec<ProbeSideData>,
+}
+
+#[derive(Deserialize, Default)]
+struct ProbeTags {
+    rotate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProbeSideData {
+    rotation: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct ProbeFormat {
+    duration: Option<String>,
+}
+
+/// Arguments for ffprobe to describe `input` as JSON, read by `parse_probe`.
+pub fn probe_args(input: &str) -> Vec<String> {
+    ["-v", "error", "-print_format", "json", "-show_streams", "-show_format", input]
+        .map(str::to_string)
+        .to_vec()
+}
+
+pub fn parse_probe(json: &str) -> Result<VideoInfo, ProbeError> {
+    let probe: Probe = serde_json::from_str(json).map_err(|e| ProbeError::Malformed(e.to_string()))?;
+    let video = probe
+        .streams
+        .iter()
+        .find(|stream| stream.codec_type.as_deref() == Some("video"))
+        .ok_or(ProbeError::NoVideo)?;
+    let (Some(width), Some(height)) = (video.width, video.height) else {
+        return Err(ProbeError::Malformed("the video stream has no size".to_string()));
+    };
+
+    // Phones record portrait videos as landscape frames with a rotation, which ffmpeg applies
+    let rotation = video
+        .side_data_list
+        .iter()
+        .find_map(|side_data| side_data.rotation)
+        .or_else(|| video.tags.rotate.as_deref().and_then(|rotate| rotate.parse().ok()))
+        .unwrap_or(0.0);
+    let (width, height) = if (rotation.abs() as u32) % 180 == 90 { (height, width) } else { (width, height) };
+
+    let duration_seconds = probe
+        .format
+        .and_then(|format| format.duration)
+        .or_else(|| video.duration.clone())
+        .and_then(|duration| duration.parse::<f64>().ok())
+        .filter(|duration| duration.is_finite() && *duration > 0.0)
+        .ok_or_else(|| ProbeError::Malformed("the file has no duration".to_string()))?;
+
+    Ok(VideoInfo {
+        width,
+        height,
+        duration_seconds,
+        has_audio: probe.streams.iter().any(|stream| stream.codec_type.as_deref() == Some("audio")),
+    })
+}
+
+/// The rungs of the ladder that do not upscale the source; small sources get the lowest rung.
+pub fn renditions_for(info: &VideoInfo) -> Vec<Rendition> {
+    let renditions: Vec<Rendition> =
+        LADDER.into_iter().filter(|rendition| rendition.short_side <= info.short_side()).collect();
+    if renditions.is_empty() { vec![LADDER[LADDER.len() - 1]] } else { renditions }
+}
+
+/// Size of a rendition of the source, keeping its aspect ratio with an even long side.
+pub fn output_size(info: &VideoInfo, rendition: &Rendition) -> (u32, u32) {
+    let (short, long) = (info.short_side().max(1) as u64, info.width.max(info.height) as u64);
+    let long_side = ((long * rendition.short_side as u64 / short) / 2 * 2) as u32;
+    if info.is_portrait() {
+        (rendition.short_side, long_side)
+    } else {
+        (long_side, rendition.short_side)
+    }
+}
+
+/// Arguments for ffmpeg to encode `input` into an HLS media playlist with segments per
+/// rendition, at `{out_dir}/{name}/index.m3u8`. The master playlist is written separately.
+pub fn transcode_args(input: &str, out_dir: &str, info: &VideoInfo, renditions: &[Rendition]) -> Vec<String> {
+    let mut args: Vec<String> = ["-hide_banner", "-loglevel", "error", "-nostdin", "-y", "-i", input]
+        .map(str::to_string)
+        .to_vec();
+
+    let splits: String = (0..renditions.len()).map(|i| format!("[v{}]", i)).collect();
+    let mut filter = format!("[0:v]split={}{}", renditions.len(), splits);
+    for (i, rendition) in renditions.iter().enumerate() {
+        let scale = if info.is_portrait() {
+            format!("{}:-2", rendition.short_side)
+        } else {
+            format!("-2:{}", rendition.short_side)
+        };
+        filter.push_str(&format!(";[v{}]scale={}[v{}out]", i, scale, i));
+    }
+    args.extend(["-filter_complex".to_string(), filter]);
+
+    let mut stream_map = Vec::with_capacity(renditions.len());
+    for (i, rendition) in renditions.iter().enumerate() {
+        args.extend([
+            "-map".to_string(),
+            format!("[v{}out]", i),
+            format!("-c:v:{}", i),
+            "libx264".to_string(),
+            format!("-b:v:{}", i),
+            format!("{}k", rendition.video_kbps),
+            format!("-maxrate:v:{}", i),
+            format!("{}k", rendition.video_kbps * 107 / 100),
+            format!("-bufsize:v:{}", i),
+            format!("{}k", rendition.video_kbps * 3 / 2),
+        ]);
+        if info.has_audio {
+            args.extend([
+                "-map".to_string(),
+                "0:a:0".to_string(),
+                format!("-c:a:{}", i),
+                "aac".to_string(),
+                format!("-b:a:{}", i),
+                format!("{}k", rendition.audio_kbps),
+            ]);
+            stream_map.push(format!("v:{},a:{},name:{}", i, i, rendition.name));
+        } else {
+            stream_map.push(format!("v:{},name:{}", i, rendition.name));
+        }
+    }
+
+    if info.has_audio {
+        args.extend(["-ac".to_string(), "2".to_string()]);
+    }
+
+    let out_dir = out_dir.trim_end_matches('/');
+    args.extend(
+        [
+            "-profile:v",
+            "main",
+            "-level:v",
+            "4.0",
+            "-pix_fmt",
+            "yuv420p",
+            "-preset",
+            "veryfast",
+            "-force_key_frames",
+            &format!("expr:gte(t,n_forced*{})", SEGMENT_SECONDS),
+            "-sc_threshold",
+            "0",
+            "-f",
+            "hls",
+            "-hls_time",
+            &SEGMENT_SECONDS.to_string(),
+            "-hls_playlist_type",
+            "vod",
+            "-hls_flags",
+            "independent_segments",
+            "-hls_segment_filename",
+            &format!("{}/%v/segment_%04d.ts", out_dir),
+            "-var_stream_map",
+            &stream_map.join(" "),
+            &format!("{}/%v/{}", out_dir, MEDIA_PLAYLIST),
+        ]
+        .map(str::to_string),
+    );
+    args
+}
+
+/// Arguments for ffmpeg to grab the poster frame, a tenth into the video but at most 10 seconds.
+pub fn poster_args(input: &str, output: &str, info: &VideoInfo) -> Vec<String> {
+    let at = (info.duration_seconds / 10.0).min(10.0);
+    [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-nostdin",
+        "-y",
+        "-ss",
+        &format!("{:.3}", at),
+        "-i",
+        input,
+        "-frames:v",
+        "1",
+        "-vf",
+        &format!("scale={}:-2", POSTER_WIDTH),
+        "-q:v",
+        "3",
+        output,
+    ]
+    .map(str::to_string)
+    .to_vec()
+}
+
+/// The master playlist listing every rendition, highest first.
+pub fn master_playlist(info: &VideoInfo, renditions: &[Rendition]) -> String {
+    let codecs = if info.has_audio { format!("{},{}", VIDEO_CODEC, AUDIO_CODEC) } else { VIDEO_CODEC.to_string() };
+
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-INDEPENDENT-SEGMENTS\n");
+    for rendition in renditions {
+        let (width, height) = output_size(info, rendition);
+        playlist.push_str(&format!(
+            "#EXT-X-STREAM-INF:BANDWIDTH={},RESOLUTION={}x{},CODECS=\"{}\"\n{}/{}\n",
+            rendition.bandwidth(),
+            width,
+            height,
+            codecs,
+            rendition.name,
+            MEDIA_PLAYLIST
+        ));
+    }
+    playlist
+}
+
+/// Replaces every URI of a playlist, on URI lines and in `URI="..."` attributes.
+pub fn rewrite_uris(playlist: &str, mut rewrite: impl FnMut(&str) -> String) -> String {
+    let mut rewritten = String::with_capacity(playlist.len() * 2);
+    for line in playlist.lines().map(str::trim_end).filter(|line| !line.is_empty()) {
+        if !line.starts_with('#') {
+            rewritten.push_str(&rewrite(line));
+        } else if let Some(start) = line.find("URI=\"").map(|start| start + 5)
+            && let Some(length) = line[start..].find('"')
+        {
+            rewritten.push_str(&line[..start]);
+            rewritten.push_str(&rewrite(&line[start..start + length]));
+            rewritten.push_str(&line[start + length..]);
+        } else {
+            rewritten.push_str(line);
+        }
+        rewritten.push('\n');
+    }
+    rewritten
+}
+
+/// How long a playback link stays valid: players fetch segments throughout playback, so the
+/// link has to outlive the video, with room for pausing.
+pub fn playback_ttl(duration_seconds: f64) -> Duration {
+    Duration::from_secs((duration_seconds * 3.0) as u64)
+        .clamp(Duration::from_secs(60 * 60), Duration::from_secs(12 * 60 * 60))
+}
+
+fn playback_mac(secret: &[u8], material_id: &str, expires: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(format!("hls\n{}\n{}\n", material_id, expires).as_bytes());
+    mac
+}
+
+/// Signature of the playlist URLs of a material; players cannot send bearer tokens.
+pub fn sign_playback(secret: &[u8], material_id: &str, expires: i64) -> String {
+    hex::encode(playback_mac(secret, material_id, expires).finalize().into_bytes())
+}
+
+/// Whether a signature made by `sign_playback` is valid and has not expired at `now`.
+pub fn verify_playback(secret: &[u8], material_id: &str, expires: i64, signature: &str, now: i64) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    now <= expires && playback_mac(secret, material_id, expires).verify_slice(&signature).is_ok()
+}
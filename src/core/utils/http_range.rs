@@ -0,0 +1,62 @@
+use std::ops::RangeInclusive;
+
+use thiserror::Error;
+
+/// A `Range` header that cannot be served for an object of the given size (HTTP 416).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Range not satisfiable for {size} bytes")]
+pub struct RangeNotSatisfiable {
+    pub size: u64,
+}
+
+/// Resolves a `Range` header against an object of `size` bytes (RFC 9110 section 14).
+///
+/// Only single byte ranges are served; headers this does not understand, including
+/// multiple ranges, yield `Ok(None)` and the whole object is sent, as the RFC allows.
+pub fn parse_range(header: &str, size: u64) -> Result<Option<RangeInclusive<u64>>, RangeNotSatisfiable> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        // Suffix range: the last `n` bytes
+        ("", suffix) => {
+            let Ok(suffix) = suffix.parse::<u64>() else {
+                return Ok(None);
+            };
+            if suffix == 0 || size == 0 {
+                return Err(RangeNotSatisfiable { size });
+            }
+            size.saturating_sub(suffix)..=size - 1
+        }
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return Ok(None);
+            };
+            let end = match end {
+                "" => u64::MAX,
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => end,
+                    _ => return Ok(None),
+                },
+            };
+            if start >= size {
+                return Err(RangeNotSatisfiable { size });
+            }
+            start..=end.min(size - 1)
+        }
+    };
+
+    Ok(Some(range))
+}
+
+/// `Content-Range` value of a partial response.
+pub fn content_range(range: &RangeInclusive<u64>, size: u64) -> String {
+    format!("bytes {}-{}/{}", range.start(), range.end(), size)
+}
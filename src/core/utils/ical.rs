@@ -0,0 +1,134 @@
+use time::macros::format_description;
+use time::{OffsetDateTime, UtcOffset};
+
+/// Content lines longer than this many octets are folded (RFC 5545 section 3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+const PRODUCT_ID: &str = "-//OHS Backend//Calendar//EN";
+
+/// A single `VEVENT`.
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub uid: String,
+    /// Bumped whenever the event changes so clients replace their copy.
+    pub sequence: i64,
+    pub last_modified: OffsetDateTime,
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub cancelled: bool,
+}
+
+/// A `VCALENDAR` published to subscribers or downloaded as a single `.ics` file.
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    pub name: String,
+    pub time_zone: Option<&'static str>,
+    pub events: Vec<CalendarEvent>,
+}
+
+#[allow(unused)]
+impl Calendar {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            time_zone: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Hints the zone clients should display the calendar in; event times are always UTC.
+    pub fn with_time_zone(mut self, time_zone: &'static str) -> Self {
+        self.time_zone = Some(time_zone);
+        self
+    }
+
+    pub fn with_events(mut self, events: impl IntoIterator<Item = CalendarEvent>) -> Self {
+        self.events.extend(events);
+        self
+    }
+
+    /// Serializes the calendar with CRLF line endings and folded content lines.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        push_line(&mut out, "BEGIN:VCALENDAR");
+        push_line(&mut out, "VERSION:2.0");
+        push_line(&mut out, &format!("PRODID:{}", PRODUCT_ID));
+        push_line(&mut out, "CALSCALE:GREGORIAN");
+        push_line(&mut out, "METHOD:PUBLISH");
+        push_line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(&self.name)));
+        if let Some(time_zone) = self.time_zone {
+            push_line(&mut out, &format!("X-WR-TIMEZONE:{}", time_zone));
+        }
+
+        for event in &self.events {
+            push_line(&mut out, "BEGIN:VEVENT");
+            push_line(&mut out, &format!("UID:{}", escape_text(&event.uid)));
+            push_line(&mut out, &format!("SEQUENCE:{}", event.sequence.max(0)));
+            push_line(&mut out, &format!("DTSTAMP:{}", format_utc(event.last_modified)));
+            push_line(&mut out, &format!("LAST-MODIFIED:{}", format_utc(event.last_modified)));
+            push_line(&mut out, &format!("DTSTART:{}", format_utc(event.start)));
+            push_line(&mut out, &format!("DTEND:{}", format_utc(event.end)));
+            push_line(&mut out, &format!("SUMMARY:{}", escape_text(&event.summary)));
+            if let Some(description) = &event.description {
+                push_line(&mut out, &format!("DESCRIPTION:{}", escape_text(description)));
+            }
+            if let Some(url) = &event.url {
+                push_line(&mut out, &format!("URL:{}", url));
+                push_line(&mut out, &format!("LOCATION:{}", escape_text(url)));
+            }
+            let status = if event.cancelled { "CANCELLED" } else { "CONFIRMED" };
+            push_line(&mut out, &format!("STATUS:{}", status));
+            push_line(&mut out, "END:VEVENT");
+        }
+
+        push_line(&mut out, "END:VCALENDAR");
+        out
+    }
+}
+
+/// Formats an instant as a UTC `DATE-TIME` (`20250330T083000Z`).
+pub fn format_utc(date_time: OffsetDateTime) -> String {
+    date_time
+        .to_offset(UtcOffset::UTC)
+        .format(format_description!("[year][month][day]T[hour][minute][second]Z"))
+        .expect("UTC date-times always format")
+}
+
+/// Escapes a `TEXT` property value.
+pub fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Appends a content line, folding it so no physical line exceeds 75 octets.
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space of a continuation line counts towards its length
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+
+    out.push_str("\r\n");
+}
@@ -0,0 +1,144 @@
+use thiserror::Error;
+
+use super::file_type::matches_declared;
+
+const MIB: u64 = 1024 * 1024;
+
+/// Largest file any material may have; S3 takes at most 5 GiB in a single PUT.
+pub const MAX_MATERIAL_BYTES: u64 = 2048 * MIB;
+
+const MAX_FILE_NAME_LENGTH: usize = 200;
+
+/// What a training material file holds, mirroring `training_material_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaterialKind {
+    Video,
+    Pdf,
+    Slides,
+    Other,
+}
+
+impl MaterialKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MaterialKind::Video => "Video",
+            MaterialKind::Pdf => "PDF",
+            MaterialKind::Slides => "Slide deck",
+            MaterialKind::Other => "Other",
+        }
+    }
+
+    /// Content types accepted for the kind.
+    pub fn content_types(&self) -> &'static [&'static str] {
+        match self {
+            MaterialKind::Video => &["video/mp4", "video/webm", "video/quicktime"],
+            MaterialKind::Pdf => &["application/pdf"],
+            MaterialKind::Slides => &[
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+                "application/vnd.ms-powerpoint",
+                "application/vnd.oasis.opendocument.presentation",
+                "application/pdf",
+            ],
+            MaterialKind::Other => &[
+                "application/pdf",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                "application/vnd.oasis.opendocument.text",
+                "application/zip",
+                "image/jpeg",
+                "image/png",
+                "image/webp",
+                "text/plain",
+                "text/csv",
+            ],
+        }
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        match self {
+            MaterialKind::Video => MAX_MATERIAL_BYTES,
+            MaterialKind::Pdf => 100 * MIB,
+            MaterialKind::Slides => 200 * MIB,
+            MaterialKind::Other => 100 * MIB,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum UploadError {
+    #[error("{kind} materials cannot be of type {content_type:?}")]
+    ContentType { kind: &'static str, content_type: String },
+
+    #[error("The file is empty")]
+    Empty,
+
+    #[error("{kind} materials may be at most {max_bytes} bytes")]
+    TooLarge { kind: &'static str, max_bytes: u64 },
+
+    #[error("File names must have 1-{MAX_FILE_NAME_LENGTH} characters")]
+    FileName,
+
+    #[error("The file content is {}, not the declared {declared}", .actual.as_deref().unwrap_or("of an unknown type"))]
+    ContentMismatch { declared: String, actual: Option<String> },
+}
+
+/// Lowercases a content type and drops parameters such as `; charset=utf-8`.
+pub fn normalize_content_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Checks a file declared for upload against the limits of its kind, returning the normalized content type.
+pub fn check_upload(kind: MaterialKind, content_type: &str, size_bytes: u64) -> Result<String, UploadError> {
+    let content_type = normalize_content_type(content_type);
+    if !kind.content_types().contains(&content_type.as_str()) {
+        return Err(UploadError::ContentType {
+            kind: kind.label(),
+            content_type,
+        });
+    }
+    if size_bytes == 0 {
+        return Err(UploadError::Empty);
+    }
+    if size_bytes > kind.max_bytes() {
+        return Err(UploadError::TooLarge {
+            kind: kind.label(),
+            max_bytes: kind.max_bytes(),
+        });
+    }
+
+    Ok(content_type)
+}
+
+/// Keeps the last path segment of a client supplied file name, without control characters
+/// or quotes, so it can be echoed in a `Content-Disposition` header.
+pub fn sanitize_file_name(file_name: &str) -> Result<String, UploadError> {
+    let base = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let sanitized: String = base
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .collect::<String>()
+        .trim()
+        .to_string();
+
+    if sanitized.is_empty() || sanitized == "." || sanitized == ".." || sanitized.chars().count() > MAX_FILE_NAME_LENGTH
+    {
+        return Err(UploadError::FileName);
+    }
+    Ok(sanitized)
+}
+
+/// Checks the type sniffed from an uploaded file against the type it was declared and accepted as.
+pub fn check_content(declared: &str, sniffed: Option<&str>) -> Result<(), UploadError> {
+    match sniffed {
+        Some(sniffed) if matches_declared(declared, sniffed) => Ok(()),
+        _ => Err(UploadError::ContentMismatch {
+            declared: declared.to_string(),
+            actual: sniffed.map(str::to_string),
+        }),
+    }
+}
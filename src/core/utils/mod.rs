@@ -0,0 +1,22 @@
+pub mod appointment;
+pub mod attendance;
+pub mod certificate;
+pub mod compliance;
+pub mod consultation;
+pub mod csv;
+pub mod document;
+pub mod exif;
+pub mod file_type;
+pub mod hls;
+pub mod http_range;
+pub mod ical;
+pub mod material;
+pub mod pdf;
+pub mod progress;
+pub mod quiz;
+pub mod reminder;
+pub mod time_zone;
+pub mod training;
+pub mod turn;
+pub mod visit_summary;
+pub mod waitlist;
@@ -0,0 +1,62 @@
+use printpdf::{IndirectFontRef, PdfDocumentReference};
+use thiserror::Error;
+use ttf_parser::Face;
+
+pub const REGULAR_FONT: &[u8] = include_bytes!("../../../assets/fonts/DejaVuSans.ttf");
+pub const BOLD_FONT: &[u8] = include_bytes!("../../../assets/fonts/DejaVuSans-Bold.ttf");
+
+pub const PT_TO_MM: f32 = 25.4 / 72.0;
+
+#[derive(Debug, Error)]
+pub enum PdfError {
+    #[error("Failed to load font: {0}")]
+    Font(String),
+
+    #[error("Failed to write PDF: {0}")]
+    Write(#[from] printpdf::Error),
+}
+
+/// Font with the metrics needed to lay out centred and wrapped text.
+pub struct Typeface<'a> {
+    pub face: Face<'a>,
+    pub font: IndirectFontRef,
+}
+
+impl Typeface<'_> {
+    pub fn text_width(&self, text: &str, size: f32) -> f32 {
+        let units_per_em = f32::from(self.face.units_per_em());
+        let advance: f32 = text
+            .chars()
+            .map(|c| {
+                self.face
+                    .glyph_index(c)
+                    .and_then(|glyph| self.face.glyph_hor_advance(glyph))
+                    .map_or(units_per_em / 2.0, f32::from)
+            })
+            .sum();
+
+        advance / units_per_em * size * PT_TO_MM
+    }
+
+    /// Splits `text` into lines no wider than `max_width`, breaking between words.
+    pub fn wrap(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        for word in text.split_whitespace() {
+            match lines.last_mut() {
+                Some(line) if self.text_width(&format!("{} {}", line, word), size) <= max_width => {
+                    line.push(' ');
+                    line.push_str(word);
+                }
+                _ => lines.push(word.to_string()),
+            }
+        }
+        lines
+    }
+}
+
+/// Parses `bytes` for its metrics and embeds it into `doc`.
+pub fn load_typeface<'a>(doc: &PdfDocumentReference, bytes: &'a [u8]) -> Result<Typeface<'a>, PdfError> {
+    let face = Face::parse(bytes, 0).map_err(|e| PdfError::Font(e.to_string()))?;
+    let font = doc.add_external_font(bytes)?;
+    Ok(Typeface { face, font })
+}
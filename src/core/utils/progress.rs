@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+/// Fastest playback rate players offer; watching faster than this is not believed.
+pub const MAX_PLAYBACK_RATE: f64 = 2.0;
+
+/// Seconds of video a participant may claim before any wall time has passed, covering
+/// the span a player reports with its first ping.
+pub const WATCH_SLACK_SECONDS: f64 = 30.0;
+
+/// Gaps up to this long between watched ranges, as left by rounding in the pings, are closed.
+const MERGE_GAP_SECONDS: f64 = 1.0;
+
+/// A `[start, end)` span of a video in seconds.
+pub type WatchedRange = (f64, f64);
+
+/// Adds the span played since the last ping to `ranges`, returning them merged and sorted.
+///
+/// The span is cut to the video's duration, when known, and to what could have been watched
+/// at [`MAX_PLAYBACK_RATE`] since the first ping, so a client cannot claim a whole video at once.
+pub fn add_watched(
+    ranges: &[WatchedRange],
+    span: WatchedRange,
+    duration_seconds: Option<f64>,
+    since_first_ping: Duration,
+) -> Vec<WatchedRange> {
+    let mut merged = ranges.to_vec();
+
+    let (from, to) = span;
+    if from.is_finite() && to.is_finite() {
+        let from = from.max(0.0);
+        let to = duration_seconds.map_or(to, |duration| to.min(duration));
+        let budget = (since_first_ping.as_secs_f64() * MAX_PLAYBACK_RATE + WATCH_SLACK_SECONDS)
+            - watched_seconds(ranges);
+        let to = to.min(from + budget.max(0.0));
+        if to > from {
+            merged.push((from, to));
+        }
+    }
+
+    merged.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut result: Vec<WatchedRange> = Vec::with_capacity(merged.len());
+    for (start, end) in merged {
+        match result.last_mut() {
+            Some(last) if start <= last.1 + MERGE_GAP_SECONDS => last.1 = last.1.max(end),
+            _ => result.push((start, end)),
+        }
+    }
+    result
+}
+
+/// Seconds covered by merged ranges.
+pub fn watched_seconds(ranges: &[WatchedRange]) -> f64 {
+    ranges.iter().fold(0.0, |total, (start, end)| total + (end - start).max(0.0))
+}
+
+/// Resume position within `[0, duration]`; None for positions that are not numbers.
+pub fn resume_position(position_seconds: f64, duration_seconds: Option<f64>) -> Option<f64> {
+    if !position_seconds.is_finite() {
+        return None;
+    }
+    let position = position_seconds.max(0.0);
+    Some(duration_seconds.map_or(position, |duration| position.min(duration)))
+}
+
+/// Adds newly viewed pages, dropping those outside `1..=page_count`; the result is sorted.
+pub fn add_pages(viewed: &[i32], pages: &[i32], page_count: Option<i32>) -> Vec<i32> {
+    let mut result: Vec<i32> = viewed
+        .iter()
+        .chain(pages)
+        .copied()
+        .filter(|&page| page >= 1 && page_count.is_none_or(|count| page <= count))
+        .collect();
+    result.sort_unstable();
+    result.dedup();
+    result
+}
+
+/// Whole percent `part` is of `whole`, at most 100; zero when the whole is unknown.
+pub fn percent_of(part: f64, whole: f64) -> i32 {
+    if whole.is_nan() || whole <= 0.0 || !part.is_finite() {
+        return 0;
+    }
+    ((part / whole) * 100.0).floor().clamp(0.0, 100.0) as i32
+}
+
+/// What a participant consumed of a material so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Consumption {
+    pub watched_seconds: f64,
+    pub duration_seconds: Option<f64>,
+    pub pages_viewed: usize,
+    pub page_count: Option<i32>,
+}
+
+/// When a material counts as done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionRule {
+    /// At least this share of the video was watched.
+    Watched(i32),
+    /// At least this share of the pages was viewed.
+    PagesViewed(i32),
+    /// Opening the material is enough, for files without a notion of progress.
+    Opened,
+}
+
+impl CompletionRule {
+    /// Progress towards the rule, from 0 to 100.
+    pub fn percent(&self, consumption: &Consumption) -> i32 {
+        match self {
+            CompletionRule::Watched(_) => {
+                percent_of(consumption.watched_seconds, consumption.duration_seconds.unwrap_or(0.0))
+            }
+            CompletionRule::PagesViewed(_) => percent_of(
+                consumption.pages_viewed as f64,
+                f64::from(consumption.page_count.unwrap_or(0)),
+            ),
+            CompletionRule::Opened => 100,
+        }
+    }
+
+    pub fn is_met(&self, consumption: &Consumption) -> bool {
+        let percent = self.percent(consumption);
+        match self {
+            CompletionRule::Watched(min_percent) | CompletionRule::PagesViewed(min_percent) => {
+                percent > 0 && percent >= *min_percent
+            }
+            CompletionRule::Opened => true,
+        }
+    }
+}
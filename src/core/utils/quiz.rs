@@ -0,0 +1,389 @@
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const MIN_CHOICE_OPTIONS: usize = 2;
+const MAX_CHOICE_OPTIONS: usize = 10;
+const MAX_OPTION_KEY_LENGTH: usize = 20;
+const MAX_OPTION_TEXT_LENGTH: usize = 500;
+const MAX_ANSWER_TEXT_LENGTH: usize = 5000;
+const MAX_TAG_LENGTH: usize = 40;
+
+/// Separates the keys of a multiple choice answer in `correct_answer_key`.
+const KEY_SEPARATOR: char = ',';
+
+/// How a question is answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestionKind {
+    SingleChoice,
+    MultipleChoice,
+    TrueFalse,
+    FreeText,
+}
+
+/// One answer option of a choice question. Keys are chosen by the author and are what
+/// answers and the answer key refer to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuizOption {
+    pub key: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum QuestionError {
+    #[error("Choice questions need between {MIN_CHOICE_OPTIONS} and {MAX_CHOICE_OPTIONS} options")]
+    OptionCount,
+
+    #[error("Option keys must be 1-{MAX_OPTION_KEY_LENGTH} letters, digits, '-' or '_': {0:?}")]
+    InvalidKey(String),
+
+    #[error("Option key {0:?} is used more than once")]
+    DuplicateKey(String),
+
+    #[error("Option {0:?} needs a text of at most {MAX_OPTION_TEXT_LENGTH} characters")]
+    InvalidText(String),
+
+    #[error("{0} questions do not take options")]
+    UnexpectedOptions(&'static str),
+
+    #[error("Correct answer {0:?} is not one of the options")]
+    UnknownAnswer(String),
+
+    #[error("Single choice and true/false questions need exactly one correct answer")]
+    ExactlyOneAnswer,
+
+    #[error("Multiple choice questions need at least one correct answer")]
+    MissingAnswer,
+
+    #[error("Free text questions are graded manually and take no correct answer")]
+    UnexpectedAnswer,
+
+    #[error("Tags must be 1-{MAX_TAG_LENGTH} letters, digits, '-' or '_': {0:?}")]
+    InvalidTag(String),
+}
+
+/// A question's options and answer key in the form they are stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedQuestion {
+    pub options: Option<Vec<QuizOption>>,
+    pub correct_answer_key: Option<String>,
+}
+
+fn true_false_options() -> Vec<QuizOption> {
+    [("true", "True"), ("false", "False")]
+        .into_iter()
+        .map(|(key, text)| QuizOption {
+            key: key.to_string(),
+            text: text.to_string(),
+        })
+        .collect()
+}
+
+fn validate_options(options: Vec<QuizOption>) -> Result<Vec<QuizOption>, QuestionError> {
+    if !(MIN_CHOICE_OPTIONS..=MAX_CHOICE_OPTIONS).contains(&options.len()) {
+        return Err(QuestionError::OptionCount);
+    }
+
+    let mut validated: Vec<QuizOption> = Vec::with_capacity(options.len());
+    for option in options {
+        let key = option.key.trim().to_string();
+        let text = option.text.trim().to_string();
+
+        let valid_key = !key.is_empty()
+            && key.chars().count() <= MAX_OPTION_KEY_LENGTH
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_key {
+            return Err(QuestionError::InvalidKey(key));
+        }
+        if validated.iter().any(|existing| existing.key == key) {
+            return Err(QuestionError::DuplicateKey(key));
+        }
+        if text.is_empty() || text.chars().count() > MAX_OPTION_TEXT_LENGTH {
+            return Err(QuestionError::InvalidText(key));
+        }
+
+        validated.push(QuizOption { key, text });
+    }
+
+    Ok(validated)
+}
+
+/// Checks that the options and correct answers fit the question kind and returns them
+/// normalized: keys and texts trimmed, true/false options filled in, answers encoded.
+pub fn normalize_question(
+    kind: QuestionKind,
+    options: Option<Vec<QuizOption>>,
+    correct_answers: &[String],
+) -> Result<NormalizedQuestion, QuestionError> {
+    let options = match (kind, options) {
+        (QuestionKind::FreeText, Some(_)) => return Err(QuestionError::UnexpectedOptions("Free text")),
+        (QuestionKind::TrueFalse, Some(_)) => return Err(QuestionError::UnexpectedOptions("True/false")),
+        (QuestionKind::FreeText, None) => None,
+        (QuestionKind::TrueFalse, None) => Some(true_false_options()),
+        (_, options) => Some(validate_options(options.unwrap_or_default())?),
+    };
+
+    let mut answers: Vec<String> = correct_answers.iter().map(|answer| answer.trim().to_string()).collect();
+    answers.sort();
+    answers.dedup();
+
+    if let Some(options) = &options
+        && let Some(unknown) = answers.iter().find(|answer| !options.iter().any(|option| &option.key == *answer))
+    {
+        return Err(QuestionError::UnknownAnswer(unknown.clone()));
+    }
+
+    match kind {
+        QuestionKind::SingleChoice | QuestionKind::TrueFalse if answers.len() != 1 => {
+            return Err(QuestionError::ExactlyOneAnswer);
+        }
+        QuestionKind::MultipleChoice if answers.is_empty() => return Err(QuestionError::MissingAnswer),
+        QuestionKind::FreeText if !answers.is_empty() => return Err(QuestionError::UnexpectedAnswer),
+        _ => {}
+    }
+
+    Ok(NormalizedQuestion {
+        options,
+        correct_answer_key: (!answers.is_empty()).then(|| encode_answer_keys(&answers)),
+    })
+}
+
+/// Stored form of a set of option keys: sorted and comma separated.
+pub fn encode_answer_keys(keys: &[String]) -> String {
+    let mut keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys.join(&KEY_SEPARATOR.to_string())
+}
+
+/// Option keys of a stored answer key.
+pub fn decode_answer_keys(encoded: &str) -> Vec<String> {
+    encoded
+        .split(KEY_SEPARATOR)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AnswerError {
+    #[error("{0:?} is not one of the options of the question")]
+    UnknownOption(String),
+
+    #[error("Only one option can be chosen for this question")]
+    SingleChoiceOnly,
+
+    #[error("Choice questions are answered with option keys, not text")]
+    TextForChoice,
+
+    #[error("Free text questions are answered with text, not option keys")]
+    KeysForText,
+
+    #[error("Answers can be at most {MAX_ANSWER_TEXT_LENGTH} characters")]
+    TextTooLong,
+}
+
+/// A participant's answer in the form it is stored: option keys for choice questions,
+/// text for free text questions. Both are None for a question left blank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedAnswer {
+    pub answer_key: Option<String>,
+    pub answer_text: Option<String>,
+}
+
+/// Checks an answer against the question it is given for.
+pub fn normalize_answer(
+    kind: QuestionKind,
+    options: &[QuizOption],
+    keys: &[String],
+    text: Option<&str>,
+) -> Result<NormalizedAnswer, AnswerError> {
+    let text = text.map(str::trim).filter(|text| !text.is_empty());
+
+    if kind == QuestionKind::FreeText {
+        if !keys.is_empty() {
+            return Err(AnswerError::KeysForText);
+        }
+        if text.is_some_and(|text| text.chars().count() > MAX_ANSWER_TEXT_LENGTH) {
+            return Err(AnswerError::TextTooLong);
+        }
+        return Ok(NormalizedAnswer {
+            answer_key: None,
+            answer_text: text.map(str::to_string),
+        });
+    }
+
+    if text.is_some() {
+        return Err(AnswerError::TextForChoice);
+    }
+
+    let mut keys: Vec<String> = keys.iter().map(|key| key.trim().to_string()).collect();
+    keys.sort();
+    keys.dedup();
+
+    if let Some(unknown) = keys.iter().find(|key| !options.iter().any(|option| &option.key == *key)) {
+        return Err(AnswerError::UnknownOption(unknown.clone()));
+    }
+    if kind != QuestionKind::MultipleChoice && keys.len() > 1 {
+        return Err(AnswerError::SingleChoiceOnly);
+    }
+
+    Ok(NormalizedAnswer {
+        answer_key: (!keys.is_empty()).then(|| encode_answer_keys(&keys)),
+        answer_text: None,
+    })
+}
+
+/// Whether the chosen keys match the answer key exactly; multiple choice questions earn
+/// their points only when every correct option and no other is chosen.
+pub fn is_correct_choice(correct_answer_key: &str, answer_key: Option<&str>) -> bool {
+    answer_key.is_some_and(|answer| decode_answer_keys(answer) == decode_answer_keys(correct_answer_key))
+}
+
+/// Share of the points earned in percent, rounded to two decimals.
+pub fn score_percent(points_earned: i32, points_possible: i32) -> f64 {
+    if points_possible <= 0 {
+        return 0.0;
+    }
+    (f64::from(points_earned) * 10_000.0 / f64::from(points_possible)).round() / 100.0
+}
+
+/// Whether the points reach the pass threshold; compared exactly, without rounding.
+pub fn passes(points_earned: i32, points_possible: i32, pass_threshold_percent: i32) -> bool {
+    points_possible > 0
+        && i64::from(points_earned) * 100 >= i64::from(pass_threshold_percent) * i64::from(points_possible)
+}
+
+/// Whether a participant is shown which of their answers were right. Until they passed or used
+/// up their attempts, that would give the answer key away for the next try, so they only see
+/// their score.
+pub fn reveals_feedback(passed: bool, attempts_taken: usize, max_attempts: Option<i32>) -> bool {
+    passed || max_attempts.is_some_and(|max_attempts| attempts_taken >= max_attempts.max(0) as usize)
+}
+
+/// Stored form of a question tag: trimmed and lowercase, so that quotas match regardless of case.
+pub fn normalize_tag(tag: &str) -> Result<String, QuestionError> {
+    let normalized = tag.trim().to_lowercase();
+    let valid = !normalized.is_empty()
+        && normalized.chars().count() <= MAX_TAG_LENGTH
+        && normalized.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(QuestionError::InvalidTag(tag.trim().to_string()));
+    }
+    Ok(normalized)
+}
+
+/// How the questions of an attempt are chosen from the quiz.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuestionDraw {
+    /// Every question, in the authored order.
+    All,
+    /// This many questions picked at random, in random order.
+    Random(usize),
+    /// This many questions per tag picked at random, mixed in random order.
+    PerTag(BTreeMap<String, usize>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DrawError {
+    #[error("Set either a number of questions per attempt or tag quotas, not both")]
+    ConflictingDraw,
+
+    #[error("Tag quotas must draw at least one question per tag: {0:?}")]
+    InvalidQuota(String),
+
+    #[error("The quiz draws {requested} questions per attempt but has only {available}")]
+    NotEnoughQuestions { requested: usize, available: usize },
+
+    #[error("The quiz draws {requested} questions tagged {tag:?} but has only {available}")]
+    NotEnoughTagged {
+        tag: String,
+        requested: usize,
+        available: usize,
+    },
+}
+
+impl DrawError {
+    /// Whether the draw cannot be made from the questions of the quiz, as opposed to being
+    /// configured wrongly.
+    pub fn is_pool_too_small(&self) -> bool {
+        matches!(self, DrawError::NotEnoughQuestions { .. } | DrawError::NotEnoughTagged { .. })
+    }
+}
+
+/// Checks the draw settings of a quiz and returns the tag quotas with normalized tags.
+pub fn normalize_draw(
+    questions_per_attempt: Option<i32>,
+    tag_quotas: Option<BTreeMap<String, i32>>,
+) -> Result<Option<BTreeMap<String, i32>>, DrawError> {
+    let Some(tag_quotas) = tag_quotas.filter(|quotas| !quotas.is_empty()) else {
+        return Ok(None);
+    };
+    if questions_per_attempt.is_some() {
+        return Err(DrawError::ConflictingDraw);
+    }
+
+    let mut normalized = BTreeMap::new();
+    for (tag, count) in tag_quotas {
+        let key = normalize_tag(&tag).map_err(|_| DrawError::InvalidQuota(tag.clone()))?;
+        if count < 1 || normalized.insert(key, count).is_some() {
+            return Err(DrawError::InvalidQuota(tag));
+        }
+    }
+    Ok(Some(normalized))
+}
+
+/// Picks the questions of an attempt from the pool, which is in authored order.
+pub fn draw_questions<'a, T, R: Rng + ?Sized>(
+    pool: &'a [T],
+    tag_of: impl Fn(&T) -> Option<&str>,
+    draw: &QuestionDraw,
+    rng: &mut R,
+) -> Result<Vec<&'a T>, DrawError> {
+    let mut drawn: Vec<&T> = match draw {
+        QuestionDraw::All => return Ok(pool.iter().collect()),
+        QuestionDraw::Random(requested) => {
+            if *requested > pool.len() {
+                return Err(DrawError::NotEnoughQuestions {
+                    requested: *requested,
+                    available: pool.len(),
+                });
+            }
+            let mut candidates: Vec<&T> = pool.iter().collect();
+            candidates.shuffle(rng);
+            candidates.truncate(*requested);
+            candidates
+        }
+        QuestionDraw::PerTag(quotas) => {
+            let mut drawn = Vec::new();
+            for (tag, requested) in quotas {
+                let mut candidates: Vec<&T> = pool.iter().filter(|question| tag_of(question) == Some(tag)).collect();
+                if *requested > candidates.len() {
+                    return Err(DrawError::NotEnoughTagged {
+                        tag: tag.clone(),
+                        requested: *requested,
+                        available: candidates.len(),
+                    });
+                }
+                candidates.shuffle(rng);
+                drawn.extend(candidates.into_iter().take(*requested));
+            }
+            drawn
+        }
+    };
+
+    drawn.shuffle(rng);
+    Ok(drawn)
+}
+
+/// Order in which an attempt shows the options of a question; true/false keeps its order.
+pub fn shuffled_option_keys<R: Rng + ?Sized>(kind: QuestionKind, options: &[QuizOption], rng: &mut R) -> Vec<String> {
+    let mut keys: Vec<String> = options.iter().map(|option| option.key.clone()).collect();
+    if kind != QuestionKind::TrueFalse {
+        keys.shuffle(rng);
+    }
+    keys
+}
@@ -0,0 +1,33 @@
+use time::{Duration, OffsetDateTime};
+
+use super::time_zone::Zone;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// When a reminder `offset_minutes` before `starts_at` is due for a recipient in `zone`.
+///
+/// Whole-day offsets keep the wall-clock time ("one day before, 10:00"), so they stay correct
+/// across daylight saving transitions; shorter offsets are plain durations.
+pub fn remind_at(starts_at: OffsetDateTime, offset_minutes: i64, zone: Zone) -> OffsetDateTime {
+    if offset_minutes % MINUTES_PER_DAY == 0 {
+        zone.shift_days(starts_at, -(offset_minutes / MINUTES_PER_DAY))
+    } else {
+        starts_at - Duration::minutes(offset_minutes)
+    }
+}
+
+/// The reminders of an event still ahead of `now` for a recipient in `zone`, as the offset in
+/// minutes with the time it is due. Offsets that are not positive are ignored.
+pub fn reminder_schedule(
+    starts_at: OffsetDateTime,
+    offsets_minutes: &[i64],
+    zone: Zone,
+    now: OffsetDateTime,
+) -> Vec<(i64, OffsetDateTime)> {
+    offsets_minutes
+        .iter()
+        .filter(|&&offset_minutes| offset_minutes > 0)
+        .map(|&offset_minutes| (offset_minutes, remind_at(starts_at, offset_minutes, zone)))
+        .filter(|&(_, remind_at)| remind_at > now)
+        .collect()
+}
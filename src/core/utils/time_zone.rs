@@ -0,0 +1,126 @@
+use serde::Deserialize;
+use thiserror::Error;
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime};
+use time_tz::{timezones, Offset, OffsetDateTimeExt, OffsetResult, PrimitiveDateTimeExt, TimeZone, Tz};
+
+/// Zone used when neither the user nor their company has one configured.
+pub const DEFAULT_TIME_ZONE: &str = "UTC";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TimeZoneError {
+    #[error("Unknown time zone: {0}")]
+    Unknown(String),
+
+    #[error("{0} does not exist in {1} because of a daylight saving transition")]
+    NonexistentLocalTime(PrimitiveDateTime, &'static str),
+}
+
+/// An IANA time zone from the bundled tz database.
+#[derive(Clone, Copy)]
+pub struct Zone(&'static Tz);
+
+impl std::fmt::Debug for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Zone").field(&self.name()).finish()
+    }
+}
+
+impl PartialEq for Zone {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for Zone {}
+
+#[allow(unused)]
+impl Zone {
+    pub fn parse(name: &str) -> Result<Self, TimeZoneError> {
+        timezones::get_by_name(name.trim())
+            .map(Zone)
+            .ok_or_else(|| TimeZoneError::Unknown(name.to_string()))
+    }
+
+    pub fn utc() -> Self {
+        Self::parse(DEFAULT_TIME_ZONE).expect("UTC is always part of the tz database")
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Expresses an instant with the offset in effect in this zone at that instant.
+    pub fn localize(&self, date_time: OffsetDateTime) -> OffsetDateTime {
+        date_time.to_timezone(self.0)
+    }
+
+    /// The calendar date of an instant as seen in this zone.
+    pub fn local_date(&self, date_time: OffsetDateTime) -> Date {
+        self.localize(date_time).date()
+    }
+
+    /// Resolves a wall-clock time entered by a user.
+    ///
+    /// Times repeated by a backward transition resolve to their first occurrence; times skipped
+    /// by a forward transition are rejected, since the user asked for something that never
+    /// shows on a clock in this zone.
+    pub fn resolve_local(&self, local: PrimitiveDateTime) -> Result<OffsetDateTime, TimeZoneError> {
+        match local.assume_timezone(self.0) {
+            OffsetResult::Some(date_time) => Ok(date_time),
+            OffsetResult::Ambiguous(first, second) => Ok(first.min(second)),
+            OffsetResult::None => Err(TimeZoneError::NonexistentLocalTime(local, self.name())),
+        }
+    }
+
+    /// Resolves a wall-clock time produced by a rule (recurrences, reminders).
+    ///
+    /// Same as [`Zone::resolve_local`], except that a time inside a forward-transition gap is
+    /// pushed forward by the length of the gap (02:30 becomes 03:30 when clocks jump from
+    /// 02:00 to 03:00), so a recurring event never silently disappears.
+    pub fn resolve_local_lenient(&self, local: PrimitiveDateTime) -> OffsetDateTime {
+        self.resolve_local(local).unwrap_or_else(|_| {
+            let offset_before_gap = self
+                .0
+                .get_offset_utc(&(local - Duration::days(1)).assume_utc())
+                .to_utc();
+            local.assume_offset(offset_before_gap)
+        })
+    }
+
+    /// Moves an instant by whole calendar days while keeping its wall-clock time in this zone.
+    ///
+    /// "One day before 10:00" stays at 10:00 across a DST change, whereas subtracting 24 hours
+    /// would land on 09:00 or 11:00.
+    pub fn shift_days(&self, date_time: OffsetDateTime, days: i64) -> OffsetDateTime {
+        let local = self.localize(date_time);
+        let shifted = PrimitiveDateTime::new(local.date(), local.time()) + Duration::days(days);
+        self.resolve_local_lenient(shifted)
+    }
+}
+
+time::serde::format_description!(
+    local_date_time,
+    PrimitiveDateTime,
+    "[year]-[month]-[day]T[hour]:[minute][optional [:[second]]]"
+);
+
+/// A date-time sent by a client.
+///
+/// Values with an explicit offset (RFC 3339) are taken as-is; values without one are read as
+/// wall-clock time in the caller's zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ClientDateTime {
+    Absolute(#[serde(with = "time::serde::rfc3339")] OffsetDateTime),
+    Local(#[serde(with = "local_date_time")] PrimitiveDateTime),
+}
+
+#[allow(unused)]
+impl ClientDateTime {
+    pub fn resolve(self, zone: Zone) -> Result<OffsetDateTime, TimeZoneError> {
+        match self {
+            ClientDateTime::Absolute(date_time) => Ok(date_time),
+            ClientDateTime::Local(local) => zone.resolve_local(local),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use sqlx::types::Uuid;
+use thiserror::Error;
+
+/// Where a training session is in its life cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStage {
+    Scheduled,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum TransitionError {
+    #[error("Training session has not been published")]
+    NotPublished,
+    #[error("Cannot move a training session from {0:?} to {1:?}")]
+    NotAllowed(SessionStage, SessionStage),
+}
+
+/// Sessions move `scheduled -> in_progress -> completed`; only unfinished ones can be cancelled.
+/// Drafts cannot run, but may be called off before they were ever published.
+pub fn check_transition(published: bool, from: SessionStage, to: SessionStage) -> Result<(), TransitionError> {
+    if !published && to != SessionStage::Cancelled {
+        return Err(TransitionError::NotPublished);
+    }
+    let allowed = matches!(
+        (from, to),
+        (SessionStage::Scheduled, SessionStage::InProgress)
+            | (SessionStage::InProgress, SessionStage::Completed)
+            | (SessionStage::Scheduled, SessionStage::Cancelled)
+            | (SessionStage::InProgress, SessionStage::Cancelled)
+    );
+    if !allowed {
+        return Err(TransitionError::NotAllowed(from, to));
+    }
+    Ok(())
+}
+
+/// A caller as far as access to training sessions goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionViewer {
+    pub user_id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub super_admin: bool,
+    pub tenant_admin: bool,
+    pub ohs_specialist: bool,
+}
+
+/// What access to a training session depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionScope {
+    pub tenant_id: Uuid,
+    pub host_user_id: Uuid,
+    pub published: bool,
+}
+
+impl SessionViewer {
+    /// Tenant admins manage every session of their tenant, OHS specialists the ones they host.
+    pub fn can_manage(&self, session: &SessionScope) -> bool {
+        self.tenant_id == Some(session.tenant_id)
+            && (self.tenant_admin || (self.ohs_specialist && session.host_user_id == self.user_id))
+    }
+
+    /// Drafts are limited to those who manage them; super admins see every session.
+    pub fn can_view(&self, session: &SessionScope) -> bool {
+        if self.super_admin {
+            return true;
+        }
+        self.tenant_id == Some(session.tenant_id) && (session.published || self.can_manage(session))
+    }
+}
@@ -0,0 +1,33 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha1::Sha1;
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+
+/// A TURN username and password that coturn accepts until `expires_at`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TurnCredential {
+    pub username: String,
+    pub credential: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+/// Issues a credential under the TURN REST API scheme coturn implements with
+/// `use-auth-secret`: the username is `{expiry}:{user id}` and the password is the base64
+/// HMAC-SHA1 of the username keyed with the shared secret. coturn checks both without asking
+/// us, and refuses the username once the expiry has passed.
+pub fn rest_credential(shared_secret: &[u8], user_id: Uuid, expires_at: OffsetDateTime) -> TurnCredential {
+    // coturn only sees whole seconds
+    let expires_at = expires_at.replace_nanosecond(0).unwrap_or(expires_at);
+    let username = format!("{}:{}", expires_at.unix_timestamp(), user_id);
+    let mut mac = Hmac::<Sha1>::new_from_slice(shared_secret).expect("HMAC accepts keys of any length");
+    mac.update(username.as_bytes());
+
+    TurnCredential {
+        credential: STANDARD.encode(mac.finalize().into_bytes()),
+        username,
+        expires_at,
+    }
+}
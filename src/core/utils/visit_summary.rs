@@ -0,0 +1,139 @@
+use printpdf::path::PaintMode;
+use printpdf::{Color, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Rect, Rgb};
+
+use super::pdf::{load_typeface, PdfError, Typeface, BOLD_FONT, PT_TO_MM, REGULAR_FONT};
+
+// A4 portrait
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const TEXT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+const LINE_SPACING: f32 = 1.4;
+
+const ACCENT: (u8, u8, u8) = (0x0F, 0x76, 0x6E);
+const BLACK: (u8, u8, u8) = (0x1F, 0x29, 0x37);
+const GREY: (u8, u8, u8) = (0x6B, 0x72, 0x80);
+
+/// What a visit summary says; all values are printed as given.
+#[derive(Debug, Clone)]
+pub struct VisitSummaryContent {
+    pub appointment_label: String,
+    pub local_start: String,
+    pub time_zone: String,
+    pub reason_for_visit: Option<String>,
+    /// Label and text of each section shared with the employee, in template order.
+    pub sections: Vec<(String, String)>,
+    pub signed_at: String,
+    pub version: i32,
+}
+
+/// Writes lines top to bottom, starting a new page when the current one is full.
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: Typeface<'static>,
+    bold: Typeface<'static>,
+    y: f32,
+}
+
+impl Writer {
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Summary");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Moves to a new page unless `height` still fits on the current one.
+    fn keep(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn fill(&self, (r, g, b): (u8, u8, u8)) {
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(
+            f32::from(r) / 255.0,
+            f32::from(g) / 255.0,
+            f32::from(b) / 255.0,
+            None,
+        )));
+    }
+
+    fn line(&mut self, text: &str, size: f32, bold: bool, color: (u8, u8, u8)) {
+        let height = size * PT_TO_MM * LINE_SPACING;
+        self.keep(height);
+        self.y -= height;
+
+        self.fill(color);
+        let typeface = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(MARGIN), Mm(self.y), &typeface.font);
+    }
+
+    /// Wraps `text` to the text width; line breaks in it are kept and blank lines become gaps.
+    fn paragraph(&mut self, text: &str, size: f32, bold: bool, color: (u8, u8, u8)) {
+        for source_line in text.lines() {
+            let typeface = if bold { &self.bold } else { &self.regular };
+            let lines = typeface.wrap(source_line, size, TEXT_WIDTH);
+            if lines.is_empty() {
+                self.y -= size * PT_TO_MM * LINE_SPACING;
+            }
+            for line in lines {
+                self.line(&line, size, bold, color);
+            }
+        }
+    }
+
+    fn rule(&mut self) {
+        self.y -= 1.5;
+        self.fill((0xE5, 0xE7, 0xEB));
+        self.layer.add_rect(
+            Rect::new(Mm(MARGIN), Mm(self.y - 0.3), Mm(PAGE_WIDTH - MARGIN), Mm(self.y)).with_mode(PaintMode::Fill),
+        );
+        self.y -= 1.5;
+    }
+}
+
+/// Renders a visit summary as an A4 portrait PDF, running onto further pages as needed.
+pub fn render_visit_summary(content: &VisitSummaryContent) -> Result<Vec<u8>, PdfError> {
+    let (doc, page, layer) = PdfDocument::new(
+        format!("Visit summary - {}", content.appointment_label),
+        Mm(PAGE_WIDTH),
+        Mm(PAGE_HEIGHT),
+        "Summary",
+    );
+    let mut writer = Writer {
+        layer: doc.get_page(page).get_layer(layer),
+        regular: load_typeface(&doc, REGULAR_FONT)?,
+        bold: load_typeface(&doc, BOLD_FONT)?,
+        doc,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    writer.paragraph("Visit summary", 20.0, true, ACCENT);
+    writer.y -= 2.0;
+    writer.paragraph(&content.appointment_label, 11.0, false, GREY);
+    writer.paragraph(&format!("{} ({})", content.local_start, content.time_zone), 11.0, false, GREY);
+    if let Some(reason) = &content.reason_for_visit {
+        writer.paragraph(&format!("Reason for visit: {}", reason), 11.0, false, GREY);
+    }
+
+    for (label, text) in &content.sections {
+        writer.y -= 6.0;
+        // Keeps a heading together with the first lines of its section
+        writer.keep(12.0 * PT_TO_MM * LINE_SPACING + 3.0 + 2.0 * 10.5 * PT_TO_MM * LINE_SPACING);
+        writer.paragraph(label, 12.0, true, BLACK);
+        writer.rule();
+        writer.paragraph(text, 10.5, false, BLACK);
+    }
+
+    writer.y -= 8.0;
+    writer.paragraph(
+        &format!("Signed on {}. Version {}.", content.signed_at, content.version),
+        9.0,
+        false,
+        GREY,
+    );
+
+    Ok(writer.doc.save_to_bytes()?)
+}
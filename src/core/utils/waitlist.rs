@@ -0,0 +1,30 @@
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+
+/// Seats still free in a session when `seats_taken` are held; None when it has no participant limit.
+pub fn open_seats(max_participants: Option<i32>, seats_taken: i64) -> Option<i64> {
+    max_participants.map(|max_participants| (i64::from(max_participants) - seats_taken).max(0))
+}
+
+/// Whether a new enrollment has to wait for a seat.
+pub fn must_wait(open_seats: Option<i64>) -> bool {
+    open_seats.is_some_and(|seats| seats <= 0)
+}
+
+/// A waitlisted enrollment, as the queue sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuedEnrollment {
+    pub id: Uuid,
+    pub enrolled_at: Option<OffsetDateTime>,
+}
+
+/// The waitlisted enrollments that move onto `open_seats`: first come, first served, with the id
+/// settling enrollments of the same instant. Everyone moves up when there is no limit.
+pub fn promotion_order(waitlist: &[QueuedEnrollment], open_seats: Option<i64>) -> Vec<Uuid> {
+    let mut queue = waitlist.to_vec();
+    // Like the database, enrollments without a time go last
+    queue.sort_by_key(|enrollment| (enrollment.enrolled_at.is_none(), enrollment.enrolled_at, enrollment.id));
+
+    let take = open_seats.map_or(queue.len(), |seats| usize::try_from(seats).unwrap_or(0));
+    queue.into_iter().take(take).map(|enrollment| enrollment.id).collect()
+}
@@ -1,4 +1,5 @@
-mod repositories;
+pub mod repositories;
+pub mod rls;
 mod models;
 mod error;
 
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use time::{Date, OffsetDateTime};
+
+use crate::core::utils::time_zone::ClientDateTime;
+
+#[derive(Debug, Deserialize)]
+pub struct AppointmentAnalyticsQuery {
+    pub from: Option<ClientDateTime>,
+    pub to: Option<ClientDateTime>,
+    pub company_id: Option<Uuid>,
+    pub professional_user_id: Option<Uuid>,
+}
+
+/// Resolved scope of the appointment analytics queries.
+#[derive(Debug, Clone, Copy)]
+pub struct AppointmentAnalyticsScope {
+    pub tenant_id: Uuid,
+    pub from: OffsetDateTime,
+    pub to: OffsetDateTime,
+    pub company_id: Option<Uuid>,
+    pub professional_user_id: Option<Uuid>,
+}
+
+/// Bookings made in the week starting on `week_start` (Monday, in the caller's zone).
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct WeeklyBookings {
+    pub week_start: Date,
+    pub bookings: i64,
+}
+
+/// Outcome counts of the appointments of one professional or company.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct AppointmentOutcomeRates {
+    pub id: Uuid,
+    pub total: i64,
+    pub cancelled: i64,
+    pub no_show: i64,
+    pub cancellation_rate: f64,
+    pub no_show_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppointmentAnalytics {
+    pub time_zone: &'static str,
+    pub bookings_per_week: Vec<WeeklyBookings>,
+    pub by_professional: Vec<AppointmentOutcomeRates>,
+    pub by_company: Vec<AppointmentOutcomeRates>,
+    /// Average time between booking and appointment start, in hours.
+    pub average_lead_time_hours: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CallMinutesQuery {
+    pub from: Option<ClientDateTime>,
+    pub to: Option<ClientDateTime>,
+    pub tenant_id: Option<Uuid>,  // Super admins only
+    pub professional_user_id: Option<Uuid>,
+}
+
+/// Resolved scope of the call minutes report; no tenant means every tenant.
+#[derive(Debug, Clone, Copy)]
+pub struct CallMinutesScope {
+    pub tenant_id: Option<Uuid>,
+    pub from: OffsetDateTime,
+    pub to: OffsetDateTime,
+    pub professional_user_id: Option<Uuid>,
+}
+
+/// Finished calls of one tenant or professional.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct CallMinutes {
+    pub id: Uuid,
+    pub calls: i64,
+    pub total_seconds: i64,
+    pub total_minutes: f64,
+    pub average_minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallMinutesReport {
+    pub time_zone: &'static str,
+    #[serde(with = "time::serde::rfc3339")]
+    pub from: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub to: OffsetDateTime,
+    pub by_tenant: Vec<CallMinutes>,
+    pub by_professional: Vec<CallMinutes>,
+}
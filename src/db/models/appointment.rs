@@ -1,20 +1,77 @@
 use serde::{Deserialize, Serialize};
 use sqlx::types::Uuid;
-use time::{OffsetDateTime, Duration};
+use time::{Date, OffsetDateTime, Time, Duration};
 use validator::Validate;
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+use crate::core::utils::appointment::VisitStage;
+use crate::core::utils::time_zone::{ClientDateTime, Zone};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "appointment_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum AppointmentStatus {
-    Scheduled,
+    Pending,
     Confirmed,
+    CancelledByProfessional,
+    CancelledByEmployee,
+    Completed,
+    NoShow,
+}
+
+#[allow(unused)]
+impl AppointmentStatus {
+    pub fn is_cancelled(&self) -> bool {
+        matches!(
+            self,
+            AppointmentStatus::CancelledByProfessional | AppointmentStatus::CancelledByEmployee
+        )
+    }
+
+    /// Pending and confirmed appointments still hold their slot.
+    pub fn is_active(&self) -> bool {
+        matches!(self, AppointmentStatus::Pending | AppointmentStatus::Confirmed)
+    }
+}
+
+impl From<AppointmentStatus> for VisitStage {
+    fn from(status: AppointmentStatus) -> Self {
+        match status {
+            AppointmentStatus::Pending => VisitStage::Pending,
+            AppointmentStatus::Confirmed => VisitStage::Confirmed,
+            AppointmentStatus::CancelledByProfessional | AppointmentStatus::CancelledByEmployee => {
+                VisitStage::Cancelled
+            }
+            AppointmentStatus::Completed => VisitStage::Completed,
+            AppointmentStatus::NoShow => VisitStage::NoShow,
+        }
+    }
+}
+
+/// What the professional records once an appointment was due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppointmentOutcome {
     Completed,
-    Cancelled,
     NoShow,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+impl From<AppointmentOutcome> for AppointmentStatus {
+    fn from(outcome: AppointmentOutcome) -> Self {
+        match outcome {
+            AppointmentOutcome::Completed => AppointmentStatus::Completed,
+            AppointmentOutcome::NoShow => AppointmentStatus::NoShow,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordAppointmentOutcome {
+    pub outcome: AppointmentOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "appointment_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum AppointmentType {
     OhsConsultation,
     MedicalCheckup,
@@ -23,46 +80,171 @@ pub enum AppointmentType {
 #[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
 pub struct Appointment {
     pub id: Uuid,
-    pub employee_id: Uuid,
-    pub specialist_id: Uuid,  // Either OhsSpecialist or Doctor
+    pub tenant_id: Uuid,
+    pub company_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub professional_user_id: Uuid,  // Either OhsSpecialist or Doctor
+    pub appointment_type: AppointmentType,
+    pub start_time: OffsetDateTime,
+    pub end_time: OffsetDateTime,
+    pub status: AppointmentStatus,
+    pub reason_for_visit: Option<String>,
+    pub notes_by_professional: Option<String>,
+    pub call_session_id: Option<String>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// An appointment as returned by the API, with its times expressed in the caller's zone.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppointmentResponse {
+    pub id: Uuid,
     pub company_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub professional_user_id: Uuid,
     pub appointment_type: AppointmentType,
     pub status: AppointmentStatus,
+    #[serde(with = "time::serde::rfc3339")]
     pub start_time: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
     pub end_time: OffsetDateTime,
-    pub notes: Option<String>,
-    pub created_at: OffsetDateTime,
-    pub updated_at: OffsetDateTime,
-    pub cancelled_by: Option<Uuid>,
-    pub cancellation_reason: Option<String>,
+    pub time_zone: &'static str,
+    pub reason_for_visit: Option<String>,
+    pub call_session_id: Option<String>,
+}
+
+impl AppointmentResponse {
+    pub fn new(appointment: Appointment, zone: Zone) -> Self {
+        Self {
+            id: appointment.id,
+            company_id: appointment.company_id,
+            employee_user_id: appointment.employee_user_id,
+            professional_user_id: appointment.professional_user_id,
+            appointment_type: appointment.appointment_type,
+            status: appointment.status,
+            start_time: zone.localize(appointment.start_time),
+            end_time: zone.localize(appointment.end_time),
+            time_zone: zone.name(),
+            reason_for_visit: appointment.reason_for_visit,
+            call_session_id: appointment.call_session_id,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
 pub struct NewAppointment {
-    pub employee_id: Uuid,
-    pub specialist_id: Uuid,
-    pub company_id: Uuid,
+    pub professional_user_id: Uuid,
     pub appointment_type: AppointmentType,
-    pub start_time: OffsetDateTime,
+    pub start_time: ClientDateTime,
+    #[validate(range(min = 5, max = 480))]
     pub duration_minutes: i64,  // Will be used to calculate end_time
-    pub notes: Option<String>,
+    pub reason_for_visit: Option<String>,
 }
 
 #[allow(unused)]
 impl NewAppointment {
-    pub fn end_time(&self) -> OffsetDateTime {
-        self.start_time + Duration::minutes(self.duration_minutes)
+    pub fn end_time(&self, start_time: OffsetDateTime) -> OffsetDateTime {
+        start_time + Duration::minutes(self.duration_minutes)
     }
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
-pub struct UpdateAppointment {
+pub struct RescheduleAppointment {
+    pub start_time: ClientDateTime,
+    #[validate(range(min = 5, max = 480))]
+    pub duration_minutes: Option<i64>,  // Keeps the current duration when omitted
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AppointmentHistoryQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub from: Option<ClientDateTime>,
+    pub to: Option<ClientDateTime>,
+    pub status: Option<AppointmentStatus>,
+    pub appointment_type: Option<AppointmentType>,
+    pub employee_user_id: Option<Uuid>,
+    pub professional_user_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+}
+
+/// Resolved filters of an appointment history listing.
+#[derive(Debug, Clone)]
+pub struct AppointmentHistoryFilter {
+    pub from: Option<OffsetDateTime>,
+    pub to: OffsetDateTime,
     pub status: Option<AppointmentStatus>,
-    pub start_time: Option<OffsetDateTime>,
-    pub end_time: Option<OffsetDateTime>,
-    pub notes: Option<String>,
-    pub cancelled_by: Option<Uuid>,
-    pub cancellation_reason: Option<String>,
-} 
\ No newline at end of file
+    pub appointment_type: Option<AppointmentType>,
+    pub employee_user_id: Option<Uuid>,
+    pub professional_user_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(unused)]
+pub struct ScheduleRangeQuery {
+    pub from: Option<ClientDateTime>,
+    pub to: Option<ClientDateTime>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ProfessionalAvailability {
+    pub id: Uuid,
+    pub professional_user_id: Uuid,
+    pub tenant_id: Uuid,
+    pub start_time: OffsetDateTime,
+    pub end_time: OffsetDateTime,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct NewProfessionalAvailability {
+    pub start_time: ClientDateTime,
+    pub end_time: ClientDateTime,
+}
+
+time::serde::format_description!(wall_clock_time, Time, "[hour]:[minute]");
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct RecurringAvailability {
+    pub id: Uuid,
+    pub professional_user_id: Uuid,
+    pub tenant_id: Uuid,
+    pub weekday: i16,  // ISO 8601: 1 = Monday
+    #[serde(with = "wall_clock_time")]
+    pub local_start_time: Time,
+    #[serde(with = "wall_clock_time")]
+    pub local_end_time: Time,
+    pub time_zone: String,
+    pub valid_from: Date,
+    pub valid_until: Option<Date>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct NewRecurringAvailability {
+    #[validate(range(min = 1, max = 7))]
+    pub weekday: i16,
+    #[serde(with = "wall_clock_time")]
+    pub local_start_time: Time,
+    #[serde(with = "wall_clock_time")]
+    pub local_end_time: Time,
+    pub time_zone: Option<String>,  // Defaults to the caller's zone
+    pub valid_from: Date,
+    pub valid_until: Option<Date>,
+}
+
+/// A concrete bookable window, either a one-off availability or an expanded weekly rule.
+#[derive(Debug, Clone, Serialize)]
+pub struct AvailabilitySlot {
+    #[serde(with = "time::serde::rfc3339")]
+    pub start_time: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub end_time: OffsetDateTime,
+    pub time_zone: &'static str,
+    pub recurring_availability_id: Option<Uuid>,
+}
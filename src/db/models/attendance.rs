@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::core::utils::attendance::Presence;
+use crate::db::{ParticipantStatus, TrainingEnrollment};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "attendance_event_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AttendanceEventType {
+    Join,
+    Leave,
+}
+
+impl From<AttendanceEventType> for Presence {
+    fn from(event_type: AttendanceEventType) -> Self {
+        match event_type {
+            AttendanceEventType::Join => Presence::Join,
+            AttendanceEventType::Leave => Presence::Leave,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
+pub struct AttendanceEvent {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub training_session_id: Uuid,
+    pub enrollment_id: Uuid,
+    pub user_id: Uuid,
+    pub event_type: AttendanceEventType,
+    pub source: String,  // 'client', 'websocket' or 'media_room'
+    pub occurred_at: OffsetDateTime,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// Manual attendance decision by the host or a tenant admin; takes precedence over the events.
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct AttendanceOverride {
+    pub attended: bool,
+    #[validate(length(max = 1000))]
+    pub note: Option<String>,
+}
+
+/// Attendance of one enrollment as shown to the host.
+#[derive(Debug, Clone, Serialize)]
+pub struct AttendanceRecord {
+    pub enrollment_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub status: ParticipantStatus,
+    pub attended: bool,
+    pub attended_minutes: i32,
+    pub overridden: bool,
+    pub override_note: Option<String>,
+}
+
+impl From<TrainingEnrollment> for AttendanceRecord {
+    fn from(enrollment: TrainingEnrollment) -> Self {
+        Self {
+            enrollment_id: enrollment.id,
+            employee_user_id: enrollment.employee_user_id,
+            status: enrollment.status,
+            attended: enrollment.attended.unwrap_or(false),
+            attended_minutes: enrollment.attended_minutes.unwrap_or(0),
+            overridden: enrollment.attendance_overridden_at.is_some(),
+            override_note: enrollment.attendance_override_note,
+        }
+    }
+}
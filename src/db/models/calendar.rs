@@ -0,0 +1,20 @@
+use serde::Serialize;
+use sqlx::types::Uuid;
+
+use crate::db::UserRole;
+
+/// The user a calendar feed token belongs to.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CalendarFeedOwner {
+    pub user_id: Uuid,
+    pub tenant_id: Uuid,
+    pub company_id: Option<Uuid>,
+    pub roles: Vec<UserRole>,  // Within the tenant of the token
+}
+
+/// Returned once when a feed token is created; the plain token is never stored.
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarFeedTokenResponse {
+    pub token: String,
+    pub feed_url: String,
+}
@@ -0,0 +1,33 @@
+use serde::Serialize;
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+
+/// A call between the participants of an appointment; open until `end_time` is set.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct CallLog {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub appointment_id: Option<Uuid>,
+    pub initiator_user_id: Uuid,
+    pub receiver_user_id: Uuid,
+    pub professional_user_id: Option<Uuid>,
+    pub start_time: OffsetDateTime,
+    pub end_time: Option<OffsetDateTime>,
+    pub duration_seconds: Option<i32>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// A call that was just answered.
+#[derive(Debug, Clone)]
+pub struct NewCallLog {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub appointment_id: Uuid,
+    pub initiator_user_id: Uuid,
+    pub receiver_user_id: Uuid,
+    pub professional_user_id: Uuid,
+    pub start_time: OffsetDateTime,
+    pub metadata: serde_json::Value,
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use time::{Date, OffsetDateTime};
+use validator::Validate;
+
+use crate::core::storage::tenant_key;
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct TrainingCertificate {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub enrollment_id: Uuid,
+    pub training_session_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub verification_code: String,
+    pub tenant_name: String,  // Printed details are a snapshot taken when the certificate was issued
+    pub company_name: String,
+    pub employee_name: String,
+    pub training_title: String,
+    pub host_name: Option<String>,
+    pub completed_at: OffsetDateTime,
+    pub storage_key: Option<String>,  // None until the PDF has been rendered and stored
+    pub render_attempts: i32,
+    pub last_render_error: Option<String>,
+    pub issued_at: OffsetDateTime,
+    pub rendered_at: Option<OffsetDateTime>,
+    pub revoked_at: Option<OffsetDateTime>,
+    pub revoked_reason: Option<String>,
+    pub revoked_by_user_id: Option<Uuid>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+impl TrainingCertificate {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn object_key(&self) -> String {
+        tenant_key(self.tenant_id, &format!("certificates/{}.pdf", self.id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainingCertificateResponse {
+    pub id: Uuid,
+    pub training_session_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub employee_name: String,
+    pub training_title: String,
+    pub verification_code: String,
+    pub verification_url: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub completed_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub issued_at: OffsetDateTime,
+    pub ready: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub revoked_at: Option<OffsetDateTime>,
+    pub revoked_reason: Option<String>,
+}
+
+impl TrainingCertificateResponse {
+    pub fn new(certificate: TrainingCertificate, public_url: &str) -> Self {
+        Self {
+            verification_url: certificate_verification_url(public_url, &certificate.verification_code),
+            ready: certificate.storage_key.is_some(),
+            id: certificate.id,
+            training_session_id: certificate.training_session_id,
+            employee_user_id: certificate.employee_user_id,
+            employee_name: certificate.employee_name,
+            training_title: certificate.training_title,
+            verification_code: certificate.verification_code,
+            completed_at: certificate.completed_at,
+            issued_at: certificate.issued_at,
+            revoked_at: certificate.revoked_at,
+            revoked_reason: certificate.revoked_reason,
+        }
+    }
+}
+
+/// Public page a certificate's QR code points to.
+pub fn certificate_verification_url(public_url: &str, verification_code: &str) -> String {
+    format!("{}/certificates/verify/{}", public_url, verification_code)
+}
+
+/// Result of queueing certificates for a session's completed enrollments.
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateIssueResult {
+    pub issued: usize,
+    pub already_issued: usize,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RevokeCertificate {
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateValidity {
+    Valid,
+    Revoked,
+}
+
+/// What the public verification reveals about a certificate; the holder is only named by
+/// first name and initial.
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateVerification {
+    pub verification_code: String,
+    pub status: CertificateValidity,
+    pub holder: String,
+    pub training_title: String,
+    pub completed_on: Date,
+    pub issued_by: String,
+    pub revoked_on: Option<Date>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerificationQuery {
+    pub code: Option<String>,
+}
@@ -5,6 +5,7 @@ use validator::Validate;
 
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "company_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum CompanyStatus {
     Active,
     Inactive,
@@ -12,20 +13,16 @@ pub enum CompanyStatus {
 }
 
 #[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
 pub struct Company {
     pub id: Uuid,
+    pub tenant_id: Uuid,
     pub name: String,
+    pub description: Option<String>,
     pub status: CompanyStatus,
-    pub address: Option<String>,
-    pub city: Option<String>,
-    pub state: Option<String>,
-    pub country: Option<String>,
-    pub postal_code: Option<String>,
-    pub website: Option<String>,
-    pub phone_number: Option<String>,
-    pub logo_url: Option<String>,
-    pub created_at: OffsetDateTime,
-    pub updated_at: OffsetDateTime,
+    pub time_zone: String,  // IANA name, e.g. "Europe/Istanbul"
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -33,26 +30,20 @@ pub struct Company {
 pub struct NewCompany {
     #[validate(length(min = 1))]
     pub name: String,
-    pub address: Option<String>,
-    pub city: Option<String>,
-    pub state: Option<String>,
-    pub country: Option<String>,
-    pub postal_code: Option<String>,
-    pub website: Option<String>,
-    pub phone_number: Option<String>,
+    pub description: Option<String>,
+    pub time_zone: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
 pub struct UpdateCompany {
     pub name: Option<String>,
+    pub description: Option<String>,
     pub status: Option<CompanyStatus>,
-    pub address: Option<String>,
-    pub city: Option<String>,
-    pub state: Option<String>,
-    pub country: Option<String>,
-    pub postal_code: Option<String>,
-    pub website: Option<String>,
-    pub phone_number: Option<String>,
-    pub logo_url: Option<String>,
-} 
\ No newline at end of file
+    pub time_zone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCompanyTimeZone {
+    pub time_zone: String,
+}
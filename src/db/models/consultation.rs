@@ -0,0 +1,117 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::{Json, Uuid};
+use time::OffsetDateTime;
+use validator::{Validate, ValidationError};
+
+use super::AppointmentType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "consultation_note_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ConsultationNoteStatus {
+    Draft,
+    Signed,
+}
+
+/// One structured section of a consultation note template.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct TemplateSection {
+    #[validate(length(min = 1, max = 64))]
+    pub key: String,
+    #[validate(length(min = 1, max = 200))]
+    pub label: String,
+    #[serde(default)]
+    pub required: bool,
+    /// Whether the section appears in the summary the employee can download.
+    #[serde(default)]
+    pub shared_with_employee: bool,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct ConsultationNoteTemplate {
+    pub id: Uuid,
+    pub tenant_id: Option<Uuid>,  // None for system defaults
+    pub appointment_type: AppointmentType,
+    pub name: String,
+    pub sections: Json<Vec<TemplateSection>>,
+    pub is_active: bool,
+    pub created_by_user_id: Option<Uuid>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+fn validate_unique_section_keys(sections: &[TemplateSection]) -> Result<(), ValidationError> {
+    let mut keys = HashSet::new();
+
+    if sections.iter().all(|section| keys.insert(section.key.as_str())) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("duplicate_section_key"))
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct NewConsultationNoteTemplate {
+    pub appointment_type: AppointmentType,
+    #[validate(length(min = 1, max = 200))]
+    pub name: String,
+    #[validate(length(min = 1, max = 50), nested, custom(function = "validate_unique_section_keys"))]
+    pub sections: Vec<TemplateSection>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateQuery {
+    pub appointment_type: Option<AppointmentType>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct ConsultationNote {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub appointment_id: Uuid,
+    pub template_id: Uuid,
+    pub author_user_id: Uuid,
+    pub status: ConsultationNoteStatus,
+    pub current_version: i32,
+    pub signed_at: Option<OffsetDateTime>,
+    pub signed_by_user_id: Option<Uuid>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct ConsultationNoteVersion {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub version: i32,
+    pub content: Json<BTreeMap<String, String>>,
+    pub edited_by_user_id: Uuid,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// Saves a new version of the note of an appointment, creating the note on first save.
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct SaveConsultationNote {
+    /// Only honoured when the note is created; defaults to the tenant's template for the type.
+    pub template_id: Option<Uuid>,
+    /// Version the edit is based on; a mismatch means someone else saved in between.
+    pub expected_version: Option<i32>,
+    pub content: BTreeMap<String, String>,
+}
+
+/// A note together with its template and latest content.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsultationNoteResponse {
+    #[serde(flatten)]
+    pub note: ConsultationNote,
+    pub template_name: String,
+    pub sections: Vec<TemplateSection>,
+    pub content: BTreeMap<String, String>,
+}
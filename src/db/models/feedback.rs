@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use time::{Date, OffsetDateTime};
+use validator::Validate;
+
+use crate::core::utils::time_zone::ClientDateTime;
+use crate::db::TrainingEnrollment;
+
+/// Feedback of a participant on a session they attended.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmitTrainingFeedback {
+    #[validate(range(min = 1, max = 5))]
+    pub rating: i16,
+    #[validate(length(max = 2000))]
+    pub comment: Option<String>,
+}
+
+/// A participant's own feedback.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainingFeedback {
+    pub training_session_id: Uuid,
+    pub rating: Option<i16>,
+    pub comment: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub submitted_at: Option<OffsetDateTime>,
+}
+
+impl From<TrainingEnrollment> for TrainingFeedback {
+    fn from(enrollment: TrainingEnrollment) -> Self {
+        Self {
+            training_session_id: enrollment.training_session_id,
+            rating: enrollment.feedback_rating,
+            comment: enrollment.feedback_text,
+            submitted_at: enrollment.feedback_submitted_at,
+        }
+    }
+}
+
+/// Rating counts of a session; `participants` are those who attended, and could respond.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct FeedbackSummary {
+    pub participants: i64,
+    pub responses: i64,
+    pub average_rating: Option<f64>,
+    pub rating_1: i64,
+    pub rating_2: i64,
+    pub rating_3: i64,
+    pub rating_4: i64,
+    pub rating_5: i64,
+}
+
+/// A comment left with a rating; hosts see comments without who wrote them.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct FeedbackComment {
+    pub rating: i16,
+    pub comment: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub submitted_at: Option<OffsetDateTime>,
+}
+
+/// The feedback of one session, for its host and tenant admins.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionFeedback {
+    pub training_session_id: Uuid,
+    #[serde(flatten)]
+    pub summary: FeedbackSummary,
+    pub comments: Vec<FeedbackComment>,
+}
+
+/// What the rows of a feedback report stand for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackGrouping {
+    #[default]
+    Session,
+    Host,
+    Title,
+}
+
+/// Splits a feedback report by the week or month the sessions started in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriod {
+    Week,
+    Month,
+}
+
+impl ReportPeriod {
+    /// Unit for `date_trunc`.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            ReportPeriod::Week => "week",
+            ReportPeriod::Month => "month",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedbackReportQuery {
+    pub from: Option<ClientDateTime>,
+    pub to: Option<ClientDateTime>,
+    #[serde(default)]
+    pub group_by: FeedbackGrouping,
+    pub period: Option<ReportPeriod>,
+    pub host_user_id: Option<Uuid>,
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+/// Resolved scope of a feedback report.
+#[derive(Debug, Clone, Copy)]
+pub struct FeedbackReportScope {
+    pub tenant_id: Uuid,
+    pub from: OffsetDateTime,
+    pub to: OffsetDateTime,
+    pub host_user_id: Option<Uuid>,
+    pub group_by: FeedbackGrouping,
+    pub period: Option<ReportPeriod>,
+}
+
+/// One row of a feedback report; the fields identifying the row depend on the grouping.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct FeedbackReportRow {
+    pub period_start: Option<Date>,
+    pub training_session_id: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub session_start: Option<OffsetDateTime>,
+    pub training_title: Option<String>,
+    pub host_user_id: Option<Uuid>,
+    pub host_name: Option<String>,
+    pub sessions: i64,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub summary: FeedbackSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedbackReport {
+    pub time_zone: &'static str,
+    pub group_by: FeedbackGrouping,
+    pub period: Option<ReportPeriod>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub from: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub to: OffsetDateTime,
+    pub rows: Vec<FeedbackReportRow>,
+}
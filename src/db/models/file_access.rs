@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "file_resource_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FileResourceType {
+    TrainingMaterial,
+    SafetyReportAttachment,
+    TrainingCertificate,
+}
+
+/// A download link handed out to a user.
+#[derive(Debug, Clone)]
+pub struct FileAccessEntry {
+    pub tenant_id: Uuid,
+    pub user_id: Uuid,
+    pub resource_type: FileResourceType,
+    pub resource_id: Uuid,
+    pub object_key: String,
+    pub byte_range: Option<String>,
+    pub user_agent: Option<String>,
+}
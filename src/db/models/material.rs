@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::core::storage::{tenant_key, PresignedUrl};
+use crate::core::utils::material::MaterialKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "training_material_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MaterialType {
+    Video,
+    Pdf,
+    Slides,
+    Other,
+}
+
+impl From<MaterialType> for MaterialKind {
+    fn from(material_type: MaterialType) -> Self {
+        match material_type {
+            MaterialType::Video => MaterialKind::Video,
+            MaterialType::Pdf => MaterialKind::Pdf,
+            MaterialType::Slides => MaterialKind::Slides,
+            MaterialType::Other => MaterialKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "material_processing_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MaterialProcessingStatus {
+    Pending,
+    Processing,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct TrainingMaterial {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub uploader_user_id: Uuid,
+    pub training_session_id: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub material_type: MaterialType,
+    #[serde(skip)]
+    pub file_s3_key: String,
+    pub file_size_bytes: Option<i64>,
+    pub content_type: Option<String>,
+    pub file_name: Option<String>,
+    pub processing_status: Option<MaterialProcessingStatus>,  // None for materials that need no processing
+    pub processing_attempts: i32,
+    pub processing_error: Option<String>,
+    #[serde(skip)]
+    pub processing_started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub processed_at: Option<OffsetDateTime>,
+    pub hls_renditions: Option<Vec<String>>,
+    pub duration_seconds: Option<f64>,
+    #[serde(skip)]
+    pub poster_key: Option<String>,
+    pub page_count: Option<i32>,  // Counted at upload for documents and slide decks
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+impl TrainingMaterial {
+    /// Key of a file produced by processing the material, such as an HLS playlist.
+    pub fn rendition_key(&self, path: &str) -> String {
+        tenant_key(self.tenant_id, &format!("renditions/{}/{}", self.id, path))
+    }
+}
+
+/// Links to stream a processed video; the playlists carry their own signatures.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaterialPlayback {
+    pub master_playlist_url: String,
+    pub poster_url: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub renditions: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+/// A file the uploader declares before sending it straight to storage.
+#[derive(Debug, Deserialize, Validate)]
+pub struct NewMaterialUpload {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    pub material_type: MaterialType,
+    pub training_session_id: Option<Uuid>,
+    pub file_name: String,
+    pub content_type: String,
+    #[validate(range(min = 1))]
+    pub size_bytes: i64,
+}
+
+/// A declared upload, pending until confirmed or quarantined.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct MaterialUpload {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub uploader_user_id: Uuid,
+    pub training_session_id: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub material_type: MaterialType,
+    #[serde(skip)]
+    pub object_key: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+    pub material_id: Option<Uuid>,
+    /// Set while a confirmation checks the stored file.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub confirm_started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub confirmed_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub quarantined_at: Option<OffsetDateTime>,
+    pub quarantine_reason: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// `related_entity_type` of notifications about an upload.
+pub const NOTIFICATION_ENTITY_MATERIAL_UPLOAD: &str = "training_material_upload";
+
+/// Where and how to send the file; confirm the upload once the PUT succeeded.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaterialUploadResponse {
+    pub upload_id: Uuid,
+    pub upload: PresignedUrl,
+}
@@ -4,6 +4,20 @@ mod appointment;
 mod training;
 mod safety_report;
 mod notification;
+mod calendar;
+mod reminder;
+mod consultation;
+mod pagination;
+mod analytics;
+mod requirement;
+mod attendance;
+mod certificate;
+mod quiz;
+mod feedback;
+mod material;
+mod file_access;
+mod progress;
+mod call_log;
 
 #[allow(unused)]
 pub use user::*;
@@ -16,4 +30,32 @@ pub use training::*;
 #[allow(unused)]
 pub use safety_report::*;
 #[allow(unused)]
-pub use notification::*; 
\ No newline at end of file
+pub use notification::*;
+#[allow(unused)]
+pub use calendar::*;
+#[allow(unused)]
+pub use reminder::*;
+#[allow(unused)]
+pub use consultation::*;
+#[allow(unused)]
+pub use pagination::*;
+#[allow(unused)]
+pub use analytics::*;
+#[allow(unused)]
+pub use requirement::*;
+#[allow(unused)]
+pub use attendance::*;
+#[allow(unused)]
+pub use certificate::*;
+#[allow(unused)]
+pub use quiz::*;
+#[allow(unused)]
+pub use feedback::*;
+#[allow(unused)]
+pub use material::*;
+#[allow(unused)]
+pub use file_access::*;
+#[allow(unused)]
+pub use progress::*;
+#[allow(unused)]
+pub use call_log::*;
@@ -3,8 +3,9 @@ use sqlx::types::Uuid;
 use time::OffsetDateTime;
 use validator::Validate;
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "notification_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum NotificationType {
     AppointmentReminder,
     AppointmentConfirmed,
@@ -18,32 +19,59 @@ pub enum NotificationType {
 }
 
 #[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
 pub struct Notification {
     pub id: Uuid,
     pub user_id: Uuid,
+    pub tenant_id: Option<Uuid>,
     pub notification_type: NotificationType,
     pub title: String,
     pub message: String,
-    pub is_read: bool,
-    pub reference_id: Option<Uuid>,  // Related entity ID (appointment, training, etc.)
-    pub reference_type: Option<String>,  // Type of the referenced entity
-    pub created_at: OffsetDateTime,
+    pub related_entity_id: Option<Uuid>,  // Related entity ID (appointment, training, etc.)
+    pub related_entity_type: Option<String>,  // Type of the referenced entity
+    pub is_read: Option<bool>,
     pub read_at: Option<OffsetDateTime>,
-    pub expires_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
 pub struct NewNotification {
     pub user_id: Uuid,
+    pub tenant_id: Option<Uuid>,
     pub notification_type: NotificationType,
     #[validate(length(min = 1))]
     pub title: String,
     #[validate(length(min = 1))]
     pub message: String,
-    pub reference_id: Option<Uuid>,
-    pub reference_type: Option<String>,
-    pub expires_at: Option<OffsetDateTime>,
+    pub related_entity_id: Option<Uuid>,
+    pub related_entity_type: Option<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct PushToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub device_name: Option<String>,
+    pub last_used_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct RegisterPushToken {
+    #[validate(length(min = 1, max = 255))]
+    pub token: String,
+    #[validate(length(max = 100))]
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(unused)]
+pub struct RemovePushToken {
+    pub token: String,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::{Json, Uuid};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::core::utils::progress::WatchedRange;
+use crate::db::{MaterialType, ParticipantStatus};
+
+/// How far a participant got through one material of a self-paced session.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+#[allow(unused)]
+pub struct MaterialProgress {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub training_session_id: Uuid,
+    pub enrollment_id: Uuid,
+    pub material_id: Uuid,
+    pub user_id: Uuid,
+    pub position_seconds: Option<f64>,  // Where playback resumes
+    pub watched_ranges: Json<Vec<WatchedRange>>,
+    pub watched_seconds: f64,
+    #[serde(skip)]
+    pub duration_seconds: Option<f64>,  // Reported by the player while the video is not processed
+    pub current_page: Option<i32>,  // Where reading resumes
+    pub pages_viewed: Vec<i32>,
+    pub page_count: Option<i32>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_ping_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// Progress reported by a player or document viewer, every few seconds while it is open.
+#[derive(Debug, Deserialize, Validate)]
+pub struct MaterialProgressPing {
+    pub position_seconds: Option<f64>,
+    pub watched: Option<WatchedRange>,  // Span played since the last ping, as [from, to] seconds
+    #[validate(range(min = 1.0, max = 86400.0))]
+    pub duration_seconds: Option<f64>,
+    #[validate(range(min = 1))]
+    pub current_page: Option<i32>,
+    #[validate(length(max = 10000))]
+    pub pages_viewed: Option<Vec<i32>>,  // Pages shown since the last ping
+}
+
+/// Values written by a progress ping.
+#[derive(Debug, Clone)]
+pub struct MaterialProgressFields {
+    pub position_seconds: Option<f64>,
+    pub watched_ranges: Vec<WatchedRange>,
+    pub watched_seconds: f64,
+    pub duration_seconds: Option<f64>,
+    pub current_page: Option<i32>,
+    pub pages_viewed: Vec<i32>,
+    pub page_count: Option<i32>,
+    pub completed: bool,  // Once set, the material stays completed
+}
+
+/// Progress of the caller on a material, with the share done under the session's rule.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaterialProgressResponse {
+    #[serde(flatten)]
+    pub progress: MaterialProgress,
+    pub percent: i32,
+    pub enrollment_status: ParticipantStatus,
+}
+
+/// Progress on one material of a session, as listed for the session.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaterialProgressSummary {
+    pub material_id: Uuid,
+    pub title: String,
+    pub material_type: MaterialType,
+    pub percent: i32,
+    pub position_seconds: Option<f64>,
+    pub current_page: Option<i32>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+}
+
+/// Progress of one participant through the materials of a session.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnrollmentProgress {
+    pub enrollment_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub status: ParticipantStatus,
+    pub completed_materials: usize,
+    pub total_materials: usize,
+    pub materials: Vec<MaterialProgressSummary>,
+}
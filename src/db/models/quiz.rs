@@ -0,0 +1,461 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::{Json, Uuid};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::core::utils::quiz::{decode_answer_keys, QuestionDraw, QuestionKind, QuizOption};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "quiz_question_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum QuizQuestionType {
+    SingleChoice,
+    MultipleChoice,
+    TrueFalse,
+    FreeText,
+}
+
+impl From<QuizQuestionType> for QuestionKind {
+    fn from(question_type: QuizQuestionType) -> Self {
+        match question_type {
+            QuizQuestionType::SingleChoice => QuestionKind::SingleChoice,
+            QuizQuestionType::MultipleChoice => QuestionKind::MultipleChoice,
+            QuizQuestionType::TrueFalse => QuestionKind::TrueFalse,
+            QuizQuestionType::FreeText => QuestionKind::FreeText,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
+pub struct TrainingQuiz {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub training_session_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub pass_threshold_percent: i32,  // Share of the total points needed to pass
+    pub time_limit_minutes: Option<i32>,  // None for no limit
+    pub max_attempts: Option<i32>,  // None for unlimited
+    pub questions_per_attempt: Option<i32>,  // Random questions drawn per attempt
+    pub tag_quotas: Option<Json<BTreeMap<String, i32>>>,  // Random questions drawn per tag
+    pub created_by_user_id: Uuid,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[allow(unused)]
+impl TrainingQuiz {
+    /// How the questions of an attempt are chosen.
+    pub fn question_draw(&self) -> QuestionDraw {
+        if let Some(quotas) = &self.tag_quotas {
+            return QuestionDraw::PerTag(
+                quotas.0.iter().map(|(tag, count)| (tag.clone(), (*count).max(0) as usize)).collect(),
+            );
+        }
+        match self.questions_per_attempt {
+            Some(count) => QuestionDraw::Random(count.max(0) as usize),
+            None => QuestionDraw::All,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
+pub struct QuizQuestion {
+    pub id: Uuid,
+    pub quiz_id: Uuid,
+    pub tenant_id: Uuid,
+    pub question_text: String,
+    pub question_type: QuizQuestionType,
+    pub options: Option<Json<Vec<QuizOption>>>,  // None for free text questions
+    pub correct_answer_key: Option<String>,  // Comma separated for multiple choice; None for free text
+    pub points: i32,
+    pub position: i32,
+    pub tag: Option<String>,  // Groups questions for per-tag draws
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// Creates or replaces the quiz of a training session.
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct NewTrainingQuiz {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(max = 5000))]
+    pub description: Option<String>,
+    #[validate(range(min = 1, max = 100))]
+    pub pass_threshold_percent: Option<i32>,  // Defaults to 70
+    #[validate(range(min = 1, max = 600))]
+    pub time_limit_minutes: Option<i32>,
+    #[validate(range(min = 1, max = 20))]
+    pub max_attempts: Option<i32>,
+    /// Draw this many random questions per attempt instead of asking all of them.
+    #[validate(range(min = 1, max = 200))]
+    pub questions_per_attempt: Option<i32>,
+    /// Draw this many random questions per tag instead; excludes `questions_per_attempt`.
+    pub tag_quotas: Option<BTreeMap<String, i32>>,
+}
+
+/// Creates or replaces a question; options and answers are checked against the type.
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct NewQuizQuestion {
+    #[validate(length(min = 1, max = 2000))]
+    pub question_text: String,
+    pub question_type: QuizQuestionType,
+    /// Required for single and multiple choice, fixed for true/false, absent for free text.
+    pub options: Option<Vec<QuizOption>>,
+    /// Keys of the correct options; empty for free text questions.
+    #[serde(default)]
+    pub correct_answers: Vec<String>,
+    #[validate(range(min = 1, max = 100))]
+    pub points: Option<i32>,  // Defaults to 1
+    pub tag: Option<String>,
+}
+
+/// The new order of all questions of a quiz.
+#[derive(Debug, Deserialize)]
+pub struct ReorderQuizQuestions {
+    pub question_ids: Vec<Uuid>,
+}
+
+/// A question as seen by its authors, answer key included.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuizQuestionResponse {
+    pub id: Uuid,
+    pub question_text: String,
+    pub question_type: QuizQuestionType,
+    pub options: Option<Vec<QuizOption>>,
+    pub correct_answers: Vec<String>,
+    pub points: i32,
+    pub position: i32,
+    pub tag: Option<String>,
+}
+
+impl From<QuizQuestion> for QuizQuestionResponse {
+    fn from(question: QuizQuestion) -> Self {
+        Self {
+            id: question.id,
+            correct_answers: question
+                .correct_answer_key
+                .as_deref()
+                .map(decode_answer_keys)
+                .unwrap_or_default(),
+            question_text: question.question_text,
+            question_type: question.question_type,
+            options: question.options.map(|options| options.0),
+            points: question.points,
+            position: question.position,
+            tag: question.tag,
+        }
+    }
+}
+
+/// A quiz with its questions, for its authors.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuizResponse {
+    #[serde(flatten)]
+    pub quiz: TrainingQuiz,
+    pub total_points: i32,
+    pub questions: Vec<QuizQuestionResponse>,
+}
+
+impl QuizResponse {
+    pub fn new(quiz: TrainingQuiz, questions: Vec<QuizQuestion>) -> Self {
+        Self {
+            quiz,
+            total_points: questions.iter().map(|question| question.points).sum(),
+            questions: questions.into_iter().map(QuizQuestionResponse::from).collect(),
+        }
+    }
+}
+
+/// What participants see of a quiz before taking it: no questions, no answers.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuizSummary {
+    pub id: Uuid,
+    pub training_session_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub pass_threshold_percent: i32,
+    pub time_limit_minutes: Option<i32>,
+    pub max_attempts: Option<i32>,
+    pub question_count: usize,  // Questions per attempt
+    pub total_points: Option<i32>,  // None when it depends on the questions drawn
+}
+
+impl QuizSummary {
+    pub fn new(quiz: TrainingQuiz, questions: &[QuizQuestion]) -> Self {
+        let pool_points: i32 = questions.iter().map(|question| question.points).sum();
+        let uniform_points = questions
+            .first()
+            .map(|first| first.points)
+            .filter(|points| questions.iter().all(|question| question.points == *points));
+        let (question_count, total_points) = match quiz.question_draw() {
+            QuestionDraw::All => (questions.len(), Some(pool_points)),
+            QuestionDraw::Random(count) => (count, uniform_points.map(|points| points * count as i32)),
+            QuestionDraw::PerTag(quotas) => {
+                let count = quotas.values().sum::<usize>();
+                (count, uniform_points.map(|points| points * count as i32))
+            }
+        };
+
+        Self {
+            id: quiz.id,
+            training_session_id: quiz.training_session_id,
+            title: quiz.title,
+            description: quiz.description,
+            pass_threshold_percent: quiz.pass_threshold_percent,
+            time_limit_minutes: quiz.time_limit_minutes,
+            max_attempts: quiz.max_attempts,
+            question_count,
+            total_points,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "quiz_attempt_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum QuizAttemptStatus {
+    InProgress,
+    PendingReview,  // Submitted; free text answers await grading
+    Graded,
+}
+
+/// A question drawn for an attempt, with the order its options are shown in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttemptLayoutEntry {
+    pub question_id: Uuid,
+    #[serde(default)]
+    pub option_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
+pub struct QuizAttempt {
+    pub id: Uuid,
+    pub quiz_id: Uuid,
+    pub enrollment_id: Option<Uuid>,
+    pub employee_user_id: Uuid,
+    pub tenant_id: Uuid,
+    pub company_id: Uuid,
+    pub attempt_number: i32,
+    pub status: QuizAttemptStatus,
+    pub started_at: Option<OffsetDateTime>,
+    pub expires_at: Option<OffsetDateTime>,  // None when the quiz has no time limit
+    pub completed_at: Option<OffsetDateTime>,  // When the attempt was submitted
+    pub score: Option<f64>,  // Percent of the points, set once graded
+    pub passed: Option<bool>,
+    pub points_earned: Option<i32>,
+    pub points_possible: Option<i32>,
+    pub layout: Json<Vec<AttemptLayoutEntry>>,  // Empty for attempts that asked every question
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[allow(unused)]
+impl QuizAttempt {
+    /// The questions of this attempt in the order they were shown, options reordered and
+    /// positions renumbered; attempts without a layout asked every question as authored.
+    pub fn arrange(&self, questions: Vec<QuizQuestion>) -> Vec<QuizQuestion> {
+        if self.layout.0.is_empty() {
+            return questions;
+        }
+
+        let mut by_id: HashMap<Uuid, QuizQuestion> =
+            questions.into_iter().map(|question| (question.id, question)).collect();
+        let mut arranged = Vec::with_capacity(self.layout.0.len());
+        for entry in &self.layout.0 {
+            let Some(mut question) = by_id.remove(&entry.question_id) else {
+                continue;
+            };
+            if let Some(options) = &mut question.options {
+                options.0.sort_by_key(|option| {
+                    entry.option_keys.iter().position(|key| *key == option.key).unwrap_or(usize::MAX)
+                });
+            }
+            question.position = arranged.len() as i32 + 1;
+            arranged.push(question);
+        }
+        arranged
+    }
+
+    /// Whether the time limit ran out at `now`, allowing `grace` for answers in flight.
+    pub fn is_expired(&self, now: OffsetDateTime, grace: time::Duration) -> bool {
+        self.expires_at.is_some_and(|expires_at| now > expires_at + grace)
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
+pub struct QuizAttemptAnswer {
+    pub id: Uuid,
+    pub attempt_id: Uuid,
+    pub question_id: Uuid,
+    pub tenant_id: Uuid,
+    pub answer_key: Option<String>,  // Chosen option keys, comma separated
+    pub answer_text: Option<String>,  // Free text answers
+    pub is_correct: Option<bool>,
+    pub points_awarded: Option<i32>,  // None until scored; free text waits for grading
+    pub graded_by_user_id: Option<Uuid>,
+    pub graded_at: Option<OffsetDateTime>,
+    pub submitted_at: Option<OffsetDateTime>,
+}
+
+/// One answer of a participant; choice questions take option keys, free text questions text.
+#[derive(Debug, Deserialize)]
+pub struct QuizAnswerInput {
+    pub question_id: Uuid,
+    #[serde(default)]
+    pub answer_keys: Vec<String>,
+    pub answer_text: Option<String>,
+}
+
+/// Saves answers of an attempt in progress; answering a question again replaces the answer.
+#[derive(Debug, Deserialize)]
+pub struct SubmitQuizAnswers {
+    pub answers: Vec<QuizAnswerInput>,
+}
+
+/// Points a grader awards to a free text answer, at most the question's points.
+#[derive(Debug, Deserialize, Validate)]
+pub struct GradeQuizAnswer {
+    #[validate(range(min = 0, max = 100))]
+    pub points_awarded: i32,
+}
+
+/// A free text answer waiting for a grader.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct PendingQuizAnswer {
+    pub answer_id: Uuid,
+    pub attempt_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub attempt_number: i32,
+    pub question_id: Uuid,
+    pub question_text: String,
+    pub max_points: i32,
+    pub answer_text: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub submitted_at: Option<OffsetDateTime>,
+}
+
+/// A question as shown to participants during an attempt: no answer key.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuizAttemptQuestion {
+    pub id: Uuid,
+    pub question_text: String,
+    pub question_type: QuizQuestionType,
+    pub options: Option<Vec<QuizOption>>,
+    pub points: i32,
+    pub position: i32,
+}
+
+impl From<QuizQuestion> for QuizAttemptQuestion {
+    fn from(question: QuizQuestion) -> Self {
+        Self {
+            id: question.id,
+            question_text: question.question_text,
+            question_type: question.question_type,
+            options: question.options.map(|options| options.0),
+            points: question.points,
+            position: question.position,
+        }
+    }
+}
+
+/// A saved answer; correctness and points are only shown once the attempt is submitted, and
+/// to participants only once retrying could no longer profit from them.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuizAttemptAnswerResponse {
+    pub question_id: Uuid,
+    pub answer_keys: Vec<String>,
+    pub answer_text: Option<String>,
+    pub is_correct: Option<bool>,
+    pub points_awarded: Option<i32>,
+}
+
+impl QuizAttemptAnswerResponse {
+    fn new(answer: QuizAttemptAnswer, feedback: bool) -> Self {
+        Self {
+            question_id: answer.question_id,
+            answer_keys: answer.answer_key.as_deref().map(decode_answer_keys).unwrap_or_default(),
+            answer_text: answer.answer_text,
+            is_correct: answer.is_correct.filter(|_| feedback),
+            points_awarded: answer.points_awarded.filter(|_| feedback),
+        }
+    }
+}
+
+/// An attempt with its questions while in progress, and its result once submitted.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuizAttemptResponse {
+    pub id: Uuid,
+    pub quiz_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub attempt_number: i32,
+    pub status: QuizAttemptStatus,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+    pub score: Option<f64>,
+    pub passed: Option<bool>,
+    pub points_earned: Option<i32>,
+    pub points_possible: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub questions: Option<Vec<QuizAttemptQuestion>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answers: Option<Vec<QuizAttemptAnswerResponse>>,
+}
+
+impl QuizAttemptResponse {
+    /// Just the attempt and its result, for listings.
+    pub fn summary(attempt: QuizAttempt) -> Self {
+        Self {
+            id: attempt.id,
+            quiz_id: attempt.quiz_id,
+            employee_user_id: attempt.employee_user_id,
+            attempt_number: attempt.attempt_number,
+            status: attempt.status,
+            started_at: attempt.started_at,
+            expires_at: attempt.expires_at,
+            completed_at: attempt.completed_at,
+            score: attempt.score,
+            passed: attempt.passed,
+            points_earned: attempt.points_earned,
+            points_possible: attempt.points_possible,
+            questions: None,
+            answers: None,
+        }
+    }
+
+    /// The attempt with its answers; questions, as arranged for the attempt, are included only
+    /// while it is in progress. Per answer feedback is left out of submitted attempts unless
+    /// `feedback` allows it.
+    pub fn detailed(
+        attempt: QuizAttempt,
+        questions: Vec<QuizQuestion>,
+        answers: Vec<QuizAttemptAnswer>,
+        feedback: bool,
+    ) -> Self {
+        let submitted = attempt.status != QuizAttemptStatus::InProgress;
+        let questions = (!submitted).then(|| questions.into_iter().map(QuizAttemptQuestion::from).collect());
+        let answers = answers
+            .into_iter()
+            .map(|answer| QuizAttemptAnswerResponse::new(answer, submitted && feedback))
+            .collect();
+
+        Self {
+            questions,
+            answers: Some(answers),
+            ..Self::summary(attempt)
+        }
+    }
+}
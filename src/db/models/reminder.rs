@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+
+use super::NotificationType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "reminder_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderStatus {
+    Pending,
+    Sent,
+    Cancelled,
+    Skipped,  // The event changed or already started before the reminder was due
+}
+
+/// Entity types a reminder can point at, stored in `related_entity_type`.
+pub const REMINDER_ENTITY_APPOINTMENT: &str = "appointment";
+pub const REMINDER_ENTITY_TRAINING_SESSION: &str = "training_session";
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
+pub struct ScheduledReminder {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub user_id: Uuid,
+    pub notification_type: NotificationType,
+    pub related_entity_id: Uuid,
+    pub related_entity_type: String,
+    pub offset_minutes: i32,
+    pub starts_at: OffsetDateTime,
+    pub remind_at: OffsetDateTime,
+    pub status: ReminderStatus,
+    pub sent_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// A reminder to (re)schedule for one recipient of an event.
+#[derive(Debug, Clone)]
+pub struct NewScheduledReminder {
+    pub tenant_id: Uuid,
+    pub user_id: Uuid,
+    pub notification_type: NotificationType,
+    pub related_entity_id: Uuid,
+    pub related_entity_type: &'static str,
+    pub offset_minutes: i32,
+    pub starts_at: OffsetDateTime,
+    pub remind_at: OffsetDateTime,
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::core::utils::compliance::{self, Standing};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "training_compliance_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceStatus {
+    Compliant,
+    DueSoon,
+    Overdue,
+}
+
+impl From<Standing> for ComplianceStatus {
+    fn from(standing: Standing) -> Self {
+        match standing {
+            Standing::Compliant => ComplianceStatus::Compliant,
+            Standing::DueSoon => ComplianceStatus::DueSoon,
+            Standing::Overdue => ComplianceStatus::Overdue,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct TrainingRequirement {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub company_id: Option<Uuid>,  // None targets every company of the tenant
+    pub department: Option<String>,
+    pub job_title: Option<String>,
+    pub recurrence_interval_months: Option<i32>,  // None for one-off requirements
+    pub due_soon_days: i32,
+    pub is_active: bool,
+    pub created_by_user_id: Option<Uuid>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// Creates or replaces a requirement; every target left empty matches all employees.
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct NewTrainingRequirement {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(max = 5000))]
+    pub description: Option<String>,
+    pub company_id: Option<Uuid>,
+    #[validate(length(min = 1, max = 200))]
+    pub department: Option<String>,
+    #[validate(length(min = 1, max = 200))]
+    pub job_title: Option<String>,
+    #[validate(range(min = 1, max = 120))]
+    pub recurrence_interval_months: Option<i32>,
+    #[validate(range(min = 0, max = 365))]
+    pub due_soon_days: Option<i32>,  // Defaults to 30
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ComplianceQuery {
+    pub requirement_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+    pub department: Option<String>,
+    pub status: Option<ComplianceStatus>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// One employee targeted by one requirement, with their last completion of a linked session.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ComplianceAssignment {
+    pub requirement_id: Uuid,
+    pub requirement_title: String,
+    pub recurrence_interval_months: Option<i32>,
+    pub due_soon_days: i32,
+    pub employee_user_id: Uuid,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company_id: Option<Uuid>,
+    pub department: Option<String>,
+    pub job_title: Option<String>,
+    pub last_completed_at: Option<OffsetDateTime>,
+}
+
+impl ComplianceAssignment {
+    pub fn assess(self, now: OffsetDateTime) -> EmployeeCompliance {
+        let due_at = compliance::due_at(self.last_completed_at, self.recurrence_interval_months);
+        EmployeeCompliance {
+            requirement_id: self.requirement_id,
+            requirement_title: self.requirement_title,
+            employee_user_id: self.employee_user_id,
+            first_name: self.first_name,
+            last_name: self.last_name,
+            company_id: self.company_id,
+            department: self.department,
+            job_title: self.job_title,
+            last_completed_at: self.last_completed_at,
+            due_at,
+            status: compliance::standing(self.last_completed_at, due_at, self.due_soon_days, now).into(),
+        }
+    }
+}
+
+/// Compliance of one employee with one requirement.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmployeeCompliance {
+    pub requirement_id: Uuid,
+    pub requirement_title: String,
+    pub employee_user_id: Uuid,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company_id: Option<Uuid>,
+    pub department: Option<String>,
+    pub job_title: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_completed_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub due_at: Option<OffsetDateTime>,  // None when never completed or the requirement does not recur
+    pub status: ComplianceStatus,
+}
+
+/// Per-requirement counts shown on the compliance dashboard.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequirementComplianceSummary {
+    pub requirement_id: Uuid,
+    pub title: String,
+    pub employees: i64,
+    pub compliant: i64,
+    pub due_soon: i64,
+    pub overdue: i64,
+}
+
+/// Tenant-wide totals; status counts are per employee and requirement, `employees` counts people.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceDashboard {
+    pub requirements: Vec<RequirementComplianceSummary>,
+    pub employees: i64,
+    pub compliant: i64,
+    pub due_soon: i64,
+    pub overdue: i64,
+}
+
+impl ComplianceDashboard {
+    pub fn new(requirements: Vec<RequirementComplianceSummary>, employees: i64) -> Self {
+        Self {
+            employees,
+            compliant: requirements.iter().map(|summary| summary.compliant).sum(),
+            due_soon: requirements.iter().map(|summary| summary.due_soon).sum(),
+            overdue: requirements.iter().map(|summary| summary.overdue).sum(),
+            requirements,
+        }
+    }
+}
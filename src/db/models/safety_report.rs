@@ -22,6 +22,7 @@ pub enum ReportPriority {
 }
 
 #[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
 pub struct SafetyReport {
     pub id: Uuid,
     pub title: String,
@@ -41,6 +42,7 @@ pub struct SafetyReport {
 }
 
 #[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[allow(unused)]
 pub struct ReportComment {
     pub id: Uuid,
     pub report_id: Uuid,
@@ -1,10 +1,14 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::types::Uuid;
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 use validator::Validate;
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+use crate::core::utils::time_zone::{ClientDateTime, Zone};
+use crate::core::utils::training::{SessionScope, SessionStage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "training_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum TrainingStatus {
     Scheduled,
     InProgress,
@@ -12,8 +16,20 @@ pub enum TrainingStatus {
     Cancelled,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+impl From<TrainingStatus> for SessionStage {
+    fn from(status: TrainingStatus) -> Self {
+        match status {
+            TrainingStatus::Scheduled => SessionStage::Scheduled,
+            TrainingStatus::InProgress => SessionStage::InProgress,
+            TrainingStatus::Completed => SessionStage::Completed,
+            TrainingStatus::Cancelled => SessionStage::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "training_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum TrainingType {
     LiveWebinar,
     RecordedVideo,
@@ -21,90 +37,300 @@ pub enum TrainingType {
     Quiz,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+impl TrainingType {
+    /// Whether participants work through the session's materials on their own, with completion
+    /// following from their progress rather than live attendance.
+    pub fn is_self_paced(&self) -> bool {
+        matches!(self, TrainingType::RecordedVideo | TrainingType::Document)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "participant_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum ParticipantStatus {
     Registered,
     Attended,
     Completed,
     NoShow,
+    Waitlisted,
+    Withdrawn,
+}
+
+#[allow(unused)]
+impl ParticipantStatus {
+    /// Whether the enrollment occupies one of the session's `max_participants` seats.
+    pub fn holds_seat(&self) -> bool {
+        !matches!(self, ParticipantStatus::Waitlisted | ParticipantStatus::Withdrawn)
+    }
 }
 
 #[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
-pub struct Training {
+pub struct TrainingSession {
     pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub host_user_id: Uuid,  // OhsSpecialist hosting the session
     pub title: String,
     pub description: Option<String>,
-    pub specialist_id: Uuid,
-    pub company_id: Uuid,
     pub training_type: TrainingType,
     pub status: TrainingStatus,
     pub start_time: OffsetDateTime,
-    pub end_time: Option<OffsetDateTime>,
-    pub duration_minutes: i64,
+    pub end_time: OffsetDateTime,
+    pub stream_details: Option<serde_json::Value>,
     pub max_participants: Option<i32>,
-    pub material_url: Option<String>,
-    pub created_at: OffsetDateTime,
-    pub updated_at: OffsetDateTime,
+    pub requirement_id: Option<Uuid>,  // Mandatory training requirement fulfilled by completing the session
+    pub min_attendance_percent: i32,  // Share of the session a participant must attend
+    pub min_watched_percent: i32,  // Share of a recorded video a participant must watch
+    pub min_pages_viewed_percent: i32,  // Share of a document's pages a participant must view
+    pub published_at: Option<OffsetDateTime>,  // None while the session is a draft
+    pub cancelled_at: Option<OffsetDateTime>,
+    pub cancellation_reason: Option<String>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
 }
 
-#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
-pub struct TrainingParticipant {
+#[allow(unused)]
+impl TrainingSession {
+    pub fn is_published(&self) -> bool {
+        self.published_at.is_some()
+    }
+
+    /// What access to the session depends on.
+    pub fn scope(&self) -> SessionScope {
+        SessionScope {
+            tenant_id: self.tenant_id,
+            host_user_id: self.host_user_id,
+            published: self.is_published(),
+        }
+    }
+}
+
+/// A training session as returned by the API, with its times expressed in the caller's zone.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainingSessionResponse {
     pub id: Uuid,
-    pub training_id: Uuid,
-    pub user_id: Uuid,
-    pub status: ParticipantStatus,
-    pub joined_at: Option<OffsetDateTime>,
-    pub completed_at: Option<OffsetDateTime>,
-    pub feedback: Option<String>,
-    pub rating: Option<i32>,
-    pub certificate_url: Option<String>,
-    pub created_at: OffsetDateTime,
-    pub updated_at: OffsetDateTime,
+    pub host_user_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub training_type: TrainingType,
+    pub status: TrainingStatus,
+    #[serde(with = "time::serde::rfc3339")]
+    pub start_time: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub end_time: OffsetDateTime,
+    pub time_zone: &'static str,
+    pub max_participants: Option<i32>,
+    pub stream_details: Option<serde_json::Value>,
+    pub requirement_id: Option<Uuid>,
+    pub min_attendance_percent: i32,
+    pub min_watched_percent: i32,
+    pub min_pages_viewed_percent: i32,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub published_at: Option<OffsetDateTime>,
+    pub cancellation_reason: Option<String>,
+}
+
+impl TrainingSessionResponse {
+    pub fn new(session: TrainingSession, zone: Zone) -> Self {
+        Self {
+            id: session.id,
+            host_user_id: session.host_user_id,
+            title: session.title,
+            description: session.description,
+            training_type: session.training_type,
+            status: session.status,
+            start_time: zone.localize(session.start_time),
+            end_time: zone.localize(session.end_time),
+            time_zone: zone.name(),
+            max_participants: session.max_participants,
+            stream_details: session.stream_details,
+            requirement_id: session.requirement_id,
+            min_attendance_percent: session.min_attendance_percent,
+            min_watched_percent: session.min_watched_percent,
+            min_pages_viewed_percent: session.min_pages_viewed_percent,
+            published_at: session.published_at.map(|published_at| zone.localize(published_at)),
+            cancellation_reason: session.cancellation_reason,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
-pub struct NewTraining {
-    #[validate(length(min = 1))]
+pub struct NewTrainingSession {
+    #[validate(length(min = 1, max = 200))]
     pub title: String,
+    #[validate(length(max = 5000))]
     pub description: Option<String>,
-    pub specialist_id: Uuid,
-    pub company_id: Uuid,
     pub training_type: TrainingType,
-    pub start_time: OffsetDateTime,
+    pub start_time: ClientDateTime,
+    #[validate(range(min = 5, max = 1440))]
     pub duration_minutes: i64,
+    pub stream_details: Option<serde_json::Value>,
+    #[validate(range(min = 1, max = 10000))]
     pub max_participants: Option<i32>,
-    pub material_url: Option<String>,
+    pub requirement_id: Option<Uuid>,
+    #[validate(range(min = 0, max = 100))]
+    pub min_attendance_percent: Option<i32>,  // Defaults to 75
+    #[validate(range(min = 1, max = 100))]
+    pub min_watched_percent: Option<i32>,  // Defaults to 90
+    #[validate(range(min = 1, max = 100))]
+    pub min_pages_viewed_percent: Option<i32>,  // Defaults to 100
+    pub host_user_id: Option<Uuid>,  // Tenant admins may host on behalf of a specialist
+}
+
+#[allow(unused)]
+impl NewTrainingSession {
+    pub fn end_time(&self, start_time: OffsetDateTime) -> OffsetDateTime {
+        start_time + Duration::minutes(self.duration_minutes)
+    }
 }
 
+/// Reads a nullable field of a partial update: `None` when it was left out, `Some(None)` when
+/// it was sent as null to clear it.
+fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Partial update of a scheduled session; omitted fields keep their value, nullable ones are
+/// cleared with null.
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
-pub struct UpdateTraining {
+pub struct UpdateTrainingSession {
+    #[validate(length(min = 1, max = 200))]
     pub title: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    #[validate(length(max = 5000))]
+    pub description: Option<Option<String>>,
+    pub training_type: Option<TrainingType>,
+    pub start_time: Option<ClientDateTime>,
+    #[validate(range(min = 5, max = 1440))]
+    pub duration_minutes: Option<i64>,  // Keeps the current duration when omitted
+    #[serde(default, deserialize_with = "double_option")]
+    pub stream_details: Option<Option<serde_json::Value>>,
+    #[serde(default, deserialize_with = "double_option")]
+    #[validate(range(min = 1, max = 10000))]
+    pub max_participants: Option<Option<i32>>,  // Null lifts the limit
+    #[serde(default, deserialize_with = "double_option")]
+    pub requirement_id: Option<Option<Uuid>>,
+    #[validate(range(min = 0, max = 100))]
+    pub min_attendance_percent: Option<i32>,
+    #[validate(range(min = 1, max = 100))]
+    pub min_watched_percent: Option<i32>,
+    #[validate(range(min = 1, max = 100))]
+    pub min_pages_viewed_percent: Option<i32>,
+}
+
+/// Resolved values written by a session create or update.
+#[derive(Debug, Clone)]
+pub struct TrainingSessionFields {
+    pub host_user_id: Uuid,
+    pub title: String,
     pub description: Option<String>,
-    pub status: Option<TrainingStatus>,
-    pub start_time: Option<OffsetDateTime>,
-    pub end_time: Option<OffsetDateTime>,
-    pub duration_minutes: Option<i64>,
+    pub training_type: TrainingType,
+    pub start_time: OffsetDateTime,
+    pub end_time: OffsetDateTime,
+    pub stream_details: Option<serde_json::Value>,
     pub max_participants: Option<i32>,
-    pub material_url: Option<String>,
+    pub requirement_id: Option<Uuid>,
+    pub min_attendance_percent: i32,
+    pub min_watched_percent: i32,
+    pub min_pages_viewed_percent: i32,
+}
+
+impl From<TrainingSession> for TrainingSessionFields {
+    fn from(session: TrainingSession) -> Self {
+        Self {
+            host_user_id: session.host_user_id,
+            title: session.title,
+            description: session.description,
+            training_type: session.training_type,
+            start_time: session.start_time,
+            end_time: session.end_time,
+            stream_details: session.stream_details,
+            max_participants: session.max_participants,
+            requirement_id: session.requirement_id,
+            min_attendance_percent: session.min_attendance_percent,
+            min_watched_percent: session.min_watched_percent,
+            min_pages_viewed_percent: session.min_pages_viewed_percent,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
-pub struct NewTrainingParticipant {
-    pub training_id: Uuid,
-    pub user_id: Uuid,
+pub struct CancelTrainingSession {
+    #[validate(length(max = 1000))]
+    pub reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
 #[allow(unused)]
-pub struct UpdateParticipantStatus {
+pub struct TrainingEnrollment {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub training_session_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub company_id: Uuid,
+    pub status: ParticipantStatus,
+    pub enrolled_at: Option<OffsetDateTime>,
+    pub attended: Option<bool>,
+    pub completion_date: Option<OffsetDateTime>,
+    pub certificate_s3_key: Option<String>,
+    pub feedback_rating: Option<i16>,
+    pub feedback_text: Option<String>,
+    pub feedback_submitted_at: Option<OffsetDateTime>,
+    pub attended_minutes: Option<i32>,
+    pub attendance_overridden_by: Option<Uuid>,  // Set when attendance was decided manually
+    pub attendance_overridden_at: Option<OffsetDateTime>,
+    pub attendance_override_note: Option<String>,
+    pub presence_seen_at: Option<OffsetDateTime>, // Last heartbeat in the live session's room
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// An enrollment as returned by the API; `waitlist_position` is 1-based and only set while waitlisted.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainingEnrollmentResponse {
+    pub id: Uuid,
+    pub training_session_id: Uuid,
+    pub employee_user_id: Uuid,
+    pub company_id: Uuid,
     pub status: ParticipantStatus,
-    pub joined_at: Option<OffsetDateTime>,
-    pub completed_at: Option<OffsetDateTime>,
-    pub feedback: Option<String>,
-    pub rating: Option<i32>,
-    pub certificate_url: Option<String>,
-} 
\ No newline at end of file
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub enrolled_at: Option<OffsetDateTime>,
+    pub waitlist_position: Option<i64>,
+}
+
+impl TrainingEnrollmentResponse {
+    pub fn new(enrollment: TrainingEnrollment, waitlist_position: Option<i64>) -> Self {
+        Self {
+            id: enrollment.id,
+            training_session_id: enrollment.training_session_id,
+            employee_user_id: enrollment.employee_user_id,
+            company_id: enrollment.company_id,
+            status: enrollment.status,
+            enrolled_at: enrollment.enrolled_at,
+            waitlist_position,
+        }
+    }
+}
+
+/// Enrolls every active employee of a company, optionally narrowed to one department.
+#[derive(Debug, Deserialize, Validate)]
+#[allow(unused)]
+pub struct BulkEnrollment {
+    pub company_id: Uuid,
+    #[validate(length(min = 1, max = 200))]
+    pub department: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkEnrollmentResult {
+    pub registered: usize,
+    pub waitlisted: usize,
+    pub already_enrolled: usize,
+}
@@ -6,12 +6,13 @@ use validator::Validate;
 
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "user_role", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum UserRole {
-    Employee,
+    SuperAdmin,
+    TenantAdmin,
     OhsSpecialist,
     Doctor,
-    Admin,
-    SuperAdmin,
+    Employee,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
@@ -74,10 +75,27 @@ pub struct UpdateUser {
     pub company_id: Option<Uuid>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserTimeZone {
+    pub time_zone: Option<String>,  // None to inherit the company's zone
+}
+
 #[derive(Debug, Deserialize, Validate)]
 #[allow(unused)]
 pub struct UserLogin {
     #[validate(email)]
     pub email: String,
     pub password: SecretBox<String>,
-} 
\ No newline at end of file
+} 
+impl UserRole {
+    /// The label used by the `user_role` Postgres enum and the RLS session settings.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::SuperAdmin => "super_admin",
+            UserRole::TenantAdmin => "tenant_admin",
+            UserRole::OhsSpecialist => "ohs_specialist",
+            UserRole::Doctor => "doctor",
+            UserRole::Employee => "employee",
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use sqlx::{Postgres, Transaction};
+
+use crate::db::{AppointmentAnalyticsScope, AppointmentOutcomeRates, DatabaseError, WeeklyBookings};
+
+// Scope filter on appointment start; binds $1 tenant, $2 from, $3 to, $4 company, $5 professional
+const SCOPED_BY_START: &str = r#"
+    tenant_id = $1
+      AND start_time >= $2 AND start_time < $3
+      AND ($4::uuid IS NULL OR company_id = $4)
+      AND ($5::uuid IS NULL OR professional_user_id = $5)
+"#;
+
+// Same scope, applied to the booking time instead of the appointment start
+const SCOPED_BY_BOOKING: &str = r#"
+    tenant_id = $1
+      AND created_at >= $2 AND created_at < $3
+      AND ($4::uuid IS NULL OR company_id = $4)
+      AND ($5::uuid IS NULL OR professional_user_id = $5)
+"#;
+
+const CANCELLED_STATUSES: &str = "('cancelled_by_professional', 'cancelled_by_employee')";
+
+pub struct AnalyticsRepository;
+
+#[allow(unused)]
+impl AnalyticsRepository {
+    // Count bookings per week; weeks start on Monday in the given zone
+    pub async fn bookings_per_week(
+        tx: &mut Transaction<'_, Postgres>,
+        scope: &AppointmentAnalyticsScope,
+        time_zone: &str,
+    ) -> Result<Vec<WeeklyBookings>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT
+                date_trunc('week', created_at AT TIME ZONE $6)::date AS week_start,
+                COUNT(*) AS bookings
+            FROM appointments
+            WHERE {}
+            GROUP BY week_start
+            ORDER BY week_start
+            "#,
+            SCOPED_BY_BOOKING
+        );
+
+        let weeks = sqlx::query_as::<_, WeeklyBookings>(&query)
+            .bind(scope.tenant_id)
+            .bind(scope.from)
+            .bind(scope.to)
+            .bind(scope.company_id)
+            .bind(scope.professional_user_id)
+            .bind(time_zone)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(weeks)
+    }
+
+    // Cancellation and no-show rates of appointments starting in the range, grouped by professional or company
+    pub async fn outcome_rates(
+        tx: &mut Transaction<'_, Postgres>,
+        scope: &AppointmentAnalyticsScope,
+        by_company: bool,
+    ) -> Result<Vec<AppointmentOutcomeRates>, DatabaseError> {
+        let group = if by_company { "company_id" } else { "professional_user_id" };
+        let query = format!(
+            r#"
+            SELECT
+                {group} AS id,
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE status IN {cancelled}) AS cancelled,
+                COUNT(*) FILTER (WHERE status = 'no_show') AS no_show,
+                (COUNT(*) FILTER (WHERE status IN {cancelled}))::float8 / COUNT(*) AS cancellation_rate,
+                (COUNT(*) FILTER (WHERE status = 'no_show'))::float8 / COUNT(*) AS no_show_rate
+            FROM appointments
+            WHERE {scope}
+            GROUP BY {group}
+            ORDER BY total DESC, {group}
+            "#,
+            group = group,
+            cancelled = CANCELLED_STATUSES,
+            scope = SCOPED_BY_START
+        );
+
+        let rates = sqlx::query_as::<_, AppointmentOutcomeRates>(&query)
+            .bind(scope.tenant_id)
+            .bind(scope.from)
+            .bind(scope.to)
+            .bind(scope.company_id)
+            .bind(scope.professional_user_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(rates)
+    }
+
+    // Average hours between booking and start for appointments booked in the range
+    pub async fn average_lead_time_hours(
+        tx: &mut Transaction<'_, Postgres>,
+        scope: &AppointmentAnalyticsScope,
+    ) -> Result<Option<f64>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT (AVG(EXTRACT(EPOCH FROM (start_time - created_at))) / 3600)::float8
+            FROM appointments
+            WHERE {}
+            "#,
+            SCOPED_BY_BOOKING
+        );
+
+        let hours = sqlx::query_scalar::<_, Option<f64>>(&query)
+            .bind(scope.tenant_id)
+            .bind(scope.from)
+            .bind(scope.to)
+            .bind(scope.company_id)
+            .bind(scope.professional_user_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(hours)
+    }
+}
@@ -0,0 +1,264 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::db::{
+    Appointment, AppointmentHistoryFilter, AppointmentStatus, AppointmentType, DatabaseError, Pagination,
+};
+
+pub(crate) const APPOINTMENT_COLUMNS: &str = r#"
+    id, tenant_id, company_id, employee_user_id, professional_user_id,
+    appointment_type, start_time, end_time, status,
+    reason_for_visit, notes_by_professional, call_session_id,
+    created_at, updated_at
+"#;
+
+pub struct AppointmentRepository;
+
+#[allow(unused)]
+impl AppointmentRepository {
+    // Find an appointment visible to the current RLS context
+    pub async fn find_by_id(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Appointment, DatabaseError> {
+        let query = format!("SELECT {} FROM appointments WHERE id = $1", APPOINTMENT_COLUMNS);
+
+        sqlx::query_as::<_, Appointment>(&query)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // List the appointments a user takes part in, either as employee or as professional
+    pub async fn list_for_participant(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<Appointment>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM appointments
+            WHERE (employee_user_id = $1 OR professional_user_id = $1)
+              AND start_time < $3 AND end_time > $2
+            ORDER BY start_time
+            "#,
+            APPOINTMENT_COLUMNS
+        );
+
+        let appointments = sqlx::query_as::<_, Appointment>(&query)
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(appointments)
+    }
+
+    // Serialize bookings for both participants until the transaction ends. The locks are taken
+    // in a fixed order so two bookings sharing both participants cannot deadlock.
+    pub async fn lock_participant_schedules(
+        tx: &mut Transaction<'_, Postgres>,
+        professional_user_id: Uuid,
+        employee_user_id: Uuid,
+    ) -> Result<(), DatabaseError> {
+        let mut user_ids = [professional_user_id, employee_user_id];
+        user_ids.sort();
+        for user_id in user_ids {
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1::text, 0))")
+                .bind(user_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    // Check whether either participant already holds an active appointment overlapping the range,
+    // among all their appointments rather than the ones visible to the caller
+    pub async fn has_overlap(
+        tx: &mut Transaction<'_, Postgres>,
+        professional_user_id: Uuid,
+        employee_user_id: Uuid,
+        start_time: OffsetDateTime,
+        end_time: OffsetDateTime,
+        exclude_id: Option<Uuid>,
+    ) -> Result<bool, DatabaseError> {
+        let overlap: bool = sqlx::query_scalar("SELECT appointment_slot_taken($1, $2, $3, $4, $5)")
+            .bind(professional_user_id)
+            .bind(employee_user_id)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(exclude_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(overlap)
+    }
+
+    // Create a pending appointment
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        company_id: Uuid,
+        employee_user_id: Uuid,
+        professional_user_id: Uuid,
+        appointment_type: AppointmentType,
+        start_time: OffsetDateTime,
+        end_time: OffsetDateTime,
+        reason_for_visit: Option<String>,
+    ) -> Result<Appointment, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO appointments (
+                tenant_id, company_id, employee_user_id, professional_user_id,
+                appointment_type, start_time, end_time, status, reason_for_visit
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending', $8)
+            RETURNING {}
+            "#,
+            APPOINTMENT_COLUMNS
+        );
+
+        let appointment = sqlx::query_as::<_, Appointment>(&query)
+            .bind(tenant_id)
+            .bind(company_id)
+            .bind(employee_user_id)
+            .bind(professional_user_id)
+            .bind(appointment_type)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(reason_for_visit)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(appointment)
+    }
+
+    // Move an appointment to a new time range
+    pub async fn reschedule(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        start_time: OffsetDateTime,
+        end_time: OffsetDateTime,
+    ) -> Result<Appointment, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE appointments
+            SET start_time = $2, end_time = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            APPOINTMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, Appointment>(&query)
+            .bind(id)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Update the status of an appointment
+    pub async fn update_status(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        status: AppointmentStatus,
+    ) -> Result<Appointment, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE appointments
+            SET status = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            APPOINTMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, Appointment>(&query)
+            .bind(id)
+            .bind(status)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Record the identifier of the call held for an appointment
+    pub async fn set_call_session(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        call_session_id: &str,
+    ) -> Result<(), DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE appointments SET call_session_id = $2, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(call_session_id)
+        .execute(&mut **tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+        Ok(())
+    }
+
+    // List past appointments visible to the current RLS context, newest first, with the total count
+    pub async fn list_history(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        filter: &AppointmentHistoryFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<Appointment>, i64), DatabaseError> {
+        const FILTER: &str = r#"
+            tenant_id = $1
+              AND start_time < $2
+              AND ($3::timestamptz IS NULL OR start_time >= $3)
+              AND ($4::appointment_status IS NULL OR status = $4)
+              AND ($5::appointment_type IS NULL OR appointment_type = $5)
+              AND ($6::uuid IS NULL OR employee_user_id = $6)
+              AND ($7::uuid IS NULL OR professional_user_id = $7)
+              AND ($8::uuid IS NULL OR company_id = $8)
+        "#;
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM appointments WHERE {}", FILTER))
+            .bind(tenant_id)
+            .bind(filter.to)
+            .bind(filter.from)
+            .bind(filter.status)
+            .bind(filter.appointment_type)
+            .bind(filter.employee_user_id)
+            .bind(filter.professional_user_id)
+            .bind(filter.company_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        let query = format!(
+            "SELECT {} FROM appointments WHERE {} ORDER BY start_time DESC, id LIMIT $9 OFFSET $10",
+            APPOINTMENT_COLUMNS, FILTER
+        );
+
+        let appointments = sqlx::query_as::<_, Appointment>(&query)
+            .bind(tenant_id)
+            .bind(filter.to)
+            .bind(filter.from)
+            .bind(filter.status)
+            .bind(filter.appointment_type)
+            .bind(filter.employee_user_id)
+            .bind(filter.professional_user_id)
+            .bind(filter.company_id)
+            .bind(pagination.limit())
+            .bind(pagination.offset())
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok((appointments, total))
+    }
+}
@@ -0,0 +1,193 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::db::repositories::ENROLLMENT_COLUMNS;
+use crate::db::{AttendanceEvent, AttendanceEventType, DatabaseError, ParticipantStatus, TrainingEnrollment};
+
+const ATTENDANCE_EVENT_COLUMNS: &str = r#"
+    id, tenant_id, training_session_id, enrollment_id, user_id, event_type, source, occurred_at, created_at
+"#;
+
+/// Outcome written to an enrollment when attendance is decided.
+#[derive(Debug, Clone, Copy)]
+pub struct AttendanceOutcome {
+    pub attended_minutes: i32,
+    pub attended: bool,
+    pub status: ParticipantStatus,
+    pub completion_date: Option<OffsetDateTime>,
+}
+
+pub struct AttendanceRepository;
+
+#[allow(unused)]
+impl AttendanceRepository {
+    // Append a join or leave event for an enrollment
+    pub async fn record_event(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment: &TrainingEnrollment,
+        event_type: AttendanceEventType,
+        source: &str,
+        occurred_at: OffsetDateTime,
+    ) -> Result<AttendanceEvent, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_attendance_events (
+                tenant_id, training_session_id, enrollment_id, user_id, event_type, source, occurred_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            ATTENDANCE_EVENT_COLUMNS
+        );
+
+        let event = sqlx::query_as::<_, AttendanceEvent>(&query)
+            .bind(enrollment.tenant_id)
+            .bind(enrollment.training_session_id)
+            .bind(enrollment.id)
+            .bind(enrollment.employee_user_id)
+            .bind(event_type)
+            .bind(source)
+            .bind(occurred_at)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(event)
+    }
+
+    // List the events of an enrollment in order
+    pub async fn list_events(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+    ) -> Result<Vec<AttendanceEvent>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_attendance_events WHERE enrollment_id = $1 ORDER BY occurred_at, id",
+            ATTENDANCE_EVENT_COLUMNS
+        );
+
+        let events = sqlx::query_as::<_, AttendanceEvent>(&query)
+            .bind(enrollment_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(events)
+    }
+
+    // Store the attended minutes computed so far
+    pub async fn update_minutes(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+        attended_minutes: i32,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE training_enrollments SET attended_minutes = $2, updated_at = NOW() WHERE id = $1")
+            .bind(enrollment_id)
+            .bind(attended_minutes)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    // Note that a participant was still connected to the rooms of these live sessions
+    pub async fn mark_seen(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        training_session_ids: &[Uuid],
+        seen_at: OffsetDateTime,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE training_enrollments te
+            SET presence_seen_at = GREATEST(te.presence_seen_at, $3)
+            FROM training_sessions ts
+            WHERE ts.id = te.training_session_id
+              AND te.training_session_id = ANY($2)
+              AND te.employee_user_id = $1
+              AND ts.status = 'in_progress'
+            "#,
+        )
+        .bind(user_id)
+        .bind(training_session_ids)
+        .bind(seen_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Apply the computed outcome unless the attendance was overridden manually;
+    // returns None for overridden enrollments
+    pub async fn apply_outcome(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+        outcome: AttendanceOutcome,
+    ) -> Result<Option<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_enrollments
+            SET attended_minutes = $2, attended = $3, status = $4,
+                completion_date = COALESCE($5, completion_date), updated_at = NOW()
+            WHERE id = $1 AND attendance_overridden_at IS NULL
+            RETURNING {}
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        let enrollment = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(enrollment_id)
+            .bind(outcome.attended_minutes)
+            .bind(outcome.attended)
+            .bind(outcome.status)
+            .bind(outcome.completion_date)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(enrollment)
+    }
+
+    // Record a manual attendance decision
+    pub async fn override_outcome(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+        outcome: AttendanceOutcome,
+        overridden_by: Uuid,
+        note: Option<&str>,
+    ) -> Result<TrainingEnrollment, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_enrollments
+            SET attended_minutes = $2, attended = $3, status = $4, completion_date = $5,
+                attendance_overridden_by = $6, attendance_overridden_at = NOW(),
+                attendance_override_note = $7, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(enrollment_id)
+            .bind(outcome.attended_minutes)
+            .bind(outcome.attended)
+            .bind(outcome.status)
+            .bind(outcome.completion_date)
+            .bind(overridden_by)
+            .bind(note)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Check whether a session has a quiz that decides completion
+    pub async fn session_has_quiz(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<bool, DatabaseError> {
+        let exists = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM training_quizzes WHERE training_session_id = $1)")
+            .bind(training_session_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(exists)
+    }
+}
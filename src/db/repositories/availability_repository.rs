@@ -0,0 +1,142 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::{Date, OffsetDateTime};
+
+use crate::db::{DatabaseError, NewRecurringAvailability, ProfessionalAvailability, RecurringAvailability};
+
+pub struct AvailabilityRepository;
+
+#[allow(unused)]
+impl AvailabilityRepository {
+    // List one-off availabilities of a professional overlapping a range
+    pub async fn list_one_off(
+        tx: &mut Transaction<'_, Postgres>,
+        professional_user_id: Uuid,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<ProfessionalAvailability>, DatabaseError> {
+        let availabilities = sqlx::query_as::<_, ProfessionalAvailability>(
+            r#"
+            SELECT id, professional_user_id, tenant_id, start_time, end_time, created_at
+            FROM professional_availabilities
+            WHERE professional_user_id = $1 AND start_time < $3 AND end_time > $2
+            ORDER BY start_time
+            "#,
+        )
+        .bind(professional_user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(availabilities)
+    }
+
+    // Create a one-off availability for the calling professional
+    pub async fn create_one_off(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        professional_user_id: Uuid,
+        start_time: OffsetDateTime,
+        end_time: OffsetDateTime,
+    ) -> Result<ProfessionalAvailability, DatabaseError> {
+        let availability = sqlx::query_as::<_, ProfessionalAvailability>(
+            r#"
+            INSERT INTO professional_availabilities (tenant_id, professional_user_id, start_time, end_time)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, professional_user_id, tenant_id, start_time, end_time, created_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(professional_user_id)
+        .bind(start_time)
+        .bind(end_time)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(availability)
+    }
+
+    // List weekly rules of a professional that are valid at some point within a date range
+    pub async fn list_recurring(
+        tx: &mut Transaction<'_, Postgres>,
+        professional_user_id: Uuid,
+        from: Date,
+        to: Date,
+    ) -> Result<Vec<RecurringAvailability>, DatabaseError> {
+        let rules = sqlx::query_as::<_, RecurringAvailability>(
+            r#"
+            SELECT
+                id, professional_user_id, tenant_id, weekday, local_start_time, local_end_time,
+                time_zone, valid_from, valid_until, created_at
+            FROM professional_recurring_availabilities
+            WHERE professional_user_id = $1
+              AND valid_from <= $3
+              AND (valid_until IS NULL OR valid_until >= $2)
+            ORDER BY weekday, local_start_time
+            "#,
+        )
+        .bind(professional_user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(rules)
+    }
+
+    // Create a weekly rule for the calling professional
+    pub async fn create_recurring(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        professional_user_id: Uuid,
+        rule: &NewRecurringAvailability,
+        time_zone: &str,
+    ) -> Result<RecurringAvailability, DatabaseError> {
+        let created = sqlx::query_as::<_, RecurringAvailability>(
+            r#"
+            INSERT INTO professional_recurring_availabilities (
+                tenant_id, professional_user_id, weekday, local_start_time, local_end_time,
+                time_zone, valid_from, valid_until
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING
+                id, professional_user_id, tenant_id, weekday, local_start_time, local_end_time,
+                time_zone, valid_from, valid_until, created_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(professional_user_id)
+        .bind(rule.weekday)
+        .bind(rule.local_start_time)
+        .bind(rule.local_end_time)
+        .bind(time_zone)
+        .bind(rule.valid_from)
+        .bind(rule.valid_until)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(created)
+    }
+
+    // Delete a weekly rule owned by a professional
+    pub async fn delete_recurring(
+        tx: &mut Transaction<'_, Postgres>,
+        professional_user_id: Uuid,
+        id: Uuid,
+    ) -> Result<(), DatabaseError> {
+        let result = sqlx::query(
+            "DELETE FROM professional_recurring_availabilities WHERE id = $1 AND professional_user_id = $2",
+        )
+        .bind(id)
+        .bind(professional_user_id)
+        .execute(&mut **tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::db::repositories::{APPOINTMENT_COLUMNS, TRAINING_SESSION_COLUMNS};
+use crate::db::{Appointment, CalendarFeedOwner, DatabaseError, TrainingSession};
+
+/// Backs the token-protected calendar feeds.
+///
+/// Feed requests come from calendar clients without a session: the token is resolved to its
+/// owner first, and the feed is then read in an RLS transaction of that owner.
+pub struct CalendarRepository;
+
+#[allow(unused)]
+impl CalendarRepository {
+    // Create or replace the feed token of a user
+    pub async fn upsert_feed_token(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        token_hash: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO calendar_feed_tokens (user_id, tenant_id, token_hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE
+            SET tenant_id = EXCLUDED.tenant_id,
+                token_hash = EXCLUDED.token_hash,
+                last_accessed_at = NULL,
+                created_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(tenant_id)
+        .bind(token_hash)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Revoke the feed token of a user
+    pub async fn delete_feed_token(tx: &mut Transaction<'_, Postgres>, user_id: Uuid) -> Result<(), DatabaseError> {
+        let result = sqlx::query("DELETE FROM calendar_feed_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    // Resolve a token hash to its active owner, recording the access; there is no caller to
+    // begin an RLS transaction for yet, so this goes through a security definer function
+    pub async fn touch_feed_token(
+        pool: &PgPool,
+        token_hash: &str,
+    ) -> Result<CalendarFeedOwner, DatabaseError> {
+        sqlx::query_as::<_, CalendarFeedOwner>(
+            "SELECT user_id, tenant_id, company_id, roles FROM calendar_feed_owner($1)",
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(DatabaseError::NotFound)
+    }
+
+    // List the appointments of a feed owner, including cancelled ones so clients drop them
+    pub async fn list_appointments(
+        tx: &mut Transaction<'_, Postgres>,
+        owner: &CalendarFeedOwner,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<Appointment>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM appointments
+            WHERE tenant_id = $1
+              AND (employee_user_id = $2 OR professional_user_id = $2)
+              AND start_time < $4 AND end_time > $3
+            ORDER BY start_time
+            "#,
+            APPOINTMENT_COLUMNS
+        );
+
+        let appointments = sqlx::query_as::<_, Appointment>(&query)
+            .bind(owner.tenant_id)
+            .bind(owner.user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(appointments)
+    }
+
+    // List the training sessions a feed owner hosts or holds a seat in; waitlisted and withdrawn
+    // enrollments stay out of the feed
+    pub async fn list_training_sessions(
+        tx: &mut Transaction<'_, Postgres>,
+        owner: &CalendarFeedOwner,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<TrainingSession>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_sessions ts
+            WHERE ts.tenant_id = $1
+              AND (
+                ts.host_user_id = $2
+                OR ts.published_at IS NOT NULL AND EXISTS (
+                    SELECT 1 FROM training_enrollments te
+                    WHERE te.training_session_id = ts.id AND te.employee_user_id = $2
+                      AND te.status IN ('registered', 'attended', 'completed')
+                )
+              )
+              AND ts.start_time < $4 AND ts.end_time > $3
+            ORDER BY ts.start_time
+            "#,
+            TRAINING_SESSION_COLUMNS
+        );
+
+        let sessions = sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(owner.tenant_id)
+            .bind(owner.user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(sessions)
+    }
+}
@@ -0,0 +1,191 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::db::{CallLog, CallMinutes, CallMinutesScope, DatabaseError, NewCallLog};
+
+const CALL_LOG_COLUMNS: &str = r#"
+    id, tenant_id, appointment_id, initiator_user_id, receiver_user_id, professional_user_id,
+    start_time, end_time, duration_seconds, metadata, created_at
+"#;
+
+pub struct CallLogRepository;
+
+#[allow(unused)]
+impl CallLogRepository {
+    // Time limit of the tenant's subscription for the calls of an appointment in minutes; None when unlimited,
+    // 0 without a subscription
+    pub async fn time_limit_minutes(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+    ) -> Result<Option<i32>, DatabaseError> {
+        let minutes = sqlx::query_scalar::<_, Option<i32>>(
+            "SELECT get_tenant_effective_limit($1, 'live_session_time_limit_minutes')",
+        )
+        .bind(tenant_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(minutes)
+    }
+
+    // Seconds the finished calls of an appointment lasted together
+    pub async fn talked_seconds(
+        tx: &mut Transaction<'_, Postgres>,
+        appointment_id: Uuid,
+    ) -> Result<i64, DatabaseError> {
+        let seconds = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(duration_seconds), 0)::int8
+            FROM call_logs
+            WHERE appointment_id = $1 AND end_time IS NOT NULL
+            "#,
+        )
+        .bind(appointment_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(seconds)
+    }
+
+    // Open the log of a call that was answered
+    pub async fn start(tx: &mut Transaction<'_, Postgres>, log: &NewCallLog) -> Result<CallLog, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO call_logs (
+                id, tenant_id, appointment_id, initiator_user_id, receiver_user_id, professional_user_id,
+                start_time, metadata
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {}
+            "#,
+            CALL_LOG_COLUMNS
+        );
+
+        let log = sqlx::query_as::<_, CallLog>(&query)
+            .bind(log.id)
+            .bind(log.tenant_id)
+            .bind(log.appointment_id)
+            .bind(log.initiator_user_id)
+            .bind(log.receiver_user_id)
+            .bind(log.professional_user_id)
+            .bind(log.start_time)
+            .bind(&log.metadata)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(log)
+    }
+
+    // Close an open call log, merging how the call ended into its metadata
+    pub async fn finish(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        end_time: OffsetDateTime,
+        metadata: &serde_json::Value,
+    ) -> Result<Option<CallLog>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE call_logs
+            SET end_time = GREATEST($2, start_time),
+                duration_seconds = FLOOR(EXTRACT(EPOCH FROM (GREATEST($2, start_time) - start_time)))::int,
+                metadata = COALESCE(metadata, '{{}}'::jsonb) || $3
+            WHERE id = $1 AND end_time IS NULL
+            RETURNING {}
+            "#,
+            CALL_LOG_COLUMNS
+        );
+
+        let log = sqlx::query_as::<_, CallLog>(&query)
+            .bind(id)
+            .bind(end_time)
+            .bind(metadata)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(log)
+    }
+
+    // Note that answered calls still run
+    pub async fn mark_seen(
+        tx: &mut Transaction<'_, Postgres>,
+        ids: &[Uuid],
+        seen_at: OffsetDateTime,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE call_logs
+            SET last_seen_at = GREATEST(last_seen_at, $2)
+            WHERE id = ANY($1) AND end_time IS NULL
+            "#,
+        )
+        .bind(ids)
+        .bind(seen_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Close the open logs last seen before the cutoff at the time they were last seen, merging
+    // how the call ended into their metadata; returns how many were closed
+    pub async fn finish_stale(
+        tx: &mut Transaction<'_, Postgres>,
+        seen_before: OffsetDateTime,
+        metadata: &serde_json::Value,
+    ) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE call_logs
+            SET end_time = COALESCE(last_seen_at, start_time),
+                duration_seconds = FLOOR(EXTRACT(EPOCH FROM (COALESCE(last_seen_at, start_time) - start_time)))::int,
+                metadata = COALESCE(metadata, '{}'::jsonb) || $2
+            WHERE end_time IS NULL AND COALESCE(last_seen_at, start_time) < $1
+            "#,
+        )
+        .bind(seen_before)
+        .bind(metadata)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // Minutes of the finished calls that started in the range, grouped by tenant or professional
+    pub async fn minutes(
+        tx: &mut Transaction<'_, Postgres>,
+        scope: &CallMinutesScope,
+        by_professional: bool,
+    ) -> Result<Vec<CallMinutes>, DatabaseError> {
+        let group = if by_professional { "professional_user_id" } else { "tenant_id" };
+        let query = format!(
+            r#"
+            SELECT
+                {group} AS id,
+                COUNT(*) AS calls,
+                SUM(duration_seconds)::int8 AS total_seconds,
+                (SUM(duration_seconds) / 60.0)::float8 AS total_minutes,
+                (AVG(duration_seconds) / 60.0)::float8 AS average_minutes
+            FROM call_logs
+            WHERE end_time IS NOT NULL
+              AND {group} IS NOT NULL
+              AND start_time >= $1 AND start_time < $2
+              AND ($3::uuid IS NULL OR tenant_id = $3)
+              AND ($4::uuid IS NULL OR professional_user_id = $4)
+            GROUP BY {group}
+            ORDER BY total_seconds DESC, {group}
+            "#,
+            group = group
+        );
+
+        let minutes = sqlx::query_as::<_, CallMinutes>(&query)
+            .bind(scope.from)
+            .bind(scope.to)
+            .bind(scope.tenant_id)
+            .bind(scope.professional_user_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(minutes)
+    }
+}
@@ -0,0 +1,279 @@
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::db::{DatabaseError, TrainingCertificate};
+
+pub(crate) const CERTIFICATE_COLUMNS: &str = r#"
+    id, tenant_id, enrollment_id, training_session_id, employee_user_id, verification_code,
+    tenant_name, company_name, employee_name, training_title, host_name, completed_at,
+    storage_key, render_attempts, last_render_error, issued_at, rendered_at,
+    revoked_at, revoked_reason, revoked_by_user_id, created_at, updated_at
+"#;
+
+/// Renders are given up after this many failures; the error stays on the row for inspection.
+pub const MAX_CERTIFICATE_RENDER_ATTEMPTS: i32 = 5;
+
+pub struct CertificateRepository;
+
+#[allow(unused)]
+impl CertificateRepository {
+    // Issue a certificate for a completed enrollment, snapshotting the printed details;
+    // returns None if the enrollment is not completed or already has one
+    pub async fn issue(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+        verification_code: &str,
+    ) -> Result<Option<TrainingCertificate>, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_certificates (
+                tenant_id, enrollment_id, training_session_id, employee_user_id, verification_code,
+                tenant_name, company_name, employee_name, training_title, host_name, completed_at
+            )
+            SELECT e.tenant_id, e.id, e.training_session_id, e.employee_user_id, $2,
+                   t.name, c.name, COALESCE(p.first_name || ' ' || p.last_name, u.email), ts.title,
+                   hp.first_name || ' ' || hp.last_name, COALESCE(e.completion_date, ts.end_time)
+            FROM training_enrollments e
+            JOIN tenants t ON t.id = e.tenant_id
+            JOIN companies c ON c.id = e.company_id
+            JOIN users u ON u.id = e.employee_user_id
+            LEFT JOIN user_profiles p ON p.user_id = e.employee_user_id
+            JOIN training_sessions ts ON ts.id = e.training_session_id
+            LEFT JOIN user_profiles hp ON hp.user_id = ts.host_user_id
+            WHERE e.id = $1 AND e.status = 'completed'
+            ON CONFLICT (enrollment_id) DO NOTHING
+            RETURNING {}
+            "#,
+            CERTIFICATE_COLUMNS
+        );
+
+        let certificate = sqlx::query_as::<_, TrainingCertificate>(&query)
+            .bind(enrollment_id)
+            .bind(verification_code)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(certificate)
+    }
+
+    // Find the certificate of an employee for a session
+    pub async fn find_for_participant(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+        employee_user_id: Uuid,
+    ) -> Result<Option<TrainingCertificate>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_certificates WHERE training_session_id = $1 AND employee_user_id = $2",
+            CERTIFICATE_COLUMNS
+        );
+
+        let certificate = sqlx::query_as::<_, TrainingCertificate>(&query)
+            .bind(training_session_id)
+            .bind(employee_user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(certificate)
+    }
+
+    // Find a certificate of a tenant
+    pub async fn find_by_id(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<TrainingCertificate>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_certificates WHERE id = $1 AND tenant_id = $2",
+            CERTIFICATE_COLUMNS
+        );
+
+        let certificate = sqlx::query_as::<_, TrainingCertificate>(&query)
+            .bind(id)
+            .bind(tenant_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(certificate)
+    }
+
+    // Find a certificate by its verification code, for the public verification page
+    pub async fn find_by_code(
+        db: &PgPool,
+        verification_code: &str,
+    ) -> Result<Option<TrainingCertificate>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_certificates WHERE verification_code = $1",
+            CERTIFICATE_COLUMNS
+        );
+
+        let certificate = sqlx::query_as::<_, TrainingCertificate>(&query)
+            .bind(verification_code)
+            .fetch_optional(db)
+            .await?;
+
+        Ok(certificate)
+    }
+
+    // Revoke a certificate; returns None if it was already revoked
+    pub async fn revoke(
+        tx: &mut Transaction<'_, Postgres>,
+        certificate_id: Uuid,
+        revoked_by: Uuid,
+        reason: &str,
+    ) -> Result<Option<TrainingCertificate>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_certificates
+            SET revoked_at = NOW(), revoked_reason = $3, revoked_by_user_id = $2, updated_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL
+            RETURNING {}
+            "#,
+            CERTIFICATE_COLUMNS
+        );
+
+        let certificate = sqlx::query_as::<_, TrainingCertificate>(&query)
+            .bind(certificate_id)
+            .bind(revoked_by)
+            .bind(reason)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(certificate)
+    }
+
+    // List the certificates issued for a session
+    pub async fn list_for_session(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<Vec<TrainingCertificate>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_certificates WHERE training_session_id = $1 ORDER BY employee_name, id",
+            CERTIFICATE_COLUMNS
+        );
+
+        let certificates = sqlx::query_as::<_, TrainingCertificate>(&query)
+            .bind(training_session_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(certificates)
+    }
+
+    // List completed enrollments of a session that have no certificate yet
+    pub async fn list_uncertified_enrollment_ids(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<Vec<Uuid>, DatabaseError> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT e.id FROM training_enrollments e
+            WHERE e.training_session_id = $1 AND e.status = 'completed'
+              AND NOT EXISTS (SELECT 1 FROM training_certificates c WHERE c.enrollment_id = e.id)
+            ORDER BY e.enrolled_at, e.id
+            "#,
+        )
+        .bind(training_session_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(ids)
+    }
+
+    // Claim certificates waiting to be rendered, counting the attempt. Certificates another
+    // worker claimed are skipped until the claim is older than `stale_after_seconds`.
+    pub async fn claim_pending(
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+        stale_after_seconds: i64,
+    ) -> Result<Vec<TrainingCertificate>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_certificates
+            SET render_claimed_at = NOW(), render_attempts = render_attempts + 1, updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM training_certificates
+                WHERE storage_key IS NULL AND revoked_at IS NULL AND render_attempts < $1
+                  AND (render_claimed_at IS NULL OR render_claimed_at < NOW() - make_interval(secs => $3))
+                ORDER BY created_at, id
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            CERTIFICATE_COLUMNS
+        );
+
+        let certificates = sqlx::query_as::<_, TrainingCertificate>(&query)
+            .bind(MAX_CERTIFICATE_RENDER_ATTEMPTS)
+            .bind(limit)
+            .bind(stale_after_seconds as f64)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(certificates)
+    }
+
+    // Record where the rendered PDF was stored, on the certificate and its enrollment
+    pub async fn mark_rendered(
+        tx: &mut Transaction<'_, Postgres>,
+        certificate: &TrainingCertificate,
+        storage_key: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE training_certificates
+            SET storage_key = $2, rendered_at = NOW(), render_claimed_at = NULL, last_render_error = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(certificate.id)
+        .bind(storage_key)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query("UPDATE training_enrollments SET certificate_s3_key = $2, updated_at = NOW() WHERE id = $1")
+            .bind(certificate.enrollment_id)
+            .bind(storage_key)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    // Release the claim of a certificate that failed to render, keeping the error
+    pub async fn mark_failed(
+        tx: &mut Transaction<'_, Postgres>,
+        certificate_id: Uuid,
+        error: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE training_certificates
+            SET render_claimed_at = NULL, last_render_error = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(certificate_id)
+        .bind(error)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Get the branding settings of a tenant, if it has any
+    pub async fn tenant_branding(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+    ) -> Result<Option<serde_json::Value>, DatabaseError> {
+        let branding = sqlx::query_scalar(
+            "SELECT setting_value FROM system_settings WHERE tenant_id = $1 AND setting_key = 'TENANT_BRANDING'",
+        )
+        .bind(tenant_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(branding)
+    }
+}
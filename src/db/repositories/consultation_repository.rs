@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+
+use sqlx::types::{Json, Uuid};
+use sqlx::{Postgres, Transaction};
+
+use crate::db::{
+    AppointmentType, ConsultationNote, ConsultationNoteTemplate, ConsultationNoteVersion,
+    DatabaseError, NewConsultationNoteTemplate,
+};
+
+const TEMPLATE_COLUMNS: &str = r#"
+    id, tenant_id, appointment_type, name, sections, is_active,
+    created_by_user_id, created_at, updated_at
+"#;
+
+const NOTE_COLUMNS: &str = r#"
+    id, tenant_id, appointment_id, template_id, author_user_id, status,
+    current_version, signed_at, signed_by_user_id, created_at, updated_at
+"#;
+
+const VERSION_COLUMNS: &str = "id, note_id, version, content, edited_by_user_id, created_at";
+
+pub struct ConsultationRepository;
+
+#[allow(unused)]
+impl ConsultationRepository {
+    // List the active templates available to a tenant, tenant-specific ones first
+    pub async fn list_templates(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        appointment_type: Option<AppointmentType>,
+    ) -> Result<Vec<ConsultationNoteTemplate>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM consultation_note_templates
+            WHERE is_active
+              AND (tenant_id = $1 OR tenant_id IS NULL)
+              AND ($2::appointment_type IS NULL OR appointment_type = $2)
+            ORDER BY tenant_id IS NULL, appointment_type, created_at DESC
+            "#,
+            TEMPLATE_COLUMNS
+        );
+
+        let templates = sqlx::query_as::<_, ConsultationNoteTemplate>(&query)
+            .bind(tenant_id)
+            .bind(appointment_type)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(templates)
+    }
+
+    // Find a template usable by a tenant
+    pub async fn find_template(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        id: Uuid,
+    ) -> Result<ConsultationNoteTemplate, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM consultation_note_templates WHERE id = $1 AND (tenant_id = $2 OR tenant_id IS NULL)",
+            TEMPLATE_COLUMNS
+        );
+
+        sqlx::query_as::<_, ConsultationNoteTemplate>(&query)
+            .bind(id)
+            .bind(tenant_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Create a tenant-specific template
+    pub async fn create_template(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        created_by_user_id: Uuid,
+        template: &NewConsultationNoteTemplate,
+    ) -> Result<ConsultationNoteTemplate, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO consultation_note_templates (tenant_id, appointment_type, name, sections, created_by_user_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            TEMPLATE_COLUMNS
+        );
+
+        let created = sqlx::query_as::<_, ConsultationNoteTemplate>(&query)
+            .bind(tenant_id)
+            .bind(template.appointment_type)
+            .bind(&template.name)
+            .bind(Json(&template.sections))
+            .bind(created_by_user_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(created)
+    }
+
+    // Find the note of an appointment
+    pub async fn find_note_by_appointment(
+        tx: &mut Transaction<'_, Postgres>,
+        appointment_id: Uuid,
+    ) -> Result<Option<ConsultationNote>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM consultation_notes WHERE appointment_id = $1",
+            NOTE_COLUMNS
+        );
+
+        let note = sqlx::query_as::<_, ConsultationNote>(&query)
+            .bind(appointment_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(note)
+    }
+
+    // Create an empty draft note for an appointment
+    pub async fn create_note(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        appointment_id: Uuid,
+        template_id: Uuid,
+        author_user_id: Uuid,
+    ) -> Result<ConsultationNote, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO consultation_notes (tenant_id, appointment_id, template_id, author_user_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            NOTE_COLUMNS
+        );
+
+        sqlx::query_as::<_, ConsultationNote>(&query)
+            .bind(tenant_id)
+            .bind(appointment_id)
+            .bind(template_id)
+            .bind(author_user_id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => DatabaseError::Duplicate,
+                e => DatabaseError::Sqlx(e),
+            })
+    }
+
+    // Append a version to a draft note; returns None when the note moved past `expected_version`
+    pub async fn add_version(
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        expected_version: i32,
+        content: &BTreeMap<String, String>,
+        edited_by_user_id: Uuid,
+    ) -> Result<Option<(ConsultationNote, ConsultationNoteVersion)>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE consultation_notes
+            SET current_version = current_version + 1, updated_at = NOW()
+            WHERE id = $1 AND status = 'draft' AND current_version = $2
+            RETURNING {}
+            "#,
+            NOTE_COLUMNS
+        );
+
+        let Some(note) = sqlx::query_as::<_, ConsultationNote>(&query)
+            .bind(note_id)
+            .bind(expected_version)
+            .fetch_optional(&mut **tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let query = format!(
+            r#"
+            INSERT INTO consultation_note_versions (note_id, version, content, edited_by_user_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            VERSION_COLUMNS
+        );
+
+        let version = sqlx::query_as::<_, ConsultationNoteVersion>(&query)
+            .bind(note.id)
+            .bind(note.current_version)
+            .bind(Json(content))
+            .bind(edited_by_user_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(Some((note, version)))
+    }
+
+    // Find one version of a note
+    pub async fn find_version(
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        version: i32,
+    ) -> Result<Option<ConsultationNoteVersion>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM consultation_note_versions WHERE note_id = $1 AND version = $2",
+            VERSION_COLUMNS
+        );
+
+        let version = sqlx::query_as::<_, ConsultationNoteVersion>(&query)
+            .bind(note_id)
+            .bind(version)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(version)
+    }
+
+    // List the edit history of a note, newest first
+    pub async fn list_versions(
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Vec<ConsultationNoteVersion>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM consultation_note_versions WHERE note_id = $1 ORDER BY version DESC",
+            VERSION_COLUMNS
+        );
+
+        let versions = sqlx::query_as::<_, ConsultationNoteVersion>(&query)
+            .bind(note_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(versions)
+    }
+
+    // Sign and lock a draft note
+    pub async fn sign(
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        signed_by_user_id: Uuid,
+    ) -> Result<Option<ConsultationNote>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE consultation_notes
+            SET status = 'signed', signed_at = NOW(), signed_by_user_id = $2, updated_at = NOW()
+            WHERE id = $1 AND status = 'draft'
+            RETURNING {}
+            "#,
+            NOTE_COLUMNS
+        );
+
+        let note = sqlx::query_as::<_, ConsultationNote>(&query)
+            .bind(note_id)
+            .bind(signed_by_user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(note)
+    }
+}
@@ -0,0 +1,292 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+
+use crate::db::{DatabaseError, ParticipantStatus, TrainingEnrollment};
+
+pub(crate) const ENROLLMENT_COLUMNS: &str = r#"
+    id, tenant_id, training_session_id, employee_user_id, company_id, status, enrolled_at,
+    attended, completion_date, certificate_s3_key, feedback_rating, feedback_text, feedback_submitted_at,
+    attended_minutes, attendance_overridden_by, attendance_overridden_at, attendance_override_note,
+    presence_seen_at, created_at, updated_at
+"#;
+
+pub struct EnrollmentRepository;
+
+#[allow(unused)]
+impl EnrollmentRepository {
+    // Find the enrollment of an employee in a session
+    pub async fn find(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+        employee_user_id: Uuid,
+    ) -> Result<Option<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_enrollments WHERE training_session_id = $1 AND employee_user_id = $2",
+            ENROLLMENT_COLUMNS
+        );
+
+        let enrollment = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(training_session_id)
+            .bind(employee_user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(enrollment)
+    }
+
+    // Find and lock the enrollment of an employee in a session
+    pub async fn find_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+        employee_user_id: Uuid,
+    ) -> Result<Option<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_enrollments WHERE training_session_id = $1 AND employee_user_id = $2 FOR UPDATE",
+            ENROLLMENT_COLUMNS
+        );
+
+        let enrollment = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(training_session_id)
+            .bind(employee_user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(enrollment)
+    }
+
+    // List the enrollments of a session, seat holders first, then the waitlist in order
+    pub async fn list_for_session(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<Vec<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_enrollments
+            WHERE training_session_id = $1
+            ORDER BY status IN ('waitlisted', 'withdrawn'), status = 'withdrawn', enrolled_at, id
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        let enrollments = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(training_session_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(enrollments)
+    }
+
+    // Count the enrollments holding a seat; callers must hold the session lock
+    pub async fn count_seats(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<i64, DatabaseError> {
+        let seats = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM training_enrollments
+            WHERE training_session_id = $1 AND status NOT IN ('waitlisted', 'withdrawn')
+            "#,
+        )
+        .bind(training_session_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(seats)
+    }
+
+    // Position of a waitlisted enrollment in the queue, 1-based
+    pub async fn waitlist_position(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment: &TrainingEnrollment,
+    ) -> Result<i64, DatabaseError> {
+        let position = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) + 1 FROM training_enrollments
+            WHERE training_session_id = $1 AND status = 'waitlisted'
+              AND (enrolled_at, id) < ($2, $3)
+            "#,
+        )
+        .bind(enrollment.training_session_id)
+        .bind(enrollment.enrolled_at)
+        .bind(enrollment.id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(position)
+    }
+
+    // Enroll an employee, reviving a withdrawn enrollment at the back of the queue;
+    // returns None when the employee is already enrolled or waitlisted
+    pub async fn enroll(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        training_session_id: Uuid,
+        employee_user_id: Uuid,
+        company_id: Uuid,
+        status: ParticipantStatus,
+    ) -> Result<Option<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_enrollments (
+                tenant_id, training_session_id, employee_user_id, company_id, status, enrolled_at
+            )
+            VALUES ($1, $2, $3, $4, $5, clock_timestamp())
+            ON CONFLICT (training_session_id, employee_user_id) DO UPDATE
+            SET status = EXCLUDED.status, company_id = EXCLUDED.company_id,
+                enrolled_at = EXCLUDED.enrolled_at, updated_at = NOW()
+            WHERE training_enrollments.status = 'withdrawn'
+            RETURNING {}
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        let enrollment = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(tenant_id)
+            .bind(training_session_id)
+            .bind(employee_user_id)
+            .bind(company_id)
+            .bind(status)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(enrollment)
+    }
+
+    // Change the status of an enrollment
+    pub async fn set_status(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        status: ParticipantStatus,
+    ) -> Result<TrainingEnrollment, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_enrollments
+            SET status = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(id)
+            .bind(status)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Complete an attended enrollment whose participant passed the quiz; returns None unless it was attended
+    pub async fn complete_attended(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_enrollments
+            SET status = 'completed', completion_date = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'attended'
+            RETURNING {}
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        let enrollment = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(enrollment)
+    }
+
+    // Lock and list the waitlist of a session in queue order
+    pub async fn list_waitlisted(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<Vec<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_enrollments
+            WHERE training_session_id = $1 AND status = 'waitlisted'
+            ORDER BY enrolled_at, id
+            FOR UPDATE
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        let waitlist = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(training_session_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(waitlist)
+    }
+
+    // Move waitlisted enrollments onto seats
+    pub async fn promote(
+        tx: &mut Transaction<'_, Postgres>,
+        ids: &[Uuid],
+    ) -> Result<Vec<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_enrollments
+            SET status = 'registered', updated_at = NOW()
+            WHERE id = ANY($1) AND status = 'waitlisted'
+            RETURNING {}
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        let promoted = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(ids)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(promoted)
+    }
+
+    // Check that a company belongs to a tenant
+    pub async fn company_in_tenant(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        company_id: Uuid,
+    ) -> Result<bool, DatabaseError> {
+        let exists = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM companies WHERE id = $1 AND tenant_id = $2)")
+            .bind(company_id)
+            .bind(tenant_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(exists)
+    }
+
+    // List the active employees of a company, optionally in one department
+    pub async fn list_company_employee_ids(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        company_id: Uuid,
+        department: Option<&str>,
+    ) -> Result<Vec<Uuid>, DatabaseError> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT u.id
+            FROM users u
+            JOIN user_tenant_context_roles r
+              ON r.user_id = u.id AND r.role = 'employee' AND r.tenant_id = $1 AND r.company_id = $2
+            LEFT JOIN user_profiles p ON p.user_id = u.id
+            WHERE u.status = 'active'
+              AND ($3::text IS NULL OR lower(p.department) = lower($3))
+            ORDER BY u.id
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(company_id)
+        .bind(department)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(ids)
+    }
+}
@@ -0,0 +1,155 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+
+use crate::db::{
+    DatabaseError, FeedbackComment, FeedbackGrouping, FeedbackReportRow, FeedbackReportScope, FeedbackSummary,
+    TrainingEnrollment,
+};
+
+use super::ENROLLMENT_COLUMNS;
+
+// Rating counts over the enrollments `te` of participants who attended
+const SUMMARY_COLUMNS: &str = r#"
+    COUNT(te.id) AS participants,
+    COUNT(te.feedback_rating) AS responses,
+    AVG(te.feedback_rating)::float8 AS average_rating,
+    COUNT(*) FILTER (WHERE te.feedback_rating = 1) AS rating_1,
+    COUNT(*) FILTER (WHERE te.feedback_rating = 2) AS rating_2,
+    COUNT(*) FILTER (WHERE te.feedback_rating = 3) AS rating_3,
+    COUNT(*) FILTER (WHERE te.feedback_rating = 4) AS rating_4,
+    COUNT(*) FILTER (WHERE te.feedback_rating = 5) AS rating_5
+"#;
+
+const ATTENDED_STATUSES: &str = "('attended', 'completed')";
+
+pub struct FeedbackRepository;
+
+#[allow(unused)]
+impl FeedbackRepository {
+    // Store the feedback of an enrollment; returns None if feedback was already given
+    pub async fn submit(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+        rating: i16,
+        comment: Option<&str>,
+    ) -> Result<Option<TrainingEnrollment>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_enrollments
+            SET feedback_rating = $2, feedback_text = $3, feedback_submitted_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND feedback_submitted_at IS NULL
+            RETURNING {}
+            "#,
+            ENROLLMENT_COLUMNS
+        );
+
+        let enrollment = sqlx::query_as::<_, TrainingEnrollment>(&query)
+            .bind(enrollment_id)
+            .bind(rating)
+            .bind(comment)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(enrollment)
+    }
+
+    // Rating counts of a session
+    pub async fn session_summary(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<FeedbackSummary, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_enrollments te WHERE te.training_session_id = $1 AND te.status IN {}",
+            SUMMARY_COLUMNS, ATTENDED_STATUSES
+        );
+
+        let summary = sqlx::query_as::<_, FeedbackSummary>(&query)
+            .bind(training_session_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(summary)
+    }
+
+    // List the comments left on a session, newest first
+    pub async fn list_comments(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<Vec<FeedbackComment>, DatabaseError> {
+        let comments = sqlx::query_as::<_, FeedbackComment>(
+            r#"
+            SELECT feedback_rating AS rating, feedback_text AS comment, feedback_submitted_at AS submitted_at
+            FROM training_enrollments
+            WHERE training_session_id = $1 AND feedback_rating IS NOT NULL
+              AND feedback_text IS NOT NULL AND feedback_text <> ''
+            ORDER BY feedback_submitted_at DESC
+            "#,
+        )
+        .bind(training_session_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(comments)
+    }
+
+    // Rating counts of the sessions starting in the range, grouped by session, host or title
+    // and optionally by the week or month they started in, in the given zone
+    pub async fn rating_report(
+        tx: &mut Transaction<'_, Postgres>,
+        scope: &FeedbackReportScope,
+        time_zone: &str,
+    ) -> Result<Vec<FeedbackReportRow>, DatabaseError> {
+        let period = match scope.period {
+            Some(period) => format!("date_trunc('{}', ts.start_time AT TIME ZONE $5)::date", period.unit()),
+            None => "NULL::date".to_string(),
+        };
+        let host_name = "NULLIF(concat_ws(' ', hp.first_name, hp.last_name), '')";
+        let (session_id, session_start, title, host_id, host) = match scope.group_by {
+            FeedbackGrouping::Session => ("ts.id", "ts.start_time", "ts.title", "ts.host_user_id", host_name),
+            FeedbackGrouping::Host => ("NULL::uuid", "NULL::timestamptz", "NULL::text", "ts.host_user_id", host_name),
+            FeedbackGrouping::Title => ("NULL::uuid", "NULL::timestamptz", "ts.title", "NULL::uuid", "NULL::text"),
+        };
+
+        let query = format!(
+            r#"
+            SELECT
+                {period} AS period_start,
+                {session_id} AS training_session_id,
+                {session_start} AS session_start,
+                {title} AS training_title,
+                {host_id} AS host_user_id,
+                {host} AS host_name,
+                COUNT(DISTINCT ts.id) AS sessions,
+                {summary}
+            FROM training_sessions ts
+            JOIN training_enrollments te ON te.training_session_id = ts.id AND te.status IN {attended}
+            LEFT JOIN user_profiles hp ON hp.user_id = ts.host_user_id
+            WHERE ts.tenant_id = $1
+              AND ts.start_time >= $2 AND ts.start_time < $3
+              AND ($4::uuid IS NULL OR ts.host_user_id = $4)
+            GROUP BY 1, 2, 3, 4, 5, 6
+            ORDER BY 1 NULLS FIRST, 3 NULLS FIRST, 4, 6, 2
+            "#,
+            period = period,
+            session_id = session_id,
+            session_start = session_start,
+            title = title,
+            host_id = host_id,
+            host = host,
+            summary = SUMMARY_COLUMNS,
+            attended = ATTENDED_STATUSES
+        );
+
+        let mut rows = sqlx::query_as::<_, FeedbackReportRow>(&query)
+            .bind(scope.tenant_id)
+            .bind(scope.from)
+            .bind(scope.to)
+            .bind(scope.host_user_id);
+        if scope.period.is_some() {
+            rows = rows.bind(time_zone);
+        }
+        let rows = rows.fetch_all(&mut **tx).await?;
+
+        Ok(rows)
+    }
+}
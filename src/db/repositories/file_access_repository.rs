@@ -0,0 +1,31 @@
+use sqlx::{Postgres, Transaction};
+
+use crate::db::{DatabaseError, FileAccessEntry};
+
+pub struct FileAccessRepository;
+
+#[allow(unused)]
+impl FileAccessRepository {
+    // Record a download link handed out
+    pub async fn record(tx: &mut Transaction<'_, Postgres>, entry: &FileAccessEntry) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO file_access_logs (
+                tenant_id, user_id, resource_type, resource_id, object_key, byte_range, user_agent
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(entry.tenant_id)
+        .bind(entry.user_id)
+        .bind(entry.resource_type)
+        .bind(entry.resource_id)
+        .bind(&entry.object_key)
+        .bind(entry.byte_range.as_deref())
+        .bind(entry.user_agent.as_deref())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,369 @@
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::db::{DatabaseError, MaterialUpload, NewMaterialUpload, TrainingMaterial};
+
+pub(crate) const MATERIAL_COLUMNS: &str = r#"
+    id, tenant_id, uploader_user_id, training_session_id, title, description, material_type,
+    file_s3_key, file_size_bytes, content_type, file_name, processing_status, processing_attempts,
+    processing_error, processing_started_at, processed_at, hls_renditions, duration_seconds, poster_key,
+    page_count, created_at, updated_at
+"#;
+
+const UPLOAD_COLUMNS: &str = r#"
+    id, tenant_id, uploader_user_id, training_session_id, title, description, material_type,
+    object_key, file_name, content_type, size_bytes, expires_at, material_id, confirm_started_at,
+    confirmed_at, quarantined_at, quarantine_reason, created_at
+"#;
+
+/// Processing is given up after this many failures; the error stays on the material.
+pub const MAX_MATERIAL_PROCESSING_ATTEMPTS: i32 = 3;
+
+pub struct MaterialRepository;
+
+#[allow(unused)]
+impl MaterialRepository {
+    // Record an upload the caller was handed a presigned URL for
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_upload(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        tenant_id: Uuid,
+        uploader_user_id: Uuid,
+        upload: &NewMaterialUpload,
+        object_key: &str,
+        file_name: &str,
+        content_type: &str,
+        expires_at: OffsetDateTime,
+    ) -> Result<MaterialUpload, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_material_uploads (
+                id, tenant_id, uploader_user_id, training_session_id, title, description, material_type,
+                object_key, file_name, content_type, size_bytes, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING {}
+            "#,
+            UPLOAD_COLUMNS
+        );
+
+        let upload = sqlx::query_as::<_, MaterialUpload>(&query)
+            .bind(id)
+            .bind(tenant_id)
+            .bind(uploader_user_id)
+            .bind(upload.training_session_id)
+            .bind(upload.title.trim())
+            .bind(upload.description.as_deref())
+            .bind(upload.material_type)
+            .bind(object_key)
+            .bind(file_name)
+            .bind(content_type)
+            .bind(upload.size_bytes)
+            .bind(expires_at)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(upload)
+    }
+
+    // Find and lock an upload of the uploader
+    pub async fn find_upload_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        uploader_user_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<MaterialUpload>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_material_uploads
+            WHERE id = $1 AND tenant_id = $2 AND uploader_user_id = $3
+            FOR UPDATE
+            "#,
+            UPLOAD_COLUMNS
+        );
+
+        let upload = sqlx::query_as::<_, MaterialUpload>(&query)
+            .bind(id)
+            .bind(tenant_id)
+            .bind(uploader_user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(upload)
+    }
+
+    // Mark an upload as being confirmed while its file is checked
+    pub async fn start_confirmation(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE training_material_uploads SET confirm_started_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    // Let an upload whose checks did not pass be confirmed again
+    pub async fn release_confirmation(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE training_material_uploads SET confirm_started_at = NULL WHERE id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    // Create the material of an upload, stored under its own key, and mark the upload confirmed
+    pub async fn confirm_upload(
+        tx: &mut Transaction<'_, Postgres>,
+        upload: &MaterialUpload,
+        material_id: Uuid,
+        file_s3_key: &str,
+        file_size_bytes: i64,
+        page_count: Option<i32>,
+    ) -> Result<TrainingMaterial, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_materials (
+                id, tenant_id, uploader_user_id, training_session_id, title, description, material_type,
+                file_s3_key, file_size_bytes, content_type, file_name, page_count, processing_status
+            )
+            SELECT $3, tenant_id, uploader_user_id, training_session_id, title, description, material_type,
+                   $4, $2, content_type, file_name, $5,
+                   CASE WHEN material_type = 'video' THEN 'pending'::material_processing_status END
+            FROM training_material_uploads
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            MATERIAL_COLUMNS
+        );
+
+        let material = sqlx::query_as::<_, TrainingMaterial>(&query)
+            .bind(upload.id)
+            .bind(file_size_bytes)
+            .bind(material_id)
+            .bind(file_s3_key)
+            .bind(page_count)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        sqlx::query("UPDATE training_material_uploads SET confirmed_at = NOW(), material_id = $2 WHERE id = $1")
+            .bind(upload.id)
+            .bind(material.id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(material)
+    }
+
+    // Mark an upload the malware scan flagged, recording where the flagged object was kept
+    pub async fn quarantine_upload(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        reason: &str,
+        object_key: &str,
+    ) -> Result<MaterialUpload, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_material_uploads
+            SET quarantined_at = NOW(), quarantine_reason = $2, object_key = $3
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            UPLOAD_COLUMNS
+        );
+
+        let upload = sqlx::query_as::<_, MaterialUpload>(&query)
+            .bind(id)
+            .bind(reason)
+            .bind(object_key)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(upload)
+    }
+
+    // List the uploads of an uploader, newest first
+    pub async fn list_uploads(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        uploader_user_id: Uuid,
+    ) -> Result<Vec<MaterialUpload>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_material_uploads
+            WHERE tenant_id = $1 AND uploader_user_id = $2
+            ORDER BY created_at DESC
+            "#,
+            UPLOAD_COLUMNS
+        );
+
+        let uploads = sqlx::query_as::<_, MaterialUpload>(&query)
+            .bind(tenant_id)
+            .bind(uploader_user_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(uploads)
+    }
+
+    // Find a material of a tenant
+    pub async fn find_by_id(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<TrainingMaterial>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_materials WHERE id = $1 AND tenant_id = $2",
+            MATERIAL_COLUMNS
+        );
+
+        let material = sqlx::query_as::<_, TrainingMaterial>(&query)
+            .bind(id)
+            .bind(tenant_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(material)
+    }
+
+    // List the materials of a session, oldest first
+    pub async fn list_for_session(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        training_session_id: Uuid,
+    ) -> Result<Vec<TrainingMaterial>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_materials
+            WHERE training_session_id = $1 AND tenant_id = $2
+            ORDER BY created_at, id
+            "#,
+            MATERIAL_COLUMNS
+        );
+
+        let materials = sqlx::query_as::<_, TrainingMaterial>(&query)
+            .bind(training_session_id)
+            .bind(tenant_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(materials)
+    }
+
+    // Claim the oldest video waiting to be processed, or one whose worker stopped answering
+    // `stale_after_seconds` ago, skipping rows another worker holds
+    pub async fn claim_next_video(
+        tx: &mut Transaction<'_, Postgres>,
+        stale_after_seconds: i64,
+    ) -> Result<Option<TrainingMaterial>, DatabaseError> {
+        // Abandoned claims that used up their attempts are not retried
+        sqlx::query(
+            r#"
+            UPDATE training_materials
+            SET processing_status = 'failed', processing_error = 'Processing did not finish in time', updated_at = NOW()
+            WHERE processing_status = 'processing'
+              AND processing_started_at < NOW() - make_interval(secs => $1)
+              AND processing_attempts >= $2
+            "#,
+        )
+        .bind(stale_after_seconds as f64)
+        .bind(MAX_MATERIAL_PROCESSING_ATTEMPTS)
+        .execute(&mut **tx)
+        .await?;
+
+        let query = format!(
+            r#"
+            UPDATE training_materials
+            SET processing_status = 'processing', processing_started_at = NOW(),
+                processing_attempts = processing_attempts + 1, updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM training_materials
+                WHERE (processing_status = 'pending'
+                       OR (processing_status = 'processing'
+                           AND processing_started_at < NOW() - make_interval(secs => $1)))
+                  AND processing_attempts < $2
+                ORDER BY created_at, id
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            MATERIAL_COLUMNS
+        );
+
+        let material = sqlx::query_as::<_, TrainingMaterial>(&query)
+            .bind(stale_after_seconds as f64)
+            .bind(MAX_MATERIAL_PROCESSING_ATTEMPTS)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(material)
+    }
+
+    // Record the renditions of a processed video
+    pub async fn mark_processed(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        renditions: &[String],
+        duration_seconds: f64,
+        poster_key: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE training_materials
+            SET processing_status = 'ready', processing_error = NULL, processed_at = NOW(),
+                hls_renditions = $2, duration_seconds = $3, poster_key = $4, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(renditions)
+        .bind(duration_seconds)
+        .bind(poster_key)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Record a failed processing attempt; the video is retried until it runs out of attempts
+    pub async fn mark_processing_failed(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        error: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE training_materials
+            SET processing_status = CASE WHEN processing_attempts >= $3 THEN 'failed' ELSE 'pending' END
+                    ::material_processing_status,
+                processing_error = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(MAX_MATERIAL_PROCESSING_ATTEMPTS)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Find a material by id alone, for playlist requests authorized by their signature
+    pub async fn find_for_playback(db: &PgPool, id: Uuid) -> Result<Option<TrainingMaterial>, DatabaseError> {
+        let query = format!("SELECT {} FROM training_materials WHERE id = $1", MATERIAL_COLUMNS);
+
+        let material = sqlx::query_as::<_, TrainingMaterial>(&query)
+            .bind(id)
+            .fetch_optional(db)
+            .await?;
+
+        Ok(material)
+    }
+}
@@ -1 +1,46 @@
-mod user_repository;
\ No newline at end of file
+mod user_repository;
+mod time_zone_repository;
+mod appointment_repository;
+mod availability_repository;
+mod training_repository;
+mod calendar_repository;
+mod notification_repository;
+mod reminder_repository;
+mod consultation_repository;
+mod analytics_repository;
+mod enrollment_repository;
+mod requirement_repository;
+mod attendance_repository;
+mod certificate_repository;
+mod quiz_attempt_repository;
+mod quiz_repository;
+mod feedback_repository;
+mod material_repository;
+mod file_access_repository;
+mod safety_report_repository;
+mod progress_repository;
+mod call_log_repository;
+
+#[allow(unused)]
+pub use user_repository::*;
+pub use time_zone_repository::*;
+pub use appointment_repository::*;
+pub use availability_repository::*;
+pub use training_repository::*;
+pub use calendar_repository::*;
+pub use notification_repository::*;
+pub use reminder_repository::*;
+pub use consultation_repository::*;
+pub use analytics_repository::*;
+pub use enrollment_repository::*;
+pub use requirement_repository::*;
+pub use attendance_repository::*;
+pub use certificate_repository::*;
+pub use quiz_repository::*;
+pub use quiz_attempt_repository::*;
+pub use feedback_repository::*;
+pub use material_repository::*;
+pub use file_access_repository::*;
+pub use safety_report_repository::*;
+pub use progress_repository::*;
+pub use call_log_repository::*;
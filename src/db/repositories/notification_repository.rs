@@ -0,0 +1,119 @@
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::db::{DatabaseError, NewNotification, Notification, PushToken};
+
+const NOTIFICATION_COLUMNS: &str = r#"
+    id, user_id, tenant_id, notification_type, title, message,
+    related_entity_id, related_entity_type, is_read, read_at, created_at
+"#;
+
+pub struct NotificationRepository;
+
+#[allow(unused)]
+impl NotificationRepository {
+    // Store an in-app notification
+    pub async fn create(
+        tx: &mut Transaction<'_, Postgres>,
+        notification: &NewNotification,
+    ) -> Result<Notification, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO notifications (
+                user_id, tenant_id, notification_type, title, message,
+                related_entity_id, related_entity_type
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            NOTIFICATION_COLUMNS
+        );
+
+        let created = sqlx::query_as::<_, Notification>(&query)
+            .bind(notification.user_id)
+            .bind(notification.tenant_id)
+            .bind(notification.notification_type)
+            .bind(&notification.title)
+            .bind(&notification.message)
+            .bind(notification.related_entity_id)
+            .bind(&notification.related_entity_type)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(created)
+    }
+
+    // Find the email address a notification for a user should go to
+    pub async fn find_email(pool: &PgPool, user_id: Uuid) -> Result<Option<String>, DatabaseError> {
+        let email = sqlx::query_scalar("SELECT email FROM users WHERE id = $1 AND status = 'active'")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(email)
+    }
+
+    // List the push tokens registered by a user
+    pub async fn list_push_tokens(pool: &PgPool, user_id: Uuid) -> Result<Vec<PushToken>, DatabaseError> {
+        let tokens = sqlx::query_as::<_, PushToken>(
+            r#"
+            SELECT id, user_id, token, device_name, last_used_at, created_at
+            FROM user_push_tokens
+            WHERE user_id = $1
+            ORDER BY last_used_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    // Register a push token for a user, refreshing it when already known
+    pub async fn upsert_push_token(
+        pool: &PgPool,
+        user_id: Uuid,
+        token: &str,
+        device_name: Option<&str>,
+    ) -> Result<PushToken, DatabaseError> {
+        let push_token = sqlx::query_as::<_, PushToken>(
+            r#"
+            INSERT INTO user_push_tokens (user_id, token, device_name)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, token) DO UPDATE
+            SET device_name = COALESCE(EXCLUDED.device_name, user_push_tokens.device_name),
+                last_used_at = NOW()
+            RETURNING id, user_id, token, device_name, last_used_at, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(token)
+        .bind(device_name)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(push_token)
+    }
+
+    // Remove a push token of a user
+    pub async fn delete_push_token(pool: &PgPool, user_id: Uuid, token: &str) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM user_push_tokens WHERE user_id = $1 AND token = $2")
+            .bind(user_id)
+            .bind(token)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // Remove a push token the push service reported as no longer registered
+    pub async fn delete_push_token_everywhere(pool: &PgPool, token: &str) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM user_push_tokens WHERE token = $1")
+            .bind(token)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use sqlx::types::{Json, Uuid};
+use sqlx::{Postgres, Transaction};
+
+use crate::db::{DatabaseError, MaterialProgress, MaterialProgressFields, TrainingEnrollment};
+
+const PROGRESS_COLUMNS: &str = r#"
+    id, tenant_id, training_session_id, enrollment_id, material_id, user_id, position_seconds,
+    watched_ranges, watched_seconds, duration_seconds, current_page, pages_viewed, page_count,
+    completed_at, last_ping_at, created_at, updated_at
+"#;
+
+pub struct ProgressRepository;
+
+#[allow(unused)]
+impl ProgressRepository {
+    // Find the progress of an enrollment on a material
+    pub async fn find(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+        material_id: Uuid,
+    ) -> Result<Option<MaterialProgress>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_material_progress WHERE enrollment_id = $1 AND material_id = $2",
+            PROGRESS_COLUMNS
+        );
+
+        let progress = sqlx::query_as::<_, MaterialProgress>(&query)
+            .bind(enrollment_id)
+            .bind(material_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(progress)
+    }
+
+    // Record a progress ping, creating the row on the first one
+    pub async fn save(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment: &TrainingEnrollment,
+        material_id: Uuid,
+        fields: &MaterialProgressFields,
+    ) -> Result<MaterialProgress, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_material_progress (
+                tenant_id, training_session_id, enrollment_id, material_id, user_id, position_seconds,
+                watched_ranges, watched_seconds, duration_seconds, current_page, pages_viewed, page_count,
+                completed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, CASE WHEN $13 THEN NOW() END)
+            ON CONFLICT (enrollment_id, material_id) DO UPDATE
+            SET position_seconds = EXCLUDED.position_seconds, watched_ranges = EXCLUDED.watched_ranges,
+                watched_seconds = EXCLUDED.watched_seconds, duration_seconds = EXCLUDED.duration_seconds,
+                current_page = EXCLUDED.current_page, pages_viewed = EXCLUDED.pages_viewed,
+                page_count = EXCLUDED.page_count,
+                completed_at = COALESCE(training_material_progress.completed_at, EXCLUDED.completed_at),
+                last_ping_at = NOW(), updated_at = NOW()
+            RETURNING {}
+            "#,
+            PROGRESS_COLUMNS
+        );
+
+        let progress = sqlx::query_as::<_, MaterialProgress>(&query)
+            .bind(enrollment.tenant_id)
+            .bind(enrollment.training_session_id)
+            .bind(enrollment.id)
+            .bind(material_id)
+            .bind(enrollment.employee_user_id)
+            .bind(fields.position_seconds)
+            .bind(Json(&fields.watched_ranges))
+            .bind(fields.watched_seconds)
+            .bind(fields.duration_seconds)
+            .bind(fields.current_page)
+            .bind(&fields.pages_viewed)
+            .bind(fields.page_count)
+            .bind(fields.completed)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(progress)
+    }
+
+    // List the progress rows of a session, limited to one participant when `user_id` is set
+    pub async fn list_for_session(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<MaterialProgress>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_material_progress
+            WHERE training_session_id = $1 AND ($2::uuid IS NULL OR user_id = $2)
+            "#,
+            PROGRESS_COLUMNS
+        );
+
+        let progress = sqlx::query_as::<_, MaterialProgress>(&query)
+            .bind(training_session_id)
+            .bind(user_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(progress)
+    }
+
+    // Whether an enrollment completed every material of its session; false for sessions without materials
+    pub async fn completed_all(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment: &TrainingEnrollment,
+    ) -> Result<bool, DatabaseError> {
+        let completed = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) > 0 AND COUNT(*) = COUNT(p.completed_at)
+            FROM training_materials tm
+            LEFT JOIN training_material_progress p ON p.material_id = tm.id AND p.enrollment_id = $3
+            WHERE tm.training_session_id = $1 AND tm.tenant_id = $2
+            "#,
+        )
+        .bind(enrollment.training_session_id)
+        .bind(enrollment.tenant_id)
+        .bind(enrollment.id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(completed)
+    }
+}
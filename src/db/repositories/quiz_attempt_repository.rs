@@ -0,0 +1,355 @@
+use sqlx::types::{Json, Uuid};
+use sqlx::{Postgres, Transaction};
+
+use crate::core::utils::quiz::NormalizedAnswer;
+use crate::db::{
+    AttemptLayoutEntry, DatabaseError, PendingQuizAnswer, QuizAttempt, QuizAttemptAnswer, QuizAttemptStatus,
+    TrainingEnrollment, TrainingQuiz,
+};
+
+pub(crate) const ATTEMPT_COLUMNS: &str = r#"
+    id, quiz_id, enrollment_id, employee_user_id, tenant_id, company_id, attempt_number, status,
+    started_at, expires_at, completed_at, score::float8 AS score, passed, points_earned, points_possible,
+    layout, created_at, updated_at
+"#;
+
+pub(crate) const ANSWER_COLUMNS: &str = r#"
+    id, attempt_id, question_id, tenant_id, answer_key, answer_text, is_correct, points_awarded,
+    graded_by_user_id, graded_at, submitted_at
+"#;
+
+/// Result written to an attempt once every answer is scored.
+#[derive(Debug, Clone, Copy)]
+pub struct AttemptResult {
+    pub points_earned: i32,
+    pub points_possible: i32,
+    pub score: f64,
+    pub passed: bool,
+}
+
+pub struct QuizAttemptRepository;
+
+#[allow(unused)]
+impl QuizAttemptRepository {
+    // Check whether anyone has started the quiz
+    pub async fn quiz_has_attempts(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+    ) -> Result<bool, DatabaseError> {
+        let exists = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM quiz_attempts WHERE quiz_id = $1)")
+            .bind(quiz_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(exists)
+    }
+
+    // Check whether an enrollment has a passing attempt
+    pub async fn has_passed(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+    ) -> Result<bool, DatabaseError> {
+        let passed = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM quiz_attempts WHERE enrollment_id = $1 AND passed IS TRUE)",
+        )
+        .bind(enrollment_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(passed)
+    }
+
+    // List the attempts of an enrollment, oldest first
+    pub async fn list_for_enrollment(
+        tx: &mut Transaction<'_, Postgres>,
+        enrollment_id: Uuid,
+    ) -> Result<Vec<QuizAttempt>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM quiz_attempts WHERE enrollment_id = $1 ORDER BY attempt_number",
+            ATTEMPT_COLUMNS
+        );
+
+        let attempts = sqlx::query_as::<_, QuizAttempt>(&query)
+            .bind(enrollment_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(attempts)
+    }
+
+    // List every attempt at a quiz, grouped by participant
+    pub async fn list_for_quiz(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+    ) -> Result<Vec<QuizAttempt>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM quiz_attempts WHERE quiz_id = $1 ORDER BY employee_user_id, attempt_number",
+            ATTEMPT_COLUMNS
+        );
+
+        let attempts = sqlx::query_as::<_, QuizAttempt>(&query)
+            .bind(quiz_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(attempts)
+    }
+
+    // Find and lock an attempt at a quiz
+    pub async fn find_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+        attempt_id: Uuid,
+    ) -> Result<Option<QuizAttempt>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM quiz_attempts WHERE quiz_id = $1 AND id = $2 FOR UPDATE",
+            ATTEMPT_COLUMNS
+        );
+
+        let attempt = sqlx::query_as::<_, QuizAttempt>(&query)
+            .bind(quiz_id)
+            .bind(attempt_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(attempt)
+    }
+
+    // Start the next attempt of an enrollment with the questions drawn for it;
+    // the deadline follows from the quiz's time limit
+    pub async fn start(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz: &TrainingQuiz,
+        enrollment: &TrainingEnrollment,
+        layout: &[AttemptLayoutEntry],
+    ) -> Result<QuizAttempt, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO quiz_attempts (
+                quiz_id, enrollment_id, employee_user_id, tenant_id, company_id, attempt_number,
+                status, started_at, expires_at, layout
+            )
+            VALUES (
+                $1, $2, $3, $4, $5,
+                (SELECT COALESCE(MAX(attempt_number), 0) + 1 FROM quiz_attempts WHERE enrollment_id = $2),
+                'in_progress', NOW(), NOW() + make_interval(mins => $6), $7
+            )
+            RETURNING {}
+            "#,
+            ATTEMPT_COLUMNS
+        );
+
+        let attempt = sqlx::query_as::<_, QuizAttempt>(&query)
+            .bind(quiz.id)
+            .bind(enrollment.id)
+            .bind(enrollment.employee_user_id)
+            .bind(enrollment.tenant_id)
+            .bind(enrollment.company_id)
+            .bind(quiz.time_limit_minutes)
+            .bind(Json(layout))
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(attempt)
+    }
+
+    // List the answers of an attempt
+    pub async fn list_answers(
+        tx: &mut Transaction<'_, Postgres>,
+        attempt_id: Uuid,
+    ) -> Result<Vec<QuizAttemptAnswer>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM quiz_attempt_answers WHERE attempt_id = $1 ORDER BY submitted_at, id",
+            ANSWER_COLUMNS
+        );
+
+        let answers = sqlx::query_as::<_, QuizAttemptAnswer>(&query)
+            .bind(attempt_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(answers)
+    }
+
+    // Save the answer to a question, replacing an earlier one
+    pub async fn save_answer(
+        tx: &mut Transaction<'_, Postgres>,
+        attempt: &QuizAttempt,
+        question_id: Uuid,
+        answer: &NormalizedAnswer,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO quiz_attempt_answers (attempt_id, question_id, tenant_id, answer_key, answer_text)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (attempt_id, question_id) DO UPDATE
+            SET answer_key = EXCLUDED.answer_key, answer_text = EXCLUDED.answer_text, submitted_at = NOW()
+            "#,
+        )
+        .bind(attempt.id)
+        .bind(question_id)
+        .bind(attempt.tenant_id)
+        .bind(answer.answer_key.as_deref())
+        .bind(answer.answer_text.as_deref())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Store the automatic score of an answer
+    pub async fn score_answer(
+        tx: &mut Transaction<'_, Postgres>,
+        answer_id: Uuid,
+        is_correct: bool,
+        points_awarded: i32,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE quiz_attempt_answers SET is_correct = $2, points_awarded = $3 WHERE id = $1")
+            .bind(answer_id)
+            .bind(is_correct)
+            .bind(points_awarded)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    // Mark an attempt submitted; the result is only known once nothing awaits grading
+    pub async fn submit(
+        tx: &mut Transaction<'_, Postgres>,
+        attempt_id: Uuid,
+        result: Option<AttemptResult>,
+    ) -> Result<QuizAttempt, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE quiz_attempts
+            SET status = $2, completed_at = NOW(), points_earned = $3, points_possible = $4,
+                score = $5, passed = $6, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            ATTEMPT_COLUMNS
+        );
+
+        let status = match result {
+            Some(_) => QuizAttemptStatus::Graded,
+            None => QuizAttemptStatus::PendingReview,
+        };
+
+        sqlx::query_as::<_, QuizAttempt>(&query)
+            .bind(attempt_id)
+            .bind(status)
+            .bind(result.map(|result| result.points_earned))
+            .bind(result.map(|result| result.points_possible))
+            .bind(result.map(|result| result.score))
+            .bind(result.map(|result| result.passed))
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Store the result of an attempt whose last free text answer was graded
+    pub async fn complete_review(
+        tx: &mut Transaction<'_, Postgres>,
+        attempt_id: Uuid,
+        result: AttemptResult,
+    ) -> Result<QuizAttempt, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE quiz_attempts
+            SET status = 'graded', points_earned = $2, points_possible = $3, score = $4, passed = $5,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            ATTEMPT_COLUMNS
+        );
+
+        sqlx::query_as::<_, QuizAttempt>(&query)
+            .bind(attempt_id)
+            .bind(result.points_earned)
+            .bind(result.points_possible)
+            .bind(result.score)
+            .bind(result.passed)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // List the free text answers of submitted attempts that still await grading, oldest first
+    pub async fn list_pending_reviews(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+    ) -> Result<Vec<PendingQuizAnswer>, DatabaseError> {
+        let pending = sqlx::query_as::<_, PendingQuizAnswer>(
+            r#"
+            SELECT a.id AS answer_id, a.attempt_id, qa.employee_user_id, qa.attempt_number,
+                   q.id AS question_id, q.question_text, q.points AS max_points, a.answer_text,
+                   qa.completed_at AS submitted_at
+            FROM quiz_attempt_answers a
+            JOIN quiz_attempts qa ON qa.id = a.attempt_id
+            JOIN quiz_questions q ON q.id = a.question_id
+            WHERE qa.quiz_id = $1 AND qa.status = 'pending_review' AND a.points_awarded IS NULL
+            ORDER BY qa.completed_at, qa.id, q.position
+            "#,
+        )
+        .bind(quiz_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(pending)
+    }
+
+    // Find and lock a free text answer awaiting grading
+    pub async fn find_pending_answer(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+        answer_id: Uuid,
+    ) -> Result<Option<QuizAttemptAnswer>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM quiz_attempt_answers
+            WHERE id = $2 AND points_awarded IS NULL
+              AND attempt_id IN (
+                  SELECT id FROM quiz_attempts WHERE quiz_id = $1 AND status = 'pending_review'
+              )
+            FOR UPDATE
+            "#,
+            ANSWER_COLUMNS
+        );
+
+        let answer = sqlx::query_as::<_, QuizAttemptAnswer>(&query)
+            .bind(quiz_id)
+            .bind(answer_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(answer)
+    }
+
+    // Record the points a grader awarded to a free text answer
+    pub async fn grade_answer(
+        tx: &mut Transaction<'_, Postgres>,
+        answer_id: Uuid,
+        points_awarded: i32,
+        is_correct: bool,
+        graded_by: Uuid,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE quiz_attempt_answers
+            SET points_awarded = $2, is_correct = $3, graded_by_user_id = $4, graded_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(answer_id)
+        .bind(points_awarded)
+        .bind(is_correct)
+        .bind(graded_by)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use sqlx::types::{Json, Uuid};
+use sqlx::{Postgres, Transaction};
+
+use crate::core::utils::quiz::NormalizedQuestion;
+use crate::db::{DatabaseError, NewTrainingQuiz, QuizQuestion, QuizQuestionType, TrainingQuiz};
+
+pub(crate) const QUIZ_COLUMNS: &str = r#"
+    id, tenant_id, training_session_id, title, description, pass_threshold_percent,
+    time_limit_minutes, max_attempts, questions_per_attempt, tag_quotas, created_by_user_id,
+    created_at, updated_at
+"#;
+
+pub(crate) const QUESTION_COLUMNS: &str = r#"
+    id, quiz_id, tenant_id, question_text, question_type, options, correct_answer_key,
+    points, position, tag, created_at, updated_at
+"#;
+
+/// Pass threshold of quizzes created without one.
+const DEFAULT_PASS_THRESHOLD_PERCENT: i32 = 70;
+
+pub struct QuizRepository;
+
+#[allow(unused)]
+impl QuizRepository {
+    // Find the quiz of a training session
+    pub async fn find_for_session(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<Option<TrainingQuiz>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_quizzes WHERE training_session_id = $1",
+            QUIZ_COLUMNS
+        );
+
+        let quiz = sqlx::query_as::<_, TrainingQuiz>(&query)
+            .bind(training_session_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(quiz)
+    }
+
+    // Create the quiz of a training session; returns None if the session already has one
+    pub async fn create(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        training_session_id: Uuid,
+        created_by: Uuid,
+        quiz: &NewTrainingQuiz,
+        tag_quotas: Option<&BTreeMap<String, i32>>,
+    ) -> Result<Option<TrainingQuiz>, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_quizzes (
+                tenant_id, training_session_id, title, description, pass_threshold_percent,
+                time_limit_minutes, max_attempts, questions_per_attempt, tag_quotas, created_by_user_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (training_session_id) DO NOTHING
+            RETURNING {}
+            "#,
+            QUIZ_COLUMNS
+        );
+
+        let quiz = sqlx::query_as::<_, TrainingQuiz>(&query)
+            .bind(tenant_id)
+            .bind(training_session_id)
+            .bind(quiz.title.trim())
+            .bind(quiz.description.as_deref())
+            .bind(quiz.pass_threshold_percent.unwrap_or(DEFAULT_PASS_THRESHOLD_PERCENT))
+            .bind(quiz.time_limit_minutes)
+            .bind(quiz.max_attempts)
+            .bind(quiz.questions_per_attempt)
+            .bind(tag_quotas.map(Json))
+            .bind(created_by)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(quiz)
+    }
+
+    // Replace the settings of a quiz
+    pub async fn update(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+        quiz: &NewTrainingQuiz,
+        tag_quotas: Option<&BTreeMap<String, i32>>,
+    ) -> Result<TrainingQuiz, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_quizzes
+            SET title = $2, description = $3, pass_threshold_percent = $4, time_limit_minutes = $5,
+                max_attempts = $6, questions_per_attempt = $7, tag_quotas = $8, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            QUIZ_COLUMNS
+        );
+
+        sqlx::query_as::<_, TrainingQuiz>(&query)
+            .bind(quiz_id)
+            .bind(quiz.title.trim())
+            .bind(quiz.description.as_deref())
+            .bind(quiz.pass_threshold_percent.unwrap_or(DEFAULT_PASS_THRESHOLD_PERCENT))
+            .bind(quiz.time_limit_minutes)
+            .bind(quiz.max_attempts)
+            .bind(quiz.questions_per_attempt)
+            .bind(tag_quotas.map(Json))
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Delete a quiz with its questions
+    pub async fn delete(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM training_quizzes WHERE id = $1")
+            .bind(quiz_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    // List the questions of a quiz in order
+    pub async fn list_questions(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+    ) -> Result<Vec<QuizQuestion>, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM quiz_questions WHERE quiz_id = $1 ORDER BY position",
+            QUESTION_COLUMNS
+        );
+
+        let questions = sqlx::query_as::<_, QuizQuestion>(&query)
+            .bind(quiz_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(questions)
+    }
+
+    // Append a question at the end of a quiz
+    pub async fn create_question(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz: &TrainingQuiz,
+        question_text: &str,
+        question_type: QuizQuestionType,
+        normalized: &NormalizedQuestion,
+        points: i32,
+        tag: Option<&str>,
+    ) -> Result<QuizQuestion, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO quiz_questions (
+                quiz_id, tenant_id, question_text, question_type, options, correct_answer_key, points, tag,
+                position
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8,
+                (SELECT COALESCE(MAX(position), 0) + 1 FROM quiz_questions WHERE quiz_id = $1)
+            )
+            RETURNING {}
+            "#,
+            QUESTION_COLUMNS
+        );
+
+        let question = sqlx::query_as::<_, QuizQuestion>(&query)
+            .bind(quiz.id)
+            .bind(quiz.tenant_id)
+            .bind(question_text)
+            .bind(question_type)
+            .bind(normalized.options.as_ref().map(Json))
+            .bind(normalized.correct_answer_key.as_deref())
+            .bind(points)
+            .bind(tag)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(question)
+    }
+
+    // Replace a question, keeping its position
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_question(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+        question_id: Uuid,
+        question_text: &str,
+        question_type: QuizQuestionType,
+        normalized: &NormalizedQuestion,
+        points: i32,
+        tag: Option<&str>,
+    ) -> Result<QuizQuestion, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE quiz_questions
+            SET question_text = $3, question_type = $4, options = $5, correct_answer_key = $6,
+                points = $7, tag = $8, updated_at = NOW()
+            WHERE quiz_id = $1 AND id = $2
+            RETURNING {}
+            "#,
+            QUESTION_COLUMNS
+        );
+
+        sqlx::query_as::<_, QuizQuestion>(&query)
+            .bind(quiz_id)
+            .bind(question_id)
+            .bind(question_text)
+            .bind(question_type)
+            .bind(normalized.options.as_ref().map(Json))
+            .bind(normalized.correct_answer_key.as_deref())
+            .bind(points)
+            .bind(tag)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Delete a question and close the gap it leaves in the order
+    pub async fn delete_question(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+        question_id: Uuid,
+    ) -> Result<bool, DatabaseError> {
+        let deleted: Option<i32> =
+            sqlx::query_scalar("DELETE FROM quiz_questions WHERE quiz_id = $1 AND id = $2 RETURNING position")
+                .bind(quiz_id)
+                .bind(question_id)
+                .fetch_optional(&mut **tx)
+                .await?;
+
+        let Some(position) = deleted else {
+            return Ok(false);
+        };
+
+        sqlx::query("UPDATE quiz_questions SET position = position - 1 WHERE quiz_id = $1 AND position > $2")
+            .bind(quiz_id)
+            .bind(position)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(true)
+    }
+
+    // Number the questions of a quiz in the given order; the ids must be exactly its questions
+    pub async fn reorder_questions(
+        tx: &mut Transaction<'_, Postgres>,
+        quiz_id: Uuid,
+        question_ids: &[Uuid],
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE quiz_questions q
+            SET position = ordered.position, updated_at = NOW()
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS ordered(id, position)
+            WHERE q.quiz_id = $1 AND q.id = ordered.id
+            "#,
+        )
+        .bind(quiz_id)
+        .bind(question_ids)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
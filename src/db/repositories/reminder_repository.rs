@@ -0,0 +1,156 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::db::{
+    DatabaseError, NewScheduledReminder, ReminderStatus, ScheduledReminder,
+    REMINDER_ENTITY_APPOINTMENT, REMINDER_ENTITY_TRAINING_SESSION,
+};
+
+const REMINDER_COLUMNS: &str = r#"
+    id, tenant_id, user_id, notification_type, related_entity_id, related_entity_type,
+    offset_minutes, starts_at, remind_at, status, sent_at, created_at, updated_at
+"#;
+
+pub struct ReminderRepository;
+
+#[allow(unused)]
+impl ReminderRepository {
+    // Schedule a reminder, re-arming it only when the event start changed or it was cancelled
+    pub async fn schedule(
+        tx: &mut Transaction<'_, Postgres>,
+        reminder: &NewScheduledReminder,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_reminders (
+                tenant_id, user_id, notification_type, related_entity_id, related_entity_type,
+                offset_minutes, starts_at, remind_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (related_entity_type, related_entity_id, user_id, offset_minutes) DO UPDATE
+            SET starts_at = EXCLUDED.starts_at,
+                remind_at = EXCLUDED.remind_at,
+                status = 'pending',
+                sent_at = NULL,
+                updated_at = NOW()
+            WHERE scheduled_reminders.status = 'cancelled'
+               OR scheduled_reminders.starts_at IS DISTINCT FROM EXCLUDED.starts_at
+            "#,
+        )
+        .bind(reminder.tenant_id)
+        .bind(reminder.user_id)
+        .bind(reminder.notification_type)
+        .bind(reminder.related_entity_id)
+        .bind(reminder.related_entity_type)
+        .bind(reminder.offset_minutes)
+        .bind(reminder.starts_at)
+        .bind(reminder.remind_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Cancel the pending reminders of an event, optionally only those of one recipient
+    pub async fn cancel_for_entity(
+        tx: &mut Transaction<'_, Postgres>,
+        related_entity_type: &str,
+        related_entity_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE scheduled_reminders
+            SET status = 'cancelled', updated_at = NOW()
+            WHERE related_entity_type = $1 AND related_entity_id = $2
+              AND ($3::uuid IS NULL OR user_id = $3)
+              AND status = 'pending'
+            "#,
+        )
+        .bind(related_entity_type)
+        .bind(related_entity_id)
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // Lock a batch of due reminders; concurrent schedulers skip rows already claimed
+    pub async fn claim_due(
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+    ) -> Result<Vec<ScheduledReminder>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM scheduled_reminders
+            WHERE status = 'pending' AND remind_at <= NOW()
+            ORDER BY remind_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+            REMINDER_COLUMNS
+        );
+
+        let reminders = sqlx::query_as::<_, ScheduledReminder>(&query)
+            .bind(limit)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(reminders)
+    }
+
+    // Record the outcome of a claimed reminder
+    pub async fn mark(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        status: ReminderStatus,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE scheduled_reminders
+            SET status = $2,
+                sent_at = CASE WHEN $2 = 'sent'::reminder_status THEN NOW() ELSE sent_at END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // Current start time of the event a reminder points at, if it is still going ahead
+    pub async fn find_active_start(
+        tx: &mut Transaction<'_, Postgres>,
+        related_entity_type: &str,
+        related_entity_id: Uuid,
+    ) -> Result<Option<OffsetDateTime>, DatabaseError> {
+        let query = match related_entity_type {
+            REMINDER_ENTITY_APPOINTMENT => {
+                "SELECT start_time FROM appointments WHERE id = $1 AND status IN ('pending', 'confirmed')"
+            }
+            REMINDER_ENTITY_TRAINING_SESSION => {
+                "SELECT start_time FROM training_sessions WHERE id = $1 AND status = 'scheduled' AND published_at IS NOT NULL"
+            }
+            other => {
+                return Err(DatabaseError::InvalidInput(format!(
+                    "Unknown reminder entity type: {}",
+                    other
+                )));
+            }
+        };
+
+        let start_time = sqlx::query_scalar(query)
+            .bind(related_entity_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(start_time)
+    }
+}
@@ -0,0 +1,194 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+
+use crate::db::{
+    ComplianceAssignment, DatabaseError, NewTrainingRequirement, TrainingRequirement,
+};
+
+const REQUIREMENT_COLUMNS: &str = r#"
+    id, tenant_id, title, description, company_id, department, job_title,
+    recurrence_interval_months, due_soon_days, is_active, created_by_user_id, created_at, updated_at
+"#;
+
+// One row per active requirement and targeted employee of tenant $1, with the last completion
+// of a session linked to the requirement. Binds $2 requirement, $3 company, $4 department and
+// $5 employee filters.
+const ASSIGNMENTS_QUERY: &str = r#"
+    WITH employees AS (
+        SELECT DISTINCT u.id AS employee_user_id, r.company_id, p.first_name, p.last_name,
+               p.department, p.job_title
+        FROM users u
+        JOIN user_tenant_context_roles r
+          ON r.user_id = u.id AND r.role = 'employee' AND r.tenant_id = $1
+        LEFT JOIN user_profiles p ON p.user_id = u.id
+        WHERE u.status = 'active'
+          AND ($3::uuid IS NULL OR r.company_id = $3)
+          AND ($4::text IS NULL OR lower(p.department) = lower($4))
+          AND ($5::uuid IS NULL OR u.id = $5)
+    )
+    SELECT tr.id AS requirement_id, tr.title AS requirement_title, tr.recurrence_interval_months,
+           tr.due_soon_days, e.employee_user_id, e.first_name, e.last_name, e.company_id,
+           e.department, e.job_title,
+           (
+               SELECT MAX(te.completion_date)
+               FROM training_enrollments te
+               JOIN training_sessions ts ON ts.id = te.training_session_id
+               WHERE ts.requirement_id = tr.id
+                 AND te.employee_user_id = e.employee_user_id
+                 AND te.status = 'completed'
+           ) AS last_completed_at
+    FROM training_requirements tr
+    JOIN employees e
+      ON (tr.company_id IS NULL OR tr.company_id = e.company_id)
+     AND (tr.department IS NULL OR lower(tr.department) = lower(e.department))
+     AND (tr.job_title IS NULL OR lower(tr.job_title) = lower(e.job_title))
+    WHERE tr.tenant_id = $1 AND tr.is_active
+      AND ($2::uuid IS NULL OR tr.id = $2)
+"#;
+
+pub struct RequirementRepository;
+
+#[allow(unused)]
+impl RequirementRepository {
+    // Find a training requirement visible to the current RLS context
+    pub async fn find_by_id(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<TrainingRequirement, DatabaseError> {
+        let query = format!("SELECT {} FROM training_requirements WHERE id = $1", REQUIREMENT_COLUMNS);
+
+        sqlx::query_as::<_, TrainingRequirement>(&query)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // List the requirements of a tenant
+    pub async fn list_for_tenant(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        include_inactive: bool,
+    ) -> Result<Vec<TrainingRequirement>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_requirements
+            WHERE tenant_id = $1 AND (is_active OR $2)
+            ORDER BY title
+            "#,
+            REQUIREMENT_COLUMNS
+        );
+
+        let requirements = sqlx::query_as::<_, TrainingRequirement>(&query)
+            .bind(tenant_id)
+            .bind(include_inactive)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(requirements)
+    }
+
+    // Create a training requirement
+    pub async fn create(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        created_by_user_id: Uuid,
+        requirement: &NewTrainingRequirement,
+    ) -> Result<TrainingRequirement, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_requirements (
+                tenant_id, title, description, company_id, department, job_title,
+                recurrence_interval_months, due_soon_days, is_active, created_by_user_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, COALESCE($8, 30), COALESCE($9, TRUE), $10)
+            RETURNING {}
+            "#,
+            REQUIREMENT_COLUMNS
+        );
+
+        let requirement = sqlx::query_as::<_, TrainingRequirement>(&query)
+            .bind(tenant_id)
+            .bind(&requirement.title)
+            .bind(&requirement.description)
+            .bind(requirement.company_id)
+            .bind(&requirement.department)
+            .bind(&requirement.job_title)
+            .bind(requirement.recurrence_interval_months)
+            .bind(requirement.due_soon_days)
+            .bind(requirement.is_active)
+            .bind(created_by_user_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(requirement)
+    }
+
+    // Replace the definition of a training requirement
+    pub async fn update(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        requirement: &NewTrainingRequirement,
+    ) -> Result<TrainingRequirement, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_requirements
+            SET title = $2, description = $3, company_id = $4, department = $5, job_title = $6,
+                recurrence_interval_months = $7, due_soon_days = COALESCE($8, due_soon_days),
+                is_active = COALESCE($9, is_active), updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            REQUIREMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, TrainingRequirement>(&query)
+            .bind(id)
+            .bind(&requirement.title)
+            .bind(&requirement.description)
+            .bind(requirement.company_id)
+            .bind(&requirement.department)
+            .bind(&requirement.job_title)
+            .bind(requirement.recurrence_interval_months)
+            .bind(requirement.due_soon_days)
+            .bind(requirement.is_active)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Deactivate a requirement; linked sessions and history are kept
+    pub async fn deactivate(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<(), DatabaseError> {
+        let result = sqlx::query("UPDATE training_requirements SET is_active = FALSE, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+        Ok(())
+    }
+
+    // List every employee targeted by an active requirement, with their last completion
+    pub async fn list_assignments(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        requirement_id: Option<Uuid>,
+        company_id: Option<Uuid>,
+        department: Option<&str>,
+        employee_user_id: Option<Uuid>,
+    ) -> Result<Vec<ComplianceAssignment>, DatabaseError> {
+        let assignments = sqlx::query_as::<_, ComplianceAssignment>(ASSIGNMENTS_QUERY)
+            .bind(tenant_id)
+            .bind(requirement_id)
+            .bind(company_id)
+            .bind(department)
+            .bind(employee_user_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(assignments)
+    }
+}
@@ -0,0 +1,46 @@
+use sqlx::types::{Json, Uuid};
+use sqlx::{Postgres, Transaction};
+
+use crate::db::DatabaseError;
+
+pub struct SafetyReportRepository;
+
+#[allow(unused)]
+impl SafetyReportRepository {
+    // Attachments of a report the user may see: tenant admins see every report, reporters their
+    // own named ones, specialists those of their companies that are unassigned or assigned to them
+    pub async fn find_attachments_for_viewer(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        user_id: Uuid,
+        is_admin: bool,
+        is_specialist: bool,
+        report_id: Uuid,
+    ) -> Result<Option<Vec<serde_json::Value>>, DatabaseError> {
+        let attachments = sqlx::query_scalar::<_, Option<Json<Vec<serde_json::Value>>>>(
+            r#"
+            SELECT sr.attachments
+            FROM safety_reports sr
+            WHERE sr.id = $1 AND sr.tenant_id = $2
+              AND (
+                $4
+                OR (NOT sr.is_anonymous AND sr.reporter_user_id = $3)
+                OR ($5 AND (sr.assigned_to_user_id = $3 OR sr.assigned_to_user_id IS NULL)
+                    AND EXISTS (
+                        SELECT 1 FROM ohs_specialist_company_assignments osca
+                        WHERE osca.ohs_specialist_user_id = $3 AND osca.company_id = sr.company_id
+                    ))
+              )
+            "#,
+        )
+        .bind(report_id)
+        .bind(tenant_id)
+        .bind(user_id)
+        .bind(is_admin)
+        .bind(is_specialist)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(attachments.map(|attachments| attachments.map(|Json(values)| values).unwrap_or_default()))
+    }
+}
@@ -0,0 +1,61 @@
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::db::DatabaseError;
+
+pub struct TimeZoneRepository;
+
+impl TimeZoneRepository {
+    // Resolve the effective zone of a user: their own, falling back to their company's. Goes
+    // through a security definer function, as reminders need the zone of every recipient
+    pub async fn resolve_for_user(pool: &PgPool, user_id: Uuid) -> Result<Option<String>, DatabaseError> {
+        let zone: Option<String> = sqlx::query_scalar("SELECT user_time_zone($1)")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(zone)
+    }
+
+    // Set or clear (inherit from company) the zone of a user
+    pub async fn set_for_user(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        time_zone: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let result = sqlx::query("UPDATE users SET time_zone = $1, updated_at = NOW() WHERE id = $2")
+            .bind(time_zone)
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    // Set the zone of a company within a tenant
+    pub async fn set_for_company(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        company_id: Uuid,
+        time_zone: &str,
+    ) -> Result<(), DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE companies SET time_zone = $1, updated_at = NOW() WHERE id = $2 AND tenant_id = $3",
+        )
+        .bind(time_zone)
+        .bind(company_id)
+        .bind(tenant_id)
+        .execute(&mut **tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        Ok(())
+    }
+}
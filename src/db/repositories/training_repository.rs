@@ -0,0 +1,263 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::db::{DatabaseError, TrainingSession, TrainingSessionFields, TrainingStatus};
+
+pub(crate) const TRAINING_SESSION_COLUMNS: &str = r#"
+    id, tenant_id, host_user_id, title, description, training_type, status,
+    start_time, end_time, stream_details, max_participants, requirement_id, min_attendance_percent,
+    min_watched_percent, min_pages_viewed_percent, published_at, cancelled_at, cancellation_reason,
+    created_at, updated_at
+"#;
+
+pub struct TrainingRepository;
+
+#[allow(unused)]
+impl TrainingRepository {
+    // Find a training session visible to the current RLS context
+    pub async fn find_by_id(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<TrainingSession, DatabaseError> {
+        let query = format!("SELECT {} FROM training_sessions WHERE id = $1", TRAINING_SESSION_COLUMNS);
+
+        sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Find a training session and lock it against concurrent changes
+    pub async fn find_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<TrainingSession, DatabaseError> {
+        let query = format!(
+            "SELECT {} FROM training_sessions WHERE id = $1 FOR UPDATE",
+            TRAINING_SESSION_COLUMNS
+        );
+
+        sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // List the training sessions of a tenant overlapping a range; drafts are limited to
+    // the given host unless `all_drafts` is set
+    pub async fn list_for_tenant(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+        viewer_id: Uuid,
+        all_drafts: bool,
+    ) -> Result<Vec<TrainingSession>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM training_sessions
+            WHERE tenant_id = $1 AND start_time < $3 AND end_time > $2
+              AND (published_at IS NOT NULL OR $5 OR host_user_id = $4)
+            ORDER BY start_time
+            "#,
+            TRAINING_SESSION_COLUMNS
+        );
+
+        let sessions = sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(tenant_id)
+            .bind(from)
+            .bind(to)
+            .bind(viewer_id)
+            .bind(all_drafts)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(sessions)
+    }
+
+    // Check whether a user is an OHS specialist of a tenant
+    pub async fn is_specialist_in_tenant(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, DatabaseError> {
+        let exists = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM user_tenant_context_roles
+                WHERE user_id = $2 AND tenant_id = $1 AND role = 'ohs_specialist'
+            )
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(exists)
+    }
+
+    // Create a draft training session
+    pub async fn create(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: Uuid,
+        fields: &TrainingSessionFields,
+    ) -> Result<TrainingSession, DatabaseError> {
+        let query = format!(
+            r#"
+            INSERT INTO training_sessions (
+                tenant_id, host_user_id, title, description, training_type,
+                start_time, end_time, stream_details, max_participants, requirement_id,
+                min_attendance_percent, min_watched_percent, min_pages_viewed_percent
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING {}
+            "#,
+            TRAINING_SESSION_COLUMNS
+        );
+
+        let session = sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(tenant_id)
+            .bind(fields.host_user_id)
+            .bind(&fields.title)
+            .bind(&fields.description)
+            .bind(fields.training_type)
+            .bind(fields.start_time)
+            .bind(fields.end_time)
+            .bind(&fields.stream_details)
+            .bind(fields.max_participants)
+            .bind(fields.requirement_id)
+            .bind(fields.min_attendance_percent)
+            .bind(fields.min_watched_percent)
+            .bind(fields.min_pages_viewed_percent)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(session)
+    }
+
+    // Overwrite the editable fields of a training session
+    pub async fn update(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        fields: &TrainingSessionFields,
+    ) -> Result<TrainingSession, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_sessions
+            SET title = $2, description = $3, training_type = $4, start_time = $5, end_time = $6,
+                stream_details = $7, max_participants = $8, requirement_id = $9,
+                min_attendance_percent = $10, min_watched_percent = $11, min_pages_viewed_percent = $12,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            TRAINING_SESSION_COLUMNS
+        );
+
+        sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(id)
+            .bind(&fields.title)
+            .bind(&fields.description)
+            .bind(fields.training_type)
+            .bind(fields.start_time)
+            .bind(fields.end_time)
+            .bind(&fields.stream_details)
+            .bind(fields.max_participants)
+            .bind(fields.requirement_id)
+            .bind(fields.min_attendance_percent)
+            .bind(fields.min_watched_percent)
+            .bind(fields.min_pages_viewed_percent)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    // Publish a draft; returns None when it was already published
+    pub async fn publish(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<TrainingSession>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_sessions
+            SET published_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND published_at IS NULL
+            RETURNING {}
+            "#,
+            TRAINING_SESSION_COLUMNS
+        );
+
+        let session = sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(session)
+    }
+
+    // Move a session from `from` to `to`; returns None when it is no longer in `from`
+    pub async fn transition(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        from: TrainingStatus,
+        to: TrainingStatus,
+        cancellation_reason: Option<&str>,
+    ) -> Result<Option<TrainingSession>, DatabaseError> {
+        let query = format!(
+            r#"
+            UPDATE training_sessions
+            SET status = $3,
+                cancelled_at = CASE WHEN $3 = 'cancelled'::training_status THEN NOW() ELSE cancelled_at END,
+                cancellation_reason = COALESCE($4, cancellation_reason),
+                updated_at = NOW()
+            WHERE id = $1 AND status = $2
+            RETURNING {}
+            "#,
+            TRAINING_SESSION_COLUMNS
+        );
+
+        let session = sqlx::query_as::<_, TrainingSession>(&query)
+            .bind(id)
+            .bind(from)
+            .bind(to)
+            .bind(cancellation_reason)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(session)
+    }
+
+    // Delete a session that was never published
+    pub async fn delete_draft(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM training_sessions WHERE id = $1 AND published_at IS NULL")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // List the employees currently registered for a session
+    pub async fn list_registered_employee_ids(
+        tx: &mut Transaction<'_, Postgres>,
+        training_session_id: Uuid,
+    ) -> Result<Vec<Uuid>, DatabaseError> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT employee_user_id FROM training_enrollments
+            WHERE training_session_id = $1 AND status = 'registered'
+            ORDER BY enrolled_at
+            "#,
+        )
+        .bind(training_session_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(ids)
+    }
+}
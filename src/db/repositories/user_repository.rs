@@ -55,10 +55,10 @@ impl UserRepository {
         .fetch_one(pool)
         .await
         .map_err(|e| {
-            if let sqlx::Error::Database(ref db_error) = e {
-                if db_error.constraint().is_some() && db_error.constraint().unwrap() == "users_email_key" {
-                    return DatabaseError::Duplicate;
-                }
+            if let sqlx::Error::Database(ref db_error) = e
+                && db_error.constraint() == Some("users_email_key")
+            {
+                return DatabaseError::Duplicate;
             }
             DatabaseError::Sqlx(e)
         })?;
@@ -0,0 +1,37 @@
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::db::DatabaseError;
+use crate::middleware::auth::AuthUser;
+
+/// Begins a transaction with the row level security session settings for the caller.
+///
+/// The policies in the init schema read `app.current_user_id`, `app.current_tenant_id`,
+/// `app.current_company_id` and `app.current_user_roles`; they are set with `is_local = true`
+/// so they never outlive the transaction and leak onto a pooled connection. Missing tenant or
+/// company ids are written as the nil UUID: once a custom setting has been touched on a
+/// connection Postgres returns `''` instead of NULL for it, which would break the `::uuid` casts.
+pub async fn begin_for_user(
+    pool: &PgPool,
+    user: &AuthUser,
+) -> Result<Transaction<'static, Postgres>, DatabaseError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        SELECT
+            set_config('app.current_user_id', $1, true),
+            set_config('app.current_tenant_id', $2, true),
+            set_config('app.current_company_id', $3, true),
+            set_config('app.current_user_roles', $4, true)
+        "#,
+    )
+    .bind(user.user_id.to_string())
+    .bind(user.tenant_id.unwrap_or(Uuid::nil()).to_string())
+    .bind(user.company_id.unwrap_or(Uuid::nil()).to_string())
+    .bind(user.roles_setting())
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(tx)
+}
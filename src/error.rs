@@ -6,6 +6,11 @@ use axum::{
 use serde_json::json;
 use thiserror::Error;
 
+use crate::core::scanner::ScanError;
+use crate::core::storage::StorageError;
+use crate::core::utils::material::UploadError;
+use crate::core::utils::quiz::{AnswerError, DrawError, QuestionError};
+use crate::core::utils::time_zone::TimeZoneError;
 use crate::db::DatabaseError;
 
 #[derive(Debug, Error)]
@@ -79,5 +84,74 @@ impl IntoResponse for AppError {
     }
 }
 
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        AppError::Database(DatabaseError::Sqlx(err))
+    }
+}
+
+impl From<validator::ValidationErrors> for AppError {
+    fn from(err: validator::ValidationErrors) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+impl From<TimeZoneError> for AppError {
+    fn from(err: TimeZoneError) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+impl From<QuestionError> for AppError {
+    fn from(err: QuestionError) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+impl From<AnswerError> for AppError {
+    fn from(err: AnswerError) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+impl From<DrawError> for AppError {
+    fn from(err: DrawError) -> Self {
+        if err.is_pool_too_small() {
+            AppError::Conflict(err.to_string())
+        } else {
+            AppError::Validation(err.to_string())
+        }
+    }
+}
+
+impl From<UploadError> for AppError {
+    fn from(err: UploadError) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+impl From<StorageError> for AppError {
+    fn from(err: StorageError) -> Self {
+        match err {
+            StorageError::NotFound(_) => AppError::NotFound("File not found".to_string()),
+            StorageError::InvalidKey(_) | StorageError::InvalidExpiry => AppError::Validation(err.to_string()),
+            StorageError::InvalidSignature => AppError::Authorization(err.to_string()),
+            err => AppError::InternalServerError(err.to_string()),
+        }
+    }
+}
+
+impl From<ScanError> for AppError {
+    fn from(err: ScanError) -> Self {
+        match err {
+            ScanError::Storage(err) => err.into(),
+            err => {
+                tracing::error!("Malware scan failed: {}", err);
+                AppError::ServiceUnavailable("Uploads cannot be checked for malware right now".to_string())
+            }
+        }
+    }
+}
+
 #[allow(unused)]
 pub type AppResult<T> = Result<T, AppError>;
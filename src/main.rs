@@ -3,16 +3,17 @@ use app_state::AppState;
 use axum::{Router, routing::get, Json};
 use dotenvy::dotenv;
 use modules::admin::handlers::{admin_dashboard, admin_login};
+use modules::notification::{channels::{GatewayChannel, NotificationDispatcher}, reminders};
+use modules::realtime::{calls::Calls, gateway::Gateway};
 use serde_json::json;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use tokio::sync::broadcast;
-use std::sync::{Arc, Mutex};
-use websocket::ws_handler;
+use std::sync::Arc;
 
 mod modules;
 mod config;
-mod websocket;
+mod core;
+mod middleware;
 mod app_state;
 mod db;
 mod error;
@@ -35,28 +36,54 @@ async fn main() -> anyhow::Result<()> {
     let db_pool = db::init_pool().await?;
     info!("Database connection established");
 
-    let (tx, _rx) = broadcast::channel(100);
-    let ws_state = Arc::new(Mutex::new(tx));
+    let realtime = Gateway::new();
+
+    // Notifications also reach the recipient's open realtime connections
+    let notifier = NotificationDispatcher::from_config(&config.notifications)?
+        .with_channel(Arc::new(GatewayChannel::new(realtime.clone())));
+
+    let storage = core::storage::Storage::from_config(config)?;
+    info!("Object storage backend: {}", storage.backend_name());
+
+    let scanner = core::scanner::Scanner::from_config(config);
+    info!("Malware scanner: {}", scanner.backend_name());
 
     // Create app state with DB pool
-    let state = AppState::new(db_pool, config.clone(), ws_state.clone());
+    let state = AppState::new(db_pool, config.clone(), realtime, Calls::new(), notifier, storage, scanner);
 
-    let ws_app = Router::new()
-        .route("/ws", get(ws_handler))
-        .with_state(ws_state);
+    // Background jobs
+    reminders::spawn_scheduler(state.clone());
+    modules::training::certificates::spawn_certificate_worker(state.clone());
+    modules::training::transcoding::spawn_transcode_worker(state.clone());
+    modules::realtime::signaling::spawn_call_log_sweeper(state.clone());
 
     // HTMX Router
     let htmx_app = Router::new()
         .route("/", get(admin_dashboard))
         .route("/login", get(admin_login));
 
+    // JSON API Router
+    let api_app = Router::new()
+        .merge(modules::appointment::routes())
+        .merge(modules::training::routes())
+        .merge(modules::calendar::routes())
+        .merge(modules::certificate::routes())
+        .merge(modules::analytics::routes())
+        .merge(modules::notification::routes())
+        .merge(modules::storage::routes())
+        .merge(modules::realtime::api_routes())
+        .merge(modules::user::routes())
+        .merge(modules::tenant::routes());
+
     let static_dir = config.app.static_dir.to_string();
 
     let app = Router::new()
         .route("/", get(hello))
         .route("/health", get(health_check))
-        .merge(ws_app)
+        .merge(modules::realtime::routes())
+        .merge(modules::certificate::pages())
         .nest("/admin", htmx_app)
+        .nest("/api", api_app)
         .nest_service("/static", tower_http::services::ServeDir::new(static_dir))
         .with_state(state);
 
@@ -0,0 +1,117 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+
+use crate::app_state::AppState;
+use crate::db::UserRole;
+use crate::error::AppError;
+
+/// Claims carried by the access tokens issued to the mobile and admin clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+    pub roles: Vec<UserRole>,
+    pub exp: i64,
+}
+
+/// The authenticated caller, extracted from the `Authorization: Bearer` header.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub user_id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+    pub roles: Vec<UserRole>,
+}
+
+#[allow(unused)]
+impl AuthUser {
+    pub fn has_role(&self, role: &UserRole) -> bool {
+        self.roles.contains(role)
+    }
+
+    pub fn has_any_role(&self, roles: &[UserRole]) -> bool {
+        roles.iter().any(|role| self.has_role(role))
+    }
+
+    /// OHS specialists and workplace doctors both act as the professional side of an appointment.
+    pub fn is_professional(&self) -> bool {
+        self.has_any_role(&[UserRole::OhsSpecialist, UserRole::Doctor])
+    }
+
+    pub fn is_tenant_admin(&self) -> bool {
+        self.has_role(&UserRole::TenantAdmin)
+    }
+
+    /// Returns the tenant the caller acts in, failing for users without one (super admins).
+    pub fn require_tenant(&self) -> Result<Uuid, AppError> {
+        self.tenant_id
+            .ok_or_else(|| AppError::Authorization("A tenant context is required".to_string()))
+    }
+
+    /// Fails unless the caller holds at least one of the given roles.
+    pub fn require_any_role(&self, roles: &[UserRole]) -> Result<(), AppError> {
+        if self.has_any_role(roles) {
+            Ok(())
+        } else {
+            Err(AppError::Authorization(
+                "Insufficient role for this operation".to_string(),
+            ))
+        }
+    }
+
+    /// Comma separated role list in the format expected by `get_current_user_roles()`.
+    pub fn roles_setting(&self) -> String {
+        self.roles
+            .iter()
+            .map(|role| role.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Validates a bearer token and returns its claims.
+pub fn decode_token(token: &str, secret: &str) -> Result<Claims, AppError> {
+    let validation = Validation::new(Algorithm::HS256);
+
+    decode::<Claims>(token, &DecodingKey::from_secret(secret.as_bytes()), &validation)
+        .map(|data| data.claims)
+        .map_err(|e| AppError::Authentication(format!("Invalid access token: {}", e)))
+}
+
+impl From<Claims> for AuthUser {
+    fn from(claims: Claims) -> Self {
+        Self {
+            user_id: claims.sub,
+            tenant_id: claims.tenant_id,
+            company_id: claims.company_id,
+            roles: claims.roles,
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let header = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| AppError::Authentication("Missing Authorization header".to_string()))?;
+
+        let token = header
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| AppError::Authentication("Expected a Bearer token".to_string()))?;
+
+        let claims = decode_token(token, state.env.auth.jwt_secret.expose_secret())?;
+
+        Ok(claims.into())
+    }
+}
@@ -0,0 +1,41 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::app_state::AppState;
+use crate::core::utils::time_zone::Zone;
+use crate::db::repositories::TimeZoneRepository;
+use crate::error::AppError;
+use crate::middleware::auth::AuthUser;
+
+/// Header a client can send to override the stored zone, e.g. a remote worker's device zone.
+pub const TIME_ZONE_HEADER: &str = "x-time-zone";
+
+/// The zone scheduling endpoints read local times in and render times to.
+///
+/// Resolved from the `X-Time-Zone` header when present, otherwise from the caller's own
+/// `users.time_zone`, then their company's `companies.time_zone`, then UTC.
+#[derive(Debug, Clone, Copy)]
+pub struct CallerZone(pub Zone);
+
+impl FromRequestParts<AppState> for CallerZone {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        if let Some(value) = parts.headers.get(TIME_ZONE_HEADER) {
+            let name = value
+                .to_str()
+                .map_err(|_| AppError::Validation("X-Time-Zone must be valid ASCII".to_string()))?;
+            let zone = Zone::parse(name).map_err(|e| AppError::Validation(e.to_string()))?;
+            return Ok(CallerZone(zone));
+        }
+
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        let stored = TimeZoneRepository::resolve_for_user(&state.db, user.user_id).await?;
+
+        // A zone that has since been dropped from the tz database should not lock the user out.
+        let zone = stored
+            .and_then(|name| Zone::parse(&name).ok())
+            .unwrap_or_else(Zone::utc);
+
+        Ok(CallerZone(zone))
+    }
+}
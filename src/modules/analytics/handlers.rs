@@ -0,0 +1,252 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+use crate::app_state::AppState;
+use crate::core::utils::csv::CsvWriter;
+use crate::core::utils::time_zone::{ClientDateTime, Zone};
+use crate::db::repositories::{AnalyticsRepository, CallLogRepository, FeedbackRepository};
+use crate::db::rls;
+use crate::db::{
+    AppointmentAnalytics, AppointmentAnalyticsQuery, AppointmentAnalyticsScope, CallMinutesQuery, CallMinutesReport,
+    CallMinutesScope, FeedbackReport, FeedbackReportQuery, FeedbackReportScope, ReportFormat, UserRole,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::middleware::time_zone::CallerZone;
+
+/// Window reported when the caller does not pick one.
+const DEFAULT_WINDOW_DAYS: i64 = 90;
+/// Longest window a single report may cover.
+const MAX_WINDOW_DAYS: i64 = 366;
+
+/// Resolves the reported range, defaulting to the last `DEFAULT_WINDOW_DAYS` days.
+fn resolve_window(
+    zone: Zone,
+    from: Option<ClientDateTime>,
+    to: Option<ClientDateTime>,
+) -> AppResult<(OffsetDateTime, OffsetDateTime)> {
+    let to = match to {
+        Some(to) => to.resolve(zone)?,
+        None => OffsetDateTime::now_utc(),
+    };
+    let from = match from {
+        Some(from) => from.resolve(zone)?,
+        None => to - Duration::days(DEFAULT_WINDOW_DAYS),
+    };
+    if to <= from {
+        return Err(AppError::Validation("`to` must be after `from`".to_string()));
+    }
+    if to - from > Duration::days(MAX_WINDOW_DAYS) {
+        return Err(AppError::Validation(format!(
+            "Range may not exceed {} days",
+            MAX_WINDOW_DAYS
+        )));
+    }
+
+    Ok((from, to))
+}
+
+pub async fn appointment_analytics(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Query(query): Query<AppointmentAnalyticsQuery>,
+) -> AppResult<Json<AppointmentAnalytics>> {
+    user.require_any_role(&[UserRole::TenantAdmin, UserRole::OhsSpecialist, UserRole::Doctor])?;
+    let tenant_id = user.require_tenant()?;
+
+    let (from, to) = resolve_window(zone, query.from, query.to)?;
+
+    // Professionals only get figures about their own appointments
+    let professional_user_id = if user.is_tenant_admin() {
+        query.professional_user_id
+    } else {
+        Some(user.user_id)
+    };
+
+    let scope = AppointmentAnalyticsScope {
+        tenant_id,
+        from,
+        to,
+        company_id: query.company_id,
+        professional_user_id,
+    };
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let bookings_per_week = AnalyticsRepository::bookings_per_week(&mut tx, &scope, zone.name()).await?;
+    let by_professional = AnalyticsRepository::outcome_rates(&mut tx, &scope, false).await?;
+    let by_company = AnalyticsRepository::outcome_rates(&mut tx, &scope, true).await?;
+    let average_lead_time_hours = AnalyticsRepository::average_lead_time_hours(&mut tx, &scope).await?;
+    tx.commit().await?;
+
+    Ok(Json(AppointmentAnalytics {
+        time_zone: zone.name(),
+        bookings_per_week,
+        by_professional,
+        by_company,
+        average_lead_time_hours,
+    }))
+}
+
+/// Minutes of the appointment calls that started in the range, per tenant and per professional.
+/// Super admins see every tenant; tenant admins their own; professionals only their own calls.
+pub async fn call_minutes_report(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Query(query): Query<CallMinutesQuery>,
+) -> AppResult<Json<CallMinutesReport>> {
+    user.require_any_role(&[UserRole::SuperAdmin, UserRole::TenantAdmin, UserRole::OhsSpecialist, UserRole::Doctor])?;
+    let (from, to) = resolve_window(zone, query.from, query.to)?;
+
+    let (tenant_id, professional_user_id) = if user.has_role(&UserRole::SuperAdmin) {
+        (query.tenant_id, query.professional_user_id)
+    } else if user.is_tenant_admin() {
+        (Some(user.require_tenant()?), query.professional_user_id)
+    } else {
+        (Some(user.require_tenant()?), Some(user.user_id))
+    };
+
+    let scope = CallMinutesScope {
+        tenant_id,
+        from,
+        to,
+        professional_user_id,
+    };
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let by_tenant = CallLogRepository::minutes(&mut tx, &scope, false).await?;
+    let by_professional = CallLogRepository::minutes(&mut tx, &scope, true).await?;
+    tx.commit().await?;
+
+    Ok(Json(CallMinutesReport {
+        time_zone: zone.name(),
+        from,
+        to,
+        by_tenant,
+        by_professional,
+    }))
+}
+
+/// Columns of the CSV export of a feedback report.
+const FEEDBACK_CSV_HEADER: [&str; 16] = [
+    "period_start",
+    "training_session_id",
+    "session_start",
+    "training_title",
+    "host_user_id",
+    "host_name",
+    "sessions",
+    "participants",
+    "responses",
+    "average_rating",
+    "rating_1",
+    "rating_2",
+    "rating_3",
+    "rating_4",
+    "rating_5",
+    "time_zone",
+];
+
+fn feedback_csv(report: &FeedbackReport, zone: Zone) -> String {
+    let mut writer = CsvWriter::new();
+    writer.row(FEEDBACK_CSV_HEADER);
+
+    for row in &report.rows {
+        let session_start = row
+            .session_start
+            .map(|start| zone.localize(start).format(&Rfc3339).unwrap_or_default())
+            .unwrap_or_default();
+        let summary = &row.summary;
+        writer.row([
+            row.period_start.map(|date| date.to_string()).unwrap_or_default(),
+            row.training_session_id.map(|id| id.to_string()).unwrap_or_default(),
+            session_start,
+            row.training_title.clone().unwrap_or_default(),
+            row.host_user_id.map(|id| id.to_string()).unwrap_or_default(),
+            row.host_name.clone().unwrap_or_default(),
+            row.sessions.to_string(),
+            summary.participants.to_string(),
+            summary.responses.to_string(),
+            summary.average_rating.map(|average| format!("{:.2}", average)).unwrap_or_default(),
+            summary.rating_1.to_string(),
+            summary.rating_2.to_string(),
+            summary.rating_3.to_string(),
+            summary.rating_4.to_string(),
+            summary.rating_5.to_string(),
+            report.time_zone.to_string(),
+        ]);
+    }
+
+    writer.finish()
+}
+
+/// Participant ratings of the sessions starting in the range, per session, host or training title,
+/// optionally split by week or month. `format=csv` downloads the report for quality audits.
+pub async fn training_feedback_report(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Query(query): Query<FeedbackReportQuery>,
+) -> AppResult<Response> {
+    user.require_any_role(&[UserRole::TenantAdmin, UserRole::OhsSpecialist])?;
+    let tenant_id = user.require_tenant()?;
+    let (from, to) = resolve_window(zone, query.from, query.to)?;
+
+    // Specialists only get the ratings of the sessions they hosted
+    let host_user_id = if user.is_tenant_admin() {
+        query.host_user_id
+    } else {
+        Some(user.user_id)
+    };
+
+    let scope = FeedbackReportScope {
+        tenant_id,
+        from,
+        to,
+        host_user_id,
+        group_by: query.group_by,
+        period: query.period,
+    };
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let rows = FeedbackRepository::rating_report(&mut tx, &scope, zone.name()).await?;
+    tx.commit().await?;
+
+    let report = FeedbackReport {
+        time_zone: zone.name(),
+        group_by: scope.group_by,
+        period: scope.period,
+        from,
+        to,
+        rows,
+    };
+
+    match query.format {
+        ReportFormat::Json => Ok(Json(report).into_response()),
+        ReportFormat::Csv => {
+            let disposition = format!(
+                "attachment; filename=\"training-feedback-{}-{}.csv\"",
+                zone.local_date(from),
+                zone.local_date(to)
+            );
+            let csv = feedback_csv(&report, zone);
+
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                    (header::CACHE_CONTROL, "private, no-cache".to_string()),
+                ],
+                csv,
+            )
+                .into_response())
+        }
+    }
+}
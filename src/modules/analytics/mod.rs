@@ -0,0 +1,12 @@
+pub mod handlers;
+
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/analytics/appointments", get(handlers::appointment_analytics))
+        .route("/analytics/training-feedback", get(handlers::training_feedback_report))
+        .route("/analytics/calls", get(handlers::call_minutes_report))
+}
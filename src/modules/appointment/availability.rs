@@ -0,0 +1,103 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+
+use crate::core::utils::time_zone::{TimeZoneError, Zone};
+use crate::db::repositories::AvailabilityRepository;
+use crate::db::{AvailabilitySlot, RecurringAvailability};
+use crate::error::AppResult;
+
+/// Expands a weekly rule into concrete windows overlapping `[from, to)`.
+///
+/// Each occurrence is built from the rule's wall-clock times on the local date in the rule's
+/// own zone, so the UTC instants shift across daylight saving transitions while the local
+/// times stay put.
+pub fn expand_recurring(
+    rule: &RecurringAvailability,
+    from: OffsetDateTime,
+    to: OffsetDateTime,
+) -> Result<Vec<(OffsetDateTime, OffsetDateTime)>, TimeZoneError> {
+    let zone = Zone::parse(&rule.time_zone)?;
+    let mut windows = Vec::new();
+
+    // Widen by a day on each side: the local dates of the range bounds can differ from UTC
+    let mut date = zone.local_date(from) - Duration::days(1);
+    let last_date = zone.local_date(to) + Duration::days(1);
+
+    while date <= last_date {
+        let in_validity = date >= rule.valid_from && rule.valid_until.is_none_or(|until| date <= until);
+
+        if in_validity && i16::from(date.weekday().number_from_monday()) == rule.weekday {
+            let start = zone.resolve_local_lenient(PrimitiveDateTime::new(date, rule.local_start_time));
+            let end = zone.resolve_local_lenient(PrimitiveDateTime::new(date, rule.local_end_time));
+
+            if start < to && end > from && end > start {
+                windows.push((start, end));
+            }
+        }
+
+        date += Duration::days(1);
+    }
+
+    Ok(windows)
+}
+
+/// Collects the bookable windows of a professional overlapping `[from, to)`, ordered by start.
+pub async fn collect_slots(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    professional_user_id: Uuid,
+    from: OffsetDateTime,
+    to: OffsetDateTime,
+    zone: Zone,
+) -> AppResult<Vec<AvailabilitySlot>> {
+    let mut slots: Vec<AvailabilitySlot> =
+        AvailabilityRepository::list_one_off(tx, professional_user_id, from, to)
+            .await?
+            .into_iter()
+            .filter(|availability| availability.tenant_id == tenant_id)
+            .map(|availability| AvailabilitySlot {
+                start_time: zone.localize(availability.start_time),
+                end_time: zone.localize(availability.end_time),
+                time_zone: zone.name(),
+                recurring_availability_id: None,
+            })
+            .collect();
+
+    let rules = AvailabilityRepository::list_recurring(
+        tx,
+        professional_user_id,
+        zone.local_date(from) - Duration::days(1),
+        zone.local_date(to) + Duration::days(1),
+    )
+    .await?;
+
+    for rule in rules.iter().filter(|rule| rule.tenant_id == tenant_id) {
+        for (start, end) in expand_recurring(rule, from, to)? {
+            slots.push(AvailabilitySlot {
+                start_time: zone.localize(start),
+                end_time: zone.localize(end),
+                time_zone: zone.name(),
+                recurring_availability_id: Some(rule.id),
+            });
+        }
+    }
+
+    slots.sort_by_key(|slot| slot.start_time);
+    Ok(slots)
+}
+
+/// Whether `[start, end)` fits entirely inside one of the professional's windows.
+pub async fn is_within_availability(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    professional_user_id: Uuid,
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+) -> AppResult<bool> {
+    let slots = collect_slots(tx, tenant_id, professional_user_id, start, end, Zone::utc()).await?;
+
+    Ok(slots
+        .iter()
+        .any(|slot| slot.start_time <= start && slot.end_time >= end))
+}
@@ -0,0 +1,474 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::types::Uuid;
+use time::{Duration, OffsetDateTime};
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::appointment::check_outcome;
+use crate::core::utils::time_zone::Zone;
+use crate::db::repositories::{AppointmentRepository, AvailabilityRepository};
+use crate::db::rls;
+use crate::db::{
+    Appointment, AppointmentHistoryFilter, AppointmentHistoryQuery, AppointmentResponse,
+    AppointmentStatus, AvailabilitySlot, NewAppointment, NewProfessionalAvailability,
+    NewRecurringAvailability, Page, Pagination, ProfessionalAvailability, RecordAppointmentOutcome,
+    RecurringAvailability, RescheduleAppointment, ScheduleRangeQuery, UserRole,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::middleware::time_zone::CallerZone;
+use crate::modules::notification::reminders;
+use crate::modules::realtime::signaling;
+
+use super::availability;
+
+/// Longest range a schedule listing may span; bounds the recurrence expansion work.
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// Resolves an optional `from`/`to` query in the caller's zone, defaulting to the next `default_days`.
+pub(crate) fn resolve_range(
+    query: &ScheduleRangeQuery,
+    zone: Zone,
+    default_days: i64,
+) -> AppResult<(OffsetDateTime, OffsetDateTime)> {
+    let from = match query.from {
+        Some(from) => from.resolve(zone)?,
+        None => OffsetDateTime::now_utc(),
+    };
+    let to = match query.to {
+        Some(to) => to.resolve(zone)?,
+        None => from + Duration::days(default_days),
+    };
+
+    if to <= from {
+        return Err(AppError::Validation("`to` must be after `from`".to_string()));
+    }
+    if to - from > Duration::days(MAX_RANGE_DAYS) {
+        return Err(AppError::Validation(format!(
+            "Range may not exceed {} days",
+            MAX_RANGE_DAYS
+        )));
+    }
+
+    Ok((from, to))
+}
+
+fn ensure_participant(user: &AuthUser, appointment: &Appointment) -> AppResult<()> {
+    if appointment.employee_user_id == user.user_id || appointment.professional_user_id == user.user_id {
+        Ok(())
+    } else {
+        Err(AppError::Authorization(
+            "Only the participants can change this appointment".to_string(),
+        ))
+    }
+}
+
+pub async fn list_appointments(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Query(query): Query<ScheduleRangeQuery>,
+) -> AppResult<Json<Vec<AppointmentResponse>>> {
+    let (from, to) = resolve_range(&query, zone, 90)?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointments = AppointmentRepository::list_for_participant(&mut tx, user.user_id, from, to).await?;
+    tx.commit().await?;
+
+    Ok(Json(
+        appointments
+            .into_iter()
+            .map(|appointment| AppointmentResponse::new(appointment, zone))
+            .collect(),
+    ))
+}
+
+pub async fn list_appointment_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Query(query): Query<AppointmentHistoryQuery>,
+) -> AppResult<Json<Page<AppointmentResponse>>> {
+    let tenant_id = user.require_tenant()?;
+    let now = OffsetDateTime::now_utc();
+
+    let from = query.from.map(|from| from.resolve(zone)).transpose()?;
+    let to = match query.to {
+        Some(to) => to.resolve(zone)?.min(now),
+        None => now,
+    };
+    if from.is_some_and(|from| from >= to) {
+        return Err(AppError::Validation("`from` must be before `to`".to_string()));
+    }
+
+    let mut filter = AppointmentHistoryFilter {
+        from,
+        to,
+        status: query.status,
+        appointment_type: query.appointment_type,
+        employee_user_id: query.employee_user_id,
+        professional_user_id: query.professional_user_id,
+        company_id: query.company_id,
+    };
+
+    // Employees only ever see their own visits; professionals see the ones they held
+    if !user.is_tenant_admin() {
+        if user.is_professional() {
+            filter.professional_user_id = Some(user.user_id);
+        } else {
+            filter.employee_user_id = Some(user.user_id);
+        }
+    }
+
+    let pagination = Pagination::new(query.page, query.per_page);
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (appointments, total) =
+        AppointmentRepository::list_history(&mut tx, tenant_id, &filter, pagination).await?;
+    tx.commit().await?;
+
+    Ok(Json(
+        Page::new(appointments, pagination, total)
+            .map(|appointment| AppointmentResponse::new(appointment, zone)),
+    ))
+}
+
+pub async fn get_appointment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<AppointmentResponse>> {
+    let tenant_id = user.require_tenant()?;
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, id).await?;
+    tx.commit().await?;
+
+    let participant =
+        appointment.employee_user_id == user.user_id || appointment.professional_user_id == user.user_id;
+    if appointment.tenant_id != tenant_id || !(participant || user.is_tenant_admin()) {
+        return Err(AppError::NotFound("Appointment not found".to_string()));
+    }
+
+    Ok(Json(AppointmentResponse::new(appointment, zone)))
+}
+
+pub async fn book_appointment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Json(payload): Json<NewAppointment>,
+) -> AppResult<(StatusCode, Json<AppointmentResponse>)> {
+    payload.validate()?;
+    user.require_any_role(&[UserRole::Employee])?;
+    let tenant_id = user.require_tenant()?;
+    let company_id = user
+        .company_id
+        .ok_or_else(|| AppError::Validation("Employee is not assigned to a company".to_string()))?;
+
+    let start_time = payload.start_time.resolve(zone)?;
+    let end_time = payload.end_time(start_time);
+    if start_time <= OffsetDateTime::now_utc() {
+        return Err(AppError::Validation("Appointments must start in the future".to_string()));
+    }
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    AppointmentRepository::lock_participant_schedules(&mut tx, payload.professional_user_id, user.user_id).await?;
+
+    if !availability::is_within_availability(
+        &mut tx,
+        tenant_id,
+        payload.professional_user_id,
+        start_time,
+        end_time,
+    )
+    .await?
+    {
+        return Err(AppError::Conflict(
+            "The professional is not available at the requested time".to_string(),
+        ));
+    }
+
+    if AppointmentRepository::has_overlap(
+        &mut tx,
+        payload.professional_user_id,
+        user.user_id,
+        start_time,
+        end_time,
+        None,
+    )
+    .await?
+    {
+        return Err(AppError::Conflict("The requested time slot is already taken".to_string()));
+    }
+
+    let appointment = AppointmentRepository::create(
+        &mut tx,
+        tenant_id,
+        company_id,
+        user.user_id,
+        payload.professional_user_id,
+        payload.appointment_type,
+        start_time,
+        end_time,
+        payload.reason_for_visit,
+    )
+    .await?;
+    reminders::sync_appointment_reminders(&mut tx, &state, &appointment).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(AppointmentResponse::new(appointment, zone))))
+}
+
+pub async fn reschedule_appointment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RescheduleAppointment>,
+) -> AppResult<Json<AppointmentResponse>> {
+    payload.validate()?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let current = AppointmentRepository::find_by_id(&mut tx, id).await?;
+    ensure_participant(&user, &current)?;
+    if !current.status.is_active() {
+        return Err(AppError::Conflict("Only pending or confirmed appointments can be moved".to_string()));
+    }
+
+    let start_time = payload.start_time.resolve(zone)?;
+    let duration = payload
+        .duration_minutes
+        .map(Duration::minutes)
+        .unwrap_or(current.end_time - current.start_time);
+    let end_time = start_time + duration;
+    if start_time <= OffsetDateTime::now_utc() {
+        return Err(AppError::Validation("Appointments must start in the future".to_string()));
+    }
+
+    AppointmentRepository::lock_participant_schedules(&mut tx, current.professional_user_id, current.employee_user_id)
+        .await?;
+
+    if !availability::is_within_availability(
+        &mut tx,
+        tenant_id,
+        current.professional_user_id,
+        start_time,
+        end_time,
+    )
+    .await?
+    {
+        return Err(AppError::Conflict(
+            "The professional is not available at the requested time".to_string(),
+        ));
+    }
+
+    if AppointmentRepository::has_overlap(
+        &mut tx,
+        current.professional_user_id,
+        current.employee_user_id,
+        start_time,
+        end_time,
+        Some(id),
+    )
+    .await?
+    {
+        return Err(AppError::Conflict("The requested time slot is already taken".to_string()));
+    }
+
+    let mut appointment = AppointmentRepository::reschedule(&mut tx, id, start_time, end_time).await?;
+
+    // A move requested by the employee has to be accepted again by the professional
+    if user.user_id == current.employee_user_id && appointment.status == AppointmentStatus::Confirmed {
+        appointment = AppointmentRepository::update_status(&mut tx, id, AppointmentStatus::Pending).await?;
+    }
+    reminders::sync_appointment_reminders(&mut tx, &state, &appointment).await?;
+    tx.commit().await?;
+
+    Ok(Json(AppointmentResponse::new(appointment, zone)))
+}
+
+pub async fn confirm_appointment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<AppointmentResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let current = AppointmentRepository::find_by_id(&mut tx, id).await?;
+
+    if current.professional_user_id != user.user_id {
+        return Err(AppError::Authorization(
+            "Only the professional can confirm an appointment".to_string(),
+        ));
+    }
+    if current.status != AppointmentStatus::Pending {
+        return Err(AppError::Conflict("Only pending appointments can be confirmed".to_string()));
+    }
+
+    let appointment = AppointmentRepository::update_status(&mut tx, id, AppointmentStatus::Confirmed).await?;
+    tx.commit().await?;
+
+    Ok(Json(AppointmentResponse::new(appointment, zone)))
+}
+
+pub async fn cancel_appointment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<AppointmentResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let current = AppointmentRepository::find_by_id(&mut tx, id).await?;
+    ensure_participant(&user, &current)?;
+    if !current.status.is_active() {
+        return Err(AppError::Conflict("Appointment is no longer active".to_string()));
+    }
+
+    let status = if current.professional_user_id == user.user_id {
+        AppointmentStatus::CancelledByProfessional
+    } else {
+        AppointmentStatus::CancelledByEmployee
+    };
+
+    let appointment = AppointmentRepository::update_status(&mut tx, id, status).await?;
+    reminders::sync_appointment_reminders(&mut tx, &state, &appointment).await?;
+    tx.commit().await?;
+
+    signaling::appointment_cancelled(&state, &appointment, user.user_id).await;
+
+    Ok(Json(AppointmentResponse::new(appointment, zone)))
+}
+
+pub async fn record_outcome(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RecordAppointmentOutcome>,
+) -> AppResult<Json<AppointmentResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let current = AppointmentRepository::find_by_id(&mut tx, id).await?;
+
+    if current.professional_user_id != user.user_id {
+        return Err(AppError::Authorization(
+            "Only the professional can record the outcome of an appointment".to_string(),
+        ));
+    }
+    check_outcome(current.status.into(), current.start_time, OffsetDateTime::now_utc())
+        .map_err(|e| AppError::Conflict(e.to_string()))?;
+
+    let appointment = AppointmentRepository::update_status(&mut tx, id, payload.outcome.into()).await?;
+    reminders::sync_appointment_reminders(&mut tx, &state, &appointment).await?;
+    tx.commit().await?;
+
+    Ok(Json(AppointmentResponse::new(appointment, zone)))
+}
+
+pub async fn list_professional_availability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(professional_user_id): Path<Uuid>,
+    Query(query): Query<ScheduleRangeQuery>,
+) -> AppResult<Json<Vec<AvailabilitySlot>>> {
+    let tenant_id = user.require_tenant()?;
+    let (from, to) = resolve_range(&query, zone, 14)?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let slots = availability::collect_slots(&mut tx, tenant_id, professional_user_id, from, to, zone).await?;
+    tx.commit().await?;
+
+    Ok(Json(slots))
+}
+
+pub async fn create_availability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Json(payload): Json<NewProfessionalAvailability>,
+) -> AppResult<(StatusCode, Json<ProfessionalAvailability>)> {
+    if !user.is_professional() {
+        return Err(AppError::Authorization("Only professionals manage availability".to_string()));
+    }
+    let tenant_id = user.require_tenant()?;
+
+    let start_time = payload.start_time.resolve(zone)?;
+    let end_time = payload.end_time.resolve(zone)?;
+    if end_time <= start_time {
+        return Err(AppError::Validation("`end_time` must be after `start_time`".to_string()));
+    }
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let mut availability =
+        AvailabilityRepository::create_one_off(&mut tx, tenant_id, user.user_id, start_time, end_time).await?;
+    tx.commit().await?;
+
+    availability.start_time = zone.localize(availability.start_time);
+    availability.end_time = zone.localize(availability.end_time);
+
+    Ok((StatusCode::CREATED, Json(availability)))
+}
+
+pub async fn list_recurring_availability(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<RecurringAvailability>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let rules = AvailabilityRepository::list_recurring(&mut tx, user.user_id, time::Date::MIN, time::Date::MAX).await?;
+    tx.commit().await?;
+
+    Ok(Json(rules))
+}
+
+pub async fn create_recurring_availability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Json(payload): Json<NewRecurringAvailability>,
+) -> AppResult<(StatusCode, Json<RecurringAvailability>)> {
+    payload.validate()?;
+    if !user.is_professional() {
+        return Err(AppError::Authorization("Only professionals manage availability".to_string()));
+    }
+    let tenant_id = user.require_tenant()?;
+
+    if payload.local_end_time <= payload.local_start_time {
+        return Err(AppError::Validation(
+            "`local_end_time` must be after `local_start_time`".to_string(),
+        ));
+    }
+    if payload.valid_until.is_some_and(|until| until < payload.valid_from) {
+        return Err(AppError::Validation("`valid_until` must not precede `valid_from`".to_string()));
+    }
+
+    let rule_zone = match payload.time_zone.as_deref() {
+        Some(name) => Zone::parse(name)?,
+        None => zone,
+    };
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let rule =
+        AvailabilityRepository::create_recurring(&mut tx, tenant_id, user.user_id, &payload, rule_zone.name())
+            .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+pub async fn delete_recurring_availability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    AvailabilityRepository::delete_recurring(&mut tx, user.user_id, id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
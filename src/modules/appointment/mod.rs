@@ -0,0 +1,33 @@
+pub mod availability;
+pub mod handlers;
+pub mod notes;
+
+use axum::{routing::{delete, get, post}, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/appointments", get(handlers::list_appointments).post(handlers::book_appointment))
+        .route("/appointments/history", get(handlers::list_appointment_history))
+        .route("/appointments/{id}", get(handlers::get_appointment))
+        .route("/appointments/{id}/reschedule", post(handlers::reschedule_appointment))
+        .route("/appointments/{id}/confirm", post(handlers::confirm_appointment))
+        .route("/appointments/{id}/cancel", post(handlers::cancel_appointment))
+        .route("/appointments/{id}/outcome", post(handlers::record_outcome))
+        .route("/appointments/{id}/notes", get(notes::get_note).put(notes::save_note))
+        .route("/appointments/{id}/notes/versions", get(notes::list_note_versions))
+        .route("/appointments/{id}/notes/sign", post(notes::sign_note))
+        .route("/appointments/{id}/summary", get(notes::download_summary))
+        .route(
+            "/consultation-templates",
+            get(notes::list_templates).post(notes::create_template),
+        )
+        .route("/professionals/{id}/availability", get(handlers::list_professional_availability))
+        .route("/availability", post(handlers::create_availability))
+        .route(
+            "/availability/recurring",
+            get(handlers::list_recurring_availability).post(handlers::create_recurring_availability),
+        )
+        .route("/availability/recurring/{id}", delete(handlers::delete_recurring_availability))
+}
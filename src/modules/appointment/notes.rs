@@ -0,0 +1,322 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::macros::format_description;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::consultation::{in_summary, NoteAccess};
+use crate::core::utils::visit_summary::{render_visit_summary, VisitSummaryContent};
+use crate::db::repositories::{AppointmentRepository, ConsultationRepository};
+use crate::db::{
+    rls, Appointment, AppointmentType, ConsultationNote, ConsultationNoteResponse,
+    ConsultationNoteStatus, ConsultationNoteTemplate, ConsultationNoteVersion, DatabaseError,
+    NewConsultationNoteTemplate, SaveConsultationNote, TemplateQuery, UserRole,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::middleware::time_zone::CallerZone;
+
+/// Upper bound for the text of a single note section.
+const MAX_SECTION_LENGTH: usize = 20_000;
+
+fn appointment_label(appointment_type: AppointmentType) -> &'static str {
+    match appointment_type {
+        AppointmentType::OhsConsultation => "OHS consultation",
+        AppointmentType::MedicalCheckup => "Medical check-up",
+    }
+}
+
+fn note_access(user: &AuthUser, appointment: &Appointment) -> NoteAccess {
+    NoteAccess::of(user.user_id, appointment.professional_user_id, appointment.employee_user_id)
+}
+
+fn ensure_professional(user: &AuthUser, appointment: &Appointment) -> AppResult<()> {
+    if note_access(user, appointment).can_edit() {
+        Ok(())
+    } else {
+        Err(AppError::Authorization(
+            "Only the professional of the appointment can access its notes".to_string(),
+        ))
+    }
+}
+
+async fn note_response(
+    tx: &mut Transaction<'_, Postgres>,
+    note: ConsultationNote,
+) -> AppResult<ConsultationNoteResponse> {
+    let template = ConsultationRepository::find_template(tx, note.tenant_id, note.template_id).await?;
+    let content = ConsultationRepository::find_version(tx, note.id, note.current_version)
+        .await?
+        .map(|version| version.content.0)
+        .unwrap_or_default();
+
+    Ok(ConsultationNoteResponse {
+        note,
+        template_name: template.name,
+        sections: template.sections.0,
+        content,
+    })
+}
+
+pub async fn list_templates(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<TemplateQuery>,
+) -> AppResult<Json<Vec<ConsultationNoteTemplate>>> {
+    user.require_any_role(&[UserRole::OhsSpecialist, UserRole::Doctor, UserRole::TenantAdmin])?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let templates = ConsultationRepository::list_templates(&mut tx, tenant_id, query.appointment_type).await?;
+    tx.commit().await?;
+
+    Ok(Json(templates))
+}
+
+pub async fn create_template(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<NewConsultationNoteTemplate>,
+) -> AppResult<(StatusCode, Json<ConsultationNoteTemplate>)> {
+    payload.validate()?;
+    user.require_any_role(&[UserRole::TenantAdmin])?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let template = ConsultationRepository::create_template(&mut tx, tenant_id, user.user_id, &payload).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+pub async fn get_note(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(appointment_id): Path<Uuid>,
+) -> AppResult<Json<ConsultationNoteResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, appointment_id).await?;
+    ensure_professional(&user, &appointment)?;
+
+    let note = ConsultationRepository::find_note_by_appointment(&mut tx, appointment_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No notes have been written for this appointment".to_string()))?;
+    let response = note_response(&mut tx, note).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+/// Saves the content of a note as a new version, creating the note on the first save.
+pub async fn save_note(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(appointment_id): Path<Uuid>,
+    Json(payload): Json<SaveConsultationNote>,
+) -> AppResult<Json<ConsultationNoteResponse>> {
+    payload.validate()?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, appointment_id).await?;
+    ensure_professional(&user, &appointment)?;
+    if appointment.status.is_cancelled() {
+        return Err(AppError::Conflict("Cancelled appointments cannot have notes".to_string()));
+    }
+
+    let note = match ConsultationRepository::find_note_by_appointment(&mut tx, appointment_id).await? {
+        Some(note) => note,
+        None => {
+            let template = match payload.template_id {
+                Some(template_id) => ConsultationRepository::find_template(&mut tx, tenant_id, template_id).await?,
+                None => ConsultationRepository::list_templates(&mut tx, tenant_id, Some(appointment.appointment_type))
+                    .await?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| {
+                        AppError::Validation("No note template exists for this appointment type".to_string())
+                    })?,
+            };
+            if template.appointment_type != appointment.appointment_type {
+                return Err(AppError::Validation(
+                    "The template does not match the appointment type".to_string(),
+                ));
+            }
+
+            match ConsultationRepository::create_note(&mut tx, tenant_id, appointment_id, template.id, user.user_id)
+                .await
+            {
+                Ok(note) => note,
+                Err(DatabaseError::Duplicate) => {
+                    return Err(AppError::Conflict("The note was created concurrently, reload it".to_string()));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    };
+
+    if note.status == ConsultationNoteStatus::Signed {
+        return Err(AppError::Conflict("Signed notes are locked".to_string()));
+    }
+
+    let template = ConsultationRepository::find_template(&mut tx, tenant_id, note.template_id).await?;
+    for (key, text) in &payload.content {
+        if !template.sections.iter().any(|section| &section.key == key) {
+            return Err(AppError::Validation(format!("Unknown note section: {}", key)));
+        }
+        if text.chars().count() > MAX_SECTION_LENGTH {
+            return Err(AppError::Validation(format!(
+                "Section {} exceeds {} characters",
+                key, MAX_SECTION_LENGTH
+            )));
+        }
+    }
+
+    let expected_version = payload.expected_version.unwrap_or(note.current_version);
+    let (note, _) = ConsultationRepository::add_version(&mut tx, note.id, expected_version, &payload.content, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The note was changed by someone else, reload it".to_string()))?;
+
+    let response = note_response(&mut tx, note).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+pub async fn list_note_versions(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(appointment_id): Path<Uuid>,
+) -> AppResult<Json<Vec<ConsultationNoteVersion>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, appointment_id).await?;
+    ensure_professional(&user, &appointment)?;
+
+    let note = ConsultationRepository::find_note_by_appointment(&mut tx, appointment_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No notes have been written for this appointment".to_string()))?;
+    let versions = ConsultationRepository::list_versions(&mut tx, note.id).await?;
+    tx.commit().await?;
+
+    Ok(Json(versions))
+}
+
+/// Signs the current version of a note; required sections must be filled in.
+pub async fn sign_note(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(appointment_id): Path<Uuid>,
+) -> AppResult<Json<ConsultationNoteResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, appointment_id).await?;
+    ensure_professional(&user, &appointment)?;
+
+    let note = ConsultationRepository::find_note_by_appointment(&mut tx, appointment_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No notes have been written for this appointment".to_string()))?;
+    if note.status == ConsultationNoteStatus::Signed {
+        return Err(AppError::Conflict("The note is already signed".to_string()));
+    }
+
+    let template = ConsultationRepository::find_template(&mut tx, note.tenant_id, note.template_id).await?;
+    let content = ConsultationRepository::find_version(&mut tx, note.id, note.current_version)
+        .await?
+        .map(|version| version.content.0)
+        .unwrap_or_default();
+
+    let missing: Vec<&str> = template
+        .sections
+        .iter()
+        .filter(|section| section.required)
+        .filter(|section| content.get(&section.key).is_none_or(|text| text.trim().is_empty()))
+        .map(|section| section.label.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Required sections are empty: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let note = ConsultationRepository::sign(&mut tx, note.id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The note is already signed".to_string()))?;
+    let response = note_response(&mut tx, note).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+/// PDF summary of a signed note with only the sections shared with the employee.
+pub async fn download_summary(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(appointment_id): Path<Uuid>,
+) -> AppResult<Response> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, appointment_id).await?;
+    if !note_access(&user, &appointment).can_read_summary() {
+        return Err(AppError::NotFound("Appointment not found".to_string()));
+    }
+
+    let note = ConsultationRepository::find_note_by_appointment(&mut tx, appointment_id)
+        .await?
+        .filter(|note| note.status == ConsultationNoteStatus::Signed)
+        .ok_or_else(|| AppError::NotFound("No signed visit summary is available yet".to_string()))?;
+    let template = ConsultationRepository::find_template(&mut tx, note.tenant_id, note.template_id).await?;
+    let content = ConsultationRepository::find_version(&mut tx, note.id, note.current_version)
+        .await?
+        .map(|version| version.content.0)
+        .unwrap_or_default();
+    tx.commit().await?;
+
+    let sections = template
+        .sections
+        .0
+        .into_iter()
+        .filter_map(|section| {
+            content
+                .get(&section.key)
+                .filter(|text| in_summary(section.shared_with_employee, text))
+                .map(|text| (section.label, text.clone()))
+        })
+        .collect();
+
+    let display = format_description!("[day] [month repr:short] [year] [hour]:[minute]");
+    let summary = VisitSummaryContent {
+        appointment_label: appointment_label(appointment.appointment_type).to_string(),
+        local_start: zone.localize(appointment.start_time).format(display).unwrap_or_default(),
+        time_zone: zone.name().to_string(),
+        reason_for_visit: appointment.reason_for_visit.clone(),
+        sections,
+        signed_at: note
+            .signed_at
+            .map(|signed_at| zone.localize(signed_at).format(display).unwrap_or_default())
+            .unwrap_or_default(),
+        version: note.current_version,
+    };
+
+    let pdf = tokio::task::spawn_blocking(move || render_visit_summary(&summary))
+        .await
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?
+        .map_err(|e| AppError::InternalServerError(format!("Failed to render visit summary: {}", e)))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"visit-summary-{}.pdf\"", appointment.id),
+            ),
+        ],
+        pdf,
+    )
+        .into_response())
+}
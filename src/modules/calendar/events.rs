@@ -0,0 +1,57 @@
+use time::OffsetDateTime;
+
+use crate::core::utils::ical::CalendarEvent;
+use crate::db::{Appointment, AppointmentType, TrainingSession, TrainingStatus};
+
+/// Revision number derived from the row timestamps; grows with every update.
+fn sequence(created_at: Option<OffsetDateTime>, updated_at: Option<OffsetDateTime>) -> i64 {
+    match (created_at, updated_at) {
+        (Some(created_at), Some(updated_at)) => (updated_at - created_at).whole_seconds(),
+        _ => 0,
+    }
+}
+
+pub fn appointment_event(appointment: &Appointment, public_url: &str) -> CalendarEvent {
+    let summary = match appointment.appointment_type {
+        AppointmentType::OhsConsultation => "OHS consultation",
+        AppointmentType::MedicalCheckup => "Medical check-up",
+    };
+
+    CalendarEvent {
+        uid: format!("appointment-{}@ohs-backend", appointment.id),
+        sequence: sequence(appointment.created_at, appointment.updated_at),
+        last_modified: appointment
+            .updated_at
+            .or(appointment.created_at)
+            .unwrap_or(appointment.start_time),
+        start: appointment.start_time,
+        end: appointment.end_time,
+        summary: summary.to_string(),
+        description: appointment.reason_for_visit.clone(),
+        url: Some(format!("{}/appointments/{}/join", public_url, appointment.id)),
+        cancelled: appointment.status.is_cancelled(),
+    }
+}
+
+pub fn training_event(session: &TrainingSession, public_url: &str) -> CalendarEvent {
+    // Externally hosted sessions carry their own join link in the stream details
+    let join_url = session
+        .stream_details
+        .as_ref()
+        .and_then(|details| details.get("join_url"))
+        .and_then(|url| url.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}/trainings/{}/join", public_url, session.id));
+
+    CalendarEvent {
+        uid: format!("training-{}@ohs-backend", session.id),
+        sequence: sequence(session.created_at, session.updated_at),
+        last_modified: session.updated_at.or(session.created_at).unwrap_or(session.start_time),
+        start: session.start_time,
+        end: session.end_time,
+        summary: session.title.clone(),
+        description: session.description.clone(),
+        url: Some(join_url),
+        cancelled: session.status == TrainingStatus::Cancelled,
+    }
+}
@@ -0,0 +1,162 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sha2::{Digest, Sha256};
+use sqlx::types::Uuid;
+use time::{Duration, OffsetDateTime};
+
+use crate::app_state::AppState;
+use crate::core::utils::ical::Calendar;
+use crate::core::utils::time_zone::Zone;
+use crate::db::repositories::{
+    AppointmentRepository, CalendarRepository, TimeZoneRepository, TrainingRepository,
+};
+use crate::db::{rls, CalendarFeedTokenResponse, DatabaseError};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::modules::training;
+
+use super::events::{appointment_event, training_event};
+
+/// How far back a subscribed feed reaches; older events have usually been synced already.
+const FEED_PAST_DAYS: i64 = 30;
+/// How far ahead a subscribed feed reaches.
+const FEED_FUTURE_DAYS: i64 = 365;
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn ics_response(calendar: &Calendar, filename: Option<&str>) -> Response {
+    let disposition = match filename {
+        Some(filename) => format!("attachment; filename=\"{}\"", filename),
+        None => "inline".to_string(),
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        calendar.render(),
+    )
+        .into_response()
+}
+
+/// Issues a new feed URL for the caller, invalidating the previous one.
+pub async fn create_feed_token(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<(StatusCode, Json<CalendarFeedTokenResponse>)> {
+    let tenant_id = user.require_tenant()?;
+
+    let token = hex::encode(rand::random::<[u8; 32]>());
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    CalendarRepository::upsert_feed_token(&mut tx, user.user_id, tenant_id, &hash_token(&token)).await?;
+    tx.commit().await?;
+
+    let feed_url = format!("{}/api/calendar/feed/{}.ics", state.env.app.public_url, token);
+
+    Ok((StatusCode::CREATED, Json(CalendarFeedTokenResponse { token, feed_url })))
+}
+
+pub async fn revoke_feed_token(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<StatusCode> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    CalendarRepository::delete_feed_token(&mut tx, user.user_id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Subscription endpoint polled by calendar clients; the token in the path is the credential.
+pub async fn calendar_feed(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+) -> AppResult<Response> {
+    let token = file.strip_suffix(".ics").unwrap_or(&file);
+
+    let owner = match CalendarRepository::touch_feed_token(&state.db, &hash_token(token)).await {
+        Ok(owner) => owner,
+        Err(DatabaseError::NotFound) => {
+            return Err(AppError::NotFound("Calendar feed not found".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let now = OffsetDateTime::now_utc();
+    let from = now - Duration::days(FEED_PAST_DAYS);
+    let to = now + Duration::days(FEED_FUTURE_DAYS);
+
+    // The feed is read with the owner's own row level security, as if they had signed in
+    let reader = AuthUser {
+        user_id: owner.user_id,
+        tenant_id: Some(owner.tenant_id),
+        company_id: owner.company_id,
+        roles: owner.roles.clone(),
+    };
+    let mut tx = rls::begin_for_user(&state.db, &reader).await?;
+    let appointments = CalendarRepository::list_appointments(&mut tx, &owner, from, to).await?;
+    let sessions = CalendarRepository::list_training_sessions(&mut tx, &owner, from, to).await?;
+    tx.commit().await?;
+
+    let zone = TimeZoneRepository::resolve_for_user(&state.db, owner.user_id)
+        .await?
+        .and_then(|name| Zone::parse(&name).ok())
+        .unwrap_or_else(Zone::utc);
+
+    let public_url = &state.env.app.public_url;
+    let calendar = Calendar::new(format!("{} schedule", state.env.app.name))
+        .with_time_zone(zone.name())
+        .with_events(appointments.iter().map(|appointment| appointment_event(appointment, public_url)))
+        .with_events(sessions.iter().map(|session| training_event(session, public_url)));
+
+    Ok(ics_response(&calendar, None))
+}
+
+pub async fn appointment_ics(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Response> {
+    let tenant_id = user.require_tenant()?;
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, id).await?;
+    tx.commit().await?;
+
+    let participant =
+        appointment.employee_user_id == user.user_id || appointment.professional_user_id == user.user_id;
+    if appointment.tenant_id != tenant_id || !(participant || user.is_tenant_admin()) {
+        return Err(AppError::NotFound("Appointment not found".to_string()));
+    }
+
+    let calendar = Calendar::new(state.env.app.name.clone())
+        .with_events([appointment_event(&appointment, &state.env.app.public_url)]);
+
+    Ok(ics_response(&calendar, Some(&format!("appointment-{}.ics", appointment.id))))
+}
+
+pub async fn training_ics(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Response> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_by_id(&mut tx, id).await?;
+    tx.commit().await?;
+
+    if !training::handlers::can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+
+    let calendar = Calendar::new(state.env.app.name.clone())
+        .with_events([training_event(&session, &state.env.app.public_url)]);
+
+    Ok(ics_response(&calendar, Some(&format!("training-{}.ics", session.id))))
+}
@@ -0,0 +1,17 @@
+pub mod events;
+pub mod handlers;
+
+use axum::{routing::{get, post}, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/calendar/feed-token",
+            post(handlers::create_feed_token).delete(handlers::revoke_feed_token),
+        )
+        .route("/calendar/feed/{file}", get(handlers::calendar_feed))
+        .route("/appointments/{id}/ics", get(handlers::appointment_ics))
+        .route("/trainings/{id}/ics", get(handlers::training_ics))
+}
@@ -0,0 +1,141 @@
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+
+use crate::app_state::AppState;
+use crate::core::utils::certificate::{holder_display_name, verification_code_from_payload};
+use crate::db::repositories::CertificateRepository;
+use crate::db::{CertificateValidity, CertificateVerification, VerificationQuery};
+use crate::error::{AppError, AppResult};
+use crate::modules::notification::reminders::zone_for_user;
+
+#[derive(Template)]
+#[template(path = "public/certificate_verify.html")]
+struct VerifyPageTemplate {
+    code: String,
+    outcome: Option<VerificationOutcome>,
+}
+
+#[derive(Template)]
+#[template(path = "public/certificate_result.html")]
+struct VerifyResultTemplate {
+    outcome: VerificationOutcome,
+}
+
+/// What a lookup found, as shown on the verification page.
+enum VerificationOutcome {
+    Invalid,
+    NotFound,
+    Found(CertificateVerification),
+}
+
+/// Looks up a code or QR payload. Only the fields a verifier needs leave this function.
+async fn verify(state: &AppState, payload: &str) -> AppResult<VerificationOutcome> {
+    let Some(code) = verification_code_from_payload(payload) else {
+        return Ok(VerificationOutcome::Invalid);
+    };
+    let Some(certificate) = CertificateRepository::find_by_code(&state.db, &code).await? else {
+        return Ok(VerificationOutcome::NotFound);
+    };
+
+    // Dates are the holder's calendar days, matching the printed certificate
+    let zone = zone_for_user(&state.db, certificate.employee_user_id).await?;
+
+    Ok(VerificationOutcome::Found(CertificateVerification {
+        status: if certificate.is_revoked() {
+            CertificateValidity::Revoked
+        } else {
+            CertificateValidity::Valid
+        },
+        holder: holder_display_name(&certificate.employee_name),
+        training_title: certificate.training_title,
+        completed_on: zone.local_date(certificate.completed_at),
+        issued_by: certificate.tenant_name,
+        revoked_on: certificate.revoked_at.map(|revoked_at| zone.local_date(revoked_at)),
+        verification_code: certificate.verification_code,
+    }))
+}
+
+fn verification_json(outcome: VerificationOutcome) -> AppResult<Response> {
+    match outcome {
+        VerificationOutcome::Invalid => Err(AppError::BadRequest("Invalid certificate code".to_string())),
+        VerificationOutcome::NotFound => Err(AppError::NotFound("Certificate not found".to_string())),
+        VerificationOutcome::Found(verification) => {
+            // Revocations must show up immediately
+            Ok(([(header::CACHE_CONTROL, "no-store")], Json(verification)).into_response())
+        }
+    }
+}
+
+fn html(template: impl Template) -> AppResult<Response> {
+    let body = template
+        .render()
+        .map_err(|e| AppError::InternalServerError(format!("Failed to render verification page: {}", e)))?;
+
+    Ok((
+        [
+            (header::CACHE_CONTROL, "no-store"),
+            (header::HeaderName::from_static("x-robots-tag"), "noindex"),
+        ],
+        Html(body),
+    )
+        .into_response())
+}
+
+pub async fn verify_certificate(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> AppResult<Response> {
+    verification_json(verify(&state, &code).await?)
+}
+
+/// Same as [`verify_certificate`], for callers that pass a full QR payload.
+pub async fn verify_certificate_payload(
+    State(state): State<AppState>,
+    Query(query): Query<VerificationQuery>,
+) -> AppResult<Response> {
+    verification_json(verify(&state, query.code.as_deref().unwrap_or_default()).await?)
+}
+
+/// The verification form, empty.
+pub async fn verification_page() -> AppResult<Response> {
+    html(VerifyPageTemplate {
+        code: String::new(),
+        outcome: None,
+    })
+}
+
+/// The page a certificate's QR code opens.
+pub async fn verification_page_for_code(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> AppResult<Response> {
+    let outcome = verify(&state, &code).await?;
+    html(VerifyPageTemplate {
+        code,
+        outcome: Some(outcome),
+    })
+}
+
+/// Form submission; HTMX requests only get the result fragment.
+pub async fn verification_lookup(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<VerificationQuery>,
+) -> AppResult<Response> {
+    let code = query.code.unwrap_or_default();
+    let outcome = verify(&state, &code).await?;
+
+    if headers.contains_key("hx-request") {
+        html(VerifyResultTemplate { outcome })
+    } else {
+        html(VerifyPageTemplate {
+            code,
+            outcome: Some(outcome),
+        })
+    }
+}
@@ -0,0 +1,20 @@
+pub mod handlers;
+
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+
+/// Public JSON API, nested under `/api`.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/certificates/verify", get(handlers::verify_certificate_payload))
+        .route("/certificates/verify/{code}", get(handlers::verify_certificate))
+}
+
+/// Public HTML pages; the URL encoded in certificate QR codes points here.
+pub fn pages() -> Router<AppState> {
+    Router::new()
+        .route("/certificates/verify", get(handlers::verification_page))
+        .route("/certificates/verify/lookup", get(handlers::verification_lookup))
+        .route("/certificates/verify/{code}", get(handlers::verification_page_for_code))
+}
@@ -1,2 +1,12 @@
 pub mod admin;
-pub mod auth;
\ No newline at end of file
+pub mod analytics;
+pub mod appointment;
+pub mod auth;
+pub mod calendar;
+pub mod certificate;
+pub mod notification;
+pub mod realtime;
+pub mod storage;
+pub mod tenant;
+pub mod training;
+pub mod user;
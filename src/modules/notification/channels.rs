@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::PgPool;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::config::{NotificationConfig, SmtpConfig};
+use crate::db::repositories::NotificationRepository;
+use crate::db::{DatabaseError, Notification};
+use crate::modules::realtime::gateway::Gateway;
+use crate::modules::realtime::protocol::ServerMessage;
+
+#[derive(Debug, Error)]
+pub enum ChannelError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Push delivery failed: {0}")]
+    Push(#[from] reqwest::Error),
+
+    #[error("Email delivery failed: {0}")]
+    Email(String),
+}
+
+/// An out-of-band delivery channel for notifications already stored in-app.
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn deliver(&self, db: &PgPool, notification: &Notification) -> Result<(), ChannelError>;
+}
+
+/// Expo push notifications to every device the recipient registered.
+pub struct ExpoPushChannel {
+    client: reqwest::Client,
+    url: String,
+    access_token: Option<SecretString>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpoPushResponse {
+    #[serde(default)]
+    data: Vec<ExpoPushTicket>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpoPushTicket {
+    status: String,
+    message: Option<String>,
+    details: Option<serde_json::Value>,
+}
+
+impl ExpoPushChannel {
+    pub fn new(url: String, access_token: Option<SecretString>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            access_token,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for ExpoPushChannel {
+    fn name(&self) -> &'static str {
+        "push"
+    }
+
+    async fn deliver(&self, db: &PgPool, notification: &Notification) -> Result<(), ChannelError> {
+        let tokens = NotificationRepository::list_push_tokens(db, notification.user_id).await?;
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        let messages: Vec<_> = tokens
+            .iter()
+            .map(|token| {
+                json!({
+                    "to": token.token,
+                    "title": notification.title,
+                    "body": notification.message,
+                    "sound": "default",
+                    "data": {
+                        "notification_id": notification.id,
+                        "notification_type": notification.notification_type,
+                        "related_entity_id": notification.related_entity_id,
+                        "related_entity_type": notification.related_entity_type,
+                    },
+                })
+            })
+            .collect();
+
+        let mut request = self.client.post(&self.url).json(&messages);
+        if let Some(access_token) = &self.access_token {
+            request = request.bearer_auth(access_token.expose_secret());
+        }
+
+        let response: ExpoPushResponse = request.send().await?.error_for_status()?.json().await?;
+
+        // Tickets come back in the order the messages were sent
+        for (token, ticket) in tokens.iter().zip(response.data) {
+            if ticket.status == "ok" {
+                continue;
+            }
+
+            let unregistered = ticket
+                .details
+                .as_ref()
+                .and_then(|details| details.get("error"))
+                .and_then(|error| error.as_str())
+                == Some("DeviceNotRegistered");
+
+            if unregistered {
+                debug!("Removing unregistered push token {}", token.id);
+                NotificationRepository::delete_push_token_everywhere(db, &token.token).await?;
+            } else {
+                warn!(
+                    "Push ticket error for token {}: {}",
+                    token.id,
+                    ticket.message.unwrap_or_default()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Plain-text email through an SMTP relay.
+pub struct EmailChannel {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl EmailChannel {
+    pub fn new(config: &SmtpConfig) -> anyhow::Result<Self> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            .port(config.port)
+            .credentials(Credentials::new(
+                config.username.clone(),
+                config.password.expose_secret().to_string(),
+            ))
+            .build();
+
+        Ok(Self {
+            transport,
+            from: config.from.parse()?,
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn deliver(&self, db: &PgPool, notification: &Notification) -> Result<(), ChannelError> {
+        let Some(email) = NotificationRepository::find_email(db, notification.user_id).await? else {
+            return Ok(());
+        };
+
+        let to: Mailbox = email
+            .parse()
+            .map_err(|e| ChannelError::Email(format!("Invalid recipient address: {}", e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&notification.title)
+            .body(notification.message.clone())
+            .map_err(|e| ChannelError::Email(e.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| ChannelError::Email(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// The recipient's open realtime connections, as a `notification.created` event.
+pub struct GatewayChannel {
+    gateway: Gateway,
+}
+
+impl GatewayChannel {
+    pub fn new(gateway: Gateway) -> Self {
+        Self { gateway }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for GatewayChannel {
+    fn name(&self) -> &'static str {
+        "realtime"
+    }
+
+    async fn deliver(&self, _db: &PgPool, notification: &Notification) -> Result<(), ChannelError> {
+        let data = serde_json::to_value(notification).unwrap_or_default();
+        self.gateway.send_to_user(
+            notification.tenant_id,
+            notification.user_id,
+            &ServerMessage::event(None, "notification.created", data),
+        );
+        Ok(())
+    }
+}
+
+/// Fans stored notifications out to the configured external channels.
+///
+/// Delivery is best effort: the in-app notification is the record of truth, so a failing
+/// channel is logged and never rolls anything back.
+#[derive(Clone, Default)]
+pub struct NotificationDispatcher {
+    channels: Vec<Arc<dyn NotificationChannel>>,
+}
+
+#[allow(unused)]
+impl NotificationDispatcher {
+    pub fn from_config(config: &NotificationConfig) -> anyhow::Result<Self> {
+        let mut channels: Vec<Arc<dyn NotificationChannel>> = Vec::new();
+
+        if let Some(url) = &config.expo_push_url {
+            channels.push(Arc::new(ExpoPushChannel::new(
+                url.clone(),
+                config.expo_access_token.clone(),
+            )));
+        }
+        if let Some(smtp) = &config.smtp {
+            channels.push(Arc::new(EmailChannel::new(smtp)?));
+        }
+
+        Ok(Self { channels })
+    }
+
+    pub fn with_channel(mut self, channel: Arc<dyn NotificationChannel>) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    pub async fn dispatch(&self, db: &PgPool, notification: &Notification) {
+        for channel in &self.channels {
+            if let Err(e) = channel.deliver(db, notification).await {
+                warn!(
+                    "Failed to deliver notification {} via {}: {}",
+                    notification.id,
+                    channel.name(),
+                    e
+                );
+            }
+        }
+    }
+
+    pub async fn dispatch_all(&self, db: &PgPool, notifications: &[Notification]) {
+        for notification in notifications {
+            self.dispatch(db, notification).await;
+        }
+    }
+}
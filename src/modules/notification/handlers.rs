@@ -0,0 +1,36 @@
+use axum::{extract::State, http::StatusCode, Json};
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::db::repositories::NotificationRepository;
+use crate::db::{PushToken, RegisterPushToken, RemovePushToken};
+use crate::error::AppResult;
+use crate::middleware::auth::AuthUser;
+
+pub async fn register_push_token(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<RegisterPushToken>,
+) -> AppResult<(StatusCode, Json<PushToken>)> {
+    payload.validate()?;
+
+    let push_token = NotificationRepository::upsert_push_token(
+        &state.db,
+        user.user_id,
+        &payload.token,
+        payload.device_name.as_deref(),
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(push_token)))
+}
+
+pub async fn remove_push_token(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<RemovePushToken>,
+) -> AppResult<StatusCode> {
+    NotificationRepository::delete_push_token(&state.db, user.user_id, &payload.token).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
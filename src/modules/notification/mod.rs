@@ -0,0 +1,14 @@
+pub mod channels;
+pub mod handlers;
+pub mod reminders;
+
+use axum::{routing::post, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/notifications/push-tokens",
+        post(handlers::register_push_token).delete(handlers::remove_push_token),
+    )
+}
@@ -0,0 +1,242 @@
+use std::time::Duration as StdDuration;
+
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Postgres, Transaction};
+use time::macros::format_description;
+use time::OffsetDateTime;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error};
+
+use crate::app_state::AppState;
+use crate::core::utils::reminder::reminder_schedule;
+use crate::core::utils::time_zone::Zone;
+use crate::db::repositories::{
+    NotificationRepository, ReminderRepository, TimeZoneRepository, TrainingRepository,
+};
+use crate::db::{
+    Appointment, NewNotification, NewScheduledReminder, NotificationType, ReminderStatus,
+    ScheduledReminder, TrainingSession, TrainingStatus, REMINDER_ENTITY_APPOINTMENT,
+    REMINDER_ENTITY_TRAINING_SESSION,
+};
+use crate::error::AppResult;
+
+/// Reminders claimed per transaction by the scheduler.
+const REMINDER_BATCH_SIZE: i64 = 50;
+
+/// The event a set of reminders belongs to.
+#[derive(Debug, Clone)]
+pub struct ReminderTarget {
+    pub tenant_id: Uuid,
+    pub notification_type: NotificationType,
+    pub related_entity_type: &'static str,
+    pub related_entity_id: Uuid,
+    pub starts_at: OffsetDateTime,
+}
+
+/// The zone a user's notifications are written in, falling back to UTC.
+pub(crate) async fn zone_for_user(db: &PgPool, user_id: Uuid) -> AppResult<Zone> {
+    Ok(TimeZoneRepository::resolve_for_user(db, user_id)
+        .await?
+        .and_then(|name| Zone::parse(&name).ok())
+        .unwrap_or_else(Zone::utc))
+}
+
+/// (Re)schedules the reminders of an event for its recipients.
+///
+/// Pending reminders of the event are cancelled first, so offsets that no longer apply or
+/// recipients that were removed do not fire; unchanged reminders are re-armed in place and
+/// reminders already sent for the same start time are left alone.
+pub async fn schedule_reminders(
+    tx: &mut Transaction<'_, Postgres>,
+    db: &PgPool,
+    offsets_minutes: &[i64],
+    target: &ReminderTarget,
+    recipients: &[Uuid],
+) -> AppResult<()> {
+    ReminderRepository::cancel_for_entity(tx, target.related_entity_type, target.related_entity_id, None)
+        .await?;
+
+    let now = OffsetDateTime::now_utc();
+
+    for &user_id in recipients {
+        let zone = zone_for_user(db, user_id).await?;
+
+        for (offset_minutes, remind_at) in reminder_schedule(target.starts_at, offsets_minutes, zone, now) {
+            ReminderRepository::schedule(
+                tx,
+                &NewScheduledReminder {
+                    tenant_id: target.tenant_id,
+                    user_id,
+                    notification_type: target.notification_type,
+                    related_entity_id: target.related_entity_id,
+                    related_entity_type: target.related_entity_type,
+                    offset_minutes: offset_minutes as i32,
+                    starts_at: target.starts_at,
+                    remind_at,
+                },
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps the reminders of an appointment in line with its current time and status.
+pub async fn sync_appointment_reminders(
+    tx: &mut Transaction<'_, Postgres>,
+    state: &AppState,
+    appointment: &Appointment,
+) -> AppResult<()> {
+    if !appointment.status.is_active() {
+        ReminderRepository::cancel_for_entity(tx, REMINDER_ENTITY_APPOINTMENT, appointment.id, None).await?;
+        return Ok(());
+    }
+
+    let target = ReminderTarget {
+        tenant_id: appointment.tenant_id,
+        notification_type: NotificationType::AppointmentReminder,
+        related_entity_type: REMINDER_ENTITY_APPOINTMENT,
+        related_entity_id: appointment.id,
+        starts_at: appointment.start_time,
+    };
+
+    schedule_reminders(
+        tx,
+        &state.db,
+        &state.env.notifications.reminder_offsets_minutes,
+        &target,
+        &[appointment.employee_user_id, appointment.professional_user_id],
+    )
+    .await
+}
+
+/// Keeps the reminders of a training session in line with its time, status and registrations.
+pub async fn sync_training_reminders(
+    tx: &mut Transaction<'_, Postgres>,
+    state: &AppState,
+    session: &TrainingSession,
+) -> AppResult<()> {
+    if session.status != TrainingStatus::Scheduled || !session.is_published() {
+        ReminderRepository::cancel_for_entity(tx, REMINDER_ENTITY_TRAINING_SESSION, session.id, None)
+            .await?;
+        return Ok(());
+    }
+
+    let mut recipients = TrainingRepository::list_registered_employee_ids(tx, session.id).await?;
+    recipients.push(session.host_user_id);
+
+    let target = ReminderTarget {
+        tenant_id: session.tenant_id,
+        notification_type: NotificationType::TrainingReminder,
+        related_entity_type: REMINDER_ENTITY_TRAINING_SESSION,
+        related_entity_id: session.id,
+        starts_at: session.start_time,
+    };
+
+    schedule_reminders(
+        tx,
+        &state.db,
+        &state.env.notifications.reminder_offsets_minutes,
+        &target,
+        &recipients,
+    )
+    .await
+}
+
+/// Formats an instant for notification text, e.g. `31 Mar 2025 10:00`, in the recipient's zone.
+pub(crate) fn format_local(at: OffsetDateTime, zone: Zone) -> String {
+    zone.localize(at)
+        .format(format_description!("[day] [month repr:short] [year] [hour]:[minute]"))
+        .unwrap_or_else(|_| at.to_string())
+}
+
+fn reminder_notification(reminder: &ScheduledReminder, zone: Zone) -> NewNotification {
+    let (title, subject) = match reminder.notification_type {
+        NotificationType::TrainingReminder => ("Training reminder", "Your training session"),
+        _ => ("Appointment reminder", "Your appointment"),
+    };
+
+    let local_start = format_local(reminder.starts_at, zone);
+
+    NewNotification {
+        user_id: reminder.user_id,
+        tenant_id: Some(reminder.tenant_id),
+        notification_type: reminder.notification_type,
+        title: title.to_string(),
+        message: format!("{} starts at {} ({}).", subject, local_start, zone.name()),
+        related_entity_id: Some(reminder.related_entity_id),
+        related_entity_type: Some(reminder.related_entity_type.clone()),
+    }
+}
+
+/// Delivers every reminder that is due, returning how many notifications were created.
+///
+/// Each batch is claimed, turned into in-app notifications and marked sent in one transaction,
+/// so a crash either delivers the whole batch or leaves it pending for the next run. Push and
+/// email go out after the commit and are not retried.
+pub async fn run_due_reminders(state: &AppState) -> AppResult<usize> {
+    let mut delivered = 0;
+
+    loop {
+        let mut tx = state.db.begin().await?;
+        let due = ReminderRepository::claim_due(&mut tx, REMINDER_BATCH_SIZE).await?;
+        let claimed = due.len();
+        let now = OffsetDateTime::now_utc();
+        let mut notifications = Vec::with_capacity(claimed);
+
+        for reminder in due {
+            let current_start = ReminderRepository::find_active_start(
+                &mut tx,
+                &reminder.related_entity_type,
+                reminder.related_entity_id,
+            )
+            .await?;
+
+            // The event was cancelled, moved without rescheduling, or is already under way
+            if current_start != Some(reminder.starts_at) || reminder.starts_at <= now {
+                debug!("Skipping stale reminder {}", reminder.id);
+                ReminderRepository::mark(&mut tx, reminder.id, ReminderStatus::Skipped).await?;
+                continue;
+            }
+
+            let zone = zone_for_user(&state.db, reminder.user_id).await?;
+            let notification =
+                NotificationRepository::create(&mut tx, &reminder_notification(&reminder, zone)).await?;
+            ReminderRepository::mark(&mut tx, reminder.id, ReminderStatus::Sent).await?;
+            notifications.push(notification);
+        }
+
+        tx.commit().await?;
+
+        state.notifier.dispatch_all(&state.db, &notifications).await;
+        delivered += notifications.len();
+
+        if (claimed as i64) < REMINDER_BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(delivered)
+}
+
+/// Starts the background task that polls for due reminders.
+pub fn spawn_scheduler(state: AppState) -> JoinHandle<()> {
+    let period = StdDuration::from_secs(state.env.notifications.reminder_poll_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            match run_due_reminders(&state).await {
+                Ok(0) => {}
+                Ok(delivered) => debug!("Delivered {} reminders", delivered),
+                Err(e) => error!("Reminder run failed: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use sqlx::types::Uuid;
+use thiserror::Error;
+use time::{Duration, OffsetDateTime};
+
+/// How long an invite rings before a new one may replace it.
+pub const RING_TIMEOUT: Duration = Duration::seconds(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallState {
+    Ringing,
+    Active { answered_at: OffsetDateTime },
+}
+
+/// Why a call ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndReason {
+    HungUp,
+    LeftRoom,
+    Disconnected,
+    /// The tenant's time limit for live sessions was reached.
+    TimeLimit,
+    /// The appointment's time slot is over.
+    AppointmentEnded,
+    /// The appointment was cancelled during the call.
+    AppointmentCancelled,
+    /// The server stopped during the call; it counts until the call was last seen.
+    Interrupted,
+}
+
+/// A participant dialing the other one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Invitation {
+    pub appointment_id: Uuid,
+    pub caller_user_id: Uuid,
+    pub caller_connection: Uuid,
+    pub callee_user_id: Uuid,
+    pub professional_user_id: Uuid,
+    /// How long the answered calls of the appointment may last together; None for no limit.
+    pub time_limit: Option<Duration>,
+    /// When the appointment ends; no call outlasts it.
+    pub closes_at: OffsetDateTime,
+}
+
+/// A call between the two participants of an appointment. Each side is one connection: the
+/// one that invited, and the one that accepted; negotiation flows only between those two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub id: Uuid,
+    pub appointment_id: Uuid,
+    pub caller_user_id: Uuid,
+    pub caller_connection: Uuid,
+    pub callee_user_id: Uuid,
+    pub callee_connection: Option<Uuid>,
+    pub professional_user_id: Uuid,
+    pub state: CallState,
+    pub invited_at: OffsetDateTime,
+    pub time_limit: Option<Duration>,
+    pub closes_at: OffsetDateTime,
+}
+
+impl Call {
+    pub fn is_participant(&self, user_id: Uuid) -> bool {
+        self.caller_user_id == user_id || self.callee_user_id == user_id
+    }
+
+    /// The connection on the other side of an accepted call from `connection`.
+    pub fn peer_connection(&self, connection: Uuid) -> Option<Uuid> {
+        if connection == self.caller_connection {
+            self.callee_connection
+        } else if Some(connection) == self.callee_connection {
+            Some(self.caller_connection)
+        } else {
+            None
+        }
+    }
+
+    /// When an answered call is cut off: at the end of the appointment, or earlier once the
+    /// time limit is reached, after the earlier calls of the appointment `used` part of it.
+    pub fn ends_at(&self, used: Duration) -> Option<OffsetDateTime> {
+        match self.state {
+            CallState::Active { answered_at } => Some(
+                self.time_limit
+                    .map(|limit| answered_at + (limit - used).max(Duration::ZERO))
+                    .map_or(self.closes_at, |limit_reached| limit_reached.min(self.closes_at)),
+            ),
+            CallState::Ringing => None,
+        }
+    }
+
+    fn uses_connection(&self, connection: Uuid) -> bool {
+        self.caller_connection == connection || self.callee_connection == Some(connection)
+    }
+
+    fn rings_unanswered(&self, now: OffsetDateTime) -> bool {
+        self.state == CallState::Ringing && now - self.invited_at > RING_TIMEOUT
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum CallError {
+    #[error("A call is already in progress for this appointment")]
+    Busy,
+    #[error("There is no call for this appointment")]
+    NoCall,
+    #[error("Only the invited participant can answer the call")]
+    NotCallee,
+    #[error("The call has already been answered")]
+    AlreadyAnswered,
+    #[error("The call has not been accepted yet")]
+    NotAccepted,
+    #[error("The call is taking place on another connection")]
+    OtherConnection,
+    #[error("The appointment is over")]
+    Closed,
+}
+
+/// Calls in progress, at most one per appointment. Held in memory only: a call is between
+/// live connections and ends with them.
+#[derive(Clone, Default)]
+pub struct Calls {
+    calls: Arc<Mutex<HashMap<Uuid, Call>>>,
+}
+
+#[allow(unused)]
+impl Calls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Call>> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Starts ringing the callee, unless a call is already going on.
+    pub fn invite(&self, invitation: Invitation, now: OffsetDateTime) -> Result<Call, CallError> {
+        let mut calls = self.lock();
+        if calls.get(&invitation.appointment_id).is_some_and(|call| !call.rings_unanswered(now)) {
+            return Err(CallError::Busy);
+        }
+
+        let call = Call {
+            id: Uuid::now_v7(),
+            appointment_id: invitation.appointment_id,
+            caller_user_id: invitation.caller_user_id,
+            caller_connection: invitation.caller_connection,
+            callee_user_id: invitation.callee_user_id,
+            callee_connection: None,
+            professional_user_id: invitation.professional_user_id,
+            state: CallState::Ringing,
+            invited_at: now,
+            time_limit: invitation.time_limit,
+            closes_at: invitation.closes_at,
+        };
+        calls.insert(call.appointment_id, call.clone());
+        Ok(call)
+    }
+
+    /// Answers a ringing call on the callee's connection.
+    pub fn accept(
+        &self,
+        appointment_id: Uuid,
+        user_id: Uuid,
+        connection: Uuid,
+        now: OffsetDateTime,
+    ) -> Result<Call, CallError> {
+        let mut calls = self.lock();
+        let call = calls
+            .get_mut(&appointment_id)
+            .filter(|call| call.is_participant(user_id))
+            .ok_or(CallError::NoCall)?;
+        if call.callee_user_id != user_id {
+            return Err(CallError::NotCallee);
+        }
+        if call.state != CallState::Ringing {
+            return Err(CallError::AlreadyAnswered);
+        }
+        if call.rings_unanswered(now) {
+            calls.remove(&appointment_id);
+            return Err(CallError::NoCall);
+        }
+        if now >= call.closes_at {
+            calls.remove(&appointment_id);
+            return Err(CallError::Closed);
+        }
+
+        call.state = CallState::Active { answered_at: now };
+        call.callee_connection = Some(connection);
+        Ok(call.clone())
+    }
+
+    /// Turns down a ringing call; ends it.
+    pub fn decline(&self, appointment_id: Uuid, user_id: Uuid) -> Result<Call, CallError> {
+        let mut calls = self.lock();
+        let call = calls
+            .get(&appointment_id)
+            .filter(|call| call.is_participant(user_id))
+            .ok_or(CallError::NoCall)?;
+        if call.callee_user_id != user_id {
+            return Err(CallError::NotCallee);
+        }
+        if call.state != CallState::Ringing {
+            return Err(CallError::AlreadyAnswered);
+        }
+        Ok(calls.remove(&appointment_id).expect("call is present"))
+    }
+
+    /// Ends a call from either side, whether it was answered or not.
+    pub fn hang_up(&self, appointment_id: Uuid, user_id: Uuid) -> Result<Call, CallError> {
+        let mut calls = self.lock();
+        if !calls.get(&appointment_id).is_some_and(|call| call.is_participant(user_id)) {
+            return Err(CallError::NoCall);
+        }
+        Ok(calls.remove(&appointment_id).expect("call is present"))
+    }
+
+    /// The accepted call a connection negotiates media in, while the appointment lasts.
+    pub fn negotiating(
+        &self,
+        appointment_id: Uuid,
+        user_id: Uuid,
+        connection: Uuid,
+        now: OffsetDateTime,
+    ) -> Result<Call, CallError> {
+        let calls = self.lock();
+        let call = calls
+            .get(&appointment_id)
+            .filter(|call| call.is_participant(user_id))
+            .ok_or(CallError::NoCall)?;
+        if call.state == CallState::Ringing {
+            return Err(CallError::NotAccepted);
+        }
+        if !call.uses_connection(connection) {
+            return Err(CallError::OtherConnection);
+        }
+        if now >= call.closes_at {
+            return Err(CallError::Closed);
+        }
+        Ok(call.clone())
+    }
+
+    pub fn get(&self, appointment_id: Uuid) -> Option<Call> {
+        self.lock().get(&appointment_id).cloned()
+    }
+
+    /// Ends a call unless it was replaced meanwhile, as when its time is up.
+    pub fn end(&self, appointment_id: Uuid, call_id: Uuid) -> Option<Call> {
+        let mut calls = self.lock();
+        if calls.get(&appointment_id)?.id == call_id {
+            calls.remove(&appointment_id)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the call of an appointment that runs on a connection, as when it leaves the room.
+    pub fn end_on_connection(&self, appointment_id: Uuid, connection: Uuid) -> Option<Call> {
+        let mut calls = self.lock();
+        if calls.get(&appointment_id)?.uses_connection(connection) {
+            calls.remove(&appointment_id)
+        } else {
+            None
+        }
+    }
+
+    /// The answered calls running on a connection.
+    pub fn answered_on_connection(&self, connection: Uuid) -> Vec<Uuid> {
+        self.lock()
+            .values()
+            .filter(|call| call.uses_connection(connection) && call.state != CallState::Ringing)
+            .map(|call| call.id)
+            .collect()
+    }
+
+    /// Ends every call running on a connection that closed.
+    pub fn end_all_on_connection(&self, connection: Uuid) -> Vec<Call> {
+        let mut calls = self.lock();
+        let ended: Vec<Uuid> = calls
+            .values()
+            .filter(|call| call.uses_connection(connection))
+            .map(|call| call.appointment_id)
+            .collect();
+        ended.iter().filter_map(|id| calls.remove(id)).collect()
+    }
+}
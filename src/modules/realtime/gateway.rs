@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use axum::extract::ws::Utf8Bytes;
+use sqlx::types::Uuid;
+use tokio::sync::{mpsc, Notify};
+use tracing::warn;
+
+use crate::middleware::auth::AuthUser;
+
+use super::protocol::{Room, ServerMessage};
+
+/// Frames queued for a connection before it counts as too slow and is dropped.
+const OUTBOX_CAPACITY: usize = 64;
+
+/// Rooms one connection may be subscribed to at a time.
+pub const MAX_ROOMS_PER_CONNECTION: usize = 32;
+
+pub type ConnectionId = Uuid;
+
+/// A room within the tenant it belongs to. Connections only join rooms of their own tenant,
+/// so events published to a room never reach another tenant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RoomKey {
+    pub tenant_id: Uuid,
+    pub room: Room,
+}
+
+impl RoomKey {
+    pub fn new(tenant_id: Uuid, room: Room) -> Self {
+        Self { tenant_id, room }
+    }
+}
+
+/// What a connection receives: frames to send, and a signal to close when it falls behind.
+pub struct Mailbox {
+    pub id: ConnectionId,
+    pub frames: mpsc::Receiver<Utf8Bytes>,
+    pub evicted: Arc<Notify>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinError {
+    /// The connection closed meanwhile.
+    Gone,
+    /// The room belongs to another tenant than the connection.
+    OtherTenant,
+    TooManyRooms,
+}
+
+struct Connection {
+    user_id: Uuid,
+    tenant_id: Option<Uuid>,
+    outbox: mpsc::Sender<Utf8Bytes>,
+    evicted: Arc<Notify>,
+    rooms: HashSet<RoomKey>,
+}
+
+#[derive(Default)]
+struct Registry {
+    connections: HashMap<ConnectionId, Connection>,
+    users: HashMap<Uuid, HashSet<ConnectionId>>,
+    rooms: HashMap<RoomKey, HashSet<ConnectionId>>,
+}
+
+impl Registry {
+    /// Whether another connection of the user is in the room.
+    fn user_in_room(&self, user_id: Uuid, key: &RoomKey, except: ConnectionId) -> bool {
+        self.rooms.get(key).is_some_and(|members| {
+            members
+                .iter()
+                .any(|id| *id != except && self.connections.get(id).is_some_and(|c| c.user_id == user_id))
+        })
+    }
+
+    fn remove_member(&mut self, key: &RoomKey, id: ConnectionId) {
+        if let Some(members) = self.rooms.get_mut(key) {
+            members.remove(&id);
+            if members.is_empty() {
+                self.rooms.remove(key);
+            }
+        }
+    }
+}
+
+/// Tracks open WebSocket connections with the user and rooms each one is subscribed to,
+/// and routes events to them.
+///
+/// Sends never wait: frames go to a bounded per-connection queue, and a connection whose
+/// queue is full is told to close instead of holding up everyone else. The lock is never
+/// held across an await.
+#[derive(Clone, Default)]
+pub struct Gateway {
+    registry: Arc<RwLock<Registry>>,
+}
+
+#[allow(unused)]
+impl Gateway {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a connection of an authenticated user.
+    pub fn connect(&self, user: &AuthUser) -> Mailbox {
+        let id = Uuid::now_v7();
+        let (outbox, frames) = mpsc::channel(OUTBOX_CAPACITY);
+        let evicted = Arc::new(Notify::new());
+
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        registry.connections.insert(
+            id,
+            Connection {
+                user_id: user.user_id,
+                tenant_id: user.tenant_id,
+                outbox,
+                evicted: evicted.clone(),
+                rooms: HashSet::new(),
+            },
+        );
+        registry.users.entry(user.user_id).or_default().insert(id);
+
+        Mailbox { id, frames, evicted }
+    }
+
+    /// Forgets a connection, returning the rooms it was in that the user has now left entirely.
+    pub fn disconnect(&self, id: ConnectionId) -> Vec<RoomKey> {
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        let Some(connection) = registry.connections.remove(&id) else {
+            return Vec::new();
+        };
+
+        if let Some(ids) = registry.users.get_mut(&connection.user_id) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                registry.users.remove(&connection.user_id);
+            }
+        }
+
+        let mut left = Vec::new();
+        for key in connection.rooms {
+            registry.remove_member(&key, id);
+            if !registry.user_in_room(connection.user_id, &key, id) {
+                left.push(key);
+            }
+        }
+        left
+    }
+
+    /// Subscribes a connection to a room of its tenant; returns whether the user just entered
+    /// the room, rather than being in it already from another connection.
+    pub fn join(&self, id: ConnectionId, key: RoomKey) -> Result<bool, JoinError> {
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        let connection = registry.connections.get_mut(&id).ok_or(JoinError::Gone)?;
+        if connection.tenant_id != Some(key.tenant_id) {
+            return Err(JoinError::OtherTenant);
+        }
+        if connection.rooms.contains(&key) {
+            return Ok(false);
+        }
+        if connection.rooms.len() >= MAX_ROOMS_PER_CONNECTION {
+            return Err(JoinError::TooManyRooms);
+        }
+        connection.rooms.insert(key);
+        let user_id = connection.user_id;
+
+        let entered = !registry.user_in_room(user_id, &key, id);
+        registry.rooms.entry(key).or_default().insert(id);
+        Ok(entered)
+    }
+
+    /// Unsubscribes a connection from a room; returns whether the user has now left it entirely.
+    /// None if the connection was not subscribed.
+    pub fn leave(&self, id: ConnectionId, key: RoomKey) -> Option<bool> {
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        let connection = registry.connections.get_mut(&id)?;
+        if !connection.rooms.remove(&key) {
+            return None;
+        }
+        let user_id = connection.user_id;
+
+        registry.remove_member(&key, id);
+        Some(!registry.user_in_room(user_id, &key, id))
+    }
+
+    /// Room keys a connection is subscribed to.
+    pub fn rooms_of(&self, id: ConnectionId) -> Vec<RoomKey> {
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        registry
+            .connections
+            .get(&id)
+            .map(|connection| connection.rooms.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether a connection is subscribed to a room.
+    pub fn is_subscribed(&self, id: ConnectionId, key: RoomKey) -> bool {
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        registry.connections.get(&id).is_some_and(|connection| connection.rooms.contains(&key))
+    }
+
+    /// Users with at least one connection subscribed to a room.
+    pub fn users_in_room(&self, key: RoomKey) -> Vec<Uuid> {
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        let users: HashSet<Uuid> = registry
+            .rooms
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| registry.connections.get(id).map(|connection| connection.user_id))
+            .collect();
+        users.into_iter().collect()
+    }
+
+    /// Whether the user has an open connection in the tenant.
+    pub fn is_online(&self, tenant_id: Uuid, user_id: Uuid) -> bool {
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        registry.users.get(&user_id).is_some_and(|ids| {
+            ids.iter()
+                .any(|id| registry.connections.get(id).is_some_and(|c| c.tenant_id == Some(tenant_id)))
+        })
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.registry.read().unwrap_or_else(|e| e.into_inner()).connections.len()
+    }
+
+    /// Sends a message to one connection.
+    pub fn send_to_connection(&self, id: ConnectionId, message: &ServerMessage) {
+        let Some(frame) = encode(message) else {
+            return;
+        };
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        deliver(&registry, std::iter::once(&id), &frame);
+    }
+
+    /// Sends a message to every connection of a user within a tenant.
+    pub fn send_to_user(&self, tenant_id: Option<Uuid>, user_id: Uuid, message: &ServerMessage) {
+        let Some(frame) = encode(message) else {
+            return;
+        };
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(ids) = registry.users.get(&user_id) {
+            let ids = ids
+                .iter()
+                .filter(|id| registry.connections.get(id).is_some_and(|c| c.tenant_id == tenant_id));
+            deliver(&registry, ids, &frame);
+        }
+    }
+
+    /// Sends a message to every connection of some users within a tenant, optionally skipping one.
+    pub fn send_to_users(
+        &self,
+        tenant_id: Uuid,
+        user_ids: &[Uuid],
+        message: &ServerMessage,
+        except: Option<ConnectionId>,
+    ) {
+        let Some(frame) = encode(message) else {
+            return;
+        };
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        let ids = user_ids
+            .iter()
+            .filter_map(|user_id| registry.users.get(user_id))
+            .flatten()
+            .filter(|id| Some(**id) != except)
+            .filter(|id| registry.connections.get(id).is_some_and(|c| c.tenant_id == Some(tenant_id)));
+        deliver(&registry, ids, &frame);
+    }
+
+    /// Sends a message to every connection subscribed to a room, optionally skipping the sender.
+    pub fn send_to_room(&self, key: RoomKey, message: &ServerMessage, except: Option<ConnectionId>) {
+        let Some(frame) = encode(message) else {
+            return;
+        };
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(members) = registry.rooms.get(&key) {
+            deliver(&registry, members.iter().filter(|id| Some(**id) != except), &frame);
+        }
+    }
+
+    /// Publishes an event to a room of a tenant.
+    pub fn publish(&self, tenant_id: Uuid, room: Room, event: &str, data: serde_json::Value) {
+        self.send_to_room(
+            RoomKey::new(tenant_id, room),
+            &ServerMessage::event(Some(room), event, data),
+            None,
+        );
+    }
+}
+
+fn encode(message: &ServerMessage) -> Option<Utf8Bytes> {
+    match serde_json::to_string(message) {
+        Ok(text) => Some(text.into()),
+        Err(e) => {
+            warn!("Failed to encode realtime message: {}", e);
+            None
+        }
+    }
+}
+
+fn deliver<'a>(registry: &Registry, ids: impl Iterator<Item = &'a ConnectionId>, frame: &Utf8Bytes) {
+    for id in ids {
+        let Some(connection) = registry.connections.get(id) else {
+            continue;
+        };
+        if let Err(mpsc::error::TrySendError::Full(_)) = connection.outbox.try_send(frame.clone()) {
+            warn!("Realtime connection {} fell behind and is closed", id);
+            connection.evicted.notify_one();
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use axum::{extract::State, http::header, response::IntoResponse, Json};
+use secrecy::ExposeSecret;
+use serde::Serialize;
+use time::{Duration, OffsetDateTime};
+
+use crate::app_state::AppState;
+use crate::core::utils::turn::rest_credential;
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+/// An entry of `RTCConfiguration.iceServers`.
+#[derive(Debug, Serialize)]
+pub struct IceServer {
+    pub urls: Vec<String>,
+    pub username: String,
+    pub credential: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IceServersResponse {
+    pub ice_servers: Vec<IceServer>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+    pub ttl_seconds: u32,
+}
+
+/// Issues TURN credentials for the caller that expire after the configured TTL, so the
+/// TURN server's secret never leaves the backend. Clients fetch new ones before a call.
+pub async fn get_ice_servers(State(state): State<AppState>, user: AuthUser) -> AppResult<impl IntoResponse> {
+    user.require_tenant()?;
+    let turn = state
+        .env
+        .turn
+        .as_ref()
+        .ok_or_else(|| AppError::ServiceUnavailable("Calls are not configured".to_string()))?;
+    let urls: Vec<String> = [&turn.url_udp, &turn.url_tcp].into_iter().flatten().cloned().collect();
+    if urls.is_empty() {
+        return Err(AppError::ServiceUnavailable("Calls are not configured".to_string()));
+    }
+
+    let expires_at = OffsetDateTime::now_utc() + Duration::seconds(turn.credential_ttl_seconds.into());
+    let credential = rest_credential(turn.shared_secret.expose_secret().as_bytes(), user.user_id, expires_at);
+
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(IceServersResponse {
+            ice_servers: vec![IceServer {
+                urls,
+                username: credential.username,
+                credential: credential.credential,
+            }],
+            expires_at: credential.expires_at,
+            ttl_seconds: turn.credential_ttl_seconds,
+        }),
+    ))
+}
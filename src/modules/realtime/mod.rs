@@ -0,0 +1,19 @@
+pub mod calls;
+pub mod gateway;
+pub mod ice;
+pub mod protocol;
+pub mod signaling;
+pub mod socket;
+
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/ws", get(socket::connect))
+}
+
+/// Call setup endpoints under `/api`.
+pub fn api_routes() -> Router<AppState> {
+    Router::new().route("/calls/ice-servers", get(ice::get_ice_servers))
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use thiserror::Error;
+
+/// A channel of events about one resource; clients subscribe to it by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Room {
+    /// Participants, host and admins of a training session: `training:{id}`.
+    Training(Uuid),
+    /// The employee and professional of an appointment, for its video call: `appointment:{id}`.
+    Appointment(Uuid),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Unknown room {0:?}")]
+pub struct RoomError(String);
+
+impl fmt::Display for Room {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Room::Training(id) => write!(f, "training:{}", id),
+            Room::Appointment(id) => write!(f, "appointment:{}", id),
+        }
+    }
+}
+
+impl FromStr for Room {
+    type Err = RoomError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let (kind, id) = name.split_once(':').ok_or_else(|| RoomError(name.to_string()))?;
+        let id = Uuid::parse_str(id).map_err(|_| RoomError(name.to_string()))?;
+        match kind {
+            "training" => Ok(Room::Training(id)),
+            "appointment" => Ok(Room::Appointment(id)),
+            _ => Err(RoomError(name.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for Room {
+    type Error = RoomError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+impl From<Room> for String {
+    fn from(room: Room) -> Self {
+        room.to_string()
+    }
+}
+
+/// A frame sent by a client. `id` is echoed in the reply so requests can be matched up.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe { id: Option<String>, room: Room },
+    Unsubscribe { id: Option<String>, room: Room },
+    Ping { id: Option<String> },
+    /// Call control or WebRTC negotiation for the call in an appointment room.
+    Signal { id: Option<String>, room: Room, signal: Signal },
+}
+
+/// WebRTC signaling between the two participants of a call. The server relays SDP and ICE
+/// candidates untouched; it only tracks who is calling whom.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Signal {
+    /// Rings every connection of the other participant.
+    Invite,
+    Accept,
+    Decline {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// Ends the call, or withdraws an unanswered invite.
+    HangUp,
+    Offer { sdp: String },
+    Answer { sdp: String },
+    IceCandidate { candidate: IceCandidate },
+}
+
+/// An ICE candidate in the shape of the browser's `RTCIceCandidateInit`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IceCandidate {
+    pub candidate: String,
+    #[serde(default)]
+    pub sdp_mid: Option<String>,
+    #[serde(default)]
+    pub sdp_m_line_index: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username_fragment: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidMessage,
+    Forbidden,
+    NotFound,
+    Conflict,
+    Internal,
+}
+
+/// A frame sent to a client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome {
+        connection_id: Uuid,
+        user_id: Uuid,
+        heartbeat_interval_seconds: u64,
+    },
+    Subscribed {
+        id: Option<String>,
+        room: Room,
+    },
+    Unsubscribed {
+        id: Option<String>,
+        room: Room,
+    },
+    Pong {
+        id: Option<String>,
+    },
+    /// A request that has no other reply, such as a signal, was accepted.
+    Ack {
+        id: Option<String>,
+    },
+    /// A signal from the other participant of a call.
+    Signal {
+        room: Room,
+        call_id: Uuid,
+        from_user_id: Uuid,
+        signal: Signal,
+    },
+    /// Something happened in a room, or to the user when `room` is absent.
+    Event {
+        room: Option<Room>,
+        event: String,
+        data: serde_json::Value,
+    },
+    Error {
+        id: Option<String>,
+        code: ErrorCode,
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    pub fn event(room: Option<Room>, event: &str, data: serde_json::Value) -> Self {
+        ServerMessage::Event {
+            room,
+            event: event.to_string(),
+            data,
+        }
+    }
+
+    pub fn error(id: Option<String>, code: ErrorCode, message: impl Into<String>) -> Self {
+        ServerMessage::Error {
+            id,
+            code,
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::time::Duration as StdDuration;
+
+use serde_json::json;
+use sqlx::types::Uuid;
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use crate::app_state::AppState;
+use crate::db::repositories::{AppointmentRepository, CallLogRepository};
+use crate::db::{rls, Appointment, NewCallLog};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+use super::calls::{Call, CallError, CallState, EndReason, Invitation};
+use super::gateway::{ConnectionId, RoomKey};
+use super::protocol::{Room, ServerMessage, Signal};
+
+/// How long before an appointment starts its participants may enter the call.
+pub const EARLY_JOIN: Duration = Duration::minutes(10);
+
+/// Answered calls are seen on every heartbeat of their connections; a log left open for longer
+/// belongs to a call that a stopped server lost.
+const STALE_CALL_AFTER: Duration = Duration::minutes(3);
+
+/// How often open call logs are checked for lost calls.
+const STALE_CALL_SWEEP_INTERVAL: StdDuration = StdDuration::from_secs(60);
+
+/// Loads an appointment the user takes part in and checks that its call is open now: the
+/// appointment is still active and its window, opened a little early, has not passed.
+pub(super) async fn authorize(state: &AppState, user: &AuthUser, appointment_id: Uuid) -> AppResult<Appointment> {
+    let tenant_id = user.require_tenant()?;
+    let mut tx = rls::begin_for_user(&state.db, user).await?;
+    let appointment = AppointmentRepository::find_by_id(&mut tx, appointment_id).await?;
+    tx.commit().await?;
+
+    let participant =
+        appointment.employee_user_id == user.user_id || appointment.professional_user_id == user.user_id;
+    if appointment.tenant_id != tenant_id || !participant {
+        return Err(AppError::NotFound("Appointment not found".to_string()));
+    }
+    if !appointment.status.is_active() {
+        return Err(AppError::Conflict("Appointment is no longer active".to_string()));
+    }
+
+    let now = OffsetDateTime::now_utc();
+    if now < appointment.start_time - EARLY_JOIN || now >= appointment.end_time {
+        return Err(AppError::Conflict(format!(
+            "The call is open from {} minutes before the appointment until it ends",
+            EARLY_JOIN.whole_minutes()
+        )));
+    }
+    Ok(appointment)
+}
+
+fn call_error(error: CallError) -> AppError {
+    match error {
+        CallError::NoCall => AppError::NotFound(error.to_string()),
+        _ => AppError::Conflict(error.to_string()),
+    }
+}
+
+fn signal_message(room: Room, call: &Call, from_user_id: Uuid, signal: Signal) -> ServerMessage {
+    ServerMessage::Signal {
+        room,
+        call_id: call.id,
+        from_user_id,
+        signal,
+    }
+}
+
+/// Handles a signal sent in an appointment room the connection is subscribed to.
+///
+/// Call control reaches every connection of both participants but the sender, so all of the
+/// callee's devices ring and stop ringing together. Offers, answers and candidates only go to
+/// the connection on the other side of the accepted call.
+pub(super) async fn relay(
+    state: &AppState,
+    user: &AuthUser,
+    connection_id: ConnectionId,
+    room: Room,
+    signal: Signal,
+) -> AppResult<()> {
+    let Room::Appointment(appointment_id) = room else {
+        return Err(AppError::BadRequest("Signals can only be sent in appointment rooms".to_string()));
+    };
+    let tenant_id = user.require_tenant()?;
+    if !state.realtime.is_subscribed(connection_id, RoomKey::new(tenant_id, room)) {
+        return Err(AppError::Conflict("Subscribe to the room before signaling".to_string()));
+    }
+
+    let calls = &state.calls;
+    let now = OffsetDateTime::now_utc();
+
+    let call = match &signal {
+        Signal::Invite => {
+            // The window is checked again: the subscription may be older than the appointment's end
+            let appointment = authorize(state, user, appointment_id).await?;
+            let callee = if appointment.employee_user_id == user.user_id {
+                appointment.professional_user_id
+            } else {
+                appointment.employee_user_id
+            };
+
+            let mut tx = rls::begin_for_user(&state.db, user).await?;
+            let time_limit = match CallLogRepository::time_limit_minutes(&mut tx, tenant_id).await? {
+                Some(minutes) if minutes <= 0 => {
+                    return Err(AppError::Authorization(
+                        "The subscription of this tenant does not include calls".to_string(),
+                    ));
+                }
+                minutes => minutes.map(|minutes| Duration::minutes(minutes.into())),
+            };
+            // The limit counts for the appointment, so hanging up and dialing again does not reset it
+            let talked = Duration::seconds(CallLogRepository::talked_seconds(&mut tx, appointment_id).await?);
+            if time_limit.is_some_and(|limit| talked >= limit) {
+                return Err(AppError::Conflict("The time for calls of this appointment is used up".to_string()));
+            }
+            let invitation = Invitation {
+                appointment_id,
+                caller_user_id: user.user_id,
+                caller_connection: connection_id,
+                callee_user_id: callee,
+                professional_user_id: appointment.professional_user_id,
+                time_limit,
+                closes_at: appointment.end_time,
+            };
+            let call = calls.invite(invitation, now).map_err(call_error)?;
+
+            let call_session_id = call.id.to_string();
+            let recorded: AppResult<()> = async move {
+                AppointmentRepository::set_call_session(&mut tx, appointment_id, &call_session_id).await?;
+                tx.commit().await?;
+                Ok(())
+            }
+            .await;
+            if let Err(e) = recorded {
+                calls.end(appointment_id, call.id);
+                return Err(e);
+            }
+            call
+        }
+        Signal::Accept => {
+            // The appointment may have been cancelled while the call rang
+            authorize(state, user, appointment_id).await?;
+            calls.accept(appointment_id, user.user_id, connection_id, now).map_err(call_error)?
+        }
+        Signal::Decline { .. } => calls.decline(appointment_id, user.user_id).map_err(call_error)?,
+        Signal::HangUp => calls.hang_up(appointment_id, user.user_id).map_err(call_error)?,
+        Signal::Offer { .. } | Signal::Answer { .. } | Signal::IceCandidate { .. } => {
+            let call = calls
+                .negotiating(appointment_id, user.user_id, connection_id, now)
+                .map_err(call_error)?;
+            if let Some(peer) = call.peer_connection(connection_id) {
+                state
+                    .realtime
+                    .send_to_connection(peer, &signal_message(room, &call, user.user_id, signal));
+            }
+            return Ok(());
+        }
+    };
+
+    let (accepted, hung_up) = (signal == Signal::Accept, signal == Signal::HangUp);
+    state.realtime.send_to_users(
+        tenant_id,
+        &[call.caller_user_id, call.callee_user_id],
+        &signal_message(room, &call, user.user_id, signal),
+        Some(connection_id),
+    );
+
+    if accepted {
+        started(state, tenant_id, room, &call).await;
+    } else if hung_up {
+        ended(state, tenant_id, room, &call, Some(user.user_id), EndReason::HungUp).await;
+    }
+    Ok(())
+}
+
+/// Ends the call of an appointment that was cancelled, telling both participants.
+pub(crate) async fn appointment_cancelled(state: &AppState, appointment: &Appointment, cancelled_by: Uuid) {
+    let Some(call) = state.calls.get(appointment.id) else {
+        return;
+    };
+    if let Some(call) = state.calls.end(appointment.id, call.id) {
+        let room = Room::Appointment(appointment.id);
+        ended(state, appointment.tenant_id, room, &call, Some(cancelled_by), EndReason::AppointmentCancelled).await;
+    }
+}
+
+/// Notes that the answered calls of a connection that still answers heartbeats are running.
+pub(super) async fn still_talking(state: &AppState, connection_id: ConnectionId, seen_at: OffsetDateTime) {
+    let call_ids = state.calls.answered_on_connection(connection_id);
+    if call_ids.is_empty() {
+        return;
+    }
+
+    let result: AppResult<()> = async {
+        let mut tx = state.db.begin().await?;
+        CallLogRepository::mark_seen(&mut tx, &call_ids, seen_at).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+    if let Err(e) = result {
+        warn!("Recording that calls {:?} still run failed: {}", call_ids, e);
+    }
+}
+
+/// Starts the background task that closes the logs of calls lost in a restart or crash, first
+/// right away and then periodically. They end when they were last seen, so the minutes reports
+/// and the appointment's time limit count them.
+pub fn spawn_call_log_sweeper(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(STALE_CALL_SWEEP_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let metadata = json!({ "end_reason": EndReason::Interrupted });
+            let result: AppResult<u64> = async {
+                let mut tx = state.db.begin().await?;
+                let closed =
+                    CallLogRepository::finish_stale(&mut tx, OffsetDateTime::now_utc() - STALE_CALL_AFTER, &metadata)
+                        .await?;
+                tx.commit().await?;
+                Ok(closed)
+            }
+            .await;
+            match result {
+                Ok(0) => {}
+                Ok(closed) => info!("Closed the logs of {} interrupted calls", closed),
+                Err(e) => error!("Closing interrupted call logs failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Hangs up the call of an appointment room a connection left, telling the other side.
+pub(super) async fn left_room(state: &AppState, user: &AuthUser, connection_id: ConnectionId, key: RoomKey) {
+    let Room::Appointment(appointment_id) = key.room else {
+        return;
+    };
+    if let Some(call) = state.calls.end_on_connection(appointment_id, connection_id) {
+        hung_up(state, user, connection_id, key, &call);
+        ended(state, key.tenant_id, key.room, &call, Some(user.user_id), EndReason::LeftRoom).await;
+    }
+}
+
+/// Hangs up every call a closed connection took part in.
+pub(super) async fn disconnected(state: &AppState, user: &AuthUser, connection_id: ConnectionId) {
+    let Some(tenant_id) = user.tenant_id else {
+        return;
+    };
+    for call in state.calls.end_all_on_connection(connection_id) {
+        let key = RoomKey::new(tenant_id, Room::Appointment(call.appointment_id));
+        hung_up(state, user, connection_id, key, &call);
+        ended(state, tenant_id, key.room, &call, Some(user.user_id), EndReason::Disconnected).await;
+    }
+}
+
+fn hung_up(state: &AppState, user: &AuthUser, connection_id: ConnectionId, key: RoomKey, call: &Call) {
+    state.realtime.send_to_users(
+        key.tenant_id,
+        &[call.caller_user_id, call.callee_user_id],
+        &signal_message(key.room, call, user.user_id, Signal::HangUp),
+        Some(connection_id),
+    );
+}
+
+/// Opens the log of an answered call, tells both participants when it will be cut off and
+/// schedules the cut-off: when the appointment ends, or earlier once what is left of the
+/// appointment's time limit runs out. The call goes on if the log cannot be written.
+async fn started(state: &AppState, tenant_id: Uuid, room: Room, call: &Call) {
+    let CallState::Active { answered_at } = call.state else {
+        return;
+    };
+
+    let log = NewCallLog {
+        id: call.id,
+        tenant_id,
+        appointment_id: call.appointment_id,
+        initiator_user_id: call.caller_user_id,
+        receiver_user_id: call.callee_user_id,
+        professional_user_id: call.professional_user_id,
+        start_time: answered_at,
+        metadata: json!({
+            "invited_at": call.invited_at.format(&Rfc3339).ok(),
+            "caller_connection_id": call.caller_connection,
+            "callee_connection_id": call.callee_connection,
+            "time_limit_minutes": call.time_limit.map(|limit| limit.whole_minutes()),
+        }),
+    };
+    // Read again on answer: the previous call of the appointment may have been logged only
+    // after this one was dialed
+    let result: AppResult<i64> = async {
+        let mut tx = state.db.begin().await?;
+        let talked_seconds = CallLogRepository::talked_seconds(&mut tx, call.appointment_id).await?;
+        CallLogRepository::start(&mut tx, &log).await?;
+        tx.commit().await?;
+        Ok(talked_seconds)
+    }
+    .await;
+    let talked = match result {
+        Ok(seconds) => Duration::seconds(seconds),
+        Err(e) => {
+            warn!("Opening the log of call {} failed: {}", call.id, e);
+            Duration::ZERO
+        }
+    };
+    let ends_at = call.ends_at(talked);
+
+    state.realtime.send_to_users(
+        tenant_id,
+        &[call.caller_user_id, call.callee_user_id],
+        &ServerMessage::event(
+            Some(room),
+            "call.started",
+            json!({
+                "call_id": call.id,
+                "started_at": answered_at.format(&Rfc3339).ok(),
+                "ends_at": ends_at.and_then(|ends_at| ends_at.format(&Rfc3339).ok()),
+            }),
+        ),
+        None,
+    );
+
+    if let Some(ends_at) = ends_at {
+        let reason = if ends_at < call.closes_at { EndReason::TimeLimit } else { EndReason::AppointmentEnded };
+        let state = state.clone();
+        let (appointment_id, call_id) = (call.appointment_id, call.id);
+        tokio::spawn(async move {
+            let remaining = std::time::Duration::try_from(ends_at - OffsetDateTime::now_utc()).unwrap_or_default();
+            tokio::time::sleep(remaining).await;
+            if let Some(call) = state.calls.end(appointment_id, call_id) {
+                ended(&state, tenant_id, room, &call, None, reason).await;
+            }
+        });
+    }
+}
+
+/// Closes the log of a call that had been answered and tells both participants how long it
+/// lasted. Declined or withdrawn invites leave no log.
+async fn ended(
+    state: &AppState,
+    tenant_id: Uuid,
+    room: Room,
+    call: &Call,
+    ended_by: Option<Uuid>,
+    reason: EndReason,
+) {
+    let CallState::Active { answered_at } = call.state else {
+        return;
+    };
+    let end_time = OffsetDateTime::now_utc();
+
+    let metadata = json!({ "end_reason": reason, "ended_by_user_id": ended_by });
+    let result: AppResult<()> = async {
+        let mut tx = state.db.begin().await?;
+        CallLogRepository::finish(&mut tx, call.id, end_time, &metadata).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+    if let Err(e) = result {
+        warn!("Closing the log of call {} failed: {}", call.id, e);
+    }
+
+    state.realtime.send_to_users(
+        tenant_id,
+        &[call.caller_user_id, call.callee_user_id],
+        &ServerMessage::event(
+            Some(room),
+            "call.ended",
+            json!({
+                "call_id": call.id,
+                "reason": reason,
+                "ended_by_user_id": ended_by,
+                "duration_seconds": (end_time - answered_at).whole_seconds().max(0),
+            }),
+        ),
+        None,
+    );
+}
@@ -0,0 +1,386 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, Utf8Bytes, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header::AUTHORIZATION, HeaderMap},
+    response::Response,
+};
+use futures_util::{SinkExt, StreamExt};
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use crate::app_state::AppState;
+use crate::db::repositories::{AttendanceRepository, EnrollmentRepository, TrainingRepository};
+use crate::db::{rls, AttendanceEventType, DatabaseError, TrainingSession, TrainingStatus};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::{decode_token, AuthUser};
+use crate::modules::training::attendance::record_presence;
+use crate::modules::training::handlers::{can_manage, can_view};
+
+use super::gateway::{ConnectionId, JoinError, RoomKey, MAX_ROOMS_PER_CONNECTION};
+use super::protocol::{ClientMessage, ErrorCode, Room, ServerMessage};
+use super::signaling;
+
+/// How often the server pings an idle client.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A client that sent nothing, not even a pong, for this long is considered gone.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(75);
+
+/// Largest frame a client may send; client messages are small control frames.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// Close code for connections whose access token expired; clients reconnect with a fresh one.
+const CLOSE_TOKEN_EXPIRED: u16 = 4001;
+
+/// `source` of attendance events recorded for live training rooms.
+const ATTENDANCE_SOURCE: &str = "websocket";
+
+#[derive(Debug, Deserialize)]
+pub struct ConnectQuery {
+    /// For browsers, which cannot set headers on a WebSocket handshake.
+    pub access_token: Option<String>,
+}
+
+/// Upgrades an authenticated request to a realtime connection. The token comes from the
+/// `Authorization` header, or the `access_token` query parameter when no header can be set.
+pub async fn connect(
+    State(state): State<AppState>,
+    Query(query): Query<ConnectQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> AppResult<Response> {
+    let token = match headers.get(AUTHORIZATION) {
+        Some(header) => header
+            .to_str()
+            .ok()
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::Authentication("Expected a Bearer token".to_string()))?,
+        None => query
+            .access_token
+            .as_deref()
+            .ok_or_else(|| AppError::Authentication("Missing access token".to_string()))?,
+    };
+    let claims = decode_token(token, state.env.auth.jwt_secret.expose_secret())?;
+    let expires_at = OffsetDateTime::from_unix_timestamp(claims.exp)
+        .map_err(|_| AppError::Authentication("Invalid token expiry".to_string()))?;
+    let user = AuthUser::from(claims);
+
+    Ok(ws
+        .max_message_size(MAX_MESSAGE_BYTES)
+        .on_upgrade(move |socket| serve(state, socket, user, expires_at)))
+}
+
+fn close_frame(code: u16, reason: &'static str) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: Utf8Bytes::from_static(reason),
+    }))
+}
+
+fn encode(message: &ServerMessage) -> Message {
+    match serde_json::to_string(message) {
+        Ok(text) => Message::Text(text.into()),
+        Err(e) => {
+            warn!("Failed to encode realtime message: {}", e);
+            Message::Text(Utf8Bytes::from_static(r#"{"type":"error","code":"internal"}"#))
+        }
+    }
+}
+
+/// Runs one connection until the client leaves, stops answering heartbeats, falls behind
+/// or its token expires; then forgets its subscriptions.
+async fn serve(state: AppState, socket: WebSocket, user: AuthUser, expires_at: OffsetDateTime) {
+    let gateway = state.realtime.clone();
+    let mut mailbox = gateway.connect(&user);
+    let connection_id = mailbox.id;
+    debug!("Realtime connection {} opened by user {}", connection_id, user.user_id);
+
+    let (mut sink, mut stream) = socket.split();
+    let welcome = ServerMessage::Welcome {
+        connection_id,
+        user_id: user.user_id,
+        heartbeat_interval_seconds: HEARTBEAT_INTERVAL.as_secs(),
+    };
+
+    let mut heartbeat = tokio::time::interval_at(Instant::now() + HEARTBEAT_INTERVAL, HEARTBEAT_INTERVAL);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // A token accepted within the validation leeway has already expired: close right away
+    let remaining = Duration::try_from(expires_at - OffsetDateTime::now_utc()).unwrap_or_default();
+    let token_expiry = tokio::time::sleep(remaining);
+    tokio::pin!(token_expiry);
+    let mut last_seen = Instant::now();
+
+    let close = if sink.send(encode(&welcome)).await.is_err() {
+        None
+    } else {
+        loop {
+            tokio::select! {
+                frame = stream.next() => {
+                    last_seen = Instant::now();
+                    match frame {
+                        Some(Ok(Message::Text(text))) => {
+                            let reply = handle_message(&state, &user, connection_id, text.as_str()).await;
+                            if sink.send(encode(&reply)).await.is_err() {
+                                break None;
+                            }
+                        }
+                        Some(Ok(Message::Binary(_))) => {
+                            let reply =
+                                ServerMessage::error(None, ErrorCode::InvalidMessage, "Frames must be JSON text");
+                            if sink.send(encode(&reply)).await.is_err() {
+                                break None;
+                            }
+                        }
+                        // Pings are answered by the WebSocket layer itself
+                        Some(Ok(Message::Ping(_) | Message::Pong(_))) => {}
+                        Some(Ok(Message::Close(_))) | None => break None,
+                        Some(Err(e)) => {
+                            debug!("Realtime connection {} failed: {}", connection_id, e);
+                            break None;
+                        }
+                    }
+                }
+                frame = mailbox.frames.recv() => match frame {
+                    Some(text) => {
+                        if sink.send(Message::Text(text)).await.is_err() {
+                            break None;
+                        }
+                    }
+                    None => break None,
+                },
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() > HEARTBEAT_TIMEOUT {
+                        break Some(close_frame(close_code::AWAY, "Heartbeat timed out"));
+                    }
+                    still_present(&state, &user, connection_id, seen_at(last_seen)).await;
+                    signaling::still_talking(&state, connection_id, seen_at(last_seen)).await;
+                    if sink.send(Message::Ping(Default::default())).await.is_err() {
+                        break None;
+                    }
+                }
+                _ = &mut token_expiry => break Some(close_frame(CLOSE_TOKEN_EXPIRED, "Access token expired")),
+                _ = mailbox.evicted.notified() => {
+                    break Some(close_frame(close_code::AGAIN, "Too many undelivered messages"));
+                }
+            }
+        }
+    };
+
+    if let Some(close) = close {
+        let _ = sink.send(close).await;
+    }
+    signaling::disconnected(&state, &user, connection_id).await;
+    // The client was last known to be there when it last sent something
+    let left_at = seen_at(last_seen);
+    for key in gateway.disconnect(connection_id) {
+        left_room(&state, &user, key, left_at).await;
+    }
+    debug!("Realtime connection {} closed", connection_id);
+}
+
+async fn handle_message(state: &AppState, user: &AuthUser, connection_id: ConnectionId, text: &str) -> ServerMessage {
+    let message: ClientMessage = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(e) => return ServerMessage::error(None, ErrorCode::InvalidMessage, e.to_string()),
+    };
+
+    match message {
+        ClientMessage::Ping { id } => ServerMessage::Pong { id },
+        ClientMessage::Subscribe { id, room } => match subscribe(state, user, connection_id, room).await {
+            Ok(()) => ServerMessage::Subscribed { id, room },
+            Err(e) => error_reply(id, e),
+        },
+        ClientMessage::Unsubscribe { id, room } => {
+            let key = user.tenant_id.map(|tenant_id| RoomKey::new(tenant_id, room));
+            match key.and_then(|key| state.realtime.leave(connection_id, key).map(|left| (key, left))) {
+                Some((key, left)) => {
+                    signaling::left_room(state, user, connection_id, key).await;
+                    if left {
+                        left_room(state, user, key, OffsetDateTime::now_utc()).await;
+                    }
+                    ServerMessage::Unsubscribed { id, room }
+                }
+                None => ServerMessage::error(id, ErrorCode::NotFound, "Not subscribed to this room"),
+            }
+        }
+        ClientMessage::Signal { id, room, signal } => {
+            match signaling::relay(state, user, connection_id, room, signal).await {
+                Ok(()) => ServerMessage::Ack { id },
+                Err(e) => error_reply(id, e),
+            }
+        }
+    }
+}
+
+/// Error reply for a failed request; internal details stay in the log.
+fn error_reply(id: Option<String>, error: AppError) -> ServerMessage {
+    let (code, message) = match error {
+        AppError::Authentication(message) | AppError::Authorization(message) => (ErrorCode::Forbidden, message),
+        AppError::NotFound(message) => (ErrorCode::NotFound, message),
+        AppError::Database(DatabaseError::NotFound) => (ErrorCode::NotFound, "Resource not found".to_string()),
+        AppError::Conflict(message) => (ErrorCode::Conflict, message),
+        AppError::Validation(message) | AppError::BadRequest(message) => (ErrorCode::InvalidMessage, message),
+        error => {
+            warn!("Realtime request failed: {}", error);
+            (ErrorCode::Internal, "An internal server error occurred".to_string())
+        }
+    };
+    ServerMessage::error(id, code, message)
+}
+
+/// Authorizes a subscription and joins the room; entering a live training as a participant
+/// counts as joining it for attendance. Appointment rooms are open to the two participants
+/// during the appointment.
+async fn subscribe(state: &AppState, user: &AuthUser, connection_id: ConnectionId, room: Room) -> AppResult<()> {
+    let tenant_id = user.require_tenant()?;
+
+    match room {
+        Room::Training(session_id) => {
+            let mut tx = rls::begin_for_user(&state.db, user).await?;
+            let session = TrainingRepository::find_by_id(&mut tx, session_id).await?;
+            if session.tenant_id != tenant_id || !can_view(user, &session) {
+                return Err(AppError::NotFound("Training session not found".to_string()));
+            }
+            if !can_manage(user, &session) {
+                EnrollmentRepository::find(&mut tx, session_id, user.user_id)
+                    .await?
+                    .filter(|enrollment| enrollment.status.holds_seat())
+                    .ok_or_else(|| {
+                        AppError::Authorization("You are not registered for this training session".to_string())
+                    })?;
+            }
+            tx.commit().await?;
+        }
+        Room::Appointment(appointment_id) => {
+            signaling::authorize(state, user, appointment_id).await?;
+        }
+    }
+
+    let key = RoomKey::new(tenant_id, room);
+    let entered = state.realtime.join(connection_id, key).map_err(|e| match e {
+        JoinError::TooManyRooms => {
+            AppError::Conflict(format!("At most {} rooms can be subscribed at once", MAX_ROOMS_PER_CONNECTION))
+        }
+        JoinError::Gone | JoinError::OtherTenant => AppError::NotFound("Room not found".to_string()),
+    })?;
+
+    if entered {
+        state.realtime.send_to_room(
+            key,
+            &ServerMessage::event(Some(room), "room.member_joined", json!({ "user_id": user.user_id })),
+            Some(connection_id),
+        );
+        track_presence(state, user, room, AttendanceEventType::Join, OffsetDateTime::now_utc()).await;
+    }
+    Ok(())
+}
+
+/// Announces that the user left a room with their last connection.
+async fn left_room(state: &AppState, user: &AuthUser, key: RoomKey, left_at: OffsetDateTime) {
+    state.realtime.send_to_room(
+        key,
+        &ServerMessage::event(Some(key.room), "room.member_left", json!({ "user_id": user.user_id })),
+        None,
+    );
+    track_presence(state, user, key.room, AttendanceEventType::Leave, left_at).await;
+}
+
+/// Counts participants who were already in a training's room when it went live as joining it.
+pub(crate) async fn training_started(state: &AppState, session: &TrainingSession) {
+    let key = RoomKey::new(session.tenant_id, Room::Training(session.id));
+    for user_id in state.realtime.users_in_room(key) {
+        let result: AppResult<()> = async {
+            let mut tx = state.db.begin().await?;
+            let registered = EnrollmentRepository::find(&mut tx, session.id, user_id)
+                .await?
+                .is_some_and(|enrollment| enrollment.status.holds_seat());
+            if registered {
+                let now = OffsetDateTime::now_utc();
+                record_presence(&mut tx, session, user_id, AttendanceEventType::Join, ATTENDANCE_SOURCE, now).await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!("Recording the join of user {} in {} failed: {}", user_id, key.room, e);
+        }
+    }
+}
+
+/// Records attendance of registered participants in a live training room; best effort, as
+/// the subscription itself does not depend on it.
+async fn track_presence(
+    state: &AppState,
+    user: &AuthUser,
+    room: Room,
+    event_type: AttendanceEventType,
+    occurred_at: OffsetDateTime,
+) {
+    let Room::Training(session_id) = room else {
+        return;
+    };
+
+    let result: AppResult<()> = async {
+        let mut tx = rls::begin_for_user(&state.db, user).await?;
+        let session = TrainingRepository::find_by_id(&mut tx, session_id).await?;
+        let registered = EnrollmentRepository::find(&mut tx, session_id, user.user_id)
+            .await?
+            .is_some_and(|enrollment| enrollment.status.holds_seat());
+        if session.status != TrainingStatus::InProgress || !registered {
+            return Ok(());
+        }
+        record_presence(&mut tx, &session, user.user_id, event_type, ATTENDANCE_SOURCE, occurred_at).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        warn!("Recording {:?} of user {} in {} failed: {}", event_type, user.user_id, room, e);
+    }
+}
+
+/// Notes that a connection in live training rooms still answers heartbeats, so a join whose
+/// leave is never recorded stops counting there rather than at the end of the session.
+async fn still_present(state: &AppState, user: &AuthUser, connection_id: ConnectionId, seen_at: OffsetDateTime) {
+    let session_ids: Vec<Uuid> = state
+        .realtime
+        .rooms_of(connection_id)
+        .into_iter()
+        .filter_map(|key| match key.room {
+            Room::Training(session_id) => Some(session_id),
+            _ => None,
+        })
+        .collect();
+    if session_ids.is_empty() {
+        return;
+    }
+
+    let result: AppResult<()> = async {
+        let mut tx = state.db.begin().await?;
+        AttendanceRepository::mark_seen(&mut tx, user.user_id, &session_ids, seen_at).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        warn!("Recording the presence of user {} failed: {}", user.user_id, e);
+    }
+}
+
+/// Wall clock time of an instant in the past.
+fn seen_at(instant: Instant) -> OffsetDateTime {
+    OffsetDateTime::now_utc() - instant.elapsed()
+}
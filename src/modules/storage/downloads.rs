@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+
+use crate::app_state::AppState;
+use crate::core::storage::{content_disposition, is_tenant_key, PresignConditions, PresignMethod};
+use crate::core::utils::http_range::parse_range;
+use crate::db::repositories::{CertificateRepository, FileAccessRepository, SafetyReportRepository, TrainingRepository};
+use crate::db::rls;
+use crate::db::{FileAccessEntry, FileResourceType, UserRole};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::modules::training::handlers::can_manage;
+use crate::modules::training::materials::find_visible;
+
+/// How long a download link stays valid. Players request ranges again after seeking, so
+/// a link that expires mid-playback is fetched anew through the same endpoint.
+const DOWNLOAD_URL_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    /// Save the file instead of showing it in the browser.
+    #[serde(default)]
+    pub download: bool,
+}
+
+/// A file the caller may see, resolved from the resource it belongs to.
+struct Download {
+    resource_type: FileResourceType,
+    resource_id: Uuid,
+    object_key: String,
+    content_type: Option<String>,
+    file_name: String,
+}
+
+/// Redirects to a training material file.
+pub async fn material(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<DownloadQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let tenant_id = user.require_tenant()?;
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let material = find_visible(&mut tx, &user, id).await?;
+
+    let file_name = material.file_name.clone().unwrap_or_else(|| material.title.clone());
+    let download = Download {
+        resource_type: FileResourceType::TrainingMaterial,
+        resource_id: material.id,
+        object_key: material.file_s3_key,
+        content_type: material.content_type,
+        file_name,
+    };
+    redirect(&state, tx, &user, tenant_id, download, &query, &headers).await
+}
+
+/// Redirects to an attachment of a safety report, by its position in the report's list.
+pub async fn safety_report_attachment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, index)): Path<(Uuid, usize)>,
+    Query(query): Query<DownloadQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let tenant_id = user.require_tenant()?;
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let attachments = SafetyReportRepository::find_attachments_for_viewer(
+        &mut tx,
+        tenant_id,
+        user.user_id,
+        user.is_tenant_admin(),
+        user.has_role(&UserRole::OhsSpecialist),
+        id,
+    )
+    .await?
+    .ok_or_else(|| AppError::NotFound("Safety report not found".to_string()))?;
+
+    // Attachments may also be external URLs; only objects of the tenant are served from here
+    let key = attachments
+        .get(index)
+        .and_then(|attachment| attachment.as_str())
+        .filter(|key| is_tenant_key(tenant_id, key))
+        .ok_or_else(|| AppError::NotFound("Attachment not found".to_string()))?;
+
+    let download = Download {
+        resource_type: FileResourceType::SafetyReportAttachment,
+        resource_id: id,
+        object_key: key.to_string(),
+        content_type: None,
+        file_name: key.rsplit('/').next().unwrap_or(key).to_string(),
+    };
+    redirect(&state, tx, &user, tenant_id, download, &query, &headers).await
+}
+
+/// Redirects to a certificate PDF, for its holder or whoever manages the session.
+pub async fn certificate(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<DownloadQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let tenant_id = user.require_tenant()?;
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let certificate = CertificateRepository::find_by_id(&mut tx, tenant_id, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Certificate not found".to_string()))?;
+    if certificate.employee_user_id != user.user_id {
+        let session = TrainingRepository::find_by_id(&mut tx, certificate.training_session_id).await?;
+        if !can_manage(&user, &session) {
+            return Err(AppError::NotFound("Certificate not found".to_string()));
+        }
+    }
+
+    if certificate.is_revoked() {
+        return Err(AppError::Conflict("This certificate has been revoked".to_string()));
+    }
+    let key = certificate
+        .storage_key
+        .clone()
+        .ok_or_else(|| AppError::Conflict("The certificate is still being generated".to_string()))?;
+
+    let download = Download {
+        resource_type: FileResourceType::TrainingCertificate,
+        resource_id: certificate.id,
+        object_key: key,
+        content_type: Some("application/pdf".to_string()),
+        file_name: format!("certificate-{}.pdf", certificate.verification_code),
+    };
+    redirect(&state, tx, &user, tenant_id, download, &query, &headers).await
+}
+
+/// Logs the access and redirects to a short-lived presigned GET of the object.
+async fn redirect(
+    state: &AppState,
+    mut tx: Transaction<'_, Postgres>,
+    user: &AuthUser,
+    tenant_id: Uuid,
+    download: Download,
+    query: &DownloadQuery,
+    headers: &HeaderMap,
+) -> AppResult<Response> {
+    let range = headers.get(header::RANGE).and_then(|value| value.to_str().ok());
+    // A player seeking in a video asks for the rest of a file it is already playing
+    let continuation = range
+        .and_then(|range| parse_range(range, u64::MAX).ok().flatten())
+        .is_some_and(|range| *range.start() > 0);
+    if !continuation {
+        let entry = FileAccessEntry {
+            tenant_id,
+            user_id: user.user_id,
+            resource_type: download.resource_type,
+            resource_id: download.resource_id,
+            object_key: download.object_key.clone(),
+            byte_range: range.map(str::to_string),
+            user_agent: headers
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+        };
+        FileAccessRepository::record(&mut tx, &entry).await?;
+    }
+    tx.commit().await?;
+
+    let conditions = PresignConditions {
+        response_content_type: download.content_type,
+        response_content_disposition: Some(content_disposition(query.download, &download.file_name)),
+        ..Default::default()
+    };
+    let url = state
+        .storage
+        .presign(PresignMethod::Get, &download.object_key, DOWNLOAD_URL_TTL, &conditions)?;
+
+    Ok((
+        StatusCode::TEMPORARY_REDIRECT,
+        [(header::LOCATION, url.url), (header::CACHE_CONTROL, "no-store".to_string())],
+    )
+        .into_response())
+}
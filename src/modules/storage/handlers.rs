@@ -0,0 +1,149 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::app_state::AppState;
+use crate::core::storage::{PresignMethod, PresignedRequest};
+use crate::core::utils::http_range::{content_range, parse_range};
+use crate::error::{AppError, AppResult};
+
+#[derive(Debug, Deserialize)]
+pub struct PresignedQuery {
+    pub method: PresignMethod,
+    pub expires: i64,
+    pub signature: String,
+    /// `;` separated names of the headers signed into the URL.
+    pub headers: Option<String>,
+    #[serde(rename = "response-content-type")]
+    pub response_content_type: Option<String>,
+    #[serde(rename = "response-content-disposition")]
+    pub response_content_disposition: Option<String>,
+}
+
+impl PresignedQuery {
+    /// Response header overrides, in the order they are signed.
+    fn response_params(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        if let Some(disposition) = &self.response_content_disposition {
+            params.push(("response-content-disposition".to_string(), disposition.clone()));
+        }
+        if let Some(content_type) = &self.response_content_type {
+            params.push(("response-content-type".to_string(), content_type.clone()));
+        }
+        params
+    }
+}
+
+fn verify(
+    state: &AppState,
+    method: PresignMethod,
+    key: &str,
+    query: &PresignedQuery,
+    request_headers: &HeaderMap,
+) -> AppResult<()> {
+    if query.method != method {
+        return Err(AppError::Authorization("This link does not allow this request".to_string()));
+    }
+
+    let headers = query
+        .headers
+        .as_deref()
+        .unwrap_or_default()
+        .split(';')
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let value = request_headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default();
+            (name.to_ascii_lowercase(), value.to_string())
+        })
+        .collect();
+
+    state.storage.verify_presigned(&PresignedRequest {
+        method,
+        key,
+        expires: query.expires,
+        headers,
+        response_params: query.response_params(),
+        signature: &query.signature,
+    })?;
+    Ok(())
+}
+
+/// Serves an object, or the single byte range asked for, so video players can seek.
+pub async fn download_object(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(query): Query<PresignedQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    verify(&state, PresignMethod::Get, &key, &query, &headers)?;
+
+    let content_type = query
+        .response_content_type
+        .clone()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let mut response_headers = vec![
+        (header::CONTENT_TYPE, content_type),
+        (header::ACCEPT_RANGES, "bytes".to_string()),
+        (header::CACHE_CONTROL, "private, no-cache".to_string()),
+    ];
+    if let Some(disposition) = &query.response_content_disposition {
+        response_headers.push((header::CONTENT_DISPOSITION, disposition.clone()));
+    }
+
+    let range = match headers.get(header::RANGE).and_then(|value| value.to_str().ok()) {
+        Some(range) => {
+            let size = state.storage.head(&key).await?.size_bytes;
+            match parse_range(range, size) {
+                Ok(range) => range.map(|range| (range, size)),
+                Err(e) => {
+                    return Ok((
+                        StatusCode::RANGE_NOT_SATISFIABLE,
+                        [(header::CONTENT_RANGE, format!("bytes */{}", e.size))],
+                    )
+                        .into_response());
+                }
+            }
+        }
+        None => None,
+    };
+
+    let Some((range, size)) = range else {
+        let bytes = state.storage.get(&key).await?;
+        return Ok((StatusCode::OK, response_headers_map(response_headers), bytes).into_response());
+    };
+
+    let bytes = state.storage.get_range(&key, range.clone()).await?;
+    response_headers.push((header::CONTENT_RANGE, content_range(&range, size)));
+    Ok((StatusCode::PARTIAL_CONTENT, response_headers_map(response_headers), bytes).into_response())
+}
+
+fn response_headers_map(headers: Vec<(header::HeaderName, String)>) -> HeaderMap {
+    headers
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.parse().ok()?)))
+        .collect()
+}
+
+pub async fn upload_object(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(query): Query<PresignedQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<StatusCode> {
+    verify(&state, PresignMethod::Put, &key, &query, &headers)?;
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream");
+
+    state.storage.put(&key, content_type, body.to_vec()).await?;
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,47 @@
+use crate::app_state::AppState;
+use crate::core::scanner::ScanVerdict;
+use crate::core::utils::exif::strip_gps;
+use crate::core::utils::file_type::{sniff_content_type, SNIFF_BYTES};
+use crate::core::utils::material::check_content;
+use crate::error::{AppError, AppResult};
+
+/// Outcome of checking an uploaded object before it is made visible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inspection {
+    Clean,
+    /// The malware scan matched the signature; the object must not be published.
+    Quarantined(String),
+}
+
+/// Runs an uploaded object through the checks every upload passes before it becomes visible:
+/// its content must match the declared type, it is scanned for malware, and images lose
+/// their GPS location.
+///
+/// Objects whose content does not match are deleted. `content_type` is the declared type,
+/// already checked against the allowlist of the upload's category.
+pub(crate) async fn inspect_upload(
+    state: &AppState,
+    key: &str,
+    content_type: &str,
+    size_bytes: u64,
+) -> AppResult<Inspection> {
+    let head = state.storage.get_range(key, 0..=size_bytes.min(SNIFF_BYTES) - 1).await?;
+    if let Err(e) = check_content(content_type, sniff_content_type(&head)) {
+        state.storage.delete(key).await?;
+        return Err(AppError::Validation(e.to_string()));
+    }
+
+    if let ScanVerdict::Infected(signature) = state.scanner.scan_object(&state.storage, key, size_bytes).await? {
+        tracing::warn!("Quarantined upload {}: {}", key, signature);
+        return Ok(Inspection::Quarantined(signature));
+    }
+
+    if content_type.starts_with("image/") {
+        let mut image = state.storage.get(key).await?;
+        if strip_gps(content_type, &mut image) {
+            state.storage.put(key, content_type, image).await?;
+        }
+    }
+
+    Ok(Inspection::Clean)
+}
@@ -0,0 +1,31 @@
+pub mod downloads;
+pub mod handlers;
+pub mod inspection;
+pub mod streaming;
+
+use axum::{extract::DefaultBodyLimit, routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::core::utils::material::MAX_MATERIAL_BYTES;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        // Serves presigned URLs of the local storage backend; S3 URLs go to the bucket directly
+        .route(
+            "/storage/{*key}",
+            get(handlers::download_object)
+                .put(handlers::upload_object)
+                .layer(DefaultBodyLimit::max(MAX_MATERIAL_BYTES as usize)),
+        )
+        // Authorized downloads, redirecting to short-lived presigned URLs
+        .route("/files/materials/{id}", get(downloads::material))
+        .route(
+            "/files/safety-reports/{id}/attachments/{index}",
+            get(downloads::safety_report_attachment),
+        )
+        .route("/files/certificates/{id}", get(downloads::certificate))
+        .route("/files/materials/{id}/playback", get(streaming::material_playback))
+        // HLS playlists, authorized by the signature of a playback link
+        .route("/hls/materials/{id}/master.m3u8", get(streaming::master_playlist))
+        .route("/hls/materials/{id}/{rendition}/index.m3u8", get(streaming::media_playlist))
+}
@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Json,
+};
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+
+use crate::app_state::AppState;
+use crate::core::storage::{PresignConditions, PresignMethod};
+use crate::core::utils::hls::{
+    playback_ttl, rewrite_uris, sign_playback, verify_playback, MASTER_PLAYLIST, MEDIA_PLAYLIST,
+};
+use crate::db::repositories::{FileAccessRepository, MaterialRepository};
+use crate::db::rls;
+use crate::db::{FileAccessEntry, FileResourceType, MaterialPlayback, MaterialProcessingStatus, TrainingMaterial};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::modules::training::materials::find_visible;
+
+const PLAYLIST_CONTENT_TYPE: &str = "application/vnd.apple.mpegurl";
+const SEGMENT_CONTENT_TYPE: &str = "video/mp2t";
+
+/// Signature of a playback link, carried by every playlist URL of the video.
+#[derive(Debug, Deserialize)]
+pub struct PlaybackQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+impl PlaybackQuery {
+    fn query_string(&self) -> String {
+        format!("expires={}&signature={}", self.expires, self.signature)
+    }
+}
+
+fn playback_secret(state: &AppState) -> &[u8] {
+    state.env.auth.jwt_secret.expose_secret().as_bytes()
+}
+
+/// Links to stream a processed training video. Players cannot send bearer tokens, so the
+/// playlists are authorized by a signature in their URL, and segments are presigned.
+pub async fn material_playback(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> AppResult<Json<MaterialPlayback>> {
+    let tenant_id = user.require_tenant()?;
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let material = find_visible(&mut tx, &user, id).await?;
+
+    match material.processing_status {
+        Some(MaterialProcessingStatus::Ready) => {}
+        Some(MaterialProcessingStatus::Pending | MaterialProcessingStatus::Processing) => {
+            return Err(AppError::Conflict("The video is still being processed".to_string()));
+        }
+        Some(MaterialProcessingStatus::Failed) => {
+            return Err(AppError::Conflict("The video could not be processed".to_string()));
+        }
+        None => return Err(AppError::Conflict("Only videos can be streamed".to_string())),
+    }
+
+    let entry = FileAccessEntry {
+        tenant_id,
+        user_id: user.user_id,
+        resource_type: FileResourceType::TrainingMaterial,
+        resource_id: material.id,
+        object_key: material.rendition_key(MASTER_PLAYLIST),
+        byte_range: None,
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+    };
+    FileAccessRepository::record(&mut tx, &entry).await?;
+    tx.commit().await?;
+
+    let ttl = playback_ttl(material.duration_seconds.unwrap_or_default());
+    let expires_at = OffsetDateTime::now_utc() + ttl;
+    let expires = expires_at.unix_timestamp();
+    let signature = sign_playback(playback_secret(&state), &material.id.to_string(), expires);
+
+    let poster_url = match &material.poster_key {
+        Some(key) => {
+            let conditions = PresignConditions {
+                response_content_type: Some("image/jpeg".to_string()),
+                ..Default::default()
+            };
+            Some(state.storage.presign(PresignMethod::Get, key, ttl, &conditions)?.url)
+        }
+        None => None,
+    };
+
+    Ok(Json(MaterialPlayback {
+        master_playlist_url: format!(
+            "{}/api/hls/materials/{}/{}?expires={}&signature={}",
+            state.env.app.public_url, material.id, MASTER_PLAYLIST, expires, signature
+        ),
+        poster_url,
+        duration_seconds: material.duration_seconds,
+        renditions: material.hls_renditions.unwrap_or_default(),
+        expires_at,
+    }))
+}
+
+/// Loads a processed video for a playlist request with a valid playback signature.
+async fn playable(state: &AppState, id: Uuid, query: &PlaybackQuery) -> AppResult<TrainingMaterial> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    if !verify_playback(playback_secret(state), &id.to_string(), query.expires, &query.signature, now) {
+        return Err(AppError::Authorization("Invalid or expired playback link".to_string()));
+    }
+
+    MaterialRepository::find_for_playback(&state.db, id)
+        .await?
+        .filter(|material| material.processing_status == Some(MaterialProcessingStatus::Ready))
+        .ok_or_else(|| AppError::NotFound("Video not found".to_string()))
+}
+
+fn playlist_response(playlist: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, PLAYLIST_CONTENT_TYPE),
+            // The URLs inside carry signatures
+            (header::CACHE_CONTROL, "private, no-store"),
+        ],
+        playlist,
+    )
+        .into_response()
+}
+
+/// Master playlist of a video, with the signature passed on to the media playlists.
+pub async fn master_playlist(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<PlaybackQuery>,
+) -> AppResult<Response> {
+    let material = playable(&state, id, &query).await?;
+    let playlist = state.storage.get(&material.rendition_key(MASTER_PLAYLIST)).await?;
+
+    let playlist = rewrite_uris(&String::from_utf8_lossy(&playlist), |uri| {
+        format!("{}?{}", uri, query.query_string())
+    });
+    Ok(playlist_response(playlist))
+}
+
+/// Media playlist of one rendition, pointing at presigned segments that expire with the link.
+pub async fn media_playlist(
+    State(state): State<AppState>,
+    Path((id, rendition)): Path<(Uuid, String)>,
+    Query(query): Query<PlaybackQuery>,
+) -> AppResult<Response> {
+    let material = playable(&state, id, &query).await?;
+    if !material.hls_renditions.as_ref().is_some_and(|renditions| renditions.contains(&rendition)) {
+        return Err(AppError::NotFound("Rendition not found".to_string()));
+    }
+    let playlist = state
+        .storage
+        .get(&material.rendition_key(&format!("{}/{}", rendition, MEDIA_PLAYLIST)))
+        .await?;
+
+    let remaining = query.expires - OffsetDateTime::now_utc().unix_timestamp();
+    let expires_in = Duration::from_secs(remaining.max(1) as u64);
+    let conditions = PresignConditions {
+        response_content_type: Some(SEGMENT_CONTENT_TYPE.to_string()),
+        ..Default::default()
+    };
+    let mut failure = None;
+    let playlist = rewrite_uris(&String::from_utf8_lossy(&playlist), |segment| {
+        let key = material.rendition_key(&format!("{}/{}", rendition, segment));
+        match state.storage.presign(PresignMethod::Get, &key, expires_in, &conditions) {
+            Ok(url) => url.url,
+            Err(e) => {
+                failure.get_or_insert(e);
+                String::new()
+            }
+        }
+    });
+    if let Some(e) = failure {
+        return Err(e.into());
+    }
+
+    Ok(playlist_response(playlist))
+}
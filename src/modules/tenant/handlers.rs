@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use axum::Json;
+use sqlx::types::Uuid;
+
+use crate::app_state::AppState;
+use crate::core::utils::time_zone::Zone;
+use crate::db::repositories::TimeZoneRepository;
+use crate::db::{rls, UpdateCompanyTimeZone, UserRole};
+use crate::error::AppResult;
+use crate::middleware::auth::AuthUser;
+
+pub async fn update_company_time_zone(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(company_id): Path<Uuid>,
+    Json(payload): Json<UpdateCompanyTimeZone>,
+) -> AppResult<StatusCode> {
+    user.require_any_role(&[UserRole::TenantAdmin])?;
+    let tenant_id = user.require_tenant()?;
+    let zone = Zone::parse(&payload.time_zone)?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    TimeZoneRepository::set_for_company(&mut tx, tenant_id, company_id, zone.name()).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
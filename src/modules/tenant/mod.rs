@@ -0,0 +1,9 @@
+pub mod handlers;
+
+use axum::{routing::put, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/companies/{id}/time-zone", put(handlers::update_company_time_zone))
+}
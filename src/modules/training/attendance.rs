@@ -0,0 +1,229 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::attendance::{attended_duration, meets_threshold, Presence};
+use crate::db::repositories::{
+    AttendanceOutcome, AttendanceRepository, CertificateRepository, EnrollmentRepository, ProgressRepository,
+    QuizAttemptRepository,
+};
+use crate::db::rls;
+use crate::db::{
+    AttendanceEventType, AttendanceOverride, AttendanceRecord, ParticipantStatus, TrainingEnrollment,
+    TrainingSession, TrainingStatus,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+use super::certificates::issue_certificate;
+use super::handlers::find_managed;
+
+/// Minutes a participant attended so far. Open connections count until `until`, but no longer
+/// than the participant was last seen in the room.
+async fn attended_minutes(
+    tx: &mut Transaction<'_, Postgres>,
+    session: &TrainingSession,
+    enrollment: &TrainingEnrollment,
+    until: OffsetDateTime,
+) -> AppResult<i32> {
+    let until = enrollment.presence_seen_at.map_or(until, |seen_at| seen_at.min(until));
+    let events: Vec<(OffsetDateTime, Presence)> = AttendanceRepository::list_events(tx, enrollment.id)
+        .await?
+        .into_iter()
+        .map(|event| (event.occurred_at, event.event_type.into()))
+        .collect();
+
+    let attended = attended_duration(&events, session.start_time, session.end_time, until);
+    Ok(attended.whole_minutes() as i32)
+}
+
+/// Status an enrollment ends up in: without a quiz, or once it was passed, attending completes
+/// the training; otherwise completion waits for a passing attempt.
+fn outcome(
+    session: &TrainingSession,
+    enrollment: &TrainingEnrollment,
+    attended_minutes: i32,
+    attended: bool,
+    quiz_pending: bool,
+) -> AttendanceOutcome {
+    let (status, completion_date) = if !attended {
+        (ParticipantStatus::NoShow, None)
+    } else if enrollment.status == ParticipantStatus::Completed {
+        (ParticipantStatus::Completed, enrollment.completion_date)
+    } else if quiz_pending {
+        (ParticipantStatus::Attended, None)
+    } else {
+        let completed_at = session.end_time.min(OffsetDateTime::now_utc());
+        (ParticipantStatus::Completed, Some(completed_at))
+    };
+
+    AttendanceOutcome {
+        attended_minutes,
+        attended,
+        status,
+        completion_date,
+    }
+}
+
+/// Records a join or leave of a registered participant while the session is live, as seen by
+/// the realtime gateway at `occurred_at`.
+pub(crate) async fn record_presence(
+    tx: &mut Transaction<'_, Postgres>,
+    session: &TrainingSession,
+    user_id: Uuid,
+    event_type: AttendanceEventType,
+    source: &str,
+    occurred_at: OffsetDateTime,
+) -> AppResult<TrainingEnrollment> {
+    if session.status != TrainingStatus::InProgress {
+        return Err(AppError::Conflict("Training session is not live".to_string()));
+    }
+
+    let enrollment = EnrollmentRepository::find(tx, session.id, user_id)
+        .await?
+        .filter(|enrollment| enrollment.status.holds_seat())
+        .ok_or_else(|| AppError::Authorization("You are not registered for this training session".to_string()))?;
+
+    AttendanceRepository::record_event(tx, &enrollment, event_type, source, occurred_at).await?;
+    AttendanceRepository::mark_seen(tx, user_id, &[session.id], occurred_at).await?;
+    let enrollment = TrainingEnrollment {
+        presence_seen_at: enrollment.presence_seen_at.max(Some(occurred_at)),
+        ..enrollment
+    };
+
+    let minutes = attended_minutes(tx, session, &enrollment, OffsetDateTime::now_utc()).await?;
+    AttendanceRepository::update_minutes(tx, enrollment.id, minutes).await?;
+
+    Ok(TrainingEnrollment {
+        attended_minutes: Some(minutes),
+        ..enrollment
+    })
+}
+
+/// Decides attendance for every participant once a session is over; manual overrides are kept.
+pub(crate) async fn finalize_attendance(
+    tx: &mut Transaction<'_, Postgres>,
+    session: &TrainingSession,
+) -> AppResult<()> {
+    let ended_at = session.end_time.min(OffsetDateTime::now_utc());
+    let has_quiz = AttendanceRepository::session_has_quiz(tx, session.id).await?;
+
+    for enrollment in EnrollmentRepository::list_for_session(tx, session.id).await? {
+        if !enrollment.status.holds_seat() || enrollment.attendance_overridden_at.is_some() {
+            continue;
+        }
+
+        let minutes = attended_minutes(tx, session, &enrollment, ended_at).await?;
+        let quiz_pending = has_quiz && !QuizAttemptRepository::has_passed(tx, enrollment.id).await?;
+        // Self-paced sessions are attended by working through their materials
+        let attended = if session.training_type.is_self_paced() {
+            ProgressRepository::completed_all(tx, &enrollment).await?
+        } else {
+            meets_threshold(
+                time::Duration::minutes(i64::from(minutes)),
+                ended_at - session.start_time,
+                session.min_attendance_percent,
+            )
+        };
+
+        let decided = AttendanceRepository::apply_outcome(
+            tx,
+            enrollment.id,
+            outcome(session, &enrollment, minutes, attended, quiz_pending),
+        )
+        .await?;
+        if let Some(decided) = decided
+            && decided.status == ParticipantStatus::Completed
+        {
+            issue_certificate(tx, decided.id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn list_attendance(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<AttendanceRecord>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = find_managed(&mut tx, &user, id).await?;
+    let now = OffsetDateTime::now_utc();
+
+    let mut records = Vec::new();
+    for enrollment in EnrollmentRepository::list_for_session(&mut tx, id).await? {
+        if !enrollment.status.holds_seat() {
+            continue;
+        }
+
+        // Live sessions show the running total; decided ones what was stored
+        let mut record = AttendanceRecord::from(enrollment.clone());
+        if session.status == TrainingStatus::InProgress && !record.overridden {
+            record.attended_minutes = attended_minutes(&mut tx, &session, &enrollment, now).await?;
+        }
+        records.push(record);
+    }
+    tx.commit().await?;
+
+    Ok(Json(records))
+}
+
+pub async fn override_attendance(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, employee_user_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<AttendanceOverride>,
+) -> AppResult<Json<AttendanceRecord>> {
+    payload.validate()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = find_managed(&mut tx, &user, id).await?;
+    if !matches!(session.status, TrainingStatus::InProgress | TrainingStatus::Completed) {
+        return Err(AppError::Conflict(
+            "Attendance can only be set once the session has started".to_string(),
+        ));
+    }
+
+    let enrollment = EnrollmentRepository::find(&mut tx, id, employee_user_id)
+        .await?
+        .filter(|enrollment| enrollment.status.holds_seat())
+        .ok_or_else(|| AppError::NotFound("Enrollment not found".to_string()))?;
+
+    let minutes = attended_minutes(&mut tx, &session, &enrollment, OffsetDateTime::now_utc()).await?;
+    let quiz_pending = AttendanceRepository::session_has_quiz(&mut tx, id).await?
+        && !QuizAttemptRepository::has_passed(&mut tx, enrollment.id).await?;
+    let decided = outcome(&session, &enrollment, minutes, payload.attended, quiz_pending);
+
+    // A valid certificate must not outlive the completion it proves
+    if decided.status != ParticipantStatus::Completed
+        && CertificateRepository::find_for_participant(&mut tx, id, employee_user_id)
+            .await?
+            .is_some_and(|certificate| !certificate.is_revoked())
+    {
+        return Err(AppError::Conflict(
+            "This participant holds a certificate for the training; revoke it first".to_string(),
+        ));
+    }
+
+    let enrollment = AttendanceRepository::override_outcome(
+        &mut tx,
+        enrollment.id,
+        decided,
+        user.user_id,
+        payload.note.as_deref(),
+    )
+    .await?;
+    if enrollment.status == ParticipantStatus::Completed {
+        issue_certificate(&mut tx, enrollment.id).await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(AttendanceRecord::from(enrollment)))
+}
@@ -0,0 +1,256 @@
+use std::time::Duration as StdDuration;
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::macros::format_description;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error, warn};
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::certificate::{
+    generate_verification_code, render_certificate, CertificateBranding, CertificateContent,
+};
+use crate::db::repositories::{CertificateRepository, TrainingRepository};
+use crate::db::rls;
+use crate::db::{
+    certificate_verification_url, CertificateIssueResult, RevokeCertificate, TrainingCertificate,
+    TrainingCertificateResponse,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::modules::notification::reminders::zone_for_user;
+
+use super::handlers::{can_view, find_managed};
+
+/// How often the worker looks for certificates to render.
+const CERTIFICATE_POLL_INTERVAL: StdDuration = StdDuration::from_secs(10);
+
+/// Certificates claimed at once by the worker.
+const CERTIFICATE_BATCH_SIZE: i64 = 10;
+
+/// A claim older than this belongs to a worker that died; the certificate is taken over.
+const STALE_CLAIM: StdDuration = StdDuration::from_secs(10 * 60);
+
+/// Issues the certificate of a completed enrollment; it is rendered by the background worker.
+/// Returns None if the enrollment is not completed or already has a certificate.
+pub(crate) async fn issue_certificate(
+    tx: &mut Transaction<'_, Postgres>,
+    enrollment_id: Uuid,
+) -> AppResult<Option<TrainingCertificate>> {
+    let certificate = CertificateRepository::issue(tx, enrollment_id, &generate_verification_code()).await?;
+    Ok(certificate)
+}
+
+async fn certificate_content(state: &AppState, certificate: &TrainingCertificate) -> AppResult<CertificateContent> {
+    // The completion date is the employee's calendar day
+    let zone = zone_for_user(&state.db, certificate.employee_user_id).await?;
+    let completed_on = zone
+        .localize(certificate.completed_at)
+        .format(format_description!("[day] [month repr:long] [year]"))
+        .unwrap_or_else(|_| certificate.completed_at.date().to_string());
+
+    Ok(CertificateContent {
+        issuer: certificate.tenant_name.clone(),
+        company_name: certificate.company_name.clone(),
+        employee_name: certificate.employee_name.clone(),
+        training_title: certificate.training_title.clone(),
+        completed_on,
+        host_name: certificate.host_name.clone(),
+        verification_code: certificate.verification_code.clone(),
+        verification_url: certificate_verification_url(&state.env.app.public_url, &certificate.verification_code),
+    })
+}
+
+async fn render_and_store(state: &AppState, certificate: &TrainingCertificate) -> AppResult<String> {
+    let mut tx = state.db.begin().await?;
+    let branding = CertificateRepository::tenant_branding(&mut tx, certificate.tenant_id)
+        .await?
+        .map(|settings| CertificateBranding::from_settings(&settings))
+        .unwrap_or_default();
+    tx.commit().await?;
+    let content = certificate_content(state, certificate).await?;
+
+    let pdf = tokio::task::spawn_blocking(move || render_certificate(&content, &branding))
+        .await
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+    let key = certificate.object_key();
+    state.storage.put(&key, "application/pdf", pdf).await?;
+    Ok(key)
+}
+
+/// Renders and stores every certificate that is still pending, returning how many were stored.
+///
+/// Certificates are claimed in a short transaction and each is marked in its own, so rendering
+/// and uploading hold no locks. Failures are recorded on the certificate and retried on later
+/// runs, up to a limit.
+pub async fn render_pending_certificates(state: &AppState) -> AppResult<usize> {
+    let mut rendered = 0;
+
+    loop {
+        let mut tx = state.db.begin().await?;
+        let pending =
+            CertificateRepository::claim_pending(&mut tx, CERTIFICATE_BATCH_SIZE, STALE_CLAIM.as_secs() as i64).await?;
+        tx.commit().await?;
+        let claimed = pending.len();
+
+        for certificate in pending {
+            let result = render_and_store(state, &certificate).await;
+            let mut tx = state.db.begin().await?;
+            match result {
+                Ok(key) => {
+                    CertificateRepository::mark_rendered(&mut tx, &certificate, &key).await?;
+                    rendered += 1;
+                }
+                Err(e) => {
+                    warn!("Rendering certificate {} failed: {}", certificate.id, e);
+                    CertificateRepository::mark_failed(&mut tx, certificate.id, &e.to_string()).await?;
+                }
+            }
+            tx.commit().await?;
+        }
+
+        if (claimed as i64) < CERTIFICATE_BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// Starts the background task that renders issued certificates.
+pub fn spawn_certificate_worker(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CERTIFICATE_POLL_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            match render_pending_certificates(&state).await {
+                Ok(0) => {}
+                Ok(rendered) => debug!("Rendered {} certificates", rendered),
+                Err(e) => error!("Certificate run failed: {}", e),
+            }
+        }
+    })
+}
+
+fn pdf_response(certificate: &TrainingCertificate, pdf: Vec<u8>) -> Response {
+    let disposition = format!("attachment; filename=\"certificate-{}.pdf\"", certificate.verification_code);
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CACHE_CONTROL, "private, no-cache".to_string()),
+        ],
+        pdf,
+    )
+        .into_response()
+}
+
+/// Downloads the caller's certificate for a training session.
+pub async fn download_own_certificate(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Response> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_by_id(&mut tx, id).await?;
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+
+    let certificate = CertificateRepository::find_for_participant(&mut tx, id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No certificate has been issued for this training".to_string()))?;
+    tx.commit().await?;
+
+    if certificate.is_revoked() {
+        return Err(AppError::Conflict("This certificate has been revoked".to_string()));
+    }
+    let key = certificate
+        .storage_key
+        .as_deref()
+        .ok_or_else(|| AppError::Conflict("The certificate is still being generated".to_string()))?;
+    let pdf = state.storage.get(key).await?;
+
+    Ok(pdf_response(&certificate, pdf))
+}
+
+/// Lists the certificates issued for a session, for the host or a tenant admin.
+pub async fn list_certificates(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<TrainingCertificateResponse>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_managed(&mut tx, &user, id).await?;
+    let certificates = CertificateRepository::list_for_session(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(Json(
+        certificates
+            .into_iter()
+            .map(|certificate| TrainingCertificateResponse::new(certificate, &state.env.app.public_url))
+            .collect(),
+    ))
+}
+
+/// Issues certificates for completed enrollments that do not have one yet, e.g. for
+/// completions recorded before certificates existed.
+pub async fn issue_certificates(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<CertificateIssueResult>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_managed(&mut tx, &user, id).await?;
+
+    let mut issued = 0;
+    for enrollment_id in CertificateRepository::list_uncertified_enrollment_ids(&mut tx, id).await? {
+        if issue_certificate(&mut tx, enrollment_id).await?.is_some() {
+            issued += 1;
+        }
+    }
+    let already_issued = CertificateRepository::list_for_session(&mut tx, id).await?.len() - issued;
+    tx.commit().await?;
+
+    Ok(Json(CertificateIssueResult { issued, already_issued }))
+}
+
+/// Revokes a participant's certificate. The code keeps verifying, as revoked.
+pub async fn revoke_certificate(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, employee_user_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RevokeCertificate>,
+) -> AppResult<Json<TrainingCertificateResponse>> {
+    payload.validate()?;
+    if payload.reason.trim().is_empty() {
+        return Err(AppError::Validation("A reason is required to revoke a certificate".to_string()));
+    }
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_managed(&mut tx, &user, id).await?;
+
+    let certificate = CertificateRepository::find_for_participant(&mut tx, id, employee_user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Certificate not found".to_string()))?;
+    let certificate = CertificateRepository::revoke(&mut tx, certificate.id, user.user_id, payload.reason.trim())
+        .await?
+        .ok_or_else(|| AppError::Conflict("The certificate is already revoked".to_string()))?;
+    tx.commit().await?;
+
+    Ok(Json(TrainingCertificateResponse::new(certificate, &state.env.app.public_url)))
+}
@@ -0,0 +1,334 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::waitlist::{self, must_wait, promotion_order, QueuedEnrollment};
+use crate::db::repositories::{EnrollmentRepository, TrainingRepository};
+use crate::db::rls;
+use crate::db::{
+    BulkEnrollment, BulkEnrollmentResult, Notification, NotificationType, ParticipantStatus,
+    TrainingEnrollment, TrainingEnrollmentResponse, TrainingSession, TrainingStatus, UserRole,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::modules::notification::reminders;
+
+use super::handlers::{can_view, find_managed};
+use super::notices;
+
+/// Fails unless new participants may still join the session.
+fn ensure_open(session: &TrainingSession) -> AppResult<()> {
+    if !session.is_published() || session.status != TrainingStatus::Scheduled {
+        return Err(AppError::Conflict("Training session is not open for enrollment".to_string()));
+    }
+    if session.start_time <= OffsetDateTime::now_utc() {
+        return Err(AppError::Conflict("Training session has already started".to_string()));
+    }
+    Ok(())
+}
+
+/// Seats still free in a session; None when it has no participant limit.
+async fn open_seats(tx: &mut Transaction<'_, Postgres>, session: &TrainingSession) -> AppResult<Option<i64>> {
+    if session.max_participants.is_none() {
+        return Ok(None);
+    }
+    let seats_taken = EnrollmentRepository::count_seats(tx, session.id).await?;
+    Ok(waitlist::open_seats(session.max_participants, seats_taken))
+}
+
+async fn respond(
+    tx: &mut Transaction<'_, Postgres>,
+    enrollment: TrainingEnrollment,
+) -> AppResult<TrainingEnrollmentResponse> {
+    let position = match enrollment.status {
+        ParticipantStatus::Waitlisted => Some(EnrollmentRepository::waitlist_position(tx, &enrollment).await?),
+        _ => None,
+    };
+    Ok(TrainingEnrollmentResponse::new(enrollment, position))
+}
+
+/// Promotes waitlisted enrollments onto free seats, returning the notices for the promoted employees.
+///
+/// Callers must hold the session row lock so concurrent enrollments cannot take the same seat.
+pub(crate) async fn fill_open_seats(
+    tx: &mut Transaction<'_, Postgres>,
+    state: &AppState,
+    session: &TrainingSession,
+) -> AppResult<Vec<Notification>> {
+    if !session.is_published() || session.status != TrainingStatus::Scheduled {
+        return Ok(Vec::new());
+    }
+
+    let seats = open_seats(tx, session).await?;
+    if must_wait(seats) {
+        return Ok(Vec::new());
+    }
+
+    let queue: Vec<QueuedEnrollment> = EnrollmentRepository::list_waitlisted(tx, session.id)
+        .await?
+        .iter()
+        .map(|enrollment| QueuedEnrollment {
+            id: enrollment.id,
+            enrolled_at: enrollment.enrolled_at,
+        })
+        .collect();
+    let promoted = EnrollmentRepository::promote(tx, &promotion_order(&queue, seats)).await?;
+    let recipients: Vec<Uuid> = promoted.iter().map(|enrollment| enrollment.employee_user_id).collect();
+
+    notices::notify_participants(
+        tx,
+        state,
+        session,
+        &recipients,
+        NotificationType::TrainingRegistration,
+        "You're off the waitlist",
+        |title, when| format!("A seat opened up: you are now enrolled in {} on {}.", title, when),
+    )
+    .await
+}
+
+/// Withdraws an enrollment and hands its seat to the next person on the waitlist.
+async fn withdraw(
+    tx: &mut Transaction<'_, Postgres>,
+    state: &AppState,
+    session: &TrainingSession,
+    enrollment: TrainingEnrollment,
+) -> AppResult<Vec<Notification>> {
+    // Once the session runs, ended or was called off, seats are no longer given up or handed on
+    if session.status != TrainingStatus::Scheduled {
+        return Err(AppError::Conflict("Training session is no longer open for changes".to_string()));
+    }
+    if !matches!(enrollment.status, ParticipantStatus::Registered | ParticipantStatus::Waitlisted) {
+        return Err(AppError::Conflict("Enrollment can no longer be withdrawn".to_string()));
+    }
+
+    EnrollmentRepository::set_status(tx, enrollment.id, ParticipantStatus::Withdrawn).await?;
+
+    let notifications = if enrollment.status.holds_seat() {
+        fill_open_seats(tx, state, session).await?
+    } else {
+        Vec::new()
+    };
+    reminders::sync_training_reminders(tx, state, session).await?;
+
+    Ok(notifications)
+}
+
+pub async fn get_own_enrollment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<TrainingEnrollmentResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let enrollment = EnrollmentRepository::find(&mut tx, id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("You are not enrolled in this training session".to_string()))?;
+    let response = respond(&mut tx, enrollment).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+pub async fn enroll(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<(StatusCode, Json<TrainingEnrollmentResponse>)> {
+    user.require_any_role(&[UserRole::Employee])?;
+    let tenant_id = user.require_tenant()?;
+    let company_id = user
+        .company_id
+        .ok_or_else(|| AppError::Validation("Only employees of a company can enroll".to_string()))?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_for_update(&mut tx, id).await?;
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+    ensure_open(&session)?;
+
+    let status = if must_wait(open_seats(&mut tx, &session).await?) {
+        ParticipantStatus::Waitlisted
+    } else {
+        ParticipantStatus::Registered
+    };
+
+    let enrollment =
+        EnrollmentRepository::enroll(&mut tx, tenant_id, session.id, user.user_id, company_id, status)
+            .await?
+            .ok_or_else(|| AppError::Conflict("You are already enrolled in this training session".to_string()))?;
+    reminders::sync_training_reminders(&mut tx, &state, &session).await?;
+    let response = respond(&mut tx, enrollment).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn withdraw_own_enrollment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_for_update(&mut tx, id).await?;
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+    let enrollment = EnrollmentRepository::find(&mut tx, session.id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("You are not enrolled in this training session".to_string()))?;
+
+    let notifications = withdraw(&mut tx, &state, &session, enrollment).await?;
+    tx.commit().await?;
+
+    state.notifier.dispatch_all(&state.db, &notifications).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_enrollments(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<TrainingEnrollmentResponse>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_managed(&mut tx, &user, id).await?;
+    let enrollments = EnrollmentRepository::list_for_session(&mut tx, id).await?;
+    tx.commit().await?;
+
+    // The listing is already in queue order, so positions can be counted off directly
+    let mut position = 0;
+    let responses = enrollments
+        .into_iter()
+        .map(|enrollment| {
+            let waitlist_position = (enrollment.status == ParticipantStatus::Waitlisted).then(|| {
+                position += 1;
+                position
+            });
+            TrainingEnrollmentResponse::new(enrollment, waitlist_position)
+        })
+        .collect();
+
+    Ok(Json(responses))
+}
+
+pub async fn remove_enrollment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, employee_user_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<StatusCode> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = find_managed(&mut tx, &user, id).await?;
+    let enrollment = EnrollmentRepository::find(&mut tx, session.id, employee_user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Enrollment not found".to_string()))?;
+
+    let notifications = withdraw(&mut tx, &state, &session, enrollment).await?;
+    tx.commit().await?;
+
+    state.notifier.dispatch_all(&state.db, &notifications).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn bulk_enroll(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<BulkEnrollment>,
+) -> AppResult<Json<BulkEnrollmentResult>> {
+    payload.validate()?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = find_managed(&mut tx, &user, id).await?;
+    ensure_open(&session)?;
+
+    if !EnrollmentRepository::company_in_tenant(&mut tx, tenant_id, payload.company_id).await? {
+        return Err(AppError::NotFound("Company not found".to_string()));
+    }
+
+    let employees = EnrollmentRepository::list_company_employee_ids(
+        &mut tx,
+        tenant_id,
+        payload.company_id,
+        payload.department.as_deref(),
+    )
+    .await?;
+
+    let mut seats_left = open_seats(&mut tx, &session).await?;
+    let mut result = BulkEnrollmentResult::default();
+    let mut registered = Vec::new();
+    let mut waitlisted = Vec::new();
+
+    for employee_user_id in employees {
+        let status = if must_wait(seats_left) {
+            ParticipantStatus::Waitlisted
+        } else {
+            ParticipantStatus::Registered
+        };
+
+        let enrolled = EnrollmentRepository::enroll(
+            &mut tx,
+            tenant_id,
+            session.id,
+            employee_user_id,
+            payload.company_id,
+            status,
+        )
+        .await?;
+
+        match (enrolled, status) {
+            (None, _) => result.already_enrolled += 1,
+            (Some(_), ParticipantStatus::Registered) => {
+                seats_left = seats_left.map(|seats| seats - 1);
+                registered.push(employee_user_id);
+            }
+            (Some(_), _) => waitlisted.push(employee_user_id),
+        }
+    }
+
+    result.registered = registered.len();
+    result.waitlisted = waitlisted.len();
+
+    let mut notifications = notices::notify_participants(
+        &mut tx,
+        &state,
+        &session,
+        &registered,
+        NotificationType::TrainingRegistration,
+        "Training enrollment",
+        |title, when| format!("You have been enrolled in {} on {}.", title, when),
+    )
+    .await?;
+    notifications.extend(
+        notices::notify_participants(
+            &mut tx,
+            &state,
+            &session,
+            &waitlisted,
+            NotificationType::TrainingRegistration,
+            "Training waitlist",
+            |title, when| {
+                format!(
+                    "{} on {} is full; you are on the waitlist and will be notified if a seat opens up.",
+                    title, when
+                )
+            },
+        )
+        .await?,
+    );
+    reminders::sync_training_reminders(&mut tx, &state, &session).await?;
+    tx.commit().await?;
+
+    state.notifier.dispatch_all(&state.db, &notifications).await;
+
+    Ok(Json(result))
+}
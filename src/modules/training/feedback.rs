@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::types::Uuid;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::db::repositories::{EnrollmentRepository, FeedbackRepository, TrainingRepository};
+use crate::db::rls;
+use crate::db::{ParticipantStatus, SessionFeedback, SubmitTrainingFeedback, TrainingFeedback};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+use super::handlers::{can_manage, can_view};
+
+/// Rates a session; opens once the caller's attendance is confirmed and can be given once.
+pub async fn submit_feedback(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SubmitTrainingFeedback>,
+) -> AppResult<(StatusCode, Json<TrainingFeedback>)> {
+    payload.validate()?;
+    let comment = payload
+        .comment
+        .as_deref()
+        .map(str::trim)
+        .filter(|comment| !comment.is_empty());
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_by_id(&mut tx, id).await?;
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+
+    let enrollment = EnrollmentRepository::find_for_update(&mut tx, id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("You are not enrolled in this training session".to_string()))?;
+    if !matches!(enrollment.status, ParticipantStatus::Attended | ParticipantStatus::Completed) {
+        return Err(AppError::Conflict(
+            "Feedback opens once your attendance is confirmed".to_string(),
+        ));
+    }
+
+    let enrollment = FeedbackRepository::submit(&mut tx, enrollment.id, payload.rating, comment)
+        .await?
+        .ok_or_else(|| AppError::Conflict("You already gave feedback on this training session".to_string()))?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(TrainingFeedback::from(enrollment))))
+}
+
+/// Hosts and tenant admins get the ratings and anonymous comments of the session,
+/// participants their own feedback.
+pub async fn get_feedback(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Response> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_by_id(&mut tx, id).await?;
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+
+    let response = if can_manage(&user, &session) {
+        let summary = FeedbackRepository::session_summary(&mut tx, id).await?;
+        let comments = FeedbackRepository::list_comments(&mut tx, id).await?;
+        Json(SessionFeedback {
+            training_session_id: id,
+            summary,
+            comments,
+        })
+        .into_response()
+    } else {
+        let enrollment = EnrollmentRepository::find(&mut tx, id, user.user_id)
+            .await?
+            .filter(|enrollment| enrollment.feedback_submitted_at.is_some())
+            .ok_or_else(|| AppError::NotFound("You have not given feedback on this training session".to_string()))?;
+        Json(TrainingFeedback::from(enrollment)).into_response()
+    };
+    tx.commit().await?;
+
+    Ok(response)
+}
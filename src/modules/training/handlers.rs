@@ -0,0 +1,425 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::training::{check_transition, SessionViewer};
+use crate::db::repositories::{EnrollmentRepository, RequirementRepository, TrainingRepository};
+use crate::db::rls;
+use crate::db::{
+    CancelTrainingSession, NewTrainingSession, Notification, NotificationType, ScheduleRangeQuery,
+    TrainingSession, TrainingSessionFields, TrainingSessionResponse, TrainingStatus,
+    UpdateTrainingSession, UserRole,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::middleware::time_zone::CallerZone;
+use crate::modules::appointment::handlers::resolve_range;
+use crate::modules::notification::reminders;
+use crate::modules::realtime::{self, protocol::Room};
+
+use super::{attendance, enrollments, notices};
+
+/// Share of a session participants must attend unless the host picks another threshold.
+const DEFAULT_MIN_ATTENDANCE_PERCENT: i32 = 75;
+
+/// Share of a recorded video participants must watch unless the host picks another threshold.
+const DEFAULT_MIN_WATCHED_PERCENT: i32 = 90;
+
+/// Share of a document's pages participants must view unless the host picks another threshold.
+const DEFAULT_MIN_PAGES_VIEWED_PERCENT: i32 = 100;
+
+/// Whether the caller may see a session: drafts are limited to their host and tenant admins.
+pub(crate) fn can_view(user: &AuthUser, session: &TrainingSession) -> bool {
+    viewer(user).can_view(&session.scope())
+}
+
+pub(crate) fn can_manage(user: &AuthUser, session: &TrainingSession) -> bool {
+    viewer(user).can_manage(&session.scope())
+}
+
+fn viewer(user: &AuthUser) -> SessionViewer {
+    SessionViewer {
+        user_id: user.user_id,
+        tenant_id: user.tenant_id,
+        super_admin: user.has_role(&UserRole::SuperAdmin),
+        tenant_admin: user.is_tenant_admin(),
+        ohs_specialist: user.has_role(&UserRole::OhsSpecialist),
+    }
+}
+
+/// Loads and locks a session the caller may manage.
+pub(crate) async fn find_managed(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    id: Uuid,
+) -> AppResult<TrainingSession> {
+    let session = TrainingRepository::find_for_update(tx, id).await?;
+
+    if !can_view(user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+    if !can_manage(user, &session) {
+        return Err(AppError::Authorization(
+            "Only the host or a tenant admin can change this training session".to_string(),
+        ));
+    }
+
+    Ok(session)
+}
+
+/// Fails unless the linked requirement exists in the tenant.
+async fn ensure_requirement(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    requirement_id: Option<Uuid>,
+) -> AppResult<()> {
+    if let Some(requirement_id) = requirement_id {
+        let requirement = RequirementRepository::find_by_id(tx, requirement_id).await?;
+        if requirement.tenant_id != tenant_id {
+            return Err(AppError::NotFound("Training requirement not found".to_string()));
+        }
+    }
+    Ok(())
+}
+
+fn ensure_future(start_time: OffsetDateTime) -> AppResult<()> {
+    if start_time <= OffsetDateTime::now_utc() {
+        return Err(AppError::Validation("Training sessions must start in the future".to_string()));
+    }
+    Ok(())
+}
+
+pub async fn list_trainings(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Query(query): Query<ScheduleRangeQuery>,
+) -> AppResult<Json<Vec<TrainingSessionResponse>>> {
+    let tenant_id = user.require_tenant()?;
+    let (from, to) = resolve_range(&query, zone, 90)?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let sessions = TrainingRepository::list_for_tenant(
+        &mut tx,
+        tenant_id,
+        from,
+        to,
+        user.user_id,
+        user.is_tenant_admin(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|session| TrainingSessionResponse::new(session, zone))
+            .collect(),
+    ))
+}
+
+pub async fn get_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<TrainingSessionResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_by_id(&mut tx, id).await?;
+    tx.commit().await?;
+
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+
+    Ok(Json(TrainingSessionResponse::new(session, zone)))
+}
+
+pub async fn create_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Json(payload): Json<NewTrainingSession>,
+) -> AppResult<(StatusCode, Json<TrainingSessionResponse>)> {
+    payload.validate()?;
+    user.require_any_role(&[UserRole::OhsSpecialist, UserRole::TenantAdmin])?;
+    let tenant_id = user.require_tenant()?;
+
+    let start_time = payload.start_time.resolve(zone)?;
+    ensure_future(start_time)?;
+
+    let host_user_id = match payload.host_user_id {
+        Some(host_user_id) if host_user_id != user.user_id && !user.is_tenant_admin() => {
+            return Err(AppError::Authorization(
+                "Only tenant admins can schedule sessions for another host".to_string(),
+            ));
+        }
+        Some(host_user_id) => host_user_id,
+        None => user.user_id,
+    };
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+
+    if !TrainingRepository::is_specialist_in_tenant(&mut tx, tenant_id, host_user_id).await? {
+        return Err(AppError::Validation(
+            "The host must be an OHS specialist of this tenant".to_string(),
+        ));
+    }
+
+    let fields = TrainingSessionFields {
+        host_user_id,
+        end_time: payload.end_time(start_time),
+        title: payload.title,
+        description: payload.description,
+        training_type: payload.training_type,
+        start_time,
+        stream_details: payload.stream_details,
+        max_participants: payload.max_participants,
+        requirement_id: payload.requirement_id,
+        min_attendance_percent: payload.min_attendance_percent.unwrap_or(DEFAULT_MIN_ATTENDANCE_PERCENT),
+        min_watched_percent: payload.min_watched_percent.unwrap_or(DEFAULT_MIN_WATCHED_PERCENT),
+        min_pages_viewed_percent: payload.min_pages_viewed_percent.unwrap_or(DEFAULT_MIN_PAGES_VIEWED_PERCENT),
+    };
+    ensure_requirement(&mut tx, tenant_id, fields.requirement_id).await?;
+    let session = TrainingRepository::create(&mut tx, tenant_id, &fields).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(TrainingSessionResponse::new(session, zone))))
+}
+
+pub async fn update_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTrainingSession>,
+) -> AppResult<Json<TrainingSessionResponse>> {
+    payload.validate()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let current = find_managed(&mut tx, &user, id).await?;
+
+    if current.status != TrainingStatus::Scheduled {
+        return Err(AppError::Conflict("Only scheduled sessions can be edited".to_string()));
+    }
+
+    let tenant_id = current.tenant_id;
+    let duration = current.end_time - current.start_time;
+    let mut fields = TrainingSessionFields::from(current);
+
+    if let Some(start_time) = payload.start_time {
+        fields.start_time = start_time.resolve(zone)?;
+        ensure_future(fields.start_time)?;
+    }
+    fields.end_time = fields.start_time
+        + payload
+            .duration_minutes
+            .map(time::Duration::minutes)
+            .unwrap_or(duration);
+    if let Some(title) = payload.title {
+        fields.title = title;
+    }
+    if let Some(description) = payload.description {
+        fields.description = description;
+    }
+    if let Some(training_type) = payload.training_type {
+        fields.training_type = training_type;
+    }
+    if let Some(stream_details) = payload.stream_details {
+        fields.stream_details = stream_details;
+    }
+    if let Some(min_attendance_percent) = payload.min_attendance_percent {
+        fields.min_attendance_percent = min_attendance_percent;
+    }
+    if let Some(min_watched_percent) = payload.min_watched_percent {
+        fields.min_watched_percent = min_watched_percent;
+    }
+    if let Some(min_pages_viewed_percent) = payload.min_pages_viewed_percent {
+        fields.min_pages_viewed_percent = min_pages_viewed_percent;
+    }
+    if let Some(requirement_id) = payload.requirement_id {
+        ensure_requirement(&mut tx, tenant_id, requirement_id).await?;
+        fields.requirement_id = requirement_id;
+    }
+    if let Some(max_participants) = payload.max_participants {
+        if let Some(limit) = max_participants {
+            let seats = EnrollmentRepository::count_seats(&mut tx, id).await?;
+            if seats > i64::from(limit) {
+                return Err(AppError::Conflict(format!(
+                    "{} participants are already registered",
+                    seats
+                )));
+            }
+        }
+        fields.max_participants = max_participants;
+    }
+
+    let session = TrainingRepository::update(&mut tx, id, &fields).await?;
+    let notifications = enrollments::fill_open_seats(&mut tx, &state, &session).await?;
+    reminders::sync_training_reminders(&mut tx, &state, &session).await?;
+    tx.commit().await?;
+
+    state.notifier.dispatch_all(&state.db, &notifications).await;
+
+    Ok(Json(TrainingSessionResponse::new(session, zone)))
+}
+
+pub async fn delete_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_managed(&mut tx, &user, id).await?;
+
+    if !TrainingRepository::delete_draft(&mut tx, id).await? {
+        return Err(AppError::Conflict(
+            "Published sessions cannot be deleted; cancel them instead".to_string(),
+        ));
+    }
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn publish_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<TrainingSessionResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let current = find_managed(&mut tx, &user, id).await?;
+
+    if current.status != TrainingStatus::Scheduled {
+        return Err(AppError::Conflict("Only scheduled sessions can be published".to_string()));
+    }
+    ensure_future(current.start_time)?;
+
+    let session = TrainingRepository::publish(&mut tx, id)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Training session is already published".to_string()))?;
+    reminders::sync_training_reminders(&mut tx, &state, &session).await?;
+    tx.commit().await?;
+
+    Ok(Json(TrainingSessionResponse::new(session, zone)))
+}
+
+/// Applies a status transition to a published session the caller manages.
+async fn transition(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    id: Uuid,
+    next: TrainingStatus,
+    cancellation_reason: Option<&str>,
+) -> AppResult<TrainingSession> {
+    let current = find_managed(tx, user, id).await?;
+
+    check_transition(current.is_published(), current.status.into(), next.into())
+        .map_err(|e| AppError::Conflict(e.to_string()))?;
+
+    TrainingRepository::transition(tx, id, current.status, next, cancellation_reason)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Training session was changed concurrently".to_string()))
+}
+
+/// Tells everyone in the session's realtime room about its new status.
+fn publish_status(state: &AppState, session: &TrainingSession) {
+    state.realtime.publish(
+        session.tenant_id,
+        Room::Training(session.id),
+        "training.status_changed",
+        serde_json::json!({ "status": session.status, "cancellation_reason": session.cancellation_reason }),
+    );
+}
+
+pub async fn start_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<TrainingSessionResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = transition(&mut tx, &user, id, TrainingStatus::InProgress, None).await?;
+    reminders::sync_training_reminders(&mut tx, &state, &session).await?;
+    tx.commit().await?;
+    realtime::socket::training_started(&state, &session).await;
+    publish_status(&state, &session);
+
+    Ok(Json(TrainingSessionResponse::new(session, zone)))
+}
+
+pub async fn complete_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<TrainingSessionResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = transition(&mut tx, &user, id, TrainingStatus::Completed, None).await?;
+    attendance::finalize_attendance(&mut tx, &session).await?;
+    tx.commit().await?;
+    publish_status(&state, &session);
+
+    Ok(Json(TrainingSessionResponse::new(session, zone)))
+}
+
+pub async fn cancel_training(
+    State(state): State<AppState>,
+    user: AuthUser,
+    CallerZone(zone): CallerZone,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CancelTrainingSession>,
+) -> AppResult<Json<TrainingSessionResponse>> {
+    payload.validate()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = transition(
+        &mut tx,
+        &user,
+        id,
+        TrainingStatus::Cancelled,
+        payload.reason.as_deref(),
+    )
+    .await?;
+    reminders::sync_training_reminders(&mut tx, &state, &session).await?;
+    let notifications = notify_cancelled(&mut tx, &state, &session).await?;
+    tx.commit().await?;
+    publish_status(&state, &session);
+
+    state.notifier.dispatch_all(&state.db, &notifications).await;
+
+    Ok(Json(TrainingSessionResponse::new(session, zone)))
+}
+
+/// Writes a `TrainingCancelled` notification for every registered employee.
+async fn notify_cancelled(
+    tx: &mut Transaction<'_, Postgres>,
+    state: &AppState,
+    session: &TrainingSession,
+) -> AppResult<Vec<Notification>> {
+    let recipients = TrainingRepository::list_registered_employee_ids(tx, session.id).await?;
+    let reason = session
+        .cancellation_reason
+        .as_ref()
+        .map(|reason| format!(" Reason: {}", reason))
+        .unwrap_or_default();
+
+    notices::notify_participants(
+        tx,
+        state,
+        session,
+        &recipients,
+        NotificationType::TrainingCancelled,
+        "Training cancelled",
+        |title, when| format!("{} on {} has been cancelled.{}", title, when, reason),
+    )
+    .await
+}
@@ -0,0 +1,276 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::storage::{tenant_key, PresignConditions, PresignMethod, StorageError};
+use crate::core::utils::document::page_count;
+use crate::core::utils::material::{check_upload, normalize_content_type, sanitize_file_name};
+use crate::db::repositories::{MaterialRepository, NotificationRepository, TrainingRepository};
+use crate::db::rls;
+use crate::db::{
+    MaterialType, MaterialUpload, MaterialUploadResponse, NewMaterialUpload, NewNotification, NotificationType,
+    TrainingMaterial, UserRole, NOTIFICATION_ENTITY_MATERIAL_UPLOAD,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+use crate::modules::storage::inspection::{inspect_upload, Inspection};
+
+use super::handlers::{can_view, find_managed};
+
+/// How long the presigned PUT URL of an upload stays valid; the upload has to start by then.
+const UPLOAD_URL_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// How long a confirmation may check the stored file before another one may take over.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Roles that may upload training materials.
+const UPLOADER_ROLES: [UserRole; 3] = [UserRole::TenantAdmin, UserRole::OhsSpecialist, UserRole::Doctor];
+
+/// Loads a material of the caller's tenant. Materials of a session are only found by those
+/// who may see the session.
+pub(crate) async fn find_visible(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    id: Uuid,
+) -> AppResult<TrainingMaterial> {
+    let tenant_id = user.require_tenant()?;
+    let material = MaterialRepository::find_by_id(tx, tenant_id, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Training material not found".to_string()))?;
+
+    if let Some(session_id) = material.training_session_id
+        && !can_view(user, &TrainingRepository::find_by_id(tx, session_id).await?)
+    {
+        return Err(AppError::NotFound("Training material not found".to_string()));
+    }
+    Ok(material)
+}
+
+/// Declares a material file and returns a presigned URL to PUT it to storage directly.
+/// The URL only accepts the declared content type and size.
+pub async fn create_upload(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<NewMaterialUpload>,
+) -> AppResult<(StatusCode, Json<MaterialUploadResponse>)> {
+    user.require_any_role(&UPLOADER_ROLES)?;
+    let tenant_id = user.require_tenant()?;
+    payload.validate()?;
+    let file_name = sanitize_file_name(&payload.file_name)?;
+    let content_type = check_upload(payload.material_type.into(), &payload.content_type, payload.size_bytes as u64)?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    // Materials of a session are managed like the session itself
+    if let Some(session_id) = payload.training_session_id {
+        find_managed(&mut tx, &user, session_id).await?;
+    }
+
+    // Uploads get a key of their own: the URL stays valid after confirmation, so the published
+    // file must live elsewhere
+    let id = Uuid::now_v7();
+    let key = tenant_key(tenant_id, &format!("uploads/materials/{}", id));
+    let conditions = PresignConditions {
+        content_type: Some(content_type.clone()),
+        content_length: Some(payload.size_bytes as u64),
+        ..Default::default()
+    };
+    let upload_url = state.storage.presign(PresignMethod::Put, &key, UPLOAD_URL_TTL, &conditions)?;
+
+    let upload = MaterialRepository::create_upload(
+        &mut tx,
+        id,
+        tenant_id,
+        user.user_id,
+        &payload,
+        &key,
+        &file_name,
+        &content_type,
+        upload_url.expires_at,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(MaterialUploadResponse {
+            upload_id: upload.id,
+            upload: upload_url,
+        }),
+    ))
+}
+
+/// Lists the caller's uploads, including quarantined ones.
+pub async fn list_uploads(State(state): State<AppState>, user: AuthUser) -> AppResult<Json<Vec<MaterialUpload>>> {
+    user.require_any_role(&UPLOADER_ROLES)?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let uploads = MaterialRepository::list_uploads(&mut tx, tenant_id, user.user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(uploads))
+}
+
+/// Creates the material once its file is in storage, matches what was declared and passed
+/// the upload checks. Flagged files are quarantined and the uploader notified.
+///
+/// The checks can take minutes for large files, so they run without holding the upload row;
+/// the upload is marked as being confirmed meanwhile and a second confirmation is refused.
+pub async fn confirm_upload(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<(StatusCode, Json<TrainingMaterial>)> {
+    user.require_any_role(&UPLOADER_ROLES)?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let upload = MaterialRepository::find_upload_for_update(&mut tx, tenant_id, user.user_id, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Upload not found".to_string()))?;
+    if upload.confirmed_at.is_some() {
+        return Err(AppError::Conflict("The upload was already confirmed".to_string()));
+    }
+    if upload.quarantined_at.is_some() {
+        return Err(AppError::Conflict("The upload was quarantined by the malware scan".to_string()));
+    }
+    if upload
+        .confirm_started_at
+        .is_some_and(|started_at| OffsetDateTime::now_utc() - started_at < CONFIRMATION_TIMEOUT)
+    {
+        return Err(AppError::Conflict("The upload is already being confirmed".to_string()));
+    }
+    MaterialRepository::start_confirmation(&mut tx, upload.id).await?;
+    tx.commit().await?;
+
+    // Everything is checked on a copy under a key that was never presigned, so the uploader
+    // cannot swap the bytes once they passed
+    let material_id = Uuid::now_v7();
+    let key = tenant_key(tenant_id, &format!("materials/{}", material_id));
+    let checked = check_stored_upload(&state, &upload, &key).await;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let stored = match checked {
+        Ok(StoredUpload { inspection: Inspection::Quarantined(signature), .. }) => {
+            MaterialRepository::quarantine_upload(&mut tx, upload.id, &signature, &key).await?;
+            let notification = NotificationRepository::create(
+                &mut tx,
+                &NewNotification {
+                    user_id: upload.uploader_user_id,
+                    tenant_id: Some(tenant_id),
+                    notification_type: NotificationType::SystemMessage,
+                    title: "Upload quarantined".to_string(),
+                    message: format!(
+                        "\"{}\" was not published: the malware scan found {}.",
+                        upload.file_name, signature
+                    ),
+                    related_entity_id: Some(upload.id),
+                    related_entity_type: Some(NOTIFICATION_ENTITY_MATERIAL_UPLOAD.to_string()),
+                },
+            )
+            .await?;
+            tx.commit().await?;
+
+            state.notifier.dispatch(&state.db, &notification).await;
+            return Err(AppError::Validation(format!(
+                "The file was quarantined: the malware scan found {}",
+                signature
+            )));
+        }
+        Ok(stored) => stored,
+        Err(e) => {
+            MaterialRepository::release_confirmation(&mut tx, upload.id).await?;
+            tx.commit().await?;
+            return Err(e);
+        }
+    };
+
+    let material = MaterialRepository::confirm_upload(
+        &mut tx,
+        &upload,
+        material_id,
+        &key,
+        stored.size_bytes as i64,
+        stored.page_count,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(material)))
+}
+
+/// An uploaded file after the upload checks.
+struct StoredUpload {
+    size_bytes: u64,
+    page_count: Option<i32>,  // For clean documents and slide decks whose pages could be counted
+    inspection: Inspection,
+}
+
+/// Copies the uploaded file to `key` and runs the upload checks on the copy. The upload itself
+/// is only deleted once the checks reached a verdict; on any error the copy is dropped instead,
+/// so the confirmation can be retried.
+async fn check_stored_upload(state: &AppState, upload: &MaterialUpload, key: &str) -> AppResult<StoredUpload> {
+    match state.storage.copy(&upload.object_key, key).await {
+        Ok(()) => {}
+        Err(StorageError::NotFound(_)) => {
+            let message = if upload.expires_at < OffsetDateTime::now_utc() {
+                "The upload URL expired before the file was uploaded; request a new upload"
+            } else {
+                "The file has not been uploaded yet"
+            };
+            return Err(AppError::Conflict(message.to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let checked = check_copy(state, upload, key).await;
+    let stale = if checked.is_ok() { &upload.object_key } else { key };
+    if let Err(e) = state.storage.delete(stale).await {
+        tracing::warn!("Could not delete {} after checking an upload: {}", stale, e);
+    }
+    checked
+}
+
+/// Checks the copy of an upload at `key`. Pages of documents and slide decks are counted here,
+/// so progress never depends on a count the viewer reports.
+async fn check_copy(state: &AppState, upload: &MaterialUpload, key: &str) -> AppResult<StoredUpload> {
+    let object = state.storage.head(key).await?;
+
+    let type_matches = object
+        .content_type
+        .as_deref()
+        .is_none_or(|content_type| normalize_content_type(content_type) == upload.content_type);
+    if object.size_bytes != upload.size_bytes as u64 || !type_matches {
+        // The presigned URL can be used again while it is valid
+        return Err(AppError::Validation(
+            "The uploaded file does not match the declared size or content type".to_string(),
+        ));
+    }
+
+    let inspection = inspect_upload(state, key, &upload.content_type, object.size_bytes).await?;
+    let page_count = match (&inspection, upload.material_type) {
+        (Inspection::Clean, MaterialType::Pdf | MaterialType::Slides) => {
+            let count = page_count(&upload.content_type, &state.storage.get(key).await?);
+            if count.is_none() {
+                tracing::warn!("Could not count the pages of {}; viewing it will count as opening it", key);
+            }
+            count
+        }
+        _ => None,
+    };
+
+    Ok(StoredUpload {
+        size_bytes: object.size_bytes,
+        page_count,
+        inspection,
+    })
+}
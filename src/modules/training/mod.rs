@@ -0,0 +1,96 @@
+pub mod attendance;
+pub mod certificates;
+pub mod enrollments;
+pub mod feedback;
+pub mod handlers;
+pub mod materials;
+pub mod notices;
+pub mod progress;
+pub mod quiz_attempts;
+pub mod quizzes;
+pub mod requirements;
+pub mod transcoding;
+
+use axum::{routing::{delete, get, post, put}, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/trainings", get(handlers::list_trainings).post(handlers::create_training))
+        .route(
+            "/trainings/{id}",
+            get(handlers::get_training)
+                .put(handlers::update_training)
+                .delete(handlers::delete_training),
+        )
+        .route("/trainings/{id}/publish", post(handlers::publish_training))
+        .route("/trainings/{id}/start", post(handlers::start_training))
+        .route("/trainings/{id}/complete", post(handlers::complete_training))
+        .route("/trainings/{id}/cancel", post(handlers::cancel_training))
+        .route(
+            "/trainings/{id}/enrollment",
+            get(enrollments::get_own_enrollment)
+                .post(enrollments::enroll)
+                .delete(enrollments::withdraw_own_enrollment),
+        )
+        .route("/trainings/{id}/enrollments", get(enrollments::list_enrollments))
+        .route("/trainings/{id}/enrollments/bulk", post(enrollments::bulk_enroll))
+        .route(
+            "/trainings/{id}/enrollments/{user_id}",
+            delete(enrollments::remove_enrollment),
+        )
+        .route("/trainings/{id}/attendance", get(attendance::list_attendance))
+        .route("/trainings/{id}/attendance/{user_id}", put(attendance::override_attendance))
+        .route("/trainings/{id}/progress", get(progress::list_progress))
+        .route("/trainings/{id}/certificate", get(certificates::download_own_certificate))
+        .route("/trainings/{id}/certificates", get(certificates::list_certificates))
+        .route("/trainings/{id}/certificates/issue", post(certificates::issue_certificates))
+        .route(
+            "/trainings/{id}/certificates/{user_id}/revoke",
+            post(certificates::revoke_certificate),
+        )
+        .route(
+            "/trainings/{id}/feedback",
+            get(feedback::get_feedback).post(feedback::submit_feedback),
+        )
+        .route(
+            "/trainings/{id}/quiz",
+            get(quizzes::get_quiz)
+                .post(quizzes::create_quiz)
+                .put(quizzes::update_quiz)
+                .delete(quizzes::delete_quiz),
+        )
+        .route("/trainings/{id}/quiz/questions", post(quizzes::add_question))
+        .route("/trainings/{id}/quiz/questions/order", put(quizzes::reorder_questions))
+        .route(
+            "/trainings/{id}/quiz/questions/{question_id}",
+            put(quizzes::update_question).delete(quizzes::delete_question),
+        )
+        .route(
+            "/trainings/{id}/quiz/attempts",
+            get(quiz_attempts::list_attempts).post(quiz_attempts::start_attempt),
+        )
+        .route("/trainings/{id}/quiz/attempts/{attempt_id}", get(quiz_attempts::get_attempt))
+        .route("/trainings/{id}/quiz/attempts/{attempt_id}/answers", put(quiz_attempts::save_answers))
+        .route("/trainings/{id}/quiz/attempts/{attempt_id}/submit", post(quiz_attempts::submit_attempt))
+        .route("/trainings/{id}/quiz/reviews", get(quiz_attempts::list_reviews))
+        .route("/trainings/{id}/quiz/reviews/{answer_id}", post(quiz_attempts::grade_answer))
+        .route("/training-materials/uploads", get(materials::list_uploads).post(materials::create_upload))
+        .route("/training-materials/uploads/{id}/confirm", post(materials::confirm_upload))
+        .route(
+            "/training-materials/{id}/progress",
+            get(progress::get_progress).put(progress::record_progress),
+        )
+        .route(
+            "/training-requirements",
+            get(requirements::list_requirements).post(requirements::create_requirement),
+        )
+        .route(
+            "/training-requirements/{id}",
+            put(requirements::update_requirement).delete(requirements::deactivate_requirement),
+        )
+        .route("/training-requirements/dashboard", get(requirements::compliance_dashboard))
+        .route("/training-requirements/compliance", get(requirements::list_compliance))
+        .route("/users/me/training-compliance", get(requirements::own_compliance))
+}
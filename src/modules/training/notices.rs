@@ -0,0 +1,52 @@
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+
+use crate::app_state::AppState;
+use crate::db::repositories::NotificationRepository;
+use crate::db::{
+    NewNotification, Notification, NotificationType, TrainingSession, REMINDER_ENTITY_TRAINING_SESSION,
+};
+use crate::error::AppResult;
+use crate::modules::notification::reminders;
+
+/// Writes one in-app notification per recipient about a training session.
+///
+/// `message` receives the quoted session title and its start time in the recipient's zone. The
+/// returned notifications should be dispatched once the transaction has committed.
+pub async fn notify_participants(
+    tx: &mut Transaction<'_, Postgres>,
+    state: &AppState,
+    session: &TrainingSession,
+    recipients: &[Uuid],
+    notification_type: NotificationType,
+    title: &str,
+    message: impl Fn(&str, &str) -> String,
+) -> AppResult<Vec<Notification>> {
+    let mut notifications = Vec::with_capacity(recipients.len());
+
+    for &user_id in recipients {
+        let zone = reminders::zone_for_user(&state.db, user_id).await?;
+        let when = format!(
+            "{} ({})",
+            reminders::format_local(session.start_time, zone),
+            zone.name()
+        );
+
+        let notification = NotificationRepository::create(
+            tx,
+            &NewNotification {
+                user_id,
+                tenant_id: Some(session.tenant_id),
+                notification_type,
+                title: title.to_string(),
+                message: message(&format!("\"{}\"", session.title), &when),
+                related_entity_id: Some(session.id),
+                related_entity_type: Some(REMINDER_ENTITY_TRAINING_SESSION.to_string()),
+            },
+        )
+        .await?;
+        notifications.push(notification);
+    }
+
+    Ok(notifications)
+}
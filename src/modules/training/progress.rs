@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::progress::{
+    add_pages, add_watched, resume_position, watched_seconds, CompletionRule, Consumption,
+};
+use crate::db::repositories::{
+    AttendanceOutcome, AttendanceRepository, EnrollmentRepository, MaterialRepository, ProgressRepository,
+    QuizAttemptRepository, TrainingRepository,
+};
+use crate::db::rls;
+use crate::db::{
+    EnrollmentProgress, MaterialProgress, MaterialProgressFields, MaterialProgressPing, MaterialProgressResponse,
+    MaterialProgressSummary, MaterialType, ParticipantStatus, TrainingEnrollment, TrainingMaterial, TrainingSession,
+    TrainingStatus,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+use super::certificates::issue_certificate;
+use super::handlers::{can_manage, can_view};
+
+/// When a material of the session counts as done. Documents whose pages could not be counted
+/// at upload, such as legacy PowerPoint files, only need to be opened.
+fn completion_rule(session: &TrainingSession, material: &TrainingMaterial) -> CompletionRule {
+    match material.material_type {
+        MaterialType::Video => CompletionRule::Watched(session.min_watched_percent),
+        MaterialType::Pdf | MaterialType::Slides if material.page_count.is_some() => {
+            CompletionRule::PagesViewed(session.min_pages_viewed_percent)
+        }
+        MaterialType::Pdf | MaterialType::Slides | MaterialType::Other => CompletionRule::Opened,
+    }
+}
+
+/// Only the file's own length counts: a video is not done before processing measured it.
+fn consumption(material: &TrainingMaterial, progress: &MaterialProgress) -> Consumption {
+    Consumption {
+        watched_seconds: progress.watched_seconds,
+        duration_seconds: material.duration_seconds,
+        pages_viewed: progress.pages_viewed.len(),
+        page_count: material.page_count,
+    }
+}
+
+/// Loads a material of a self-paced session the caller can see, with its session.
+async fn find_tracked(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    id: Uuid,
+) -> AppResult<(TrainingMaterial, TrainingSession)> {
+    let tenant_id = user.require_tenant()?;
+    let material = MaterialRepository::find_by_id(tx, tenant_id, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Training material not found".to_string()))?;
+    let session_id = material
+        .training_session_id
+        .ok_or_else(|| AppError::Conflict("The material does not belong to a training session".to_string()))?;
+
+    let session = TrainingRepository::find_by_id(tx, session_id).await?;
+    if !can_view(user, &session) {
+        return Err(AppError::NotFound("Training material not found".to_string()));
+    }
+    if !session.training_type.is_self_paced() {
+        return Err(AppError::Conflict(
+            "Progress is only tracked for recorded video and document trainings".to_string(),
+        ));
+    }
+
+    Ok((material, session))
+}
+
+/// Completes a registered enrollment once every material of its session is done. Like live
+/// attendance, it waits for a passing attempt when the session has a quiz; manual overrides are kept.
+async fn complete_from_progress(
+    tx: &mut Transaction<'_, Postgres>,
+    enrollment: TrainingEnrollment,
+) -> AppResult<TrainingEnrollment> {
+    if enrollment.status != ParticipantStatus::Registered
+        || enrollment.attendance_overridden_at.is_some()
+        || !ProgressRepository::completed_all(tx, &enrollment).await?
+    {
+        return Ok(enrollment);
+    }
+
+    let quiz_pending = AttendanceRepository::session_has_quiz(tx, enrollment.training_session_id).await?
+        && !QuizAttemptRepository::has_passed(tx, enrollment.id).await?;
+    let (status, completion_date) = if quiz_pending {
+        (ParticipantStatus::Attended, None)
+    } else {
+        (ParticipantStatus::Completed, Some(OffsetDateTime::now_utc()))
+    };
+
+    let outcome = AttendanceOutcome {
+        attended_minutes: enrollment.attended_minutes.unwrap_or(0),
+        attended: true,
+        status,
+        completion_date,
+    };
+    let Some(decided) = AttendanceRepository::apply_outcome(tx, enrollment.id, outcome).await? else {
+        return Ok(enrollment);
+    };
+    if decided.status == ParticipantStatus::Completed {
+        issue_certificate(tx, decided.id).await?;
+    }
+
+    Ok(decided)
+}
+
+/// Records a progress ping of the caller's player or viewer. Watched spans and pages add up
+/// across pings; the enrollment completes once the session's rules are met for every material.
+pub async fn record_progress(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<MaterialProgressPing>,
+) -> AppResult<Json<MaterialProgressResponse>> {
+    payload.validate()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (material, session) = find_tracked(&mut tx, &user, id).await?;
+    if session.status == TrainingStatus::Cancelled {
+        return Err(AppError::Conflict("Training session was cancelled".to_string()));
+    }
+
+    let enrollment = EnrollmentRepository::find_for_update(&mut tx, session.id, user.user_id)
+        .await?
+        .filter(|enrollment| enrollment.status.holds_seat())
+        .ok_or_else(|| AppError::Authorization("You are not registered for this training session".to_string()))?;
+    let current = ProgressRepository::find(&mut tx, enrollment.id, material.id).await?;
+
+    // The first reported duration is kept to clamp spans until processing measured the video;
+    // completion only trusts the measured one
+    let reported_duration = current
+        .as_ref()
+        .and_then(|progress| progress.duration_seconds)
+        .or(payload.duration_seconds);
+    let duration_seconds = material.duration_seconds.or(reported_duration);
+    let page_count = material.page_count;
+    let since_first_ping = current
+        .as_ref()
+        .and_then(|progress| progress.created_at)
+        .map(|created_at| (OffsetDateTime::now_utc() - created_at).unsigned_abs())
+        .unwrap_or_default();
+
+    let previous_ranges = current.as_ref().map(|progress| progress.watched_ranges.0.as_slice()).unwrap_or_default();
+    let watched_ranges = match payload.watched {
+        Some(span) => add_watched(previous_ranges, span, duration_seconds, since_first_ping),
+        None => previous_ranges.to_vec(),
+    };
+    let previous_pages = current.as_ref().map(|progress| progress.pages_viewed.as_slice()).unwrap_or_default();
+    let pages_viewed = add_pages(previous_pages, payload.pages_viewed.as_deref().unwrap_or_default(), page_count);
+
+    let position_seconds = payload
+        .position_seconds
+        .and_then(|position| resume_position(position, duration_seconds))
+        .or(current.as_ref().and_then(|progress| progress.position_seconds));
+    let current_page = payload
+        .current_page
+        .map(|page| page_count.map_or(page, |count| page.min(count)))
+        .or(current.as_ref().and_then(|progress| progress.current_page));
+
+    let rule = completion_rule(&session, &material);
+    let consumed = Consumption {
+        watched_seconds: watched_seconds(&watched_ranges),
+        duration_seconds: material.duration_seconds,
+        pages_viewed: pages_viewed.len(),
+        page_count,
+    };
+    let fields = MaterialProgressFields {
+        position_seconds,
+        watched_seconds: consumed.watched_seconds,
+        watched_ranges,
+        duration_seconds: reported_duration,
+        current_page,
+        pages_viewed,
+        page_count,
+        completed: rule.is_met(&consumed),
+    };
+    let progress = ProgressRepository::save(&mut tx, &enrollment, material.id, &fields).await?;
+
+    let enrollment = if progress.completed_at.is_some() {
+        complete_from_progress(&mut tx, enrollment).await?
+    } else {
+        enrollment
+    };
+    tx.commit().await?;
+
+    Ok(Json(MaterialProgressResponse {
+        percent: rule.percent(&consumed),
+        enrollment_status: enrollment.status,
+        progress,
+    }))
+}
+
+/// The caller's progress on a material, to resume where they left off.
+pub async fn get_progress(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<MaterialProgressResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (material, session) = find_tracked(&mut tx, &user, id).await?;
+
+    let enrollment = EnrollmentRepository::find(&mut tx, session.id, user.user_id)
+        .await?
+        .filter(|enrollment| enrollment.status.holds_seat())
+        .ok_or_else(|| AppError::Authorization("You are not registered for this training session".to_string()))?;
+    let progress = ProgressRepository::find(&mut tx, enrollment.id, material.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No progress was recorded for this material yet".to_string()))?;
+    tx.commit().await?;
+
+    let rule = completion_rule(&session, &material);
+    Ok(Json(MaterialProgressResponse {
+        percent: rule.percent(&consumption(&material, &progress)),
+        enrollment_status: enrollment.status,
+        progress,
+    }))
+}
+
+/// Hosts and tenant admins get the progress of every participant through the session's
+/// materials, participants their own.
+pub async fn list_progress(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<EnrollmentProgress>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_by_id(&mut tx, id).await?;
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+    if !session.training_type.is_self_paced() {
+        return Err(AppError::Conflict(
+            "Progress is only tracked for recorded video and document trainings".to_string(),
+        ));
+    }
+
+    let (enrollments, progress) = if can_manage(&user, &session) {
+        let enrollments = EnrollmentRepository::list_for_session(&mut tx, id).await?;
+        (enrollments, ProgressRepository::list_for_session(&mut tx, id, None).await?)
+    } else {
+        let enrollment = EnrollmentRepository::find(&mut tx, id, user.user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("You are not enrolled in this training session".to_string()))?;
+        (vec![enrollment], ProgressRepository::list_for_session(&mut tx, id, Some(user.user_id)).await?)
+    };
+    let materials = MaterialRepository::list_for_session(&mut tx, session.tenant_id, id).await?;
+    tx.commit().await?;
+
+    let by_material: HashMap<(Uuid, Uuid), &MaterialProgress> = progress
+        .iter()
+        .map(|progress| ((progress.enrollment_id, progress.material_id), progress))
+        .collect();
+
+    let overview = enrollments
+        .into_iter()
+        .filter(|enrollment| enrollment.status.holds_seat())
+        .map(|enrollment| {
+            let summaries: Vec<MaterialProgressSummary> = materials
+                .iter()
+                .map(|material| {
+                    let progress = by_material.get(&(enrollment.id, material.id));
+                    let rule = completion_rule(&session, material);
+                    MaterialProgressSummary {
+                        material_id: material.id,
+                        title: material.title.clone(),
+                        material_type: material.material_type,
+                        percent: progress.map_or(0, |progress| rule.percent(&consumption(material, progress))),
+                        position_seconds: progress.and_then(|progress| progress.position_seconds),
+                        current_page: progress.and_then(|progress| progress.current_page),
+                        completed_at: progress.and_then(|progress| progress.completed_at),
+                    }
+                })
+                .collect();
+
+            EnrollmentProgress {
+                enrollment_id: enrollment.id,
+                employee_user_id: enrollment.employee_user_id,
+                status: enrollment.status,
+                completed_materials: summaries.iter().filter(|summary| summary.completed_at.is_some()).count(),
+                total_materials: summaries.len(),
+                materials: summaries,
+            }
+        })
+        .collect();
+
+    Ok(Json(overview))
+}
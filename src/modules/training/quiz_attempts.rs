@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::{Duration, OffsetDateTime};
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::quiz::{
+    draw_questions, is_correct_choice, normalize_answer, passes, reveals_feedback, score_percent,
+    shuffled_option_keys, DrawError, QuestionKind,
+};
+use crate::db::repositories::{
+    AttemptResult, EnrollmentRepository, QuizAttemptRepository, QuizRepository, TrainingRepository,
+};
+use crate::db::rls;
+use crate::db::{
+    AttemptLayoutEntry, GradeQuizAnswer, ParticipantStatus, PendingQuizAnswer, QuizAttempt, QuizAttemptResponse,
+    QuizAttemptStatus, QuizQuestion, QuizQuestionType, SubmitQuizAnswers, TrainingEnrollment, TrainingQuiz,
+    TrainingSession, TrainingStatus,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+use super::certificates::issue_certificate;
+use super::handlers::{can_manage, find_managed};
+
+/// Answers arriving this long after the time limit are still accepted, to allow for latency.
+const ANSWER_GRACE: Duration = Duration::seconds(30);
+
+/// Loads a session in the caller's tenant together with its quiz.
+async fn find_quiz(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    session_id: Uuid,
+) -> AppResult<(TrainingSession, TrainingQuiz)> {
+    let session = TrainingRepository::find_by_id(tx, session_id).await?;
+    if user.tenant_id != Some(session.tenant_id) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+
+    let quiz = QuizRepository::find_for_session(tx, session_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("This training session has no quiz".to_string()))?;
+
+    Ok((session, quiz))
+}
+
+/// Loads an attempt its participant or a manager of the session may see, locked.
+async fn find_attempt(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    session: &TrainingSession,
+    quiz: &TrainingQuiz,
+    attempt_id: Uuid,
+) -> AppResult<QuizAttempt> {
+    QuizAttemptRepository::find_for_update(tx, quiz.id, attempt_id)
+        .await?
+        .filter(|attempt| attempt.employee_user_id == user.user_id || can_manage(user, session))
+        .ok_or_else(|| AppError::NotFound("Quiz attempt not found".to_string()))
+}
+
+/// Draws the questions of a new attempt and the order of their options.
+fn draw_layout(quiz: &TrainingQuiz, pool: &[QuizQuestion]) -> Result<Vec<AttemptLayoutEntry>, DrawError> {
+    let mut rng = rand::rng();
+    let drawn = draw_questions(pool, |question| question.tag.as_deref(), &quiz.question_draw(), &mut rng)?;
+
+    Ok(drawn
+        .into_iter()
+        .map(|question| AttemptLayoutEntry {
+            question_id: question.id,
+            option_keys: question
+                .options
+                .as_ref()
+                .map(|options| shuffled_option_keys(question.question_type.into(), &options.0, &mut rng))
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Scores what can be scored: choice answers automatically, blank free text answers with
+/// zero points. Returns None while free text answers still await a grader.
+async fn score_attempt(
+    tx: &mut Transaction<'_, Postgres>,
+    quiz: &TrainingQuiz,
+    attempt: &QuizAttempt,
+) -> AppResult<Option<AttemptResult>> {
+    let questions = attempt.arrange(QuizRepository::list_questions(tx, quiz.id).await?);
+    let by_id: HashMap<Uuid, &QuizQuestion> = questions.iter().map(|question| (question.id, question)).collect();
+
+    let mut points_earned = 0;
+    let mut awaiting_review = false;
+    for answer in QuizAttemptRepository::list_answers(tx, attempt.id).await? {
+        if let Some(points) = answer.points_awarded {
+            points_earned += points;
+            continue;
+        }
+        let Some(question) = by_id.get(&answer.question_id) else {
+            continue;
+        };
+
+        match (&question.correct_answer_key, &answer.answer_text) {
+            (Some(correct), _) => {
+                let correct = is_correct_choice(correct, answer.answer_key.as_deref());
+                let points = if correct { question.points } else { 0 };
+                QuizAttemptRepository::score_answer(tx, answer.id, correct, points).await?;
+                points_earned += points;
+            }
+            (None, None) => QuizAttemptRepository::score_answer(tx, answer.id, false, 0).await?,
+            (None, Some(_)) => awaiting_review = true,
+        }
+    }
+
+    if awaiting_review {
+        return Ok(None);
+    }
+
+    let points_possible = questions.iter().map(|question| question.points).sum();
+    Ok(Some(AttemptResult {
+        points_earned,
+        points_possible,
+        score: score_percent(points_earned, points_possible),
+        passed: passes(points_earned, points_possible, quiz.pass_threshold_percent),
+    }))
+}
+
+/// A passed quiz completes an attended enrollment; participants whose attendance is not
+/// decided yet are completed once it is.
+async fn apply_result(tx: &mut Transaction<'_, Postgres>, attempt: &QuizAttempt) -> AppResult<()> {
+    if attempt.passed == Some(true)
+        && let Some(enrollment_id) = attempt.enrollment_id
+        && let Some(enrollment) = EnrollmentRepository::complete_attended(tx, enrollment_id).await?
+    {
+        issue_certificate(tx, enrollment.id).await?;
+    }
+    Ok(())
+}
+
+/// Submits an attempt with the answers saved so far.
+async fn submit(
+    tx: &mut Transaction<'_, Postgres>,
+    quiz: &TrainingQuiz,
+    attempt: &QuizAttempt,
+) -> AppResult<QuizAttempt> {
+    let result = score_attempt(tx, quiz, attempt).await?;
+    let attempt = QuizAttemptRepository::submit(tx, attempt.id, result).await?;
+    apply_result(tx, &attempt).await?;
+
+    Ok(attempt)
+}
+
+/// Submits an attempt whose time ran out; nobody may have been around to do it.
+async fn close_if_expired(
+    tx: &mut Transaction<'_, Postgres>,
+    quiz: &TrainingQuiz,
+    attempt: QuizAttempt,
+) -> AppResult<QuizAttempt> {
+    if attempt.status == QuizAttemptStatus::InProgress
+        && attempt.is_expired(OffsetDateTime::now_utc(), ANSWER_GRACE)
+    {
+        return submit(tx, quiz, &attempt).await;
+    }
+    Ok(attempt)
+}
+
+/// The attempt as shown to the caller. Managers see which answers were right; participants
+/// only once they passed or have no attempt left.
+async fn attempt_response(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    session: &TrainingSession,
+    quiz: &TrainingQuiz,
+    attempt: QuizAttempt,
+) -> AppResult<QuizAttemptResponse> {
+    let feedback = if can_manage(user, session) {
+        true
+    } else if let Some(enrollment_id) = attempt.enrollment_id {
+        let attempts = QuizAttemptRepository::list_for_enrollment(tx, enrollment_id).await?;
+        let passed = attempts.iter().any(|attempt| attempt.passed == Some(true));
+        reveals_feedback(passed, attempts.len(), quiz.max_attempts)
+    } else {
+        false
+    };
+
+    let questions = attempt.arrange(QuizRepository::list_questions(tx, quiz.id).await?);
+    let answers = QuizAttemptRepository::list_answers(tx, attempt.id).await?;
+    Ok(QuizAttemptResponse::detailed(attempt, questions, answers, feedback))
+}
+
+/// Checks that the participant may take the quiz now.
+fn ensure_can_attempt(session: &TrainingSession, enrollment: &TrainingEnrollment) -> AppResult<()> {
+    if !matches!(session.status, TrainingStatus::InProgress | TrainingStatus::Completed) {
+        return Err(AppError::Conflict(
+            "The quiz opens once the training session has started".to_string(),
+        ));
+    }
+
+    match enrollment.status {
+        ParticipantStatus::Registered | ParticipantStatus::Attended => Ok(()),
+        ParticipantStatus::Completed => Err(AppError::Conflict(
+            "You have already completed this training".to_string(),
+        )),
+        _ => Err(AppError::Conflict(
+            "Only participants who attended the session can take the quiz".to_string(),
+        )),
+    }
+}
+
+/// Starts the next attempt, or resumes the one in progress.
+pub async fn start_attempt(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<(StatusCode, Json<QuizAttemptResponse>)> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_quiz(&mut tx, &user, id).await?;
+
+    // The enrollment lock keeps concurrent starts from opening two attempts
+    let enrollment = EnrollmentRepository::find_for_update(&mut tx, id, user.user_id)
+        .await?
+        .filter(|enrollment| enrollment.status.holds_seat())
+        .ok_or_else(|| AppError::Authorization("You are not registered for this training session".to_string()))?;
+    ensure_can_attempt(&session, &enrollment)?;
+
+    let mut attempts = Vec::new();
+    for attempt in QuizAttemptRepository::list_for_enrollment(&mut tx, enrollment.id).await? {
+        attempts.push(close_if_expired(&mut tx, &quiz, attempt).await?);
+    }
+
+    if let Some(open) = attempts.iter().find(|attempt| attempt.status == QuizAttemptStatus::InProgress) {
+        let response = attempt_response(&mut tx, &user, &session, &quiz, open.clone()).await?;
+        tx.commit().await?;
+        return Ok((StatusCode::OK, Json(response)));
+    }
+
+    let conflict = if attempts.iter().any(|attempt| attempt.passed == Some(true)) {
+        Some("You have already passed this quiz".to_string())
+    } else if attempts.iter().any(|attempt| attempt.status == QuizAttemptStatus::PendingReview) {
+        Some("Your previous attempt is awaiting grading".to_string())
+    } else if let Some(max_attempts) = quiz.max_attempts
+        && attempts.len() >= max_attempts as usize
+    {
+        Some(format!("The quiz allows at most {} attempts", max_attempts))
+    } else {
+        None
+    };
+    if let Some(message) = conflict {
+        // Attempts closed above for running out of time stay closed
+        tx.commit().await?;
+        return Err(AppError::Conflict(message));
+    }
+
+    let pool = QuizRepository::list_questions(&mut tx, quiz.id).await?;
+    if pool.is_empty() {
+        return Err(AppError::Conflict("The quiz has no questions yet".to_string()));
+    }
+
+    let layout = draw_layout(&quiz, &pool)?;
+    let attempt = QuizAttemptRepository::start(&mut tx, &quiz, &enrollment, &layout).await?;
+    let questions = attempt.arrange(pool);
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(QuizAttemptResponse::detailed(attempt, questions, Vec::new(), false))))
+}
+
+/// Managers see every attempt at the quiz, participants their own.
+pub async fn list_attempts(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<QuizAttemptResponse>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_quiz(&mut tx, &user, id).await?;
+
+    let attempts = if can_manage(&user, &session) {
+        QuizAttemptRepository::list_for_quiz(&mut tx, quiz.id).await?
+    } else {
+        match EnrollmentRepository::find(&mut tx, id, user.user_id).await? {
+            Some(enrollment) => QuizAttemptRepository::list_for_enrollment(&mut tx, enrollment.id).await?,
+            None => Vec::new(),
+        }
+    };
+    tx.commit().await?;
+
+    Ok(Json(attempts.into_iter().map(QuizAttemptResponse::summary).collect()))
+}
+
+pub async fn get_attempt(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, attempt_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<QuizAttemptResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_quiz(&mut tx, &user, id).await?;
+
+    let attempt = find_attempt(&mut tx, &user, &session, &quiz, attempt_id).await?;
+    let attempt = close_if_expired(&mut tx, &quiz, attempt).await?;
+    let response = attempt_response(&mut tx, &user, &session, &quiz, attempt).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+/// Saves answers of the caller's attempt in progress; may be called any number of times.
+pub async fn save_answers(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, attempt_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SubmitQuizAnswers>,
+) -> AppResult<Json<QuizAttemptResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_quiz(&mut tx, &user, id).await?;
+
+    let attempt = QuizAttemptRepository::find_for_update(&mut tx, quiz.id, attempt_id)
+        .await?
+        .filter(|attempt| attempt.employee_user_id == user.user_id)
+        .ok_or_else(|| AppError::NotFound("Quiz attempt not found".to_string()))?;
+    if attempt.status != QuizAttemptStatus::InProgress {
+        return Err(AppError::Conflict("This attempt was already submitted".to_string()));
+    }
+    if attempt.is_expired(OffsetDateTime::now_utc(), ANSWER_GRACE) {
+        submit(&mut tx, &quiz, &attempt).await?;
+        tx.commit().await?;
+        return Err(AppError::Conflict(
+            "The time limit has passed; the attempt was submitted with the answers saved before".to_string(),
+        ));
+    }
+
+    let questions = attempt.arrange(QuizRepository::list_questions(&mut tx, quiz.id).await?);
+    for input in &payload.answers {
+        let question = questions
+            .iter()
+            .find(|question| question.id == input.question_id)
+            .ok_or_else(|| {
+                AppError::Validation(format!("Question {} is not part of this attempt", input.question_id))
+            })?;
+
+        let options = question.options.as_ref().map(|options| options.0.as_slice()).unwrap_or_default();
+        let answer = normalize_answer(
+            QuestionKind::from(question.question_type),
+            options,
+            &input.answer_keys,
+            input.answer_text.as_deref(),
+        )?;
+        QuizAttemptRepository::save_answer(&mut tx, &attempt, question.id, &answer).await?;
+    }
+
+    let response = attempt_response(&mut tx, &user, &session, &quiz, attempt).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+/// Submits the caller's attempt; choice questions are scored at once, free text ones queued for grading.
+pub async fn submit_attempt(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, attempt_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<QuizAttemptResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_quiz(&mut tx, &user, id).await?;
+
+    let attempt = QuizAttemptRepository::find_for_update(&mut tx, quiz.id, attempt_id)
+        .await?
+        .filter(|attempt| attempt.employee_user_id == user.user_id)
+        .ok_or_else(|| AppError::NotFound("Quiz attempt not found".to_string()))?;
+    if attempt.status != QuizAttemptStatus::InProgress {
+        return Err(AppError::Conflict("This attempt was already submitted".to_string()));
+    }
+
+    let attempt = submit(&mut tx, &quiz, &attempt).await?;
+    let response = attempt_response(&mut tx, &user, &session, &quiz, attempt).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+/// Free text answers of submitted attempts that await grading.
+pub async fn list_reviews(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<PendingQuizAnswer>>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_managed(&mut tx, &user, id).await?;
+    let quiz = QuizRepository::find_for_session(&mut tx, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("This training session has no quiz".to_string()))?;
+
+    let pending = QuizAttemptRepository::list_pending_reviews(&mut tx, quiz.id).await?;
+    tx.commit().await?;
+
+    Ok(Json(pending))
+}
+
+/// Grades a free text answer; the attempt's result is settled with its last one.
+pub async fn grade_answer(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, answer_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<GradeQuizAnswer>,
+) -> AppResult<Json<QuizAttemptResponse>> {
+    payload.validate()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_managed(&mut tx, &user, id).await?;
+    let quiz = QuizRepository::find_for_session(&mut tx, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("This training session has no quiz".to_string()))?;
+
+    let answer = QuizAttemptRepository::find_pending_answer(&mut tx, quiz.id, answer_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No answer awaiting grading was found".to_string()))?;
+    let attempt = QuizAttemptRepository::find_for_update(&mut tx, quiz.id, answer.attempt_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Quiz attempt not found".to_string()))?;
+
+    let max_points = QuizRepository::list_questions(&mut tx, quiz.id)
+        .await?
+        .into_iter()
+        .find(|question| question.id == answer.question_id && question.question_type == QuizQuestionType::FreeText)
+        .map(|question| question.points)
+        .ok_or_else(|| AppError::NotFound("Question not found".to_string()))?;
+    if payload.points_awarded > max_points {
+        return Err(AppError::Validation(format!(
+            "The question is worth at most {} points",
+            max_points
+        )));
+    }
+
+    QuizAttemptRepository::grade_answer(
+        &mut tx,
+        answer.id,
+        payload.points_awarded,
+        payload.points_awarded == max_points,
+        user.user_id,
+    )
+    .await?;
+
+    let attempt = match score_attempt(&mut tx, &quiz, &attempt).await? {
+        Some(result) => {
+            let attempt = QuizAttemptRepository::complete_review(&mut tx, attempt.id, result).await?;
+            apply_result(&mut tx, &attempt).await?;
+            attempt
+        }
+        None => attempt,
+    };
+    tx.commit().await?;
+
+    Ok(Json(QuizAttemptResponse::summary(attempt)))
+}
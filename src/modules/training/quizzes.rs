@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::core::utils::quiz::{normalize_draw, normalize_question, normalize_tag};
+use crate::db::repositories::{EnrollmentRepository, QuizAttemptRepository, QuizRepository, TrainingRepository};
+use crate::db::rls;
+use crate::db::{
+    NewQuizQuestion, NewTrainingQuiz, QuizQuestionResponse, QuizResponse, QuizSummary, ReorderQuizQuestions,
+    TrainingQuiz, TrainingSession, TrainingStatus,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+use super::handlers::{can_manage, can_view, find_managed};
+
+/// Questions a single quiz may hold.
+const MAX_QUIZ_QUESTIONS: usize = 200;
+
+/// Loads the session the caller manages together with its quiz.
+async fn find_managed_quiz(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    session_id: Uuid,
+) -> AppResult<(TrainingSession, TrainingQuiz)> {
+    let session = find_managed(tx, user, session_id).await?;
+    let quiz = QuizRepository::find_for_session(tx, session_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("This training session has no quiz".to_string()))?;
+
+    Ok((session, quiz))
+}
+
+fn ensure_editable(session: &TrainingSession) -> AppResult<()> {
+    if session.status == TrainingStatus::Cancelled {
+        return Err(AppError::Conflict("The training session was cancelled".to_string()));
+    }
+    Ok(())
+}
+
+/// Questions are frozen once someone started the quiz, so that every attempt is scored
+/// against the questions it was taken with.
+async fn ensure_no_attempts(tx: &mut Transaction<'_, Postgres>, quiz: &TrainingQuiz) -> AppResult<()> {
+    if QuizAttemptRepository::quiz_has_attempts(tx, quiz.id).await? {
+        return Err(AppError::Conflict(
+            "The quiz has attempts; its questions can no longer be changed".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn quiz_response(tx: &mut Transaction<'_, Postgres>, quiz: TrainingQuiz) -> AppResult<QuizResponse> {
+    let questions = QuizRepository::list_questions(tx, quiz.id).await?;
+    Ok(QuizResponse::new(quiz, questions))
+}
+
+/// The quiz of a session: hosts and tenant admins get every question with its answer key,
+/// participants only a summary until they start an attempt.
+pub async fn get_quiz(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Response> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = TrainingRepository::find_by_id(&mut tx, id).await?;
+    if !can_view(&user, &session) {
+        return Err(AppError::NotFound("Training session not found".to_string()));
+    }
+
+    let quiz = QuizRepository::find_for_session(&mut tx, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("This training session has no quiz".to_string()))?;
+
+    let response = if can_manage(&user, &session) {
+        Json(quiz_response(&mut tx, quiz).await?).into_response()
+    } else {
+        let registered = EnrollmentRepository::find(&mut tx, id, user.user_id)
+            .await?
+            .is_some_and(|enrollment| enrollment.status.holds_seat());
+        if !registered {
+            return Err(AppError::Authorization(
+                "You are not registered for this training session".to_string(),
+            ));
+        }
+
+        let questions = QuizRepository::list_questions(&mut tx, quiz.id).await?;
+        Json(QuizSummary::new(quiz, &questions)).into_response()
+    };
+    tx.commit().await?;
+
+    Ok(response)
+}
+
+pub async fn create_quiz(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<NewTrainingQuiz>,
+) -> AppResult<(StatusCode, Json<QuizResponse>)> {
+    payload.validate()?;
+    let tag_quotas = normalize_draw(payload.questions_per_attempt, payload.tag_quotas.clone())?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let session = find_managed(&mut tx, &user, id).await?;
+    ensure_editable(&session)?;
+
+    let quiz = QuizRepository::create(&mut tx, session.tenant_id, id, user.user_id, &payload, tag_quotas.as_ref())
+        .await?
+        .ok_or_else(|| AppError::Conflict("This training session already has a quiz".to_string()))?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(QuizResponse::new(quiz, Vec::new()))))
+}
+
+pub async fn update_quiz(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<NewTrainingQuiz>,
+) -> AppResult<Json<QuizResponse>> {
+    payload.validate()?;
+    let tag_quotas = normalize_draw(payload.questions_per_attempt, payload.tag_quotas.clone())?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_managed_quiz(&mut tx, &user, id).await?;
+    ensure_editable(&session)?;
+
+    // Attempts keep the questions drawn for them, so the draw may change at any time
+    let quiz = QuizRepository::update(&mut tx, quiz.id, &payload, tag_quotas.as_ref()).await?;
+    let response = quiz_response(&mut tx, quiz).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+pub async fn delete_quiz(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (_, quiz) = find_managed_quiz(&mut tx, &user, id).await?;
+    ensure_no_attempts(&mut tx, &quiz).await?;
+
+    QuizRepository::delete(&mut tx, quiz.id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn add_question(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<NewQuizQuestion>,
+) -> AppResult<(StatusCode, Json<QuizQuestionResponse>)> {
+    payload.validate()?;
+    let normalized = normalize_question(payload.question_type.into(), payload.options, &payload.correct_answers)?;
+    let tag = payload.tag.as_deref().map(normalize_tag).transpose()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_managed_quiz(&mut tx, &user, id).await?;
+    ensure_editable(&session)?;
+    ensure_no_attempts(&mut tx, &quiz).await?;
+
+    if QuizRepository::list_questions(&mut tx, quiz.id).await?.len() >= MAX_QUIZ_QUESTIONS {
+        return Err(AppError::Validation(format!(
+            "A quiz can hold at most {} questions",
+            MAX_QUIZ_QUESTIONS
+        )));
+    }
+
+    let question = QuizRepository::create_question(
+        &mut tx,
+        &quiz,
+        payload.question_text.trim(),
+        payload.question_type,
+        &normalized,
+        payload.points.unwrap_or(1),
+        tag.as_deref(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(QuizQuestionResponse::from(question))))
+}
+
+pub async fn update_question(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, question_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<NewQuizQuestion>,
+) -> AppResult<Json<QuizQuestionResponse>> {
+    payload.validate()?;
+    let normalized = normalize_question(payload.question_type.into(), payload.options, &payload.correct_answers)?;
+    let tag = payload.tag.as_deref().map(normalize_tag).transpose()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_managed_quiz(&mut tx, &user, id).await?;
+    ensure_editable(&session)?;
+    ensure_no_attempts(&mut tx, &quiz).await?;
+
+    let question = QuizRepository::update_question(
+        &mut tx,
+        quiz.id,
+        question_id,
+        payload.question_text.trim(),
+        payload.question_type,
+        &normalized,
+        payload.points.unwrap_or(1),
+        tag.as_deref(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(QuizQuestionResponse::from(question)))
+}
+
+pub async fn delete_question(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, question_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<StatusCode> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_managed_quiz(&mut tx, &user, id).await?;
+    ensure_editable(&session)?;
+    ensure_no_attempts(&mut tx, &quiz).await?;
+
+    if !QuizRepository::delete_question(&mut tx, quiz.id, question_id).await? {
+        return Err(AppError::NotFound("Question not found".to_string()));
+    }
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Puts the questions in the given order; every question of the quiz must be listed once.
+pub async fn reorder_questions(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReorderQuizQuestions>,
+) -> AppResult<Json<QuizResponse>> {
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let (session, quiz) = find_managed_quiz(&mut tx, &user, id).await?;
+    ensure_editable(&session)?;
+    ensure_no_attempts(&mut tx, &quiz).await?;
+
+    let current: HashSet<Uuid> = QuizRepository::list_questions(&mut tx, quiz.id)
+        .await?
+        .into_iter()
+        .map(|question| question.id)
+        .collect();
+    let requested: HashSet<Uuid> = payload.question_ids.iter().copied().collect();
+    if requested.len() != payload.question_ids.len() || requested != current {
+        return Err(AppError::Validation(
+            "The new order must list every question of the quiz exactly once".to_string(),
+        ));
+    }
+
+    QuizRepository::reorder_questions(&mut tx, quiz.id, &payload.question_ids).await?;
+    let response = quiz_response(&mut tx, quiz).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
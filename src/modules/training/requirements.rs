@@ -0,0 +1,254 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::app_state::AppState;
+use crate::db::repositories::{EnrollmentRepository, RequirementRepository};
+use crate::db::rls;
+use crate::db::{
+    ComplianceDashboard, ComplianceQuery, ComplianceStatus, EmployeeCompliance, NewTrainingRequirement, Page,
+    Pagination, RequirementComplianceSummary, TrainingRequirement, UserRole,
+};
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthUser;
+
+/// Upper bound on the requirements listed for a single employee.
+const MAX_OWN_REQUIREMENTS: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct RequirementListQuery {
+    #[serde(default)]
+    pub include_inactive: bool,
+}
+
+async fn ensure_company(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    requirement: &NewTrainingRequirement,
+) -> AppResult<()> {
+    if let Some(company_id) = requirement.company_id
+        && !EnrollmentRepository::company_in_tenant(tx, tenant_id, company_id).await?
+    {
+        return Err(AppError::NotFound("Company not found".to_string()));
+    }
+    Ok(())
+}
+
+async fn find_in_tenant(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    id: Uuid,
+) -> AppResult<TrainingRequirement> {
+    let requirement = RequirementRepository::find_by_id(tx, id).await?;
+    if requirement.tenant_id != tenant_id {
+        return Err(AppError::NotFound("Training requirement not found".to_string()));
+    }
+    Ok(requirement)
+}
+
+/// Assesses every matching assignment now, overdue first, then by due date and name.
+async fn assess_compliance(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    requirement_id: Option<Uuid>,
+    company_id: Option<Uuid>,
+    department: Option<&str>,
+    employee_user_id: Option<Uuid>,
+) -> AppResult<Vec<EmployeeCompliance>> {
+    let now = OffsetDateTime::now_utc();
+    let mut rows: Vec<EmployeeCompliance> =
+        RequirementRepository::list_assignments(tx, tenant_id, requirement_id, company_id, department, employee_user_id)
+            .await?
+            .into_iter()
+            .map(|assignment| assignment.assess(now))
+            .collect();
+
+    rows.sort_by(|a, b| {
+        let key = |row: &EmployeeCompliance| {
+            (
+                Reverse(row.status),
+                row.due_at.is_some(),
+                row.due_at,
+                row.last_name.is_none(),
+                row.last_name.clone(),
+                row.first_name.is_none(),
+                row.first_name.clone(),
+                row.employee_user_id,
+                row.requirement_id,
+            )
+        };
+        key(a).cmp(&key(b))
+    });
+    Ok(rows)
+}
+
+/// Per-requirement counts, most overdue first, and the number of distinct employees covered.
+fn summarize(rows: &[EmployeeCompliance]) -> (Vec<RequirementComplianceSummary>, i64) {
+    let mut summaries: BTreeMap<Uuid, RequirementComplianceSummary> = BTreeMap::new();
+    for row in rows {
+        let summary = summaries.entry(row.requirement_id).or_insert_with(|| RequirementComplianceSummary {
+            requirement_id: row.requirement_id,
+            title: row.requirement_title.clone(),
+            employees: 0,
+            compliant: 0,
+            due_soon: 0,
+            overdue: 0,
+        });
+        summary.employees += 1;
+        match row.status {
+            ComplianceStatus::Compliant => summary.compliant += 1,
+            ComplianceStatus::DueSoon => summary.due_soon += 1,
+            ComplianceStatus::Overdue => summary.overdue += 1,
+        }
+    }
+
+    let mut summaries: Vec<_> = summaries.into_values().collect();
+    summaries.sort_by(|a, b| b.overdue.cmp(&a.overdue).then_with(|| a.title.cmp(&b.title)));
+    let employees = rows.iter().map(|row| row.employee_user_id).collect::<HashSet<_>>().len();
+    (summaries, employees as i64)
+}
+
+pub async fn list_requirements(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<RequirementListQuery>,
+) -> AppResult<Json<Vec<TrainingRequirement>>> {
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let requirements = RequirementRepository::list_for_tenant(
+        &mut tx,
+        tenant_id,
+        query.include_inactive && user.is_tenant_admin(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(requirements))
+}
+
+pub async fn create_requirement(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<NewTrainingRequirement>,
+) -> AppResult<(StatusCode, Json<TrainingRequirement>)> {
+    payload.validate()?;
+    user.require_any_role(&[UserRole::TenantAdmin])?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    ensure_company(&mut tx, tenant_id, &payload).await?;
+    let requirement = RequirementRepository::create(&mut tx, tenant_id, user.user_id, &payload).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(requirement)))
+}
+
+pub async fn update_requirement(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<NewTrainingRequirement>,
+) -> AppResult<Json<TrainingRequirement>> {
+    payload.validate()?;
+    user.require_any_role(&[UserRole::TenantAdmin])?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_in_tenant(&mut tx, tenant_id, id).await?;
+    ensure_company(&mut tx, tenant_id, &payload).await?;
+    let requirement = RequirementRepository::update(&mut tx, id, &payload).await?;
+    tx.commit().await?;
+
+    Ok(Json(requirement))
+}
+
+pub async fn deactivate_requirement(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    user.require_any_role(&[UserRole::TenantAdmin])?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    find_in_tenant(&mut tx, tenant_id, id).await?;
+    RequirementRepository::deactivate(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn compliance_dashboard(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ComplianceQuery>,
+) -> AppResult<Json<ComplianceDashboard>> {
+    user.require_any_role(&[UserRole::TenantAdmin, UserRole::OhsSpecialist])?;
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let rows = assess_compliance(&mut tx, tenant_id, None, query.company_id, query.department.as_deref(), None).await?;
+    tx.commit().await?;
+
+    let (requirements, employees) = summarize(&rows);
+    Ok(Json(ComplianceDashboard::new(requirements, employees)))
+}
+
+pub async fn list_compliance(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ComplianceQuery>,
+) -> AppResult<Json<Page<EmployeeCompliance>>> {
+    user.require_any_role(&[UserRole::TenantAdmin, UserRole::OhsSpecialist])?;
+    let tenant_id = user.require_tenant()?;
+    let pagination = Pagination::new(query.page, query.per_page);
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let rows = assess_compliance(
+        &mut tx,
+        tenant_id,
+        query.requirement_id,
+        query.company_id,
+        query.department.as_deref(),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    let rows: Vec<_> = rows
+        .into_iter()
+        .filter(|row| query.status.is_none_or(|status| row.status == status))
+        .collect();
+    let total = rows.len() as i64;
+    let items = rows
+        .into_iter()
+        .skip(pagination.offset() as usize)
+        .take(pagination.limit() as usize)
+        .collect();
+    Ok(Json(Page::new(items, pagination, total)))
+}
+
+pub async fn own_compliance(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<EmployeeCompliance>>> {
+    let tenant_id = user.require_tenant()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    let mut rows = assess_compliance(&mut tx, tenant_id, None, None, None, Some(user.user_id)).await?;
+    tx.commit().await?;
+
+    rows.truncate(MAX_OWN_REQUIREMENTS);
+    Ok(Json(rows))
+}
@@ -0,0 +1,225 @@
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error, info, warn};
+
+use crate::app_state::AppState;
+use crate::core::utils::hls::{
+    master_playlist, parse_probe, poster_args, probe_args, renditions_for, transcode_args, MASTER_PLAYLIST, POSTER,
+};
+use crate::db::repositories::MaterialRepository;
+use crate::db::TrainingMaterial;
+use crate::error::{AppError, AppResult};
+
+/// How often the worker looks for videos to transcode.
+const TRANSCODE_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A claim older than this belongs to a worker that died; the video is taken over.
+const STALE_CLAIM: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// ffprobe and poster grabs read little of the file.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Stored error output of a failed tool run is cut to this many characters.
+const MAX_ERROR_LENGTH: usize = 2000;
+
+/// Files produced by transcoding one video.
+struct Transcoded {
+    renditions: Vec<String>,
+    duration_seconds: f64,
+    poster_key: String,
+}
+
+/// Runs a tool to completion, returning its standard output.
+async fn run<I, S>(program: &str, args: I, timeout: Duration) -> AppResult<Vec<u8>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::InternalServerError(format!("{} could not be started: {}", program, e)))?;
+
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| AppError::InternalServerError(format!("{} did not finish within {:?}", program, timeout)))?
+        .map_err(|e| AppError::InternalServerError(format!("{} failed: {}", program, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let start = stderr.len().saturating_sub(MAX_ERROR_LENGTH);
+        let start = (start..stderr.len()).find(|&i| stderr.is_char_boundary(i)).unwrap_or(stderr.len());
+        return Err(AppError::InternalServerError(format!(
+            "{} exited with {}: {}",
+            program,
+            output.status,
+            stderr[start..].trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+fn content_type_of(file_name: &str) -> &'static str {
+    match Path::new(file_name).extension().and_then(OsStr::to_str) {
+        Some("m3u8") => "application/vnd.apple.mpegurl",
+        Some("ts") => "video/mp2t",
+        Some("jpg") => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn transcode_in(state: &AppState, material: &TrainingMaterial, work_dir: &Path) -> AppResult<Transcoded> {
+    let config = &state.env.transcoding;
+    let out_dir = work_dir.join("out");
+    tokio::fs::create_dir_all(&out_dir)
+        .await
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+    let source = work_dir.join("source");
+    state.storage.download_to_file(&material.file_s3_key, &source).await?;
+    let source = source.to_string_lossy();
+
+    let probe = run(&config.ffprobe_path, probe_args(&source), PROBE_TIMEOUT).await?;
+    let info = parse_probe(&String::from_utf8_lossy(&probe)).map_err(|e| AppError::Validation(e.to_string()))?;
+    let renditions = renditions_for(&info);
+    for rendition in &renditions {
+        tokio::fs::create_dir_all(out_dir.join(rendition.name))
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+    }
+
+    // Encoding runs at a few times real time at worst
+    let timeout = Duration::from_secs_f64(info.duration_seconds * 10.0).max(Duration::from_secs(10 * 60));
+    run(
+        &config.ffmpeg_path,
+        transcode_args(&source, &out_dir.to_string_lossy(), &info, &renditions),
+        timeout,
+    )
+    .await?;
+    let poster = out_dir.join(POSTER);
+    run(&config.ffmpeg_path, poster_args(&source, &poster.to_string_lossy(), &info), PROBE_TIMEOUT).await?;
+
+    for rendition in &renditions {
+        let mut entries = tokio::fs::read_dir(out_dir.join(rendition.name))
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?
+        {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let bytes = tokio::fs::read(entry.path())
+                .await
+                .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+            let key = material.rendition_key(&format!("{}/{}", rendition.name, file_name));
+            state.storage.put(&key, content_type_of(&file_name), bytes).await?;
+        }
+    }
+
+    let poster_key = material.rendition_key(POSTER);
+    let bytes = tokio::fs::read(&poster)
+        .await
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+    state.storage.put(&poster_key, content_type_of(POSTER), bytes).await?;
+
+    // The master playlist goes last, once everything it points to is stored
+    let master = master_playlist(&info, &renditions);
+    state
+        .storage
+        .put(&material.rendition_key(MASTER_PLAYLIST), content_type_of(MASTER_PLAYLIST), master.into_bytes())
+        .await?;
+
+    Ok(Transcoded {
+        renditions: renditions.iter().map(|rendition| rendition.name.to_string()).collect(),
+        duration_seconds: info.duration_seconds,
+        poster_key,
+    })
+}
+
+/// Transcodes a video into HLS renditions in a scratch directory, removed afterwards.
+async fn transcode(state: &AppState, material: &TrainingMaterial) -> AppResult<Transcoded> {
+    let work_dir = Path::new(&state.env.transcoding.work_dir).join(material.id.to_string());
+    let result = transcode_in(state, material, &work_dir).await;
+    if let Err(e) = tokio::fs::remove_dir_all(&work_dir).await {
+        warn!("Removing {} failed: {}", work_dir.display(), e);
+    }
+    result
+}
+
+/// Transcodes every video that is waiting, one at a time, returning how many succeeded.
+///
+/// Videos are claimed in their own transaction, so a long transcode holds no locks. Failures
+/// are recorded on the material and retried on later runs, up to a limit.
+pub async fn transcode_pending_videos(state: &AppState) -> AppResult<usize> {
+    let mut transcoded = 0;
+
+    loop {
+        let mut tx = state.db.begin().await?;
+        let material = MaterialRepository::claim_next_video(&mut tx, STALE_CLAIM.as_secs() as i64).await?;
+        tx.commit().await?;
+        let Some(material) = material else {
+            break;
+        };
+
+        let result = transcode(state, &material).await;
+        let mut tx = state.db.begin().await?;
+        match result {
+            Ok(output) => {
+                MaterialRepository::mark_processed(
+                    &mut tx,
+                    material.id,
+                    &output.renditions,
+                    output.duration_seconds,
+                    &output.poster_key,
+                )
+                .await?;
+                transcoded += 1;
+            }
+            Err(e) => {
+                warn!("Transcoding material {} failed: {}", material.id, e);
+                MaterialRepository::mark_processing_failed(&mut tx, material.id, &e.to_string()).await?;
+            }
+        }
+        tx.commit().await?;
+    }
+
+    Ok(transcoded)
+}
+
+/// Starts the background task that transcodes uploaded videos, unless ffmpeg is missing.
+pub fn spawn_transcode_worker(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let config = &state.env.transcoding;
+        for tool in [&config.ffmpeg_path, &config.ffprobe_path] {
+            if let Err(e) = run(tool, ["-version"], PROBE_TIMEOUT).await {
+                warn!("Videos are not transcoded: {}", e);
+                return;
+            }
+        }
+        info!("Transcoding videos with {}", config.ffmpeg_path);
+
+        let mut interval = tokio::time::interval(TRANSCODE_POLL_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            match transcode_pending_videos(&state).await {
+                Ok(0) => {}
+                Ok(transcoded) => debug!("Transcoded {} videos", transcoded),
+                Err(e) => error!("Transcoding run failed: {}", e),
+            }
+        }
+    })
+}
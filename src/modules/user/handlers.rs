@@ -0,0 +1,38 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::app_state::AppState;
+use crate::core::utils::time_zone::Zone;
+use crate::db::repositories::TimeZoneRepository;
+use crate::db::{rls, UpdateUserTimeZone};
+use crate::error::AppResult;
+use crate::middleware::auth::AuthUser;
+use crate::middleware::time_zone::CallerZone;
+
+#[derive(Debug, Serialize)]
+pub struct TimeZoneResponse {
+    pub time_zone: &'static str,
+}
+
+pub async fn get_time_zone(CallerZone(zone): CallerZone) -> Json<TimeZoneResponse> {
+    Json(TimeZoneResponse { time_zone: zone.name() })
+}
+
+pub async fn update_time_zone(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<UpdateUserTimeZone>,
+) -> AppResult<Json<TimeZoneResponse>> {
+    let zone = payload.time_zone.as_deref().map(Zone::parse).transpose()?;
+
+    let mut tx = rls::begin_for_user(&state.db, &user).await?;
+    TimeZoneRepository::set_for_user(&mut tx, user.user_id, zone.map(|zone| zone.name())).await?;
+    tx.commit().await?;
+
+    let effective = TimeZoneRepository::resolve_for_user(&state.db, user.user_id)
+        .await?
+        .and_then(|name| Zone::parse(&name).ok())
+        .unwrap_or_else(Zone::utc);
+
+    Ok(Json(TimeZoneResponse { time_zone: effective.name() }))
+}
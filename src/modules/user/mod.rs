@@ -0,0 +1,12 @@
+pub mod handlers;
+
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/users/me/time-zone",
+        get(handlers::get_time_zone).put(handlers::update_time_zone),
+    )
+}
@@ -1,3 +0,0 @@
-pub mod ws_handler;
-
-pub use ws_handler::ws_handler;
\ No newline at end of file
@@ -0,0 +1,28 @@
+#[path = "../src/core/utils/appointment.rs"]
+#[allow(dead_code)]
+mod appointment;
+
+use appointment::{check_outcome, OutcomeError, VisitStage};
+use time::macros::datetime;
+
+#[test]
+fn outcomes_are_recorded_from_the_start_time_on() {
+    let starts_at = datetime!(2026-10-18 09:00 UTC);
+
+    assert_eq!(
+        check_outcome(VisitStage::Confirmed, starts_at, datetime!(2026-10-18 08:59:59 UTC)),
+        Err(OutcomeError::NotStarted)
+    );
+    assert_eq!(check_outcome(VisitStage::Confirmed, starts_at, starts_at), Ok(()));
+    assert_eq!(check_outcome(VisitStage::Pending, starts_at, datetime!(2026-10-20 12:00 UTC)), Ok(()));
+}
+
+#[test]
+fn recorded_outcomes_can_be_corrected_but_cancellations_stay() {
+    let starts_at = datetime!(2026-10-18 09:00 UTC);
+    let now = datetime!(2026-10-18 10:00 UTC);
+
+    assert_eq!(check_outcome(VisitStage::Completed, starts_at, now), Ok(()));
+    assert_eq!(check_outcome(VisitStage::NoShow, starts_at, now), Ok(()));
+    assert_eq!(check_outcome(VisitStage::Cancelled, starts_at, now), Err(OutcomeError::Cancelled));
+}
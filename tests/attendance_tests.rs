@@ -0,0 +1,69 @@
+#[path = "../src/core/utils/attendance.rs"]
+#[allow(dead_code)]
+mod attendance;
+
+use attendance::{attended_duration, meets_threshold, Presence};
+use time::macros::datetime;
+use time::Duration;
+
+const START: time::OffsetDateTime = datetime!(2025-05-06 09:00 UTC);
+const END: time::OffsetDateTime = datetime!(2025-05-06 10:00 UTC);
+
+#[test]
+fn time_outside_the_session_window_is_not_counted() {
+    let events = [
+        (datetime!(2025-05-06 08:50 UTC), Presence::Join),
+        (datetime!(2025-05-06 09:20 UTC), Presence::Leave),
+        (datetime!(2025-05-06 09:50 UTC), Presence::Join),
+        (datetime!(2025-05-06 10:15 UTC), Presence::Leave),
+    ];
+
+    assert_eq!(attended_duration(&events, START, END, END), Duration::minutes(30));
+}
+
+#[test]
+fn overlapping_connections_are_counted_once() {
+    // Laptop and phone joined at the same time
+    let events = [
+        (datetime!(2025-05-06 09:00 UTC), Presence::Join),
+        (datetime!(2025-05-06 09:10 UTC), Presence::Join),
+        (datetime!(2025-05-06 09:30 UTC), Presence::Leave),
+        (datetime!(2025-05-06 09:40 UTC), Presence::Leave),
+    ];
+
+    assert_eq!(attended_duration(&events, START, END, END), Duration::minutes(40));
+}
+
+#[test]
+fn open_joins_count_until_the_cut_off_and_stray_leaves_are_ignored() {
+    let events = [
+        (datetime!(2025-05-06 09:05 UTC), Presence::Leave),
+        (datetime!(2025-05-06 09:15 UTC), Presence::Join),
+    ];
+
+    assert_eq!(
+        attended_duration(&events, START, END, datetime!(2025-05-06 09:45 UTC)),
+        Duration::minutes(30)
+    );
+    assert_eq!(attended_duration(&events, START, END, END), Duration::minutes(45));
+}
+
+#[test]
+fn reconnects_at_the_same_instant_keep_the_interval_open() {
+    let events = [
+        (datetime!(2025-05-06 09:30 UTC), Presence::Leave),
+        (datetime!(2025-05-06 09:00 UTC), Presence::Join),
+        (datetime!(2025-05-06 09:30 UTC), Presence::Join),
+        (datetime!(2025-05-06 09:45 UTC), Presence::Leave),
+    ];
+
+    assert_eq!(attended_duration(&events, START, END, END), Duration::minutes(45));
+}
+
+#[test]
+fn threshold_is_a_share_of_the_session_length() {
+    assert!(meets_threshold(Duration::minutes(45), Duration::hours(1), 75));
+    assert!(!meets_threshold(Duration::minutes(44), Duration::hours(1), 75));
+    assert!(meets_threshold(Duration::ZERO, Duration::hours(1), 0));
+    assert!(!meets_threshold(Duration::hours(1), Duration::ZERO, 75));
+}
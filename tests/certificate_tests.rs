@@ -0,0 +1,87 @@
+#[path = "../src/core/utils/certificate.rs"]
+#[allow(dead_code)]
+mod certificate;
+#[path = "../src/core/utils/pdf.rs"]
+#[allow(dead_code)]
+mod pdf;
+
+use certificate::{
+    generate_verification_code, holder_display_name, normalize_verification_code, parse_hex_color,
+    render_certificate, verification_code_from_payload, CertificateBranding, CertificateContent,
+};
+use serde_json::json;
+
+#[test]
+fn generated_codes_are_grouped_and_normalize_to_themselves() {
+    let code = generate_verification_code();
+
+    assert_eq!(code.len(), 14);
+    assert_eq!(code.matches('-').count(), 2);
+    assert_eq!(normalize_verification_code(&code), Some(code.clone()));
+    assert_ne!(code, generate_verification_code());
+}
+
+#[test]
+fn typed_codes_are_normalized() {
+    assert_eq!(
+        normalize_verification_code(" 7kq2 m9xd-4trh "),
+        Some("7KQ2-M9XD-4TRH".to_string())
+    );
+    // Look-alike letters are read as digits
+    assert_eq!(normalize_verification_code("OIL0-0000-0000"), Some("0110-0000-0000".to_string()));
+    assert_eq!(normalize_verification_code("7KQ2-M9XD-4TR"), None);
+    assert_eq!(normalize_verification_code("7KQ2-M9XD-4TRU"), None);
+    assert_eq!(normalize_verification_code("7KQ2-M9XD-4TRĞ"), None);
+}
+
+#[test]
+fn codes_are_taken_from_qr_payloads() {
+    assert_eq!(
+        verification_code_from_payload("https://ohs.example.com/certificates/verify/7kq2-m9xd-4trh?utm=qr"),
+        Some("7KQ2-M9XD-4TRH".to_string())
+    );
+    assert_eq!(verification_code_from_payload("7KQ2M9XD4TRH/"), Some("7KQ2-M9XD-4TRH".to_string()));
+    assert_eq!(verification_code_from_payload("https://ohs.example.com/certificates/verify/"), None);
+}
+
+#[test]
+fn holders_are_named_by_first_name_and_initial() {
+    assert_eq!(holder_display_name("Ayşe Nur Yılmaz"), "Ayşe Y.");
+    assert_eq!(holder_display_name(" john  smith "), "john S.");
+    assert_eq!(holder_display_name("Cher"), "C.");
+    assert_eq!(holder_display_name(""), "");
+}
+
+#[test]
+fn branding_falls_back_to_defaults() {
+    assert_eq!(parse_hex_color("#1e88e5"), Some((0x1E, 0x88, 0xE5)));
+    assert_eq!(parse_hex_color("#fff"), Some((255, 255, 255)));
+    assert_eq!(parse_hex_color("blue"), None);
+
+    let branding = CertificateBranding::from_settings(&json!({
+        "primaryColor": "not-a-color",
+        "certificateFooter": "Registered OHS service provider no. 42"
+    }));
+    assert_eq!(branding.accent, CertificateBranding::default().accent);
+    assert_eq!(branding.footer.as_deref(), Some("Registered OHS service provider no. 42"));
+}
+
+#[test]
+fn certificates_render_as_pdf() {
+    let content = CertificateContent {
+        issuer: "Güvenli İş OSGB".to_string(),
+        company_name: "Acme Manufacturing".to_string(),
+        employee_name: "Ayşe Yılmaz".to_string(),
+        training_title: "Working at heights: harness inspection, anchor points and rescue planning for maintenance crews"
+            .to_string(),
+        completed_on: "2026-10-18".to_string(),
+        host_name: Some("Mehmet Öz".to_string()),
+        verification_code: "7KQ2-M9XD-4TRH".to_string(),
+        verification_url: "https://ohs.example.com/certificates/verify/7KQ2-M9XD-4TRH".to_string(),
+    };
+
+    let pdf = render_certificate(&content, &CertificateBranding::default()).unwrap();
+
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(pdf.len() > 10_000);
+}
@@ -0,0 +1,62 @@
+#[path = "../src/core/utils/compliance.rs"]
+#[allow(dead_code)]
+mod compliance;
+
+use compliance::{add_months, due_at, standing, Standing};
+use time::macros::datetime;
+
+#[test]
+fn recurrence_keeps_the_day_or_falls_back_to_the_end_of_the_month() {
+    assert_eq!(add_months(datetime!(2026-03-15 09:30 UTC), 12), datetime!(2027-03-15 09:30 UTC));
+    assert_eq!(add_months(datetime!(2026-11-30 00:00 UTC), 3), datetime!(2027-02-28 00:00 UTC));
+    assert_eq!(add_months(datetime!(2027-01-31 12:00 UTC), 1), datetime!(2027-02-28 12:00 UTC));
+    assert_eq!(add_months(datetime!(2028-01-31 12:00 UTC), 1), datetime!(2028-02-29 12:00 UTC));
+    assert_eq!(add_months(datetime!(2028-02-29 08:00 UTC), 12), datetime!(2029-02-28 08:00 UTC));
+    assert_eq!(add_months(datetime!(2026-12-31 23:59 UTC), 120), datetime!(2036-12-31 23:59 UTC));
+    // Offsets are resolved to UTC first, so the month boundary is the one in UTC
+    assert_eq!(add_months(datetime!(2026-02-01 01:00 +02:00), 1), datetime!(2026-02-28 23:00 UTC));
+}
+
+#[test]
+fn only_completed_recurring_requirements_fall_due() {
+    let completed = datetime!(2026-01-31 10:00 UTC);
+    assert_eq!(due_at(Some(completed), Some(6)), Some(datetime!(2026-07-31 10:00 UTC)));
+    assert_eq!(due_at(Some(completed), None), None);
+    assert_eq!(due_at(None, Some(6)), None);
+}
+
+#[test]
+fn the_due_date_itself_is_overdue() {
+    let completed = datetime!(2025-10-18 12:00 UTC);
+    let due = due_at(Some(completed), Some(12));
+
+    assert_eq!(standing(Some(completed), due, 30, datetime!(2026-10-18 11:59:59 UTC)), Standing::DueSoon);
+    assert_eq!(standing(Some(completed), due, 30, datetime!(2026-10-18 12:00 UTC)), Standing::Overdue);
+    assert_eq!(standing(Some(completed), due, 30, datetime!(2027-01-01 00:00 UTC)), Standing::Overdue);
+}
+
+#[test]
+fn the_due_soon_window_includes_its_last_day() {
+    let completed = datetime!(2025-10-18 12:00 UTC);
+    let due = due_at(Some(completed), Some(12));
+
+    assert_eq!(standing(Some(completed), due, 30, datetime!(2026-09-18 11:59:59 UTC)), Standing::Compliant);
+    assert_eq!(standing(Some(completed), due, 30, datetime!(2026-09-18 12:00 UTC)), Standing::DueSoon);
+    // Without a warning window the requirement stays compliant up to the due date
+    assert_eq!(standing(Some(completed), due, 0, datetime!(2026-10-18 11:59:59 UTC)), Standing::Compliant);
+}
+
+#[test]
+fn never_completed_is_overdue_and_completed_one_offs_stay_compliant() {
+    let now = datetime!(2026-10-18 12:00 UTC);
+    assert_eq!(standing(None, None, 30, now), Standing::Overdue);
+
+    let completed = datetime!(2016-01-01 00:00 UTC);
+    assert_eq!(standing(Some(completed), due_at(Some(completed), None), 30, now), Standing::Compliant);
+}
+
+#[test]
+fn standings_order_by_urgency() {
+    assert!(Standing::Compliant < Standing::DueSoon);
+    assert!(Standing::DueSoon < Standing::Overdue);
+}
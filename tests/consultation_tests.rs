@@ -0,0 +1,35 @@
+#[path = "../src/core/utils/consultation.rs"]
+#[allow(dead_code)]
+mod consultation;
+
+use consultation::{in_summary, NoteAccess};
+use sqlx::types::Uuid;
+
+#[test]
+fn only_the_professional_edits_the_note() {
+    let (professional, employee, colleague) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+
+    let access = NoteAccess::of(professional, professional, employee);
+    assert_eq!(access, NoteAccess::Author);
+    assert!(access.can_edit());
+    assert!(access.can_read_summary());
+
+    let access = NoteAccess::of(employee, professional, employee);
+    assert_eq!(access, NoteAccess::Employee);
+    assert!(!access.can_edit());
+    assert!(access.can_read_summary());
+
+    // Another professional of the tenant is neither
+    let access = NoteAccess::of(colleague, professional, employee);
+    assert_eq!(access, NoteAccess::None);
+    assert!(!access.can_edit());
+    assert!(!access.can_read_summary());
+}
+
+#[test]
+fn summaries_hold_the_filled_in_shared_sections() {
+    assert!(in_summary(true, "Keep the wrist brace on for two weeks"));
+    assert!(!in_summary(false, "Suspected repetitive strain"));
+    assert!(!in_summary(true, ""));
+    assert!(!in_summary(true, "  \n\t"));
+}
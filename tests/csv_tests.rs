@@ -0,0 +1,40 @@
+#[path = "../src/core/utils/csv.rs"]
+#[allow(dead_code)]
+mod csv;
+
+use csv::{escape_field, CsvWriter};
+
+#[test]
+fn plain_fields_are_left_as_is() {
+    assert_eq!(escape_field("Fire safety"), "Fire safety");
+    assert_eq!(escape_field(""), "");
+}
+
+#[test]
+fn fields_with_separators_or_quotes_are_quoted() {
+    assert_eq!(escape_field("PPE, basics"), "\"PPE, basics\"");
+    assert_eq!(escape_field("The \"new\" rules"), "\"The \"\"new\"\" rules\"");
+    assert_eq!(escape_field("line one\nline two"), "\"line one\nline two\"");
+}
+
+#[test]
+fn formulas_are_shown_as_text() {
+    assert_eq!(escape_field("=HYPERLINK(\"http://evil\")"), "\"'=HYPERLINK(\"\"http://evil\"\")\"");
+    assert_eq!(escape_field("+1+2"), "'+1+2");
+    assert_eq!(escape_field("-2+3,A1"), "\"'-2+3,A1\"");
+    assert_eq!(escape_field("@SUM(A1:A9)"), "'@SUM(A1:A9)");
+    assert_eq!(escape_field("\t=1"), "'\t=1");
+    // Numbers and text with a sign further in stay as they are
+    assert_eq!(escape_field("-3"), "-3");
+    assert_eq!(escape_field("+4.5"), "+4.5");
+    assert_eq!(escape_field("a=b"), "a=b");
+}
+
+#[test]
+fn rows_end_with_crlf() {
+    let mut writer = CsvWriter::new();
+    writer.row(["title", "rating"]);
+    writer.row(vec!["Ladders, part 1".to_string(), "4.5".to_string()]);
+
+    assert_eq!(writer.finish(), "title,rating\r\n\"Ladders, part 1\",4.5\r\n");
+}
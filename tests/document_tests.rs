@@ -0,0 +1,116 @@
+#[path = "../src/core/utils/document.rs"]
+#[allow(dead_code)]
+mod document;
+
+use std::io::Write;
+
+use document::{page_count, zip_entries};
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use printpdf::{Mm, PdfDocument};
+
+const PPTX: &str = "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+const ODP: &str = "application/vnd.oasis.opendocument.presentation";
+
+fn pdf_with_pages(pages: usize) -> Vec<u8> {
+    let (doc, _, _) = PdfDocument::new("Handout", Mm(210.0), Mm(297.0), "Layer");
+    for _ in 1..pages {
+        doc.add_page(Mm(210.0), Mm(297.0), "Layer");
+    }
+    doc.save_to_bytes().unwrap()
+}
+
+/// Zip archive of the given files, deflated when `deflate` is set. CRCs are left at zero, the
+/// counter does not check them.
+fn zip(files: &[(&str, &str)], deflate: bool) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+    for (name, content) in files {
+        let data = if deflate {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(content.as_bytes()).unwrap();
+            encoder.finish().unwrap()
+        } else {
+            content.as_bytes().to_vec()
+        };
+        let method: u16 = if deflate { 8 } else { 0 };
+        let offset = archive.len() as u32;
+
+        archive.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        archive.extend_from_slice(&[20, 0, 0, 0]);
+        archive.extend_from_slice(&method.to_le_bytes());
+        archive.extend_from_slice(&[0; 8]);
+        archive.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        archive.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        archive.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&0u16.to_le_bytes());
+        archive.extend_from_slice(name.as_bytes());
+        archive.extend_from_slice(&data);
+
+        directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        directory.extend_from_slice(&[20, 0, 20, 0, 0, 0]);
+        directory.extend_from_slice(&method.to_le_bytes());
+        directory.extend_from_slice(&[0; 8]);
+        directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        directory.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        directory.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        directory.extend_from_slice(&[0; 12]);
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+
+    let directory_offset = archive.len() as u32;
+    archive.extend_from_slice(&directory);
+    archive.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    archive.extend_from_slice(&[0; 4]);
+    archive.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+    archive
+}
+
+#[test]
+fn pdf_pages_are_counted() {
+    assert_eq!(page_count("application/pdf", &pdf_with_pages(1)), Some(1));
+    assert_eq!(page_count("application/pdf", &pdf_with_pages(4)), Some(4));
+    assert_eq!(page_count("application/pdf", b"%PDF-1.7 truncated"), None);
+}
+
+#[test]
+fn pptx_slides_are_counted_from_the_archive_listing() {
+    let deck = zip(
+        &[
+            ("[Content_Types].xml", "<Types/>"),
+            ("ppt/presentation.xml", "<p:presentation/>"),
+            ("ppt/slides/slide1.xml", "<p:sld/>"),
+            ("ppt/slides/slide2.xml", "<p:sld/>"),
+            ("ppt/slides/slide10.xml", "<p:sld/>"),
+            ("ppt/slides/_rels/slide1.xml.rels", "<Relationships/>"),
+            ("ppt/slideLayouts/slideLayout1.xml", "<p:sldLayout/>"),
+        ],
+        false,
+    );
+
+    assert_eq!(zip_entries(&deck).unwrap().len(), 7);
+    assert_eq!(page_count(PPTX, &deck), Some(3));
+}
+
+#[test]
+fn odp_pages_are_counted_from_deflated_content() {
+    let content = r#"<office:presentation><draw:page draw:name="1"/><draw:page draw:name="2">
+        <draw:page-thumbnail/></draw:page></office:presentation>"#;
+    let deck = zip(&[("mimetype", ODP), ("content.xml", content)], true);
+
+    assert_eq!(page_count(ODP, &deck), Some(2));
+}
+
+#[test]
+fn unreadable_or_empty_decks_have_no_count() {
+    assert_eq!(page_count(PPTX, b"PK\x03\x04 not really a zip"), None);
+    assert_eq!(page_count(PPTX, &zip(&[("ppt/presentation.xml", "<p:presentation/>")], false)), None);
+    assert_eq!(page_count(ODP, &zip(&[("mimetype", ODP)], false)), None);
+    // Legacy PowerPoint files are not parsed
+    assert_eq!(page_count("application/vnd.ms-powerpoint", b"\xd0\xcf\x11\xe0"), None);
+}
@@ -0,0 +1,90 @@
+#[path = "../src/core/utils/exif.rs"]
+#[allow(dead_code)]
+mod exif;
+
+use exif::{crc32, strip_gps};
+
+/// Little endian TIFF with an orientation entry and a GPS directory holding a latitude.
+fn tiff_with_gps() -> Vec<u8> {
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II\x2a\x00");
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    // IFD0 at 8: orientation, GPS pointer, next IFD 0
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    tiff.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0]);
+    tiff.extend_from_slice(&[0x25, 0x88, 4, 0, 1, 0, 0, 0]);
+    tiff.extend_from_slice(&38u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    // GPS IFD at 38: latitude ref "N", latitude as 3 rationals at 68, next IFD 0
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    tiff.extend_from_slice(&[0x01, 0x00, 2, 0, 2, 0, 0, 0, b'N', 0, 0, 0]);
+    tiff.extend_from_slice(&[0x02, 0x00, 5, 0, 3, 0, 0, 0]);
+    tiff.extend_from_slice(&68u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    for value in [47u32, 1, 22, 1, 1234, 100] {
+        tiff.extend_from_slice(&value.to_le_bytes());
+    }
+    tiff
+}
+
+fn jpeg_with(tiff: &[u8]) -> Vec<u8> {
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    jpeg.extend_from_slice(b"Exif\0\0");
+    jpeg.extend_from_slice(tiff);
+    jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9]);
+    jpeg
+}
+
+#[test]
+fn gps_is_removed_from_jpeg_exif() {
+    let tiff = tiff_with_gps();
+    let mut jpeg = jpeg_with(&tiff);
+    let length = jpeg.len();
+
+    assert!(strip_gps("image/jpeg", &mut jpeg));
+    assert_eq!(jpeg.len(), length);
+
+    let stripped = &jpeg[12..12 + tiff.len()];
+    // Only the orientation entry is left, followed by the next IFD offset
+    assert_eq!(&stripped[8..10], &1u16.to_le_bytes());
+    assert_eq!(&stripped[10..22], &tiff[10..22]);
+    assert_eq!(&stripped[22..26], &0u32.to_le_bytes());
+    // The GPS directory and its values are gone
+    assert!(stripped[26..].iter().all(|&byte| byte == 0));
+    // Image data is untouched
+    assert_eq!(&jpeg[length - 8..], &[0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9]);
+
+    // Nothing is left to strip the second time
+    assert!(!strip_gps("image/jpeg", &mut jpeg));
+}
+
+#[test]
+fn png_chunk_checksum_is_updated() {
+    assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+
+    let tiff = tiff_with_gps();
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend_from_slice(&(tiff.len() as u32).to_be_bytes());
+    png.extend_from_slice(b"eXIf");
+    png.extend_from_slice(&tiff);
+    png.extend_from_slice(&crc32(&[b"eXIf".as_slice(), &tiff].concat()).to_be_bytes());
+
+    assert!(strip_gps("image/png", &mut png));
+    let data_end = 16 + tiff.len();
+    assert_eq!(&png[data_end..data_end + 4], &crc32(&png[12..data_end]).to_be_bytes());
+}
+
+#[test]
+fn images_without_gps_are_left_alone() {
+    let mut tiff = tiff_with_gps();
+    // Turn the GPS pointer into an unrelated tag
+    tiff[22] = 0x31;
+    tiff[23] = 0x01;
+    let mut jpeg = jpeg_with(&tiff);
+    let original = jpeg.clone();
+
+    assert!(!strip_gps("image/jpeg", &mut jpeg));
+    assert_eq!(jpeg, original);
+    assert!(!strip_gps("application/pdf", &mut b"%PDF-1.7".to_vec()));
+}
@@ -0,0 +1,112 @@
+#[path = "../src/core/utils/hls.rs"]
+#[allow(dead_code)]
+mod hls;
+
+use std::time::Duration;
+
+use hls::{
+    master_playlist, output_size, parse_probe, playback_ttl, renditions_for, rewrite_uris, sign_playback,
+    transcode_args, verify_playback, ProbeError, VideoInfo, LADDER,
+};
+
+fn landscape() -> VideoInfo {
+    VideoInfo {
+        width: 1920,
+        height: 1080,
+        duration_seconds: 95.5,
+        has_audio: true,
+    }
+}
+
+#[test]
+fn probe_output_is_read_with_rotation() {
+    let json = r#"{
+        "streams": [
+            {"codec_type": "video", "width": 1920, "height": 1080, "side_data_list": [{"rotation": -90}]},
+            {"codec_type": "audio"}
+        ],
+        "format": {"duration": "12.480000"}
+    }"#;
+    assert_eq!(
+        parse_probe(json),
+        Ok(VideoInfo {
+            width: 1080,
+            height: 1920,
+            duration_seconds: 12.48,
+            has_audio: true,
+        })
+    );
+
+    let audio_only = r#"{"streams": [{"codec_type": "audio"}], "format": {"duration": "3.0"}}"#;
+    assert_eq!(parse_probe(audio_only), Err(ProbeError::NoVideo));
+    assert!(matches!(parse_probe("not json"), Err(ProbeError::Malformed(_))));
+}
+
+#[test]
+fn ladder_does_not_upscale() {
+    let names = |info: &VideoInfo| renditions_for(info).iter().map(|r| r.name).collect::<Vec<_>>();
+    assert_eq!(names(&landscape()), ["1080p", "720p", "480p", "360p"]);
+
+    let portrait_720 = VideoInfo { width: 720, height: 1280, ..landscape() };
+    assert_eq!(names(&portrait_720), ["720p", "480p", "360p"]);
+    assert_eq!(output_size(&portrait_720, &LADDER[2]), (480, 852));
+
+    let tiny = VideoInfo { width: 320, height: 240, ..landscape() };
+    assert_eq!(names(&tiny), ["360p"]);
+}
+
+#[test]
+fn transcode_maps_every_rendition() {
+    let info = landscape();
+    let renditions = renditions_for(&info);
+    let args = transcode_args("/work/source", "/work/out/", &info, &renditions[2..]);
+    let arg = |flag: &str| args[args.iter().position(|a| a == flag).unwrap() + 1].clone();
+
+    assert_eq!(arg("-filter_complex"), "[0:v]split=2[v0][v1];[v0]scale=-2:480[v0out];[v1]scale=-2:360[v1out]");
+    assert_eq!(arg("-var_stream_map"), "v:0,a:0,name:480p v:1,a:1,name:360p");
+    assert_eq!(arg("-b:v:1"), "800k");
+    assert_eq!(arg("-hls_segment_filename"), "/work/out/%v/segment_%04d.ts");
+    assert_eq!(args.last().unwrap(), "/work/out/%v/index.m3u8");
+
+    let silent = VideoInfo { has_audio: false, ..info };
+    let args = transcode_args("/work/source", "/work/out", &silent, &renditions[..1]);
+    assert!(!args.iter().any(|a| a == "0:a:0"));
+    assert!(args.contains(&"v:0,name:1080p".to_string()));
+}
+
+#[test]
+fn master_playlist_lists_renditions() {
+    let info = landscape();
+    let playlist = master_playlist(&info, &renditions_for(&info)[1..2]);
+    assert_eq!(
+        playlist,
+        "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-INDEPENDENT-SEGMENTS\n\
+         #EXT-X-STREAM-INF:BANDWIDTH=3124000,RESOLUTION=1280x720,CODECS=\"avc1.4d4028,mp4a.40.2\"\n\
+         720p/index.m3u8\n"
+    );
+}
+
+#[test]
+fn playlist_uris_are_rewritten() {
+    let playlist = "#EXTM3U\r\n#EXT-X-MAP:URI=\"init.mp4\"\r\n#EXTINF:6.0,\r\n\
+                    segment_0000.ts\r\n\r\n#EXT-X-ENDLIST\r\n";
+    assert_eq!(
+        rewrite_uris(playlist, |uri| format!("https://cdn/{}?sig", uri)),
+        "#EXTM3U\n#EXT-X-MAP:URI=\"https://cdn/init.mp4?sig\"\n#EXTINF:6.0,\n\
+         https://cdn/segment_0000.ts?sig\n#EXT-X-ENDLIST\n"
+    );
+}
+
+#[test]
+fn playback_links_are_signed_and_expire() {
+    let signature = sign_playback(b"secret", "material", 1_000);
+    assert!(verify_playback(b"secret", "material", 1_000, &signature, 999));
+    assert!(!verify_playback(b"secret", "material", 1_000, &signature, 1_001));
+    assert!(!verify_playback(b"secret", "other", 1_000, &signature, 999));
+    assert!(!verify_playback(b"other", "material", 1_000, &signature, 999));
+    assert!(!verify_playback(b"secret", "material", 2_000, &signature, 999));
+
+    assert_eq!(playback_ttl(60.0), Duration::from_secs(3600));
+    assert_eq!(playback_ttl(2.0 * 3600.0), Duration::from_secs(6 * 3600));
+    assert_eq!(playback_ttl(10.0 * 3600.0), Duration::from_secs(12 * 3600));
+}
@@ -0,0 +1,38 @@
+#[path = "../src/core/utils/http_range.rs"]
+#[allow(dead_code)]
+mod http_range;
+
+use http_range::{content_range, parse_range, RangeNotSatisfiable};
+
+#[test]
+fn parses_bounded_and_open_ranges() {
+    assert_eq!(parse_range("bytes=0-99", 1000), Ok(Some(0..=99)));
+    assert_eq!(parse_range("bytes=900-", 1000), Ok(Some(900..=999)));
+    assert_eq!(parse_range("bytes=900-5000", 1000), Ok(Some(900..=999)));
+}
+
+#[test]
+fn parses_suffix_ranges() {
+    assert_eq!(parse_range("bytes=-100", 1000), Ok(Some(900..=999)));
+    assert_eq!(parse_range("bytes=-5000", 1000), Ok(Some(0..=999)));
+}
+
+#[test]
+fn rejects_ranges_past_the_end() {
+    assert_eq!(parse_range("bytes=1000-", 1000), Err(RangeNotSatisfiable { size: 1000 }));
+    assert_eq!(parse_range("bytes=-0", 1000), Err(RangeNotSatisfiable { size: 1000 }));
+    assert_eq!(parse_range("bytes=0-", 0), Err(RangeNotSatisfiable { size: 0 }));
+}
+
+#[test]
+fn ignores_ranges_it_does_not_serve() {
+    assert_eq!(parse_range("items=0-10", 1000), Ok(None));
+    assert_eq!(parse_range("bytes=0-10,20-30", 1000), Ok(None));
+    assert_eq!(parse_range("bytes=50-10", 1000), Ok(None));
+    assert_eq!(parse_range("bytes=abc", 1000), Ok(None));
+}
+
+#[test]
+fn formats_content_range() {
+    assert_eq!(content_range(&(0..=99), 1000), "bytes 0-99/1000");
+}
@@ -0,0 +1,64 @@
+#[path = "../src/core/utils/ical.rs"]
+#[allow(dead_code)]
+mod ical;
+
+use ical::{escape_text, format_utc, Calendar, CalendarEvent};
+use time::macros::datetime;
+
+fn event(cancelled: bool) -> CalendarEvent {
+    CalendarEvent {
+        uid: "appointment-1@ohs-backend".to_string(),
+        sequence: 3,
+        last_modified: datetime!(2025-03-01 12:00 UTC),
+        start: datetime!(2025-03-31 10:00 +2),
+        end: datetime!(2025-03-31 10:30 +2),
+        summary: "OHS consultation".to_string(),
+        description: Some("Back pain; follow-up, second visit".to_string()),
+        url: Some("https://ohs.example.com/appointments/1/join".to_string()),
+        cancelled,
+    }
+}
+
+#[test]
+fn times_are_written_in_utc() {
+    assert_eq!(format_utc(datetime!(2025-03-31 10:00 +2)), "20250331T080000Z");
+}
+
+#[test]
+fn text_values_are_escaped() {
+    assert_eq!(escape_text("a;b,c\\d\r\ne"), "a\\;b\\,c\\\\d\\ne");
+}
+
+#[test]
+fn calendar_uses_crlf_and_reports_status() {
+    let rendered = Calendar::new("Schedule").with_events([event(false)]).render();
+
+    assert!(rendered.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(rendered.ends_with("END:VCALENDAR\r\n"));
+    assert!(rendered.contains("\r\nDTSTART:20250331T080000Z\r\n"));
+    assert!(rendered.contains("\r\nSEQUENCE:3\r\n"));
+    assert!(rendered.contains("\r\nSTATUS:CONFIRMED\r\n"));
+    assert!(rendered.contains("DESCRIPTION:Back pain\\; follow-up\\, second visit"));
+}
+
+#[test]
+fn cancelled_events_are_published_as_cancelled() {
+    let rendered = Calendar::new("Schedule").with_events([event(true)]).render();
+
+    assert!(rendered.contains("\r\nSTATUS:CANCELLED\r\n"));
+}
+
+#[test]
+fn long_lines_are_folded_on_character_boundaries() {
+    let mut long = event(false);
+    long.summary = "İş sağlığı ve güvenliği eğitimi ".repeat(6);
+
+    let rendered = Calendar::new("Schedule").with_events([long.clone()]).render();
+
+    for line in rendered.split("\r\n") {
+        assert!(line.len() <= 75, "line exceeds 75 octets: {:?}", line);
+    }
+
+    let unfolded = rendered.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!("SUMMARY:{}", long.summary)));
+}
@@ -0,0 +1,81 @@
+#[path = "../src/core/utils/file_type.rs"]
+#[allow(dead_code)]
+mod file_type;
+#[path = "../src/core/utils/material.rs"]
+#[allow(dead_code)]
+mod material;
+
+use file_type::sniff_content_type;
+use material::{check_content, check_upload, normalize_content_type, sanitize_file_name, MaterialKind, UploadError};
+
+#[test]
+fn content_types_are_normalized() {
+    assert_eq!(normalize_content_type(" Video/MP4 "), "video/mp4");
+    assert_eq!(normalize_content_type("text/plain; charset=utf-8"), "text/plain");
+}
+
+#[test]
+fn uploads_must_match_their_kind() {
+    assert_eq!(check_upload(MaterialKind::Video, "video/mp4", 1024), Ok("video/mp4".to_string()));
+    assert_eq!(check_upload(MaterialKind::Slides, "application/pdf", 1024), Ok("application/pdf".to_string()));
+    assert!(matches!(
+        check_upload(MaterialKind::Pdf, "video/mp4", 1024),
+        Err(UploadError::ContentType { .. })
+    ));
+    assert!(matches!(
+        check_upload(MaterialKind::Other, "application/x-msdownload", 1024),
+        Err(UploadError::ContentType { .. })
+    ));
+}
+
+#[test]
+fn uploads_are_limited_in_size() {
+    assert_eq!(check_upload(MaterialKind::Pdf, "application/pdf", 0), Err(UploadError::Empty));
+
+    let limit = MaterialKind::Pdf.max_bytes();
+    assert!(check_upload(MaterialKind::Pdf, "application/pdf", limit).is_ok());
+    assert!(matches!(
+        check_upload(MaterialKind::Pdf, "application/pdf", limit + 1),
+        Err(UploadError::TooLarge { .. })
+    ));
+    assert!(check_upload(MaterialKind::Video, "video/webm", limit + 1).is_ok());
+}
+
+#[test]
+fn file_names_keep_only_their_last_segment() {
+    assert_eq!(sanitize_file_name("C:\\Users\\me\\Fire drill.mp4"), Ok("Fire drill.mp4".to_string()));
+    assert_eq!(sanitize_file_name("../../etc/passwd"), Ok("passwd".to_string()));
+    assert_eq!(sanitize_file_name("say \"hi\"\n.pdf"), Ok("say hi.pdf".to_string()));
+    assert_eq!(sanitize_file_name("uploads/"), Err(UploadError::FileName));
+    assert_eq!(sanitize_file_name(".."), Err(UploadError::FileName));
+}
+
+#[test]
+fn content_is_sniffed_from_magic_numbers() {
+    assert_eq!(sniff_content_type(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n"), Some("application/pdf"));
+    assert_eq!(sniff_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+    assert_eq!(sniff_content_type("name;größe\r\nA;1\r\n".as_bytes()), Some("text/plain"));
+    // A multi-byte character cut off at the end of the head is still text
+    assert_eq!(sniff_content_type(&"ü".as_bytes()[..1]), Some("text/plain"));
+    assert_eq!(sniff_content_type(b"\x00\x01\x02binary"), None);
+}
+
+#[test]
+fn content_must_match_the_declared_type() {
+    assert_eq!(check_content("application/pdf", Some("application/pdf")), Ok(()));
+    assert_eq!(check_content("text/csv", Some("text/plain")), Ok(()));
+    // Office documents may only sniff as the archive they are stored in
+    let docx = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+    assert_eq!(check_content(docx, Some("application/zip")), Ok(()));
+    assert_eq!(check_content("application/vnd.ms-powerpoint", Some("application/x-ole-storage")), Ok(()));
+
+    assert_eq!(
+        check_content("application/pdf", Some("application/x-executable")),
+        Err(UploadError::ContentMismatch {
+            declared: "application/pdf".to_string(),
+            actual: Some("application/x-executable".to_string()),
+        })
+    );
+    assert!(check_content("text/plain", Some("text/html")).is_err());
+    assert!(check_content("video/mp4", None).is_err());
+}
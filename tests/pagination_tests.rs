@@ -0,0 +1,24 @@
+#[path = "../src/db/models/pagination.rs"]
+#[allow(dead_code)]
+mod pagination;
+
+use pagination::{Page, Pagination};
+
+#[test]
+fn pagination_defaults_and_clamps() {
+    let default = Pagination::new(None, None);
+    assert_eq!((default.page, default.per_page, default.offset()), (1, 20, 0));
+
+    let clamped = Pagination::new(Some(0), Some(1000));
+    assert_eq!((clamped.page, clamped.limit()), (1, 100));
+
+    assert_eq!(Pagination::new(Some(3), Some(25)).offset(), 50);
+}
+
+#[test]
+fn page_map_keeps_metadata() {
+    let page = Page::new(vec![1, 2, 3], Pagination::new(Some(2), Some(3)), 8).map(|n| n * 10);
+
+    assert_eq!(page.items, vec![10, 20, 30]);
+    assert_eq!((page.page, page.per_page, page.total), (2, 3, 8));
+}
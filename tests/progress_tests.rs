@@ -0,0 +1,82 @@
+#[path = "../src/core/utils/progress.rs"]
+#[allow(dead_code)]
+mod progress;
+
+use std::time::Duration;
+
+use progress::{add_pages, add_watched, percent_of, resume_position, watched_seconds, CompletionRule, Consumption};
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+#[test]
+fn watched_ranges_are_merged() {
+    let ranges = add_watched(&[], (0.0, 20.0), Some(600.0), HOUR);
+    let ranges = add_watched(&ranges, (100.0, 130.0), Some(600.0), HOUR);
+    assert_eq!(ranges, vec![(0.0, 20.0), (100.0, 130.0)]);
+
+    // Watching a part again counts it once; small gaps are closed
+    let ranges = add_watched(&ranges, (10.0, 99.5), Some(600.0), HOUR);
+    assert_eq!(ranges, vec![(0.0, 130.0)]);
+    assert_eq!(watched_seconds(&ranges), 130.0);
+    assert!(watched_seconds(&[]).is_sign_positive());
+}
+
+#[test]
+fn watched_spans_are_cut_to_the_duration_and_wall_time() {
+    assert_eq!(add_watched(&[], (-5.0, 700.0), Some(600.0), HOUR), vec![(0.0, 600.0)]);
+    assert_eq!(add_watched(&[], (20.0, 10.0), Some(600.0), HOUR), vec![]);
+    assert_eq!(add_watched(&[], (0.0, f64::NAN), None, HOUR), vec![]);
+
+    // A first ping cannot claim more than the slack
+    assert_eq!(add_watched(&[], (0.0, 600.0), None, Duration::ZERO), vec![(0.0, 30.0)]);
+
+    // Ten seconds later, at most 2 x 10 + 30 seconds in total were watched
+    let ranges = [(0.0, 30.0)];
+    assert_eq!(
+        add_watched(&ranges, (30.0, 600.0), None, Duration::from_secs(10)),
+        vec![(0.0, 50.0)]
+    );
+}
+
+#[test]
+fn resume_positions_stay_within_the_video() {
+    assert_eq!(resume_position(42.5, Some(600.0)), Some(42.5));
+    assert_eq!(resume_position(-1.0, Some(600.0)), Some(0.0));
+    assert_eq!(resume_position(900.0, Some(600.0)), Some(600.0));
+    assert_eq!(resume_position(f64::INFINITY, None), None);
+}
+
+#[test]
+fn pages_are_deduplicated_within_the_document() {
+    assert_eq!(add_pages(&[1, 2], &[2, 5, 3, 0, 12], Some(10)), vec![1, 2, 3, 5]);
+    assert_eq!(add_pages(&[], &[40, 1], None), vec![1, 40]);
+}
+
+#[test]
+fn completion_rules_follow_the_material() {
+    let video = Consumption {
+        watched_seconds: 540.0,
+        duration_seconds: Some(600.0),
+        pages_viewed: 0,
+        page_count: None,
+    };
+    assert_eq!(CompletionRule::Watched(90).percent(&video), 90);
+    assert!(CompletionRule::Watched(90).is_met(&video));
+    assert!(!CompletionRule::Watched(95).is_met(&video));
+    assert!(!CompletionRule::Watched(90).is_met(&Consumption { duration_seconds: None, ..video }));
+
+    let document = Consumption {
+        watched_seconds: 0.0,
+        duration_seconds: None,
+        pages_viewed: 9,
+        page_count: Some(10),
+    };
+    assert_eq!(CompletionRule::PagesViewed(100).percent(&document), 90);
+    assert!(!CompletionRule::PagesViewed(100).is_met(&document));
+    assert!(CompletionRule::PagesViewed(100).is_met(&Consumption { pages_viewed: 10, ..document }));
+    assert!(!CompletionRule::PagesViewed(50).is_met(&Consumption { page_count: None, ..document }));
+
+    assert!(CompletionRule::Opened.is_met(&document));
+    assert_eq!(percent_of(1.0, 3.0), 33);
+    assert_eq!(percent_of(5.0, 0.0), 0);
+}
@@ -0,0 +1,260 @@
+#[path = "../src/core/utils/quiz.rs"]
+#[allow(dead_code)]
+mod quiz;
+
+use std::collections::BTreeMap;
+
+use quiz::{
+    decode_answer_keys, draw_questions, encode_answer_keys, is_correct_choice, normalize_answer, normalize_draw,
+    normalize_question, normalize_tag, passes, reveals_feedback, score_percent, shuffled_option_keys, AnswerError,
+    DrawError, NormalizedAnswer, QuestionDraw, QuestionError, QuestionKind, QuizOption,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn option(key: &str, text: &str) -> QuizOption {
+    QuizOption {
+        key: key.to_string(),
+        text: text.to_string(),
+    }
+}
+
+fn answers(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+#[test]
+fn single_choice_needs_exactly_one_known_answer() {
+    let options = || Some(vec![option(" a ", " Helmet "), option("b", "Cap")]);
+
+    let normalized = normalize_question(QuestionKind::SingleChoice, options(), &answers(&["a"])).unwrap();
+    assert_eq!(normalized.options, Some(vec![option("a", "Helmet"), option("b", "Cap")]));
+    assert_eq!(normalized.correct_answer_key.as_deref(), Some("a"));
+
+    assert_eq!(
+        normalize_question(QuestionKind::SingleChoice, options(), &answers(&["a", "b"])),
+        Err(QuestionError::ExactlyOneAnswer)
+    );
+    assert_eq!(
+        normalize_question(QuestionKind::SingleChoice, options(), &answers(&["c"])),
+        Err(QuestionError::UnknownAnswer("c".to_string()))
+    );
+}
+
+#[test]
+fn choice_options_are_validated() {
+    assert_eq!(
+        normalize_question(QuestionKind::MultipleChoice, Some(vec![option("a", "Only")]), &answers(&["a"])),
+        Err(QuestionError::OptionCount)
+    );
+    assert_eq!(
+        normalize_question(
+            QuestionKind::MultipleChoice,
+            Some(vec![option("a", "One"), option("a", "Two")]),
+            &answers(&["a"])
+        ),
+        Err(QuestionError::DuplicateKey("a".to_string()))
+    );
+    assert_eq!(
+        normalize_question(
+            QuestionKind::MultipleChoice,
+            Some(vec![option("a,b", "One"), option("c", "Two")]),
+            &answers(&["c"])
+        ),
+        Err(QuestionError::InvalidKey("a,b".to_string()))
+    );
+    assert_eq!(
+        normalize_question(QuestionKind::MultipleChoice, Some(vec![option("a", "One"), option("b", " ")]), &[]),
+        Err(QuestionError::InvalidText("b".to_string()))
+    );
+}
+
+#[test]
+fn multiple_choice_answers_are_stored_sorted() {
+    let normalized = normalize_question(
+        QuestionKind::MultipleChoice,
+        Some(vec![option("a", "Gloves"), option("b", "Goggles"), option("c", "Sandals")]),
+        &answers(&["b", "a", "b"]),
+    )
+    .unwrap();
+
+    assert_eq!(normalized.correct_answer_key.as_deref(), Some("a,b"));
+    assert_eq!(decode_answer_keys("a,b"), answers(&["a", "b"]));
+    assert_eq!(encode_answer_keys(&answers(&["c", "a"])), "a,c");
+}
+
+#[test]
+fn true_false_options_are_fixed() {
+    let normalized = normalize_question(QuestionKind::TrueFalse, None, &answers(&["false"])).unwrap();
+    assert_eq!(normalized.options.unwrap().len(), 2);
+    assert_eq!(normalized.correct_answer_key.as_deref(), Some("false"));
+
+    assert_eq!(
+        normalize_question(QuestionKind::TrueFalse, None, &answers(&["yes"])),
+        Err(QuestionError::UnknownAnswer("yes".to_string()))
+    );
+    assert!(matches!(
+        normalize_question(QuestionKind::TrueFalse, Some(vec![]), &answers(&["true"])),
+        Err(QuestionError::UnexpectedOptions(_))
+    ));
+}
+
+#[test]
+fn free_text_takes_neither_options_nor_answers() {
+    let normalized = normalize_question(QuestionKind::FreeText, None, &[]).unwrap();
+    assert_eq!(normalized.options, None);
+    assert_eq!(normalized.correct_answer_key, None);
+
+    assert_eq!(
+        normalize_question(QuestionKind::FreeText, None, &answers(&["a"])),
+        Err(QuestionError::UnexpectedAnswer)
+    );
+}
+
+#[test]
+fn answers_are_checked_against_the_question() {
+    let options = vec![option("a", "Gloves"), option("b", "Goggles")];
+
+    let answer = normalize_answer(QuestionKind::MultipleChoice, &options, &answers(&["b", "a"]), None).unwrap();
+    assert_eq!(answer.answer_key.as_deref(), Some("a,b"));
+
+    let blank = normalize_answer(QuestionKind::SingleChoice, &options, &[], None).unwrap();
+    assert_eq!(blank, NormalizedAnswer { answer_key: None, answer_text: None });
+
+    assert_eq!(
+        normalize_answer(QuestionKind::SingleChoice, &options, &answers(&["a", "b"]), None),
+        Err(AnswerError::SingleChoiceOnly)
+    );
+    assert_eq!(
+        normalize_answer(QuestionKind::SingleChoice, &options, &answers(&["z"]), None),
+        Err(AnswerError::UnknownOption("z".to_string()))
+    );
+    assert_eq!(
+        normalize_answer(QuestionKind::SingleChoice, &options, &[], Some("Gloves")),
+        Err(AnswerError::TextForChoice)
+    );
+
+    let text = normalize_answer(QuestionKind::FreeText, &[], &[], Some("  Report it  ")).unwrap();
+    assert_eq!(text.answer_text.as_deref(), Some("Report it"));
+    assert_eq!(
+        normalize_answer(QuestionKind::FreeText, &[], &answers(&["a"]), None),
+        Err(AnswerError::KeysForText)
+    );
+}
+
+#[test]
+fn multiple_choice_needs_the_exact_set() {
+    assert!(is_correct_choice("a,b", Some("a,b")));
+    assert!(!is_correct_choice("a,b", Some("a")));
+    assert!(!is_correct_choice("a,b", Some("a,b,c")));
+    assert!(!is_correct_choice("a", None));
+}
+
+#[test]
+fn pass_threshold_is_compared_exactly() {
+    assert!(passes(7, 10, 70));
+    assert!(!passes(6, 9, 67));
+    assert!(passes(2, 3, 66));
+    assert!(!passes(0, 0, 1));
+
+    assert_eq!(score_percent(2, 3), 66.67);
+    assert_eq!(score_percent(0, 0), 0.0);
+}
+
+#[test]
+fn feedback_waits_until_no_retry_is_left() {
+    assert!(!reveals_feedback(false, 1, Some(3)));
+    assert!(!reveals_feedback(false, 2, Some(3)));
+    assert!(reveals_feedback(false, 3, Some(3)));
+    assert!(reveals_feedback(true, 1, Some(3)));
+
+    // Unlimited attempts keep the answer key hidden until the quiz is passed
+    assert!(!reveals_feedback(false, 10, None));
+    assert!(reveals_feedback(true, 10, None));
+}
+
+fn pool() -> Vec<(u32, Option<&'static str>)> {
+    vec![(1, Some("ppe")), (2, Some("ppe")), (3, Some("ppe")), (4, Some("fire")), (5, Some("fire")), (6, None)]
+}
+
+#[test]
+fn all_keeps_the_authored_order() {
+    let pool = pool();
+    let drawn = draw_questions(&pool, |q| q.1, &QuestionDraw::All, &mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(drawn.iter().map(|q| q.0).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn random_draws_distinct_questions() {
+    let pool = pool();
+    for seed in 0..20 {
+        let drawn = draw_questions(&pool, |q| q.1, &QuestionDraw::Random(4), &mut StdRng::seed_from_u64(seed)).unwrap();
+        let mut ids: Vec<u32> = drawn.iter().map(|q| q.0).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 4);
+    }
+
+    assert_eq!(
+        draw_questions(&pool, |q| q.1, &QuestionDraw::Random(7), &mut StdRng::seed_from_u64(1)),
+        Err(DrawError::NotEnoughQuestions { requested: 7, available: 6 })
+    );
+}
+
+#[test]
+fn per_tag_draws_respect_the_quotas() {
+    let pool = pool();
+    let quotas = BTreeMap::from([("ppe".to_string(), 2), ("fire".to_string(), 1)]);
+    for seed in 0..20 {
+        let drawn =
+            draw_questions(&pool, |q| q.1, &QuestionDraw::PerTag(quotas.clone()), &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+        assert_eq!(drawn.len(), 3);
+        assert_eq!(drawn.iter().filter(|q| q.1 == Some("ppe")).count(), 2);
+        assert_eq!(drawn.iter().filter(|q| q.1 == Some("fire")).count(), 1);
+    }
+
+    let too_many = BTreeMap::from([("fire".to_string(), 3)]);
+    let err =
+        draw_questions(&pool, |q| q.1, &QuestionDraw::PerTag(too_many), &mut StdRng::seed_from_u64(1)).unwrap_err();
+    assert!(err.is_pool_too_small());
+}
+
+#[test]
+fn draw_settings_are_normalized() {
+    let quotas = BTreeMap::from([(" PPE ".to_string(), 2)]);
+    assert_eq!(
+        normalize_draw(None, Some(quotas.clone())),
+        Ok(Some(BTreeMap::from([("ppe".to_string(), 2)])))
+    );
+    assert_eq!(normalize_draw(Some(3), Some(quotas)), Err(DrawError::ConflictingDraw));
+    assert_eq!(normalize_draw(Some(3), Some(BTreeMap::new())), Ok(None));
+
+    let duplicate = BTreeMap::from([("ppe".to_string(), 1), ("PPE".to_string(), 1)]);
+    assert!(matches!(normalize_draw(None, Some(duplicate)), Err(DrawError::InvalidQuota(_))));
+    assert!(matches!(
+        normalize_draw(None, Some(BTreeMap::from([("ppe".to_string(), 0)]))),
+        Err(DrawError::InvalidQuota(_))
+    ));
+
+    assert_eq!(normalize_tag(" Fire-Safety "), Ok("fire-safety".to_string()));
+    assert!(matches!(normalize_tag("a b"), Err(QuestionError::InvalidTag(_))));
+}
+
+#[test]
+fn options_are_shuffled_except_true_false() {
+    let options = vec![option("a", "A"), option("b", "B"), option("c", "C"), option("d", "D")];
+    let orders: std::collections::HashSet<Vec<String>> = (0..20)
+        .map(|seed| shuffled_option_keys(QuestionKind::SingleChoice, &options, &mut StdRng::seed_from_u64(seed)))
+        .collect();
+    assert!(orders.len() > 1);
+    assert!(orders.iter().all(|order| order.len() == 4));
+
+    let true_false = vec![option("true", "True"), option("false", "False")];
+    for seed in 0..10 {
+        assert_eq!(
+            shuffled_option_keys(QuestionKind::TrueFalse, &true_false, &mut StdRng::seed_from_u64(seed)),
+            answers(&["true", "false"])
+        );
+    }
+}
@@ -0,0 +1,329 @@
+#[path = "../src/modules/realtime/calls.rs"]
+#[allow(dead_code)]
+mod calls;
+#[path = "../src/modules/realtime/protocol.rs"]
+#[allow(dead_code)]
+mod protocol;
+
+use calls::{CallError, CallState, Calls, Invitation, RING_TIMEOUT};
+use protocol::{ClientMessage, ErrorCode, IceCandidate, Room, ServerMessage, Signal};
+use serde_json::json;
+use sqlx::types::Uuid;
+use time::{Duration, OffsetDateTime};
+
+const SESSION: &str = "aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa";
+const CANDIDATE: &str = "candidate:1 1 udp 2122260223 10.0.0.1 54321 typ host";
+
+#[test]
+fn rooms_are_named_by_kind_and_id() {
+    let room: Room = format!("training:{}", SESSION).parse().unwrap();
+    assert_eq!(room, Room::Training(Uuid::parse_str(SESSION).unwrap()));
+    assert_eq!(room.to_string(), format!("training:{}", SESSION));
+
+    let room: Room = format!("appointment:{}", SESSION).parse().unwrap();
+    assert_eq!(room, Room::Appointment(Uuid::parse_str(SESSION).unwrap()));
+    assert_eq!(room.to_string(), format!("appointment:{}", SESSION));
+
+    assert!("training".parse::<Room>().is_err());
+    assert!("training:not-a-uuid".parse::<Room>().is_err());
+    assert!(format!("tenant:{}", SESSION).parse::<Room>().is_err());
+}
+
+#[test]
+fn client_messages_are_tagged_by_type() {
+    let message: ClientMessage =
+        serde_json::from_value(json!({"type": "subscribe", "id": "1", "room": format!("training:{}", SESSION)}))
+            .unwrap();
+    assert_eq!(
+        message,
+        ClientMessage::Subscribe {
+            id: Some("1".to_string()),
+            room: Room::Training(Uuid::parse_str(SESSION).unwrap()),
+        }
+    );
+
+    let message: ClientMessage = serde_json::from_value(json!({"type": "ping"})).unwrap();
+    assert_eq!(message, ClientMessage::Ping { id: None });
+
+    assert!(serde_json::from_value::<ClientMessage>(json!({"type": "subscribe", "room": "lobby"})).is_err());
+    assert!(serde_json::from_value::<ClientMessage>(json!({"type": "broadcast", "text": "hi"})).is_err());
+}
+
+#[test]
+fn server_messages_carry_their_type() {
+    let room = Room::Training(Uuid::parse_str(SESSION).unwrap());
+    let event = ServerMessage::event(Some(room), "room.member_joined", json!({"user_id": SESSION}));
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({
+            "type": "event",
+            "room": format!("training:{}", SESSION),
+            "event": "room.member_joined",
+            "data": {"user_id": SESSION},
+        })
+    );
+
+    let error = ServerMessage::error(Some("7".to_string()), ErrorCode::Forbidden, "Access denied");
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        json!({"type": "error", "id": "7", "code": "forbidden", "message": "Access denied"})
+    );
+}
+
+#[test]
+fn signals_carry_their_kind() {
+    let room = Room::Appointment(Uuid::parse_str(SESSION).unwrap());
+    let message: ClientMessage = serde_json::from_value(json!({
+        "type": "signal",
+        "id": "3",
+        "room": room.to_string(),
+        "signal": {
+            "kind": "ice_candidate",
+            "candidate": {"candidate": CANDIDATE, "sdpMid": "0", "sdpMLineIndex": 0},
+        },
+    }))
+    .unwrap();
+    assert_eq!(
+        message,
+        ClientMessage::Signal {
+            id: Some("3".to_string()),
+            room,
+            signal: Signal::IceCandidate {
+                candidate: IceCandidate {
+                    candidate: CANDIDATE.to_string(),
+                    sdp_mid: Some("0".to_string()),
+                    sdp_m_line_index: Some(0),
+                    username_fragment: None,
+                },
+            },
+        }
+    );
+
+    let message: ClientMessage =
+        serde_json::from_value(json!({"type": "signal", "room": room.to_string(), "signal": {"kind": "decline"}}))
+            .unwrap();
+    assert_eq!(
+        message,
+        ClientMessage::Signal { id: None, room, signal: Signal::Decline { reason: None } }
+    );
+    let offer_without_sdp = json!({"type": "signal", "room": room.to_string(), "signal": {"kind": "offer"}});
+    assert!(serde_json::from_value::<ClientMessage>(offer_without_sdp).is_err());
+
+    let call_id = Uuid::now_v7();
+    let from_user_id = Uuid::now_v7();
+    let relayed = ServerMessage::Signal {
+        room,
+        call_id,
+        from_user_id,
+        signal: Signal::Offer { sdp: "v=0".to_string() },
+    };
+    assert_eq!(
+        serde_json::to_value(&relayed).unwrap(),
+        json!({
+            "type": "signal",
+            "room": room.to_string(),
+            "call_id": call_id,
+            "from_user_id": from_user_id,
+            "signal": {"kind": "offer", "sdp": "v=0"},
+        })
+    );
+}
+
+struct Parties {
+    appointment: Uuid,
+    employee: Uuid,
+    employee_phone: Uuid,
+    employee_laptop: Uuid,
+    professional: Uuid,
+    professional_laptop: Uuid,
+}
+
+impl Parties {
+    fn invitation(&self, caller: Uuid, caller_connection: Uuid, callee: Uuid) -> Invitation {
+        Invitation {
+            appointment_id: self.appointment,
+            caller_user_id: caller,
+            caller_connection,
+            callee_user_id: callee,
+            professional_user_id: self.professional,
+            time_limit: None,
+            closes_at: OffsetDateTime::now_utc() + Duration::hours(1),
+        }
+    }
+}
+
+fn parties() -> Parties {
+    Parties {
+        appointment: Uuid::now_v7(),
+        employee: Uuid::now_v7(),
+        employee_phone: Uuid::now_v7(),
+        employee_laptop: Uuid::now_v7(),
+        professional: Uuid::now_v7(),
+        professional_laptop: Uuid::now_v7(),
+    }
+}
+
+#[test]
+fn calls_are_answered_on_one_connection() {
+    let p = parties();
+    let calls = Calls::new();
+    let now = OffsetDateTime::now_utc();
+
+    let call = calls.invite(p.invitation(p.professional, p.professional_laptop, p.employee), now).unwrap();
+    assert_eq!(call.state, CallState::Ringing);
+    assert_eq!(
+        calls.invite(p.invitation(p.employee, p.employee_phone, p.professional), now),
+        Err(CallError::Busy)
+    );
+    assert_eq!(
+        calls.negotiating(p.appointment, p.professional, p.professional_laptop, now),
+        Err(CallError::NotAccepted)
+    );
+    assert_eq!(
+        calls.accept(p.appointment, p.professional, p.professional_laptop, now),
+        Err(CallError::NotCallee)
+    );
+
+    let accepted = calls.accept(p.appointment, p.employee, p.employee_phone, now).unwrap();
+    assert_eq!(accepted.id, call.id);
+    assert_eq!(accepted.state, CallState::Active { answered_at: now });
+    assert_eq!(accepted.peer_connection(p.professional_laptop), Some(p.employee_phone));
+    assert_eq!(accepted.peer_connection(p.employee_phone), Some(p.professional_laptop));
+    assert_eq!(
+        calls.accept(p.appointment, p.employee, p.employee_laptop, now),
+        Err(CallError::AlreadyAnswered)
+    );
+    assert_eq!(
+        calls.negotiating(p.appointment, p.employee, p.employee_laptop, now),
+        Err(CallError::OtherConnection)
+    );
+    assert!(calls.negotiating(p.appointment, p.employee, p.employee_phone, now).is_ok());
+    assert_eq!(calls.decline(p.appointment, p.employee), Err(CallError::AlreadyAnswered));
+
+    assert_eq!(calls.hang_up(p.appointment, Uuid::now_v7()), Err(CallError::NoCall));
+    assert_eq!(calls.hang_up(p.appointment, p.professional).unwrap().id, call.id);
+    assert_eq!(calls.get(p.appointment), None);
+}
+
+#[test]
+fn unanswered_invites_can_be_declined_or_replaced() {
+    let p = parties();
+    let calls = Calls::new();
+    let now = OffsetDateTime::now_utc();
+
+    calls.invite(p.invitation(p.employee, p.employee_phone, p.professional), now).unwrap();
+    assert_eq!(calls.decline(p.appointment, p.employee), Err(CallError::NotCallee));
+    calls.decline(p.appointment, p.professional).unwrap();
+    assert_eq!(calls.accept(p.appointment, p.professional, p.professional_laptop, now), Err(CallError::NoCall));
+
+    let first = calls.invite(p.invitation(p.employee, p.employee_phone, p.professional), now).unwrap();
+    let later = now + RING_TIMEOUT + Duration::seconds(1);
+    assert_eq!(
+        calls.accept(p.appointment, p.professional, p.professional_laptop, later),
+        Err(CallError::NoCall)
+    );
+    calls.invite(p.invitation(p.employee, p.employee_phone, p.professional), now).unwrap();
+    let second = calls.invite(p.invitation(p.professional, p.professional_laptop, p.employee), later).unwrap();
+    assert_ne!(first.id, second.id);
+    assert_eq!(second.caller_user_id, p.professional);
+}
+
+#[test]
+fn only_answered_calls_are_kept_alive_by_their_connections() {
+    let p = parties();
+    let calls = Calls::new();
+    let now = OffsetDateTime::now_utc();
+
+    let call = calls.invite(p.invitation(p.employee, p.employee_phone, p.professional), now).unwrap();
+    assert!(calls.answered_on_connection(p.employee_phone).is_empty());
+
+    calls.accept(p.appointment, p.professional, p.professional_laptop, now).unwrap();
+    assert_eq!(calls.answered_on_connection(p.employee_phone), vec![call.id]);
+    assert_eq!(calls.answered_on_connection(p.professional_laptop), vec![call.id]);
+    assert!(calls.answered_on_connection(p.employee_laptop).is_empty());
+}
+
+#[test]
+fn closing_a_connection_ends_its_calls() {
+    let p = parties();
+    let calls = Calls::new();
+    let now = OffsetDateTime::now_utc();
+
+    calls.invite(p.invitation(p.employee, p.employee_phone, p.professional), now).unwrap();
+    calls
+        .accept(p.appointment, p.professional, p.professional_laptop, now)
+        .unwrap();
+
+    assert_eq!(calls.end_on_connection(p.appointment, p.employee_laptop), None);
+    assert!(calls.end_all_on_connection(p.employee_laptop).is_empty());
+    let ended = calls.end_all_on_connection(p.professional_laptop);
+    assert_eq!(ended.len(), 1);
+    assert_eq!(ended[0].appointment_id, p.appointment);
+    assert_eq!(calls.get(p.appointment), None);
+}
+
+#[test]
+fn answered_calls_end_when_the_appointment_time_limit_is_used_up() {
+    let p = parties();
+    let calls = Calls::new();
+    let now = OffsetDateTime::now_utc();
+
+    let invitation = Invitation {
+        time_limit: Some(Duration::minutes(40)),
+        ..p.invitation(p.employee, p.employee_phone, p.professional)
+    };
+    let call = calls.invite(invitation, now).unwrap();
+    assert_eq!(call.ends_at(Duration::ZERO), None);
+
+    let answered_at = now + Duration::seconds(5);
+    let call = calls
+        .accept(p.appointment, p.professional, p.professional_laptop, answered_at)
+        .unwrap();
+    assert_eq!(call.ends_at(Duration::ZERO), Some(answered_at + Duration::minutes(40)));
+    // Earlier calls of the appointment took their share of the limit
+    assert_eq!(call.ends_at(Duration::minutes(25)), Some(answered_at + Duration::minutes(15)));
+    assert_eq!(call.ends_at(Duration::minutes(45)), Some(answered_at));
+    assert_eq!(call.professional_user_id, p.professional);
+
+    // A cut-off scheduled for an earlier call leaves a newer one alone
+    assert_eq!(calls.end(p.appointment, Uuid::now_v7()), None);
+    assert_eq!(calls.end(p.appointment, call.id), Some(call));
+    assert_eq!(calls.get(p.appointment), None);
+}
+
+#[test]
+fn calls_do_not_outlast_the_appointment() {
+    let p = parties();
+    let calls = Calls::new();
+    let now = OffsetDateTime::now_utc();
+    let closes_at = now + Duration::minutes(10);
+
+    let invitation = Invitation {
+        time_limit: Some(Duration::minutes(40)),
+        closes_at,
+        ..p.invitation(p.employee, p.employee_phone, p.professional)
+    };
+    calls.invite(invitation, now).unwrap();
+    let call = calls.accept(p.appointment, p.professional, p.professional_laptop, now).unwrap();
+    assert_eq!(call.ends_at(Duration::ZERO), Some(closes_at));
+    assert_eq!(call.ends_at(Duration::minutes(35)), Some(now + Duration::minutes(5)));
+    assert!(calls.negotiating(p.appointment, p.employee, p.employee_phone, now).is_ok());
+    assert_eq!(
+        calls.negotiating(p.appointment, p.employee, p.employee_phone, closes_at),
+        Err(CallError::Closed)
+    );
+
+    // Without a time limit the appointment's end still cuts the call off
+    calls.hang_up(p.appointment, p.employee).unwrap();
+    let invitation = Invitation { closes_at, ..p.invitation(p.employee, p.employee_phone, p.professional) };
+    calls.invite(invitation, closes_at - Duration::seconds(30)).unwrap();
+    assert_eq!(
+        calls.accept(p.appointment, p.professional, p.professional_laptop, closes_at),
+        Err(CallError::Closed)
+    );
+    assert_eq!(calls.get(p.appointment), None);
+
+    calls.invite(invitation, now).unwrap();
+    let call = calls.accept(p.appointment, p.professional, p.professional_laptop, now).unwrap();
+    assert_eq!(call.ends_at(Duration::ZERO), Some(closes_at));
+}
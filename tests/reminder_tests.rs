@@ -0,0 +1,70 @@
+#[path = "../src/core/utils/time_zone.rs"]
+#[allow(dead_code)]
+mod time_zone;
+#[path = "../src/core/utils/reminder.rs"]
+#[allow(dead_code)]
+mod reminder;
+
+use reminder::{remind_at, reminder_schedule};
+use time::macros::datetime;
+use time_zone::Zone;
+
+fn zone(name: &str) -> Zone {
+    Zone::parse(name).expect("zone should be in the tz database")
+}
+
+#[test]
+fn offsets_below_a_day_are_plain_durations() {
+    let berlin = zone("Europe/Berlin");
+
+    // 03:30 CEST, half an hour after the clocks went forward; 90 minutes earlier is 01:00 CET
+    let starts_at = datetime!(2025-03-30 01:30 UTC);
+    assert_eq!(remind_at(starts_at, 90, berlin), datetime!(2025-03-30 00:00 UTC));
+    assert_eq!(remind_at(starts_at, 25 * 60, berlin), datetime!(2025-03-29 00:30 UTC));
+}
+
+#[test]
+fn day_offsets_keep_the_local_time_across_spring_forward() {
+    let berlin = zone("Europe/Berlin");
+
+    // 10:00 CEST on the day of the change; one day before is 10:00 CET, only 23 hours earlier
+    let starts_at = datetime!(2025-03-30 08:00 UTC);
+    let reminder = remind_at(starts_at, 24 * 60, berlin);
+    assert_eq!(reminder, datetime!(2025-03-29 09:00 UTC));
+    assert_eq!(berlin.localize(reminder).time(), berlin.localize(starts_at).time());
+}
+
+#[test]
+fn day_offsets_keep_the_local_time_across_fall_back() {
+    let new_york = zone("America/New_York");
+
+    // 10:00 EST on 2025-11-02; two days before is 10:00 EDT, 49 hours earlier
+    let starts_at = datetime!(2025-11-02 15:00 UTC);
+    assert_eq!(remind_at(starts_at, 2 * 24 * 60, new_york), datetime!(2025-10-31 14:00 UTC));
+    // Without a transition in between a day is 24 hours
+    assert_eq!(remind_at(starts_at, 24 * 60, zone("UTC")), datetime!(2025-11-01 15:00 UTC));
+}
+
+#[test]
+fn day_offsets_landing_in_a_gap_move_forward() {
+    let new_york = zone("America/New_York");
+
+    // 02:30 EDT the day after the change does not exist the day before; it becomes 03:30 EDT
+    let starts_at = datetime!(2025-03-10 06:30 UTC);
+    assert_eq!(remind_at(starts_at, 24 * 60, new_york), datetime!(2025-03-09 07:30 UTC));
+}
+
+#[test]
+fn schedule_keeps_positive_offsets_still_ahead() {
+    let berlin = zone("Europe/Berlin");
+    let starts_at = datetime!(2025-03-30 08:00 UTC);
+    let now = datetime!(2025-03-29 12:00 UTC);
+
+    assert_eq!(
+        reminder_schedule(starts_at, &[24 * 60, 60, 0, -30, 15], berlin, now),
+        vec![(60, datetime!(2025-03-30 07:00 UTC)), (15, datetime!(2025-03-30 07:45 UTC))]
+    );
+    // A reminder due right now has already been missed
+    assert_eq!(reminder_schedule(starts_at, &[60], berlin, datetime!(2025-03-30 07:00 UTC)), vec![]);
+    assert_eq!(reminder_schedule(starts_at, &[], berlin, now), vec![]);
+}
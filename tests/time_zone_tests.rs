@@ -0,0 +1,116 @@
+#[path = "../src/core/utils/time_zone.rs"]
+#[allow(dead_code)]
+mod time_zone;
+
+use time::macros::{datetime, offset};
+use time_zone::{ClientDateTime, TimeZoneError, Zone};
+
+fn zone(name: &str) -> Zone {
+    Zone::parse(name).expect("zone should be in the tz database")
+}
+
+#[test]
+fn parse_rejects_unknown_zones() {
+    assert_eq!(
+        Zone::parse("Mars/Olympus_Mons"),
+        Err(TimeZoneError::Unknown("Mars/Olympus_Mons".to_string()))
+    );
+    assert_eq!(zone(" Europe/Istanbul ").name(), "Europe/Istanbul");
+}
+
+#[test]
+fn local_time_in_spring_forward_gap_is_rejected() {
+    // Berlin jumps from 02:00 to 03:00 on 2025-03-30
+    let berlin = zone("Europe/Berlin");
+
+    assert!(matches!(
+        berlin.resolve_local(datetime!(2025-03-30 02:30)),
+        Err(TimeZoneError::NonexistentLocalTime(_, "Europe/Berlin"))
+    ));
+    assert_eq!(
+        berlin.resolve_local(datetime!(2025-03-30 03:00)).unwrap(),
+        datetime!(2025-03-30 01:00 UTC)
+    );
+}
+
+#[test]
+fn lenient_resolution_pushes_gap_times_forward() {
+    let new_york = zone("America/New_York");
+
+    // New York jumps from 02:00 to 03:00 on 2025-03-09
+    let resolved = new_york.resolve_local_lenient(datetime!(2025-03-09 02:30));
+    assert_eq!(resolved, datetime!(2025-03-09 07:30 UTC));
+    assert_eq!(new_york.localize(resolved), datetime!(2025-03-09 03:30 -4));
+}
+
+#[test]
+fn local_time_in_fall_back_overlap_resolves_to_first_occurrence() {
+    // London repeats 01:00-02:00 on 2025-10-26
+    let london = zone("Europe/London");
+
+    assert_eq!(
+        london.resolve_local(datetime!(2025-10-26 01:30)).unwrap(),
+        datetime!(2025-10-26 00:30 UTC)
+    );
+}
+
+#[test]
+fn fixed_offset_zones_have_no_transitions() {
+    let istanbul = zone("Europe/Istanbul");
+
+    assert_eq!(
+        istanbul.resolve_local(datetime!(2025-03-30 02:30)).unwrap(),
+        datetime!(2025-03-29 23:30 UTC)
+    );
+    assert_eq!(istanbul.localize(datetime!(2025-07-01 12:00 UTC)).offset(), offset!(+3));
+}
+
+#[test]
+fn shift_days_keeps_wall_clock_across_transitions() {
+    let berlin = zone("Europe/Berlin");
+
+    // 10:00 CEST on Monday after the spring change, one day earlier is 10:00 CET on Sunday
+    let appointment = datetime!(2025-03-31 08:00 UTC);
+    let reminder = berlin.shift_days(appointment, -1);
+    assert_eq!(reminder, datetime!(2025-03-30 08:00 UTC));
+    assert_eq!(berlin.localize(reminder).time(), berlin.localize(appointment).time());
+
+    // Weekly recurrence across the autumn change keeps 09:00 local
+    let first = berlin.resolve_local(datetime!(2025-10-20 09:00)).unwrap();
+    let next = berlin.shift_days(first, 7);
+    assert_eq!(first, datetime!(2025-10-20 07:00 UTC));
+    assert_eq!(next, datetime!(2025-10-27 08:00 UTC));
+}
+
+#[test]
+fn local_date_follows_the_zone() {
+    let new_york = zone("America/New_York");
+
+    assert_eq!(
+        new_york.local_date(datetime!(2025-06-01 02:00 UTC)),
+        time::macros::date!(2025-05-31)
+    );
+}
+
+#[test]
+fn client_date_times_with_offset_are_kept() {
+    let parsed: ClientDateTime = serde_json::from_str("\"2025-03-30T02:30:00+05:00\"").unwrap();
+
+    assert_eq!(
+        parsed.resolve(zone("Europe/Berlin")).unwrap(),
+        datetime!(2025-03-29 21:30 UTC)
+    );
+}
+
+#[test]
+fn client_date_times_without_offset_use_the_caller_zone() {
+    let parsed: ClientDateTime = serde_json::from_str("\"2025-07-01T09:15\"").unwrap();
+    assert_eq!(parsed, ClientDateTime::Local(datetime!(2025-07-01 09:15)));
+    assert_eq!(
+        parsed.resolve(zone("America/New_York")).unwrap(),
+        datetime!(2025-07-01 13:15 UTC)
+    );
+
+    let in_gap: ClientDateTime = serde_json::from_str("\"2025-03-30T02:30:00\"").unwrap();
+    assert!(in_gap.resolve(zone("Europe/Berlin")).is_err());
+}
@@ -0,0 +1,99 @@
+#[path = "../src/core/utils/training.rs"]
+#[allow(dead_code)]
+mod training;
+
+use sqlx::types::Uuid;
+use training::{check_transition, SessionScope, SessionStage, SessionViewer, TransitionError};
+
+const STAGES: [SessionStage; 4] = [
+    SessionStage::Scheduled,
+    SessionStage::InProgress,
+    SessionStage::Completed,
+    SessionStage::Cancelled,
+];
+
+fn viewer(tenant_id: Option<Uuid>) -> SessionViewer {
+    SessionViewer {
+        user_id: Uuid::now_v7(),
+        tenant_id,
+        super_admin: false,
+        tenant_admin: false,
+        ohs_specialist: false,
+    }
+}
+
+#[test]
+fn sessions_run_forward_and_only_unfinished_ones_are_cancelled() {
+    let allowed = [
+        (SessionStage::Scheduled, SessionStage::InProgress),
+        (SessionStage::InProgress, SessionStage::Completed),
+        (SessionStage::Scheduled, SessionStage::Cancelled),
+        (SessionStage::InProgress, SessionStage::Cancelled),
+    ];
+    for from in STAGES {
+        for to in STAGES {
+            let expected = if allowed.contains(&(from, to)) {
+                Ok(())
+            } else {
+                Err(TransitionError::NotAllowed(from, to))
+            };
+            assert_eq!(check_transition(true, from, to), expected, "{:?} -> {:?}", from, to);
+        }
+    }
+}
+
+#[test]
+fn drafts_can_only_be_cancelled() {
+    assert_eq!(
+        check_transition(false, SessionStage::Scheduled, SessionStage::InProgress),
+        Err(TransitionError::NotPublished)
+    );
+    assert_eq!(check_transition(false, SessionStage::Scheduled, SessionStage::Cancelled), Ok(()));
+    assert_eq!(
+        check_transition(false, SessionStage::Cancelled, SessionStage::Cancelled),
+        Err(TransitionError::NotAllowed(SessionStage::Cancelled, SessionStage::Cancelled))
+    );
+    assert_eq!(
+        TransitionError::NotAllowed(SessionStage::Completed, SessionStage::Scheduled).to_string(),
+        "Cannot move a training session from Completed to Scheduled"
+    );
+}
+
+#[test]
+fn hosts_and_tenant_admins_manage_sessions_of_their_tenant() {
+    let tenant = Uuid::now_v7();
+    let host = SessionViewer { ohs_specialist: true, ..viewer(Some(tenant)) };
+    let session = SessionScope { tenant_id: tenant, host_user_id: host.user_id, published: true };
+
+    assert!(host.can_manage(&session));
+    assert!(SessionViewer { tenant_admin: true, ..viewer(Some(tenant)) }.can_manage(&session));
+    // Another specialist of the tenant, an employee, and the admin of another tenant do not
+    assert!(!SessionViewer { ohs_specialist: true, ..viewer(Some(tenant)) }.can_manage(&session));
+    assert!(!viewer(Some(tenant)).can_manage(&session));
+    assert!(!SessionViewer { tenant_admin: true, ..viewer(Some(Uuid::now_v7())) }.can_manage(&session));
+    // The host's role is what counts, not only their id
+    assert!(!SessionViewer { ohs_specialist: false, ..host }.can_manage(&session));
+    assert!(!SessionViewer { super_admin: true, ..viewer(None) }.can_manage(&session));
+}
+
+#[test]
+fn drafts_are_only_seen_by_those_who_manage_them() {
+    let tenant = Uuid::now_v7();
+    let host = SessionViewer { ohs_specialist: true, ..viewer(Some(tenant)) };
+    let employee = viewer(Some(tenant));
+    let outsider = viewer(Some(Uuid::now_v7()));
+    let super_admin = SessionViewer { super_admin: true, ..viewer(None) };
+
+    let published = SessionScope { tenant_id: tenant, host_user_id: host.user_id, published: true };
+    assert!(host.can_view(&published));
+    assert!(employee.can_view(&published));
+    assert!(!outsider.can_view(&published));
+    assert!(super_admin.can_view(&published));
+
+    let draft = SessionScope { published: false, ..published };
+    assert!(host.can_view(&draft));
+    assert!(SessionViewer { tenant_admin: true, ..viewer(Some(tenant)) }.can_view(&draft));
+    assert!(!employee.can_view(&draft));
+    assert!(!outsider.can_view(&draft));
+    assert!(super_admin.can_view(&draft));
+}
@@ -0,0 +1,39 @@
+#[path = "../src/core/utils/turn.rs"]
+#[allow(dead_code)]
+mod turn;
+
+use sqlx::types::Uuid;
+use time::{Duration, OffsetDateTime};
+use turn::rest_credential;
+
+const USER: &str = "44444444-4444-4444-4444-444444444444";
+
+#[test]
+fn credentials_follow_the_turn_rest_scheme() {
+    let user_id = Uuid::parse_str(USER).unwrap();
+    let expires_at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+    let credential = rest_credential(b"turn_shared_secret", user_id, expires_at);
+    assert_eq!(credential.username, format!("1700000000:{}", USER));
+    // base64(HMAC-SHA1(secret, username)), as coturn computes it
+    assert_eq!(credential.credential, "X+7TyODkIsYVWH0Ff/lVWjy5ylg=");
+    assert_eq!(credential.expires_at, expires_at);
+}
+
+#[test]
+fn credentials_are_bound_to_user_expiry_and_secret() {
+    let user_id = Uuid::parse_str(USER).unwrap();
+    let expires_at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+    let credential = rest_credential(b"turn_shared_secret", user_id, expires_at);
+
+    let other_user = rest_credential(b"turn_shared_secret", Uuid::now_v7(), expires_at);
+    let later = rest_credential(b"turn_shared_secret", user_id, expires_at + Duration::hours(1));
+    let other_secret = rest_credential(b"another_secret", user_id, expires_at);
+    assert_ne!(other_user.credential, credential.credential);
+    assert_ne!(later.credential, credential.credential);
+    assert_ne!(other_secret.credential, credential.credential);
+
+    // Fractions of a second are dropped, since the username only carries whole seconds
+    let fractional = rest_credential(b"turn_shared_secret", user_id, expires_at + Duration::milliseconds(700));
+    assert_eq!(fractional, credential);
+}
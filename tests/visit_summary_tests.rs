@@ -0,0 +1,47 @@
+#[path = "../src/core/utils/pdf.rs"]
+#[allow(dead_code)]
+mod pdf;
+#[path = "../src/core/utils/visit_summary.rs"]
+#[allow(dead_code)]
+mod visit_summary;
+
+use visit_summary::{render_visit_summary, VisitSummaryContent};
+
+fn summary(sections: Vec<(String, String)>) -> VisitSummaryContent {
+    VisitSummaryContent {
+        appointment_label: "Medical check-up".to_string(),
+        local_start: "18 Oct 2026 09:30".to_string(),
+        time_zone: "Europe/Istanbul".to_string(),
+        reason_for_visit: Some("Periodic examination for night shift workers".to_string()),
+        sections,
+        signed_at: "18 Oct 2026 10:05".to_string(),
+        version: 3,
+    }
+}
+
+fn page_count(pdf: &[u8]) -> usize {
+    pdf.windows(b"/Type/Page/".len()).filter(|window| window == b"/Type/Page/").count()
+}
+
+#[test]
+fn visit_summaries_render_as_pdf() {
+    let pdf = render_visit_summary(&summary(vec![
+        ("Findings".to_string(), "Blood pressure 120/80, hearing within normal limits.".to_string()),
+        ("Recommendations".to_string(), "Keep using hearing protection.\n\nFollow-up in İzmir clinic.".to_string()),
+    ]))
+    .unwrap();
+
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert_eq!(page_count(&pdf), 1);
+}
+
+#[test]
+fn long_summaries_run_onto_further_pages() {
+    let text = "The employee reports occasional lower back pain after lifting heavy loads. ".repeat(60);
+    let sections = (1..=4).map(|n| (format!("Section {}", n), text.clone())).collect();
+
+    let pdf = render_visit_summary(&summary(sections)).unwrap();
+
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(page_count(&pdf) > 1);
+}
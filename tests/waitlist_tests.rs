@@ -0,0 +1,59 @@
+#[path = "../src/core/utils/waitlist.rs"]
+#[allow(dead_code)]
+mod waitlist;
+
+use sqlx::types::Uuid;
+use time::macros::datetime;
+use waitlist::{must_wait, open_seats, promotion_order, QueuedEnrollment};
+
+#[test]
+fn the_last_seat_is_taken_before_the_waitlist_starts() {
+    assert_eq!(open_seats(Some(20), 19), Some(1));
+    assert!(!must_wait(open_seats(Some(20), 19)));
+
+    assert_eq!(open_seats(Some(20), 20), Some(0));
+    assert!(must_wait(open_seats(Some(20), 20)));
+    // Lowering the limit below the seats already held leaves none open, never a negative count
+    assert_eq!(open_seats(Some(10), 12), Some(0));
+
+    assert_eq!(open_seats(None, 500), None);
+    assert!(!must_wait(None));
+}
+
+#[test]
+fn the_oldest_waitlisted_enrollments_move_up_first() {
+    let (first, second, third) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+    let waitlist = [
+        QueuedEnrollment { id: third, enrolled_at: Some(datetime!(2025-05-02 09:00 UTC)) },
+        QueuedEnrollment { id: first, enrolled_at: Some(datetime!(2025-05-01 08:00 UTC)) },
+        QueuedEnrollment { id: second, enrolled_at: Some(datetime!(2025-05-01 17:30 UTC)) },
+    ];
+
+    assert_eq!(promotion_order(&waitlist, Some(1)), vec![first]);
+    assert_eq!(promotion_order(&waitlist, Some(2)), vec![first, second]);
+    assert_eq!(promotion_order(&waitlist, Some(5)), vec![first, second, third]);
+    assert_eq!(promotion_order(&waitlist, None), vec![first, second, third]);
+}
+
+#[test]
+fn a_full_session_promotes_no_one() {
+    let waitlist = [QueuedEnrollment { id: Uuid::now_v7(), enrolled_at: Some(datetime!(2025-05-01 08:00 UTC)) }];
+
+    assert!(promotion_order(&waitlist, Some(0)).is_empty());
+    assert!(promotion_order(&[], Some(3)).is_empty());
+}
+
+#[test]
+fn enrollments_of_the_same_instant_are_settled_by_id() {
+    let at = Some(datetime!(2025-05-01 08:00 UTC));
+    let (earlier_id, later_id) = (Uuid::now_v7(), Uuid::now_v7());
+    let undated = Uuid::now_v7();
+    let waitlist = [
+        QueuedEnrollment { id: undated, enrolled_at: None },
+        QueuedEnrollment { id: later_id, enrolled_at: at },
+        QueuedEnrollment { id: earlier_id, enrolled_at: at },
+    ];
+
+    assert_eq!(promotion_order(&waitlist, Some(1)), vec![earlier_id]);
+    assert_eq!(promotion_order(&waitlist, None), vec![earlier_id, later_id, undated]);
+}